@@ -0,0 +1,585 @@
+//! Songs inside zip archives, such as the ones music stores sell albums in.
+//! An archive is browsed as a directory, with its members at paths below it,
+//! e.g. `~/music/album.zip/01 - song.flac`
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::{Component, Path, PathBuf};
+
+const END_OF_CENTRAL_DIR: u32 = 0x0605_4b50;
+const ZIP64_END_OF_CENTRAL_DIR: u32 = 0x0606_4b50;
+const ZIP64_END_LOCATOR: u32 = 0x0706_4b50;
+const CENTRAL_DIR_HEADER: u32 = 0x0201_4b50;
+const LOCAL_FILE_HEADER: u32 = 0x0403_4b50;
+const ZIP64_EXTRA_FIELD: u16 = 0x0001;
+
+// the end of central directory record is followed by a comment of at most u16::MAX bytes
+const MAX_END_SEARCH: u64 = 22 + u16::MAX as u64;
+
+const METHOD_STORED: u16 = 0;
+const METHOD_DEFLATE: u16 = 8;
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+fn u16_at(b: &[u8], i: usize) -> u16 {
+    u16::from_le_bytes([b[i], b[i + 1]])
+}
+
+fn u32_at(b: &[u8], i: usize) -> u32 {
+    u32::from_le_bytes([b[i], b[i + 1], b[i + 2], b[i + 3]])
+}
+
+fn u64_at(b: &[u8], i: usize) -> u64 {
+    let mut bytes = [0; 8];
+    bytes.copy_from_slice(&b[i..i + 8]);
+    u64::from_le_bytes(bytes)
+}
+
+#[derive(Clone, Debug)]
+struct ZipMember {
+    name: String,
+    method: u16,
+    encrypted: bool,
+    compressed_size: u64,
+    size: u64,
+    header_offset: u64,
+}
+
+/// A file or directory directly inside an archive directory
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub name: String,
+    pub is_dir: bool,
+    // uncompressed size, zero for directories
+    pub size: u64,
+}
+
+pub struct ZipArchive {
+    file: fs::File,
+    // sizes read from the archive are checked against it before anything is allocated
+    file_len: u64,
+    members: Vec<ZipMember>,
+}
+
+impl ZipArchive {
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = fs::File::open(path)?;
+        let file_len = file.metadata()?.len();
+        let members = read_central_dir(&mut file, file_len)?;
+        Ok(Self {
+            file,
+            file_len,
+            members,
+        })
+    }
+
+    fn find(&self, member: &str) -> Option<&ZipMember> {
+        self.members.iter().find(|m| m.name == member)
+    }
+
+    /// Whether `dir` is the archive itself or a directory inside it
+    pub fn is_dir(&self, dir: &str) -> bool {
+        dir.is_empty()
+            || self
+                .members
+                .iter()
+                .any(|m| m.name.strip_prefix(dir).is_some_and(|s| s.starts_with('/')))
+    }
+
+    pub fn size(&self, member: &str) -> Option<u64> {
+        self.find(member).map(|m| m.size)
+    }
+
+    /// Entries directly inside `dir`, directories implied by their members' names included
+    pub fn list(&self, dir: &str) -> Vec<ArchiveEntry> {
+        let mut entries: BTreeMap<&str, ArchiveEntry> = BTreeMap::new();
+        for member in self.members.iter() {
+            let rest = if dir.is_empty() {
+                member.name.as_str()
+            } else {
+                match member
+                    .name
+                    .strip_prefix(dir)
+                    .and_then(|s| s.strip_prefix('/'))
+                {
+                    Some(rest) => rest,
+                    None => continue,
+                }
+            };
+            let entry = match rest.split_once('/') {
+                Some((name, _)) => ArchiveEntry {
+                    name: name.to_string(),
+                    is_dir: true,
+                    size: 0,
+                },
+                None => ArchiveEntry {
+                    name: rest.to_string(),
+                    is_dir: false,
+                    size: member.size,
+                },
+            };
+            if entry.name.is_empty() {
+                continue;
+            }
+            let key = &rest[..entry.name.len()];
+            entries.entry(key).or_insert(entry);
+        }
+        entries.into_values().collect()
+    }
+
+    /// Decompresses a member into memory
+    pub fn read(&mut self, member: &str) -> io::Result<Vec<u8>> {
+        let member = self
+            .find(member)
+            .cloned()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, member.to_string()))?;
+        if member.encrypted {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{} is encrypted", member.name),
+            ));
+        }
+
+        let mut header = [0; 30];
+        self.file.seek(SeekFrom::Start(member.header_offset))?;
+        self.file.read_exact(&mut header)?;
+        if u32_at(&header, 0) != LOCAL_FILE_HEADER {
+            return Err(invalid("Bad zip local file header"));
+        }
+        // the local header's name and extra field can differ from the central directory's
+        let skip = u16_at(&header, 26) as u64 + u16_at(&header, 28) as u64;
+        let data_end = (member.header_offset + 30 + skip).checked_add(member.compressed_size);
+        if data_end.is_none_or(|end| end > self.file_len) {
+            return Err(invalid("Zip member runs past the end of the archive"));
+        }
+        self.file.seek(SeekFrom::Current(skip as i64))?;
+
+        let mut data = vec![0; member.compressed_size as usize];
+        self.file.read_exact(&mut data)?;
+        match member.method {
+            METHOD_STORED => Ok(data),
+            METHOD_DEFLATE => {
+                miniz_oxide::inflate::decompress_to_vec_with_limit(&data, member.size as usize)
+                    .map_err(|err| invalid(&format!("Failed to inflate {}: {}", member.name, err)))
+            }
+            method => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                format!("{} uses unsupported compression {}", member.name, method),
+            )),
+        }
+    }
+}
+
+fn read_central_dir(file: &mut fs::File, file_len: u64) -> io::Result<Vec<ZipMember>> {
+    let search_len = file_len.min(MAX_END_SEARCH);
+    let mut tail = vec![0; search_len as usize];
+    file.seek(SeekFrom::Start(file_len - search_len))?;
+    file.read_exact(&mut tail)?;
+
+    let end = (0..tail.len().saturating_sub(21))
+        .rev()
+        .find(|i| u32_at(&tail, *i) == END_OF_CENTRAL_DIR)
+        .ok_or_else(|| invalid("Not a zip archive"))?;
+    let mut count = u16_at(&tail, end + 10) as u64;
+    let mut dir_size = u32_at(&tail, end + 12) as u64;
+    let mut dir_offset = u32_at(&tail, end + 16) as u64;
+
+    // archives over 4GB or with many files keep these in a zip64 record
+    if dir_offset == u32::MAX as u64 && end >= 20 && u32_at(&tail, end - 20) == ZIP64_END_LOCATOR {
+        let mut record = [0; 56];
+        file.seek(SeekFrom::Start(u64_at(&tail, end - 12)))?;
+        file.read_exact(&mut record)?;
+        if u32_at(&record, 0) != ZIP64_END_OF_CENTRAL_DIR {
+            return Err(invalid("Bad zip64 end of central directory"));
+        }
+        count = u64_at(&record, 32);
+        dir_size = u64_at(&record, 40);
+        dir_offset = u64_at(&record, 48);
+    }
+
+    if dir_offset
+        .checked_add(dir_size)
+        .is_none_or(|end| end > file_len)
+    {
+        return Err(invalid(
+            "Zip central directory runs past the end of the archive",
+        ));
+    }
+    let mut dir = vec![0; dir_size as usize];
+    file.seek(SeekFrom::Start(dir_offset))?;
+    file.read_exact(&mut dir)?;
+
+    // every member takes at least a 46 byte header, whatever the archive claims
+    let count = count.min(dir_size / 46);
+    let mut members = Vec::with_capacity(count as usize);
+    let mut i = 0;
+    while i + 46 <= dir.len() && u32_at(&dir, i) == CENTRAL_DIR_HEADER {
+        let flags = u16_at(&dir, i + 8);
+        let method = u16_at(&dir, i + 10);
+        let mut compressed_size = u32_at(&dir, i + 20) as u64;
+        let mut size = u32_at(&dir, i + 24) as u64;
+        let name_len = u16_at(&dir, i + 28) as usize;
+        let extra_len = u16_at(&dir, i + 30) as usize;
+        let comment_len = u16_at(&dir, i + 32) as usize;
+        let mut header_offset = u32_at(&dir, i + 42) as u64;
+
+        let name_start = i + 46;
+        let extra_start = name_start + name_len;
+        let next = extra_start + extra_len + comment_len;
+        if next > dir.len() {
+            return Err(invalid("Truncated zip central directory"));
+        }
+        let name = String::from_utf8_lossy(&dir[name_start..extra_start]).into_owned();
+
+        // sizes too large for the header are given in the zip64 extra field, in order
+        let mut extra = &dir[extra_start..extra_start + extra_len];
+        while extra.len() >= 4 {
+            let id = u16_at(extra, 0);
+            let len = (u16_at(extra, 2) as usize).min(extra.len() - 4);
+            if id == ZIP64_EXTRA_FIELD {
+                let mut field = &extra[4..4 + len];
+                for value in [&mut size, &mut compressed_size, &mut header_offset] {
+                    if *value == u32::MAX as u64 && field.len() >= 8 {
+                        *value = u64_at(field, 0);
+                        field = &field[8..];
+                    }
+                }
+            }
+            extra = &extra[4 + len..];
+        }
+
+        // directories are implied by the names of the files in them
+        if !name.ends_with('/') {
+            members.push(ZipMember {
+                name,
+                method,
+                encrypted: flags & 1 != 0,
+                compressed_size,
+                size,
+                header_offset,
+            });
+        }
+        i = next;
+    }
+    Ok(members)
+}
+
+fn is_zip_path(path: &Path) -> bool {
+    path.extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"))
+}
+
+/// Whether `path` is a zip archive, browsed as a directory
+pub fn is_archive(path: &Path) -> bool {
+    is_zip_path(path) && path.is_file()
+}
+
+/// Splits a path at the archive it goes into, the member is empty for the archive itself.
+/// None for paths that aren't in an archive
+pub fn split_archive_path(path: &Path) -> Option<(PathBuf, String)> {
+    let mut archive = PathBuf::new();
+    let mut components = path.components();
+    for component in components.by_ref() {
+        archive.push(component);
+        if matches!(component, Component::Normal(_)) && is_zip_path(&archive) {
+            break;
+        }
+    }
+    if !is_archive(&archive) {
+        return None;
+    }
+    let member: Vec<String> = components
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    Some((archive, member.join("/")))
+}
+
+/// Whether `path` is inside an archive, so it can't be found on the file system
+pub fn is_archive_member(path: &Path) -> bool {
+    split_archive_path(path).is_some_and(|(_, member)| !member.is_empty())
+}
+
+/// Entries of an archive, or of a directory inside one
+pub fn read_dir(path: &Path) -> io::Result<Vec<ArchiveEntry>> {
+    let (archive, dir) =
+        split_archive_path(path).ok_or_else(|| invalid("Not inside a zip archive"))?;
+    let archive = ZipArchive::open(&archive)?;
+    if !archive.is_dir(&dir) {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{:?} is not a directory", path),
+        ));
+    }
+    Ok(archive.list(&dir))
+}
+
+/// The entry at `path`, a member of an archive
+pub fn entry(path: &Path) -> io::Result<ArchiveEntry> {
+    let (archive, member) =
+        split_archive_path(path).ok_or_else(|| invalid("Not inside a zip archive"))?;
+    let archive = ZipArchive::open(&archive)?;
+    let name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    match archive.size(&member) {
+        Some(size) => Ok(ArchiveEntry {
+            name,
+            is_dir: false,
+            size,
+        }),
+        None if archive.is_dir(&member) => Ok(ArchiveEntry {
+            name,
+            is_dir: true,
+            size: 0,
+        }),
+        None => Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("{:?} not found", path),
+        )),
+    }
+}
+
+/// Decompresses the archive member at `path` into memory
+pub fn read(path: &Path) -> io::Result<Vec<u8>> {
+    let (archive, member) =
+        split_archive_path(path).ok_or_else(|| invalid("Not inside a zip archive"))?;
+    ZipArchive::open(&archive)?.read(&member)
+}
+
+/// Whether `path` is a directory, an archive or a directory inside one
+pub fn is_directory(path: &Path) -> bool {
+    match split_archive_path(path) {
+        Some(_) => entry(path).is_ok_and(|entry| entry.is_dir),
+        None => path.is_dir(),
+    }
+}
+
+/// Paths in a directory along with whether they are directories, archives counting as ones,
+/// for directories on the file system and in archives alike
+pub fn list_dir(path: &Path) -> io::Result<Vec<(PathBuf, bool)>> {
+    if split_archive_path(path).is_some() {
+        let entries = read_dir(path)?
+            .into_iter()
+            .map(|entry| (path.join(entry.name), entry.is_dir))
+            .collect();
+        return Ok(entries);
+    }
+    let entries = fs::read_dir(path)?
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            // follows symlinks, so linked music directories can be browsed
+            let is_dir = path.is_dir() || is_archive(&path);
+            (path, is_dir)
+        })
+        .collect();
+    Ok(entries)
+}
+
+/// Reads a file, whether it is in an archive or not
+pub fn read_file(path: &Path) -> io::Result<Vec<u8>> {
+    if is_archive_member(path) {
+        read(path)
+    } else {
+        fs::read(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use super::*;
+
+    fn write_zip(path: &Path, files: &[(&str, &[u8], bool)]) {
+        fs::File::create(path)
+            .unwrap()
+            .write_all(&zip_bytes(files))
+            .unwrap();
+    }
+
+    fn zip_bytes(files: &[(&str, &[u8], bool)]) -> Vec<u8> {
+        let mut data = Vec::new();
+        let mut central = Vec::new();
+        for (name, content, deflate) in files {
+            let (method, stored) = if *deflate {
+                (
+                    METHOD_DEFLATE,
+                    miniz_oxide::deflate::compress_to_vec(content, 6),
+                )
+            } else {
+                (METHOD_STORED, content.to_vec())
+            };
+            let offset = data.len() as u32;
+            let mut header = Vec::new();
+            header.extend(LOCAL_FILE_HEADER.to_le_bytes());
+            header.extend([20, 0, 0, 0]);
+            header.extend(method.to_le_bytes());
+            header.extend([0; 8]);
+            header.extend((stored.len() as u32).to_le_bytes());
+            header.extend((content.len() as u32).to_le_bytes());
+            header.extend((name.len() as u16).to_le_bytes());
+            header.extend([0, 0]);
+            data.extend(&header);
+            data.extend(name.as_bytes());
+            data.extend(&stored);
+
+            central.extend(CENTRAL_DIR_HEADER.to_le_bytes());
+            central.extend([20, 0, 20, 0, 0, 0]);
+            central.extend(method.to_le_bytes());
+            central.extend([0; 8]);
+            central.extend((stored.len() as u32).to_le_bytes());
+            central.extend((content.len() as u32).to_le_bytes());
+            central.extend((name.len() as u16).to_le_bytes());
+            central.extend([0; 12]);
+            central.extend(offset.to_le_bytes());
+            central.extend(name.as_bytes());
+        }
+        let dir_offset = data.len() as u32;
+        data.extend(&central);
+        data.extend(END_OF_CENTRAL_DIR.to_le_bytes());
+        data.extend([0; 4]);
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((files.len() as u16).to_le_bytes());
+        data.extend((central.len() as u32).to_le_bytes());
+        data.extend(dir_offset.to_le_bytes());
+        data.extend([0, 0]);
+        data
+    }
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dizi-{}-{}", name, std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn oversized_central_dir() {
+        let dir = temp_dir("archive-oversized");
+        let zip = dir.join("bad.zip");
+        let mut data = zip_bytes(&[("song.flac", b"song", false)]);
+        // the central directory's size in the end of central directory record
+        let end = data.len() - 22;
+        data[end + 12..end + 16].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        fs::write(&zip, &data).unwrap();
+        let err = ZipArchive::open(&zip).err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // truncated right after the local file header
+        let data = zip_bytes(&[("song.flac", b"song", false)]);
+        fs::write(&zip, &data[..40]).unwrap();
+        assert!(ZipArchive::open(&zip).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn oversized_member() {
+        let dir = temp_dir("archive-member");
+        let zip = dir.join("bad.zip");
+        let mut data = zip_bytes(&[("song.flac", b"song", false)]);
+        let end = data.len() - 22;
+        let central = u32_at(&data, end + 16) as usize;
+        // the member's compressed size in its central directory header
+        data[central + 20..central + 24].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        fs::write(&zip, &data).unwrap();
+        let err = read(&zip.join("song.flac")).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn zip64_oversized_count() {
+        let dir = temp_dir("archive-zip64");
+        let zip = dir.join("many.zip");
+        let data = zip_bytes(&[("song.flac", b"song", false)]);
+        let end = data.len() - 22;
+        let dir_size = u32_at(&data, end + 12) as u64;
+        let dir_offset = u32_at(&data, end + 16) as u64;
+
+        let mut data = data[..end].to_vec();
+        let record_offset = data.len() as u64;
+        data.extend(ZIP64_END_OF_CENTRAL_DIR.to_le_bytes());
+        data.extend(44u64.to_le_bytes());
+        data.extend([0; 12]);
+        data.extend(u64::MAX.to_le_bytes());
+        data.extend(u64::MAX.to_le_bytes());
+        data.extend(dir_size.to_le_bytes());
+        data.extend(dir_offset.to_le_bytes());
+        data.extend(ZIP64_END_LOCATOR.to_le_bytes());
+        data.extend([0; 4]);
+        data.extend(record_offset.to_le_bytes());
+        data.extend(1u32.to_le_bytes());
+        data.extend(END_OF_CENTRAL_DIR.to_le_bytes());
+        data.extend([0; 4]);
+        data.extend([0xFF; 4]);
+        data.extend([0xFF; 4]);
+        data.extend(u32::MAX.to_le_bytes());
+        data.extend([0, 0]);
+        fs::write(&zip, &data).unwrap();
+
+        let names: Vec<String> = read_dir(&zip)
+            .unwrap()
+            .into_iter()
+            .map(|e| e.name)
+            .collect();
+        assert_eq!(names, vec!["song.flac".to_string()]);
+        assert_eq!(read(&zip.join("song.flac")).unwrap(), b"song");
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn browse_and_read_members() {
+        let dir = std::env::temp_dir().join(format!("dizi-archive-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let zip = dir.join("album.zip");
+        write_zip(
+            &zip,
+            &[
+                ("cover.jpg", b"jpeg", false),
+                (
+                    "disc 1/01 song.flac",
+                    b"the first song, the first song",
+                    true,
+                ),
+                ("disc 1/02 song.flac", b"second", false),
+            ],
+        );
+
+        let names: Vec<(String, bool)> = read_dir(&zip)
+            .unwrap()
+            .into_iter()
+            .map(|e| (e.name, e.is_dir))
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                ("cover.jpg".to_string(), false),
+                ("disc 1".to_string(), true)
+            ]
+        );
+        assert_eq!(read_dir(&zip.join("disc 1")).unwrap().len(), 2);
+        assert!(entry(&zip.join("disc 1")).unwrap().is_dir);
+
+        let song = zip.join("disc 1").join("01 song.flac");
+        assert!(is_archive_member(&song));
+        assert_eq!(read(&song).unwrap(), b"the first song, the first song");
+        assert_eq!(read(&zip.join("disc 1/02 song.flac")).unwrap(), b"second");
+        assert!(read(&zip.join("missing.flac")).is_err());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn paths_outside_archives() {
+        assert_eq!(split_archive_path(Path::new("/not/an/archive.flac")), None);
+        assert!(!is_archive_member(Path::new("/missing.zip/song.flac")));
+    }
+}
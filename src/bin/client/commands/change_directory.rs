@@ -1,6 +1,7 @@
 use std::io;
 use std::path;
 
+use dizi::archive;
 use dizi::error::DiziResult;
 
 use crate::commands::reload;
@@ -9,7 +10,10 @@ use crate::context::AppContext;
 use crate::history::DirectoryHistory;
 
 pub fn cd(path: &path::Path, context: &mut AppContext) -> io::Result<()> {
-    std::env::set_current_dir(path)?;
+    // archives are browsed like directories, but can't be the working directory
+    if archive::split_archive_path(path).is_none() {
+        std::env::set_current_dir(path)?;
+    }
     context.tab_context_mut().curr_tab_mut().set_cwd(path);
     Ok(())
 }
@@ -47,7 +51,9 @@ pub fn parent_directory(context: &mut AppContext) -> DiziResult {
         .parent()
         .map(|p| p.to_path_buf())
     {
-        std::env::set_current_dir(&parent)?;
+        if archive::split_archive_path(&parent).is_none() {
+            std::env::set_current_dir(&parent)?;
+        }
         context
             .tab_context_mut()
             .curr_tab_mut()
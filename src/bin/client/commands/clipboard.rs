@@ -0,0 +1,39 @@
+use std::path::PathBuf;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+use crate::context::AppContext;
+use crate::util::clipboard;
+use crate::MESSAGES_T;
+
+use super::cursor_move;
+
+/// Copies the path of the entry at the cursor in the current view
+pub fn copy_path(context: &mut AppContext) -> DiziResult {
+    match cursor_move::cursor_path(context) {
+        Some(path) => copy(context, path),
+        None => Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "No entry to copy".to_string(),
+        )),
+    }
+}
+
+pub fn copy_playing_path(context: &mut AppContext) -> DiziResult {
+    match context.server_state_ref().player.info.song.as_ref() {
+        Some(song) => copy(context, song.file_path().to_path_buf()),
+        None => Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            MESSAGES_T.get("message.nothing_playing").to_string(),
+        )),
+    }
+}
+
+fn copy(context: &mut AppContext, path: PathBuf) -> DiziResult {
+    let text = path.to_string_lossy();
+    clipboard::copy(&text)?;
+    context
+        .message_queue_mut()
+        .push_success(format!("Copied {}", text));
+    Ok(())
+}
@@ -2,12 +2,16 @@ use std::str::FromStr;
 
 use dizi::error::DiziResult;
 
+use crate::config::option::WidgetType;
 use crate::config::AppKeyMapping;
 use crate::context::AppContext;
 use crate::key_command::{AppExecute, Command};
 use crate::ui::views::TuiTextField;
 use crate::ui::AppBackend;
 
+use super::cursor_move;
+use super::search_string;
+
 pub fn read_and_execute(
     context: &mut AppContext,
     backend: &mut AppBackend,
@@ -16,17 +20,35 @@ pub fn read_and_execute(
     suffix: &str,
 ) -> DiziResult {
     context.flush_event();
+
+    // remember where we were, so an incremental search can be undone
+    let start_index = cursor_move::cursor_index(context, WidgetType::FileBrowser);
+    let start_search = context.get_search_context().cloned();
+
     let user_input: Option<String> = TuiTextField::default()
         .prompt(":")
         .prefix(prefix)
         .suffix(suffix)
+        .on_change(Box::new(move |context: &mut AppContext, input: &str| {
+            search_string::search_incremental(context, start_index, input)
+        }))
         .get_input(backend, context);
 
+    // the final command runs from the original cursor position
+    if let Some(index) = start_index {
+        cursor_move::cursor_move_for_widget(context, WidgetType::FileBrowser, index);
+    }
+
     if let Some(s) = user_input {
         let trimmed = s.trim_start();
         let command = Command::from_str(trimmed)?;
+        context.macro_context_mut().record(&command);
         command.execute(context, backend, keymap_t)
     } else {
+        match start_search {
+            Some(pattern) => context.set_search_context(pattern),
+            None => context.clear_search_context(),
+        }
         Ok(())
     }
 }
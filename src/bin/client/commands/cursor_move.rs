@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use dizi::error::DiziResult;
 
 use crate::config::option::WidgetType;
@@ -21,6 +23,8 @@ pub fn cursor_move_for_widget(context: &mut AppContext, widget: WidgetType, new_
     match widget {
         WidgetType::FileBrowser => set_curr_dirlist_index(context, new_index),
         WidgetType::Playlist => set_playlist_index(context, new_index),
+        WidgetType::Library => set_library_index(context, new_index),
+        WidgetType::MessageLog => set_message_log_index(context, new_index),
         _ => {}
     }
 }
@@ -29,10 +33,35 @@ pub fn cursor_index(context: &mut AppContext, widget: WidgetType) -> Option<usiz
     match widget {
         WidgetType::FileBrowser => get_curr_dirlist_index(context),
         WidgetType::Playlist => get_playlist_index(context),
+        WidgetType::Library => context.library_context_ref().get_index(),
+        WidgetType::MessageLog => context.message_queue_ref().get_history_index(),
         _ => None,
     }
 }
 
+/// Path of the entry at the cursor in the current view
+pub fn cursor_path(context: &AppContext) -> Option<PathBuf> {
+    match context.get_view_widget() {
+        WidgetType::Playlist => {
+            let playlist = context.server_state_ref().playlist_ref();
+            playlist
+                .get_cursor_index()
+                .and_then(|index| playlist.list_ref().get(index))
+                .map(|entry| entry.file_path().to_path_buf())
+        }
+        WidgetType::Library => context
+            .library_context_ref()
+            .curr_song_ref()
+            .map(|path| path.to_path_buf()),
+        _ => context
+            .tab_context_ref()
+            .curr_tab_ref()
+            .curr_list_ref()
+            .and_then(|s| s.curr_entry_ref())
+            .map(|entry| entry.file_path_buf()),
+    }
+}
+
 fn get_curr_dirlist_index(context: &AppContext) -> Option<usize> {
     context
         .tab_context_ref()
@@ -70,32 +99,54 @@ fn set_curr_dirlist_index(context: &mut AppContext, new_index: usize) {
 }
 
 pub fn get_playlist_index(context: &AppContext) -> Option<usize> {
-    context
-        .server_state_ref()
-        .player
-        .playlist
-        .get_cursor_index()
+    context.server_state_ref().playlist_ref().get_cursor_index()
 }
 fn get_playlist_len(context: &AppContext) -> usize {
-    context.server_state_ref().player.playlist.len()
+    context.server_state_ref().playlist_ref().len()
 }
 pub fn set_playlist_index(context: &mut AppContext, new_index: usize) {
-    let playlist_len = context.server_state_ref().player.playlist.len();
+    let playlist_len = context.server_state_ref().playlist_ref().len();
     if playlist_len <= new_index {
         context
             .server_state_mut()
-            .player
-            .playlist
+            .playlist_mut()
             .set_cursor_index(Some(safe_subtract(playlist_len, 1)));
     } else {
         context
             .server_state_mut()
-            .player
-            .playlist
+            .playlist_mut()
             .set_cursor_index(Some(new_index));
     }
 }
 
+pub fn set_library_index(context: &mut AppContext, new_index: usize) {
+    let library = context.library_context_mut();
+    if library.is_empty() {
+        return;
+    }
+    let library_len = library.len();
+    if library_len <= new_index {
+        library.set_index(Some(library_len - 1));
+    } else {
+        library.set_index(Some(new_index));
+    }
+}
+
+fn set_message_log_index(context: &mut AppContext, new_index: usize) {
+    let log_len = context.message_queue_ref().history_ref().len();
+    if log_len == 0 {
+        return;
+    }
+    let new_index = if log_len <= new_index {
+        log_len - 1
+    } else {
+        new_index
+    };
+    context
+        .message_queue_mut()
+        .set_history_index(Some(new_index));
+}
+
 pub fn up(context: &mut AppContext, u: usize) -> DiziResult {
     let widget = context.get_view_widget();
     let index = cursor_index(context, widget);
@@ -133,15 +184,13 @@ pub fn home(context: &mut AppContext) -> DiziResult {
 
 pub fn end(context: &mut AppContext) -> DiziResult {
     let widget = context.get_view_widget();
-    let index = match widget {
-        WidgetType::FileBrowser => get_curr_dirlist_index(context),
-        WidgetType::Playlist => get_playlist_index(context),
-        _ => None,
-    };
+    let index = cursor_index(context, widget);
 
     let len = match widget {
         WidgetType::FileBrowser => get_curr_dirlist_len(context),
         WidgetType::Playlist => Some(get_playlist_len(context)),
+        WidgetType::Library => Some(context.library_context_ref().len()),
+        WidgetType::MessageLog => Some(context.message_queue_ref().history_ref().len()),
         _ => None,
     };
 
@@ -0,0 +1,143 @@
+use std::fs;
+use std::path::Path;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+use crate::config::option::WidgetType;
+use crate::context::AppContext;
+use crate::history::DirectoryHistory;
+use crate::ui::views::TuiTextField;
+use crate::ui::widgets::TuiPrompt;
+use crate::ui::AppBackend;
+use crate::util::trash;
+use crate::MESSAGES_T;
+
+use super::{reload, selection};
+
+pub fn rename_file(context: &mut AppContext, backend: &mut AppBackend) -> DiziResult {
+    if context.get_view_widget() != WidgetType::FileBrowser {
+        return Ok(());
+    }
+    let path = match context
+        .tab_context_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .and_then(|s| s.curr_entry_ref())
+    {
+        Some(entry) => entry.file_path_buf(),
+        None => return Ok(()),
+    };
+    let file_name = path
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+
+    context.flush_event();
+    let user_input = TuiTextField::default()
+        .prompt(MESSAGES_T.get("prompt.rename"))
+        .prefix(file_name.as_str())
+        .get_input(backend, context);
+
+    let new_name = match user_input {
+        Some(s) if !s.is_empty() && s != file_name => s,
+        _ => return Ok(()),
+    };
+    if new_name.contains('/') {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            MESSAGES_T.get("message.name_has_slash").to_string(),
+        ));
+    }
+    let new_path = path.with_file_name(&new_name);
+    if new_path.exists() {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            MESSAGES_T.format("message.name_exists", &[("name", &new_name)]),
+        ));
+    }
+    fs::rename(&path, &new_path)?;
+
+    refresh(context)?;
+    context.message_queue_mut().push_success(MESSAGES_T.format(
+        "message.renamed",
+        &[("from", &file_name), ("to", &new_name)],
+    ));
+    Ok(())
+}
+
+/// Moves the selected entries, or the entry at the cursor, to the trash
+pub fn delete_files(context: &mut AppContext, backend: &mut AppBackend) -> DiziResult {
+    if context.get_view_widget() != WidgetType::FileBrowser {
+        return Ok(());
+    }
+    let mut paths = selection::selected_paths(context);
+    if paths.is_empty() {
+        if let Some(entry) = context
+            .tab_context_ref()
+            .curr_tab_ref()
+            .curr_list_ref()
+            .and_then(|s| s.curr_entry_ref())
+        {
+            paths.push(entry.file_path_buf());
+        }
+    }
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    if context
+        .config_ref()
+        .client_ref()
+        .confirm_options_ref()
+        .delete_files
+    {
+        let prompt_str = match paths.as_slice() {
+            [path] => MESSAGES_T.format("prompt.trash_file", &[("name", &display_name(path))]),
+            paths => MESSAGES_T.format("prompt.trash_files", &[("count", &paths.len())]),
+        };
+        if !TuiPrompt::new(&prompt_str).confirm(backend, context) {
+            return Ok(());
+        }
+    }
+
+    let mut res = Ok(());
+    let mut trashed = 0;
+    for path in paths.iter() {
+        match trash::trash(path) {
+            Ok(_) => trashed += 1,
+            Err(e) => {
+                res = Err(DiziError::new(
+                    DiziErrorKind::IoError(e.kind()),
+                    MESSAGES_T.format(
+                        "message.trash_failed",
+                        &[("name", &display_name(path)), ("error", &e)],
+                    ),
+                ));
+                break;
+            }
+        }
+    }
+
+    refresh(context)?;
+    if trashed > 0 {
+        context
+            .message_queue_mut()
+            .push_success(MESSAGES_T.format("message.trashed", &[("count", &trashed)]));
+    }
+    res
+}
+
+fn display_name(path: &Path) -> String {
+    path.file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_else(|| path.to_string_lossy().to_string())
+}
+
+fn refresh(context: &mut AppContext) -> DiziResult {
+    let cwd = context.tab_context_ref().curr_tab_ref().cwd().to_path_buf();
+    for tab in context.tab_context_mut().iter_mut() {
+        tab.history_mut().depreciate_entry(cwd.as_path());
+    }
+    reload::soft_reload(context.tab_context_ref().index, context)?;
+    Ok(())
+}
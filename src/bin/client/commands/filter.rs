@@ -0,0 +1,24 @@
+use dizi::error::DiziResult;
+
+use crate::context::AppContext;
+use crate::history::DirectoryHistory;
+
+use super::reload;
+
+/// Only lists the entries of the current directory whose name contains `pattern`,
+/// an empty pattern lists every entry again
+pub fn filter(context: &mut AppContext, pattern: &str) -> DiziResult {
+    let cwd = context.tab_context_ref().curr_tab_ref().cwd().to_path_buf();
+    let pattern = (!pattern.is_empty()).then(|| pattern.to_lowercase());
+
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_dir_filter(cwd.clone(), pattern);
+
+    for tab in context.tab_context_mut().iter_mut() {
+        tab.history_mut().depreciate_entry(cwd.as_path());
+    }
+    reload::soft_reload(context.tab_context_ref().index, context)?;
+    Ok(())
+}
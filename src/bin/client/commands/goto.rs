@@ -2,7 +2,7 @@ use dizi::error::DiziResult;
 use dizi::playlist::PlaylistType;
 
 use crate::commands::change_directory;
-use crate::commands::cursor_move::set_playlist_index;
+use crate::commands::cursor_move::{self, set_library_index, set_playlist_index};
 use crate::commands::search_string;
 use crate::config::option::WidgetType;
 use crate::context::AppContext;
@@ -10,7 +10,7 @@ use crate::context::AppContext;
 fn _directory_goto_playing(context: &mut AppContext) -> DiziResult {
     let player_state = &context.server_state_ref().player;
 
-    if let Some(song) = player_state.song.clone() {
+    if let Some(song) = player_state.info.song.clone() {
         let file_path = song.file_path();
         if let Some(parent) = file_path.parent() {
             change_directory::change_directory(context, parent)?;
@@ -22,11 +22,22 @@ fn _directory_goto_playing(context: &mut AppContext) -> DiziResult {
 }
 
 fn _playlist_goto_playing(context: &mut AppContext) -> DiziResult {
+    if context.server_state_ref().shows_directory_playlist() {
+        let playing_index = context
+            .server_state_ref()
+            .player
+            .directory_playlist
+            .get_playing_index();
+        if let Some(index) = playing_index {
+            set_playlist_index(context, index);
+        }
+        return Ok(());
+    }
     let player_state = &context.server_state_ref().player;
 
-    match player_state.playlist_status {
+    match player_state.info.playlist_status {
         PlaylistType::DirectoryListing => {
-            if let Some(song) = player_state.song.clone() {
+            if let Some(song) = player_state.info.song.clone() {
                 let file_path = song.file_path();
                 if let Some((index, _)) = player_state
                     .playlist
@@ -48,12 +59,87 @@ fn _playlist_goto_playing(context: &mut AppContext) -> DiziResult {
     Ok(())
 }
 
+fn _library_goto_playing(context: &mut AppContext) -> DiziResult {
+    if let Some(song) = context.server_state_ref().player.info.song.as_ref() {
+        let index = context
+            .library_context_ref()
+            .songs_ref()
+            .iter()
+            .position(|p| p.as_path() == song.file_path());
+        if let Some(index) = index {
+            set_library_index(context, index);
+        }
+    }
+    Ok(())
+}
+
 pub fn goto_playing(context: &mut AppContext) -> DiziResult {
+    if context.server_state_ref().player.info.song.is_none() {
+        context
+            .message_queue_mut()
+            .push_info("No song is playing".to_string());
+        return Ok(());
+    }
+
     let widget = context.get_view_widget();
     match widget {
-        WidgetType::FileBrowser => _directory_goto_playing(context)?,
         WidgetType::Playlist => _playlist_goto_playing(context)?,
-        _ => {}
+        WidgetType::Library => _library_goto_playing(context)?,
+        // views without songs fall back to the file browser
+        _ => {
+            context.set_view_widget(WidgetType::FileBrowser);
+            _directory_goto_playing(context)?;
+        }
+    }
+    Ok(())
+}
+
+/// Moves the playlist cursor to the 1-based `index` shown in the playlist pane
+/// and focuses the pane
+pub fn goto_playlist_index(context: &mut AppContext, index: usize) -> DiziResult {
+    if context.server_state_ref().player.playlist.is_empty() {
+        return Ok(());
+    }
+    set_playlist_index(context, index.saturating_sub(1));
+    context.set_view_widget(WidgetType::Playlist);
+    Ok(())
+}
+
+/// Keeps the cursors of the file browser, playlist and library on the playing song.
+/// Unlike `goto_playing`, the file browser never changes directory
+pub fn follow_playing(context: &mut AppContext) -> DiziResult {
+    let file_path = match context.server_state_ref().player.info.song.as_ref() {
+        Some(song) => song.file_path().to_path_buf(),
+        None => return Ok(()),
+    };
+
+    let curr_tab = context.tab_context_ref().curr_tab_ref();
+    if file_path.parent() == Some(curr_tab.cwd()) {
+        let index = curr_tab
+            .curr_list_ref()
+            .and_then(|list| list.iter().position(|e| e.file_path() == file_path));
+        if let Some(index) = index {
+            cursor_move::cursor_move_for_widget(context, WidgetType::FileBrowser, index);
+        }
+    }
+    _playlist_goto_playing(context)?;
+    _library_goto_playing(context)?;
+    Ok(())
+}
+
+pub fn toggle_follow(context: &mut AppContext) -> DiziResult {
+    let opposite = !context.config_ref().display_options_ref().follow_playing();
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_follow_playing(opposite);
+
+    let status = if opposite { "ON" } else { "OFF" };
+    context
+        .message_queue_mut()
+        .push_success(format!("Follow playing {}", status));
+    if opposite {
+        follow_playing(context)?;
     }
     Ok(())
 }
@@ -0,0 +1,46 @@
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+use crate::config::AppKeyMapping;
+use crate::context::AppContext;
+use crate::key_command::AppExecute;
+use crate::ui::AppBackend;
+
+/// Starts recording into `register`, or stops the recording in progress
+pub fn macro_record(context: &mut AppContext, register: char) -> DiziResult {
+    match context.macro_context_mut().stop_recording() {
+        Some((register, len)) => {
+            context
+                .message_queue_mut()
+                .push_success(format!("Recorded {} commands to @{}", len, register));
+        }
+        None => {
+            context.macro_context_mut().start_recording(register);
+        }
+    }
+    Ok(())
+}
+
+/// Replays the commands of `register` `times` times, stopping at the first error
+pub fn macro_play(
+    context: &mut AppContext,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+    register: char,
+    times: usize,
+) -> DiziResult {
+    let commands = match context.macro_context_ref().register_ref(register) {
+        Some(commands) if !commands.is_empty() => commands.to_vec(),
+        _ => {
+            return Err(DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                format!("Register @{} is empty", register),
+            ))
+        }
+    };
+    for _ in 0..times {
+        for command in commands.iter() {
+            command.execute(context, backend, keymap_t)?;
+        }
+    }
+    Ok(())
+}
@@ -1,14 +1,24 @@
 pub mod change_directory;
+pub mod clipboard;
 pub mod command_line;
 pub mod cursor_move;
+pub mod file_operations;
+pub mod filter;
 pub mod goto;
+pub mod macros;
 pub mod open_file;
+pub mod playlist;
 pub mod quit;
+pub mod rating;
 pub mod reload;
+pub mod scroll;
 pub mod search;
 pub mod search_glob;
+pub mod search_regex;
 pub mod search_skim;
 pub mod search_string;
 pub mod selection;
+pub mod session;
 pub mod show_hidden;
 pub mod sort;
+pub mod view;
@@ -1,4 +1,5 @@
 use dizi::error::DiziResult;
+use dizi::playlist_format::PlaylistFormat;
 use dizi::request::client::ClientRequest;
 
 use crate::config::option::WidgetType;
@@ -13,6 +14,7 @@ pub fn open(context: &mut AppContext) -> DiziResult {
     match widget {
         WidgetType::FileBrowser => file_browser_open(context)?,
         WidgetType::Playlist => playlist_open(context)?,
+        WidgetType::Library => library_open(context)?,
         _ => {}
     }
     Ok(())
@@ -25,33 +27,23 @@ pub fn file_browser_open(context: &mut AppContext) -> DiziResult {
         .curr_list_ref()
         .and_then(|s| s.curr_entry_ref())
     {
-        if entry.file_path().is_dir() {
+        // archives are opened like directories
+        if entry.metadata.is_dir() {
             let path = entry.file_path().to_path_buf();
             change_directory::cd(path.as_path(), context)?;
         } else {
-            match entry.file_path().extension() {
-                Some(s) => {
-                    let s = s.to_string_lossy();
-                    if s.as_ref().starts_with("m3u") {
-                        let cwd = context.tab_context_ref().curr_tab_ref().cwd().to_path_buf();
-                        let request = ClientRequest::PlaylistOpen {
-                            cwd: Some(cwd),
-                            path: Some(entry.file_path().to_path_buf()),
-                        };
-                        send_client_request(context, &request)?;
-                    } else {
-                        let request = ClientRequest::PlayerFilePlay {
-                            path: Some(entry.file_path().to_path_buf()),
-                        };
-                        send_client_request(context, &request)?;
-                    }
-                }
-                None => {
-                    let request = ClientRequest::PlayerFilePlay {
-                        path: Some(entry.file_path().to_path_buf()),
-                    };
-                    send_client_request(context, &request)?;
-                }
+            if PlaylistFormat::from_path(entry.file_path()).is_some() {
+                let cwd = context.tab_context_ref().curr_tab_ref().cwd().to_path_buf();
+                let request = ClientRequest::PlaylistOpen {
+                    cwd: Some(cwd),
+                    path: Some(entry.file_path().to_path_buf()),
+                };
+                send_client_request(context, &request)?;
+            } else {
+                let request = ClientRequest::PlayerFilePlay {
+                    path: Some(entry.file_path().to_path_buf()),
+                };
+                send_client_request(context, &request)?;
             }
         }
     }
@@ -70,3 +62,13 @@ pub fn playlist_open(context: &mut AppContext) -> DiziResult {
     }
     Ok(())
 }
+
+pub fn library_open(context: &mut AppContext) -> DiziResult {
+    if let Some(path) = context.library_context_ref().curr_song_ref() {
+        let request = ClientRequest::PlayerFilePlay {
+            path: Some(path.to_path_buf()),
+        };
+        send_client_request(context, &request)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+use dizi::archive;
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+
+use crate::context::AppContext;
+use crate::util::audio::is_audio_file;
+use crate::util::request::send_client_request;
+
+/// Appends the songs of the current directory, sorted by name, in one request.
+/// Unlike `add_recursive`, subdirectories are left out
+pub fn add_cwd(context: &mut AppContext) -> DiziResult {
+    let cwd = context.tab_context_ref().curr_tab_ref().cwd().to_path_buf();
+    let mut paths: Vec<PathBuf> = archive::list_dir(&cwd)?
+        .into_iter()
+        .filter(|(path, is_dir)| !is_dir && is_audio_file(path))
+        .map(|(path, _)| path)
+        .collect();
+    if paths.is_empty() {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("No songs in {:?}", cwd),
+        ));
+    }
+    alphanumeric_sort::sort_path_slice(&mut paths);
+
+    let request = ClientRequest::PlaylistAppendBatch { paths, next: false };
+    send_client_request(context, &request)
+}
+
+/// Asks the server to append every song under the directory at the cursor
+pub fn add_recursive(context: &mut AppContext) -> DiziResult {
+    let path = context
+        .tab_context_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .and_then(|s| s.curr_entry_ref())
+        .filter(|entry| entry.metadata.is_dir())
+        .map(|entry| entry.file_path_buf())
+        .ok_or_else(|| {
+            DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                "Cursor is not on a directory".to_string(),
+            )
+        })?;
+
+    let request = ClientRequest::PlaylistAppendRecursive {
+        path: Some(path.clone()),
+    };
+    send_client_request(context, &request)?;
+    context.server_state_mut().add_scan(path);
+    Ok(())
+}
+
+/// Asks the server to write the playlist to `path`, relative to the current directory
+pub fn save(context: &mut AppContext, path: &Path) -> DiziResult {
+    let path = context.tab_context_ref().curr_tab_ref().cwd().join(path);
+    send_client_request(context, &ClientRequest::playlist_save(path))
+}
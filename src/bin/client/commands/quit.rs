@@ -2,14 +2,26 @@ use dizi::error::DiziResult;
 use dizi::request::client::ClientRequest;
 
 use crate::context::{AppContext, QuitType};
+use crate::ui::widgets::TuiPrompt;
+use crate::ui::AppBackend;
 use crate::util::request::send_client_request;
+use crate::MESSAGES_T;
 
 pub fn close(context: &mut AppContext) -> DiziResult {
     context.quit = QuitType::Normal;
     Ok(())
 }
 
-pub fn server_quit(context: &mut AppContext) -> DiziResult {
+pub fn server_quit(context: &mut AppContext, backend: &mut AppBackend) -> DiziResult {
+    if context
+        .config_ref()
+        .client_ref()
+        .confirm_options_ref()
+        .server_quit
+        && !TuiPrompt::new(MESSAGES_T.get("prompt.server_quit")).confirm(backend, context)
+    {
+        return Ok(());
+    }
     let request = ClientRequest::ServerQuit;
     let _ = send_client_request(context, &request);
     context.quit = QuitType::Server;
@@ -0,0 +1,54 @@
+use std::path::PathBuf;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+
+use crate::config::option::WidgetType;
+use crate::context::AppContext;
+use crate::util::request::send_client_request;
+use crate::MESSAGES_T;
+
+use super::{cursor_move, selection};
+
+/// Rates the selected songs, or the entry at the cursor when none are selected
+pub fn rate(context: &mut AppContext, rating: u8) -> DiziResult {
+    let mut paths = if context.get_view_widget() == WidgetType::FileBrowser {
+        selection::selected_paths(context)
+    } else {
+        Vec::new()
+    };
+    if paths.is_empty() {
+        paths.extend(cursor_move::cursor_path(context));
+    }
+    if paths.is_empty() {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "No entry to rate".to_string(),
+        ));
+    }
+    for path in paths {
+        send_rating(context, path, rating)?;
+    }
+    Ok(())
+}
+
+pub fn rate_playing(context: &mut AppContext, rating: u8) -> DiziResult {
+    match context.server_state_ref().player.info.song.as_ref() {
+        Some(song) => {
+            let path = song.file_path().to_path_buf();
+            send_rating(context, path, rating)
+        }
+        None => Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            MESSAGES_T.get("message.nothing_playing").to_string(),
+        )),
+    }
+}
+
+fn send_rating(context: &mut AppContext, path: PathBuf, rating: u8) -> DiziResult {
+    let request = ClientRequest::LibraryRate {
+        path: Some(path),
+        rating,
+    };
+    send_client_request(context, &request)
+}
@@ -0,0 +1,45 @@
+use dizi::error::DiziResult;
+use unicode_width::UnicodeWidthStr;
+
+use crate::context::{AppContext, NameScroll};
+
+use super::cursor_move;
+
+/// Shifts the name of the entry at the cursor to the left, revealing more of its end
+pub fn scroll_name_right(context: &mut AppContext, columns: usize) -> DiziResult {
+    if let Some(path) = cursor_move::cursor_path(context) {
+        let offset = context
+            .ui_context_ref()
+            .name_scroll
+            .as_ref()
+            .map(|scroll| scroll.offset_for(&path))
+            .unwrap_or(0);
+        // keep at least the last character of the name visible
+        let max_offset = path
+            .file_name()
+            .map(|name| name.to_string_lossy().width())
+            .unwrap_or(0)
+            .saturating_sub(1);
+        context.ui_context_mut().name_scroll = Some(NameScroll {
+            path,
+            offset: (offset + columns).min(max_offset),
+        });
+    }
+    Ok(())
+}
+
+pub fn scroll_name_left(context: &mut AppContext, columns: usize) -> DiziResult {
+    if let Some(path) = cursor_move::cursor_path(context) {
+        let offset = context
+            .ui_context_ref()
+            .name_scroll
+            .as_ref()
+            .map(|scroll| scroll.offset_for(&path))
+            .unwrap_or(0);
+        context.ui_context_mut().name_scroll = Some(NameScroll {
+            path,
+            offset: offset.saturating_sub(columns),
+        });
+    }
+    Ok(())
+}
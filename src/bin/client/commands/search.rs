@@ -4,8 +4,46 @@ use crate::context::AppContext;
 use crate::util::search::SearchPattern;
 
 use super::cursor_move;
+use crate::tab::JoshutoTab;
+
 use super::search_glob;
-use super::search_string;
+
+pub fn search_pattern_fwd(curr_tab: &JoshutoTab, pattern: &SearchPattern) -> Option<usize> {
+    let offset = curr_tab.curr_list_ref()?.get_index()? + 1;
+    search_pattern_from(curr_tab, pattern, offset)
+}
+
+/// Index of the first entry matching `pattern`, starting at `offset` and wrapping around
+pub fn search_pattern_from(
+    curr_tab: &JoshutoTab,
+    pattern: &SearchPattern,
+    offset: usize,
+) -> Option<usize> {
+    let curr_list = curr_tab.curr_list_ref()?;
+
+    let contents_len = curr_list.contents.len();
+    for i in 0..contents_len {
+        let file_name = curr_list.contents[(offset + i) % contents_len].file_name();
+        if pattern.is_match(file_name) {
+            return Some((offset + i) % contents_len);
+        }
+    }
+    None
+}
+
+pub fn search_pattern_rev(curr_tab: &JoshutoTab, pattern: &SearchPattern) -> Option<usize> {
+    let curr_list = curr_tab.curr_list_ref()?;
+
+    let offset = curr_list.get_index()?;
+    let contents_len = curr_list.contents.len();
+    for i in (0..contents_len).rev() {
+        let file_name = curr_list.contents[(offset + i) % contents_len].file_name();
+        if pattern.is_match(file_name) {
+            return Some((offset + i) % contents_len);
+        }
+    }
+    None
+}
 
 pub fn search_next(context: &mut AppContext) -> DiziResult {
     if let Some(search_context) = context.get_search_context() {
@@ -13,9 +51,7 @@ pub fn search_next(context: &mut AppContext) -> DiziResult {
             SearchPattern::Glob(s) => {
                 search_glob::search_glob_fwd(context.tab_context_ref().curr_tab_ref(), s)
             }
-            SearchPattern::String(s) => {
-                search_string::search_string_fwd(context.tab_context_ref().curr_tab_ref(), s)
-            }
+            pattern => search_pattern_fwd(context.tab_context_ref().curr_tab_ref(), pattern),
         };
         if let Some(index) = index {
             cursor_move::cursor_move(context, index);
@@ -30,9 +66,7 @@ pub fn search_prev(context: &mut AppContext) -> DiziResult {
             SearchPattern::Glob(s) => {
                 search_glob::search_glob_rev(context.tab_context_ref().curr_tab_ref(), s)
             }
-            SearchPattern::String(s) => {
-                search_string::search_string_rev(context.tab_context_ref().curr_tab_ref(), s)
-            }
+            pattern => search_pattern_rev(context.tab_context_ref().curr_tab_ref(), pattern),
         };
         if let Some(index) = index {
             cursor_move::cursor_move(context, index);
@@ -0,0 +1,30 @@
+use regex::RegexBuilder;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+use crate::context::AppContext;
+use crate::util::search::SearchPattern;
+
+use super::cursor_move;
+use super::search;
+
+pub fn search_regex(context: &mut AppContext, pattern: &str) -> DiziResult {
+    let ignore_case = context
+        .config_ref()
+        .client_ref()
+        .search_options_ref()
+        .case_sensitivity
+        .ignores_case(pattern);
+    let regex = RegexBuilder::new(pattern)
+        .case_insensitive(ignore_case)
+        .build()
+        .map_err(|e| DiziError::new(DiziErrorKind::ParseError, e.to_string()))?;
+
+    let pattern = SearchPattern::Regex(regex);
+    let index = search::search_pattern_fwd(context.tab_context_ref().curr_tab_ref(), &pattern);
+    if let Some(index) = index {
+        cursor_move::cursor_move(context, index);
+    }
+    context.set_search_context(pattern);
+    Ok(())
+}
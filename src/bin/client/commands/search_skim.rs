@@ -6,13 +6,18 @@ use std::thread;
 use skim::prelude::*;
 
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
 
 use crate::commands::cursor_move;
 use crate::config::option::WidgetType;
-use crate::context::AppContext;
+use crate::context::{read_library, AppContext};
 use crate::ui::AppBackend;
+use crate::util::request::send_client_request;
 use crate::util::search::SearchPattern;
 
+// skim key appending the chosen songs of the library to the playlist
+const LIBRARY_APPEND_KEY: &str = "ctrl-a";
+
 #[derive(Clone, Debug)]
 pub struct DiziSkimItem {
     pub idx: usize,
@@ -25,17 +30,111 @@ impl SkimItem for DiziSkimItem {
     }
 }
 
-pub fn search_skim(context: &mut AppContext, backend: &mut AppBackend) -> DiziResult {
+pub fn search_skim(
+    context: &mut AppContext,
+    backend: &mut AppBackend,
+    library: bool,
+) -> DiziResult {
     let widget = context.get_view_widget();
 
     match widget {
+        _ if library => search_library_skim(context, backend)?,
         WidgetType::FileBrowser => search_directory_skim(context, backend, widget)?,
         WidgetType::Playlist => search_playlist_skim(context, backend, widget)?,
+        WidgetType::Library => search_library_skim(context, backend)?,
         _ => {}
     }
     Ok(())
 }
 
+/// Searches every song of the library, going to the chosen song in the library view
+/// or appending the chosen songs to the playlist with `ctrl-a`
+fn search_library_skim(context: &mut AppContext, backend: &mut AppBackend) -> DiziResult {
+    if !context.library_context_ref().is_loaded() {
+        let root = context.library_context_ref().root().to_path_buf();
+        let songs = read_library(root.as_path())?;
+        context.library_context_mut().set_songs(songs);
+    }
+
+    let options = SkimOptionsBuilder::default()
+        .height(Some("100%"))
+        .multi(true)
+        .expect(Some(LIBRARY_APPEND_KEY.to_string()))
+        .header(Some("enter: go to song, ctrl-a: append to playlist"))
+        .build()
+        .unwrap();
+
+    let library = context.library_context_ref();
+    let items: Vec<DiziSkimItem> = library
+        .songs_ref()
+        .iter()
+        .enumerate()
+        .map(|(i, path)| DiziSkimItem {
+            idx: i,
+            value: path
+                .strip_prefix(library.root())
+                .unwrap_or(path)
+                .to_string_lossy()
+                .to_string(),
+        })
+        .collect();
+
+    if items.is_empty() {
+        return Err(DiziError::new(
+            DiziErrorKind::IoError(io::ErrorKind::InvalidData),
+            "no songs to select".to_string(),
+        ));
+    }
+
+    let (s, r): (SkimItemSender, SkimItemReceiver) = unbounded();
+    let thread = thread::spawn(move || {
+        for item in items {
+            let _ = s.send(sync::Arc::new(item));
+        }
+    });
+
+    backend.terminal_drop();
+
+    let skim_output = Skim::run_with(&options, Some(r));
+
+    backend.terminal_restore()?;
+
+    let _ = thread.join();
+
+    if let Some(skim_output) = skim_output {
+        if skim_output.final_key == Key::ESC {
+            return Ok(());
+        }
+
+        let mut indices = Vec::new();
+        for sk_item in skim_output.selected_items {
+            let item: Option<&DiziSkimItem> = (*sk_item).as_any().downcast_ref::<DiziSkimItem>();
+
+            match item {
+                Some(item) => indices.push(item.idx),
+                None => {
+                    return Err(DiziError::new(
+                        DiziErrorKind::IoError(io::ErrorKind::InvalidData),
+                        "Error casting".to_string(),
+                    ))
+                }
+            }
+        }
+
+        if skim_output.final_key == Key::Ctrl('a') {
+            let songs = context.library_context_ref().songs_ref();
+            let paths = indices.iter().map(|i| songs[*i].clone()).collect();
+            let request = ClientRequest::PlaylistAppendBatch { paths, next: false };
+            send_client_request(context, &request)?;
+        } else if let Some(index) = indices.last() {
+            context.set_view_widget(WidgetType::Library);
+            cursor_move::set_library_index(context, *index);
+        }
+    }
+
+    Ok(())
+}
+
 fn search_playlist_skim(
     context: &mut AppContext,
     backend: &mut AppBackend,
@@ -89,7 +188,12 @@ fn search_playlist_skim(
 
         let query = skim_output.query;
         if !query.is_empty() {
-            context.set_search_context(SearchPattern::String(query));
+            let case_sensitivity = context
+                .config_ref()
+                .client_ref()
+                .search_options_ref()
+                .case_sensitivity;
+            context.set_search_context(SearchPattern::string(&query, case_sensitivity));
         }
 
         for sk_item in skim_output.selected_items {
@@ -169,7 +273,12 @@ fn search_directory_skim(
 
         let query = skim_output.query;
         if !query.is_empty() {
-            context.set_search_context(SearchPattern::String(query));
+            let case_sensitivity = context
+                .config_ref()
+                .client_ref()
+                .search_options_ref()
+                .case_sensitivity;
+            context.set_search_context(SearchPattern::string(&query, case_sensitivity));
         }
 
         for sk_item in skim_output.selected_items {
@@ -1,10 +1,13 @@
 use dizi::error::DiziResult;
 
+use crate::config::option::WidgetType;
 use crate::context::AppContext;
+use crate::key_command::CMD_SEARCH_STRING;
 use crate::tab::JoshutoTab;
 use crate::util::search::SearchPattern;
 
 use super::cursor_move;
+use super::search;
 
 fn _search_exact(curr_tab: &JoshutoTab, pattern: &str) -> Option<usize> {
     let curr_list = curr_tab.curr_list_ref()?;
@@ -27,43 +30,57 @@ pub fn search_exact(context: &mut AppContext, pattern: &str) -> DiziResult {
     Ok(())
 }
 
-pub fn search_string_fwd(curr_tab: &JoshutoTab, pattern: &str) -> Option<usize> {
-    let curr_list = curr_tab.curr_list_ref()?;
-
-    let offset = curr_list.get_index()? + 1;
-    let contents_len = curr_list.contents.len();
-    for i in 0..contents_len {
-        let file_name_lower = curr_list.contents[(offset + i) % contents_len]
-            .file_name()
-            .to_lowercase();
-        if file_name_lower.contains(pattern) {
-            return Some((offset + i) % contents_len);
-        }
+pub fn search_string(context: &mut AppContext, pattern: &str) -> DiziResult {
+    let case_sensitivity = context
+        .config_ref()
+        .client_ref()
+        .search_options_ref()
+        .case_sensitivity;
+    let pattern = SearchPattern::string(pattern, case_sensitivity);
+    let index = search::search_pattern_fwd(context.tab_context_ref().curr_tab_ref(), &pattern);
+    if let Some(index) = index {
+        cursor_move::cursor_move(context, index);
     }
-    None
+    context.set_search_context(pattern);
+    Ok(())
 }
-pub fn search_string_rev(curr_tab: &JoshutoTab, pattern: &str) -> Option<usize> {
-    let curr_list = curr_tab.curr_list_ref()?;
 
-    let offset = curr_list.get_index()?;
-    let contents_len = curr_list.contents.len();
-    for i in (0..contents_len).rev() {
-        let file_name_lower = curr_list.contents[(offset + i) % contents_len]
-            .file_name()
-            .to_lowercase();
-        if file_name_lower.contains(pattern) {
-            return Some((offset + i) % contents_len);
-        }
+/// Previews `search_string` while its pattern is being typed: the cursor is moved
+/// to the first match after `start_index`, the entry the search began from
+pub fn search_incremental(context: &mut AppContext, start_index: Option<usize>, input: &str) {
+    let (command, arg) = match input.trim_start().split_once(' ') {
+        Some((command, arg)) => (command, arg.trim_start()),
+        None => (input.trim_start(), ""),
+    };
+    if command != CMD_SEARCH_STRING {
+        return;
     }
-    None
-}
+    let start_index = match start_index {
+        Some(i) => i,
+        None => return,
+    };
 
-pub fn search_string(context: &mut AppContext, pattern: &str) -> DiziResult {
-    let pattern = pattern.to_lowercase();
-    let index = search_string_fwd(context.tab_context_ref().curr_tab_ref(), pattern.as_str());
-    if let Some(index) = index {
-        cursor_move::cursor_move(context, index);
+    if arg.is_empty() {
+        context.clear_search_context();
+        cursor_move::cursor_move_for_widget(context, WidgetType::FileBrowser, start_index);
+        return;
     }
-    context.set_search_context(SearchPattern::String(pattern));
-    Ok(())
+
+    let case_sensitivity = context
+        .config_ref()
+        .client_ref()
+        .search_options_ref()
+        .case_sensitivity;
+    let pattern = SearchPattern::string(arg, case_sensitivity);
+    let index = search::search_pattern_from(
+        context.tab_context_ref().curr_tab_ref(),
+        &pattern,
+        start_index + 1,
+    );
+    cursor_move::cursor_move_for_widget(
+        context,
+        WidgetType::FileBrowser,
+        index.unwrap_or(start_index),
+    );
+    context.set_search_context(pattern);
 }
@@ -1,7 +1,14 @@
+use std::path::PathBuf;
+
+use globset::GlobBuilder;
+
 use dizi::error::DiziResult;
 
 use crate::config::option::SelectOption;
 use crate::context::AppContext;
+use crate::fs::JoshutoDirEntry;
+
+use super::cursor_move;
 
 pub fn select_files(context: &mut AppContext, pattern: &str, options: &SelectOption) -> DiziResult {
     if pattern.is_empty() {
@@ -11,14 +18,72 @@ pub fn select_files(context: &mut AppContext, pattern: &str, options: &SelectOpt
     }
 }
 
-fn select_without_pattern(_context: &mut AppContext, _options: &SelectOption) -> DiziResult {
+/// Paths of the selected entries in the current directory
+pub fn selected_paths(context: &AppContext) -> Vec<PathBuf> {
+    match context.tab_context_ref().curr_tab_ref().curr_list_ref() {
+        Some(curr_list) => curr_list
+            .iter()
+            .filter(|entry| entry.is_selected())
+            .map(|entry| entry.file_path_buf())
+            .collect(),
+        None => Vec::new(),
+    }
+}
+
+pub fn clear_selection(context: &mut AppContext) {
+    if let Some(curr_list) = context.tab_context_mut().curr_tab_mut().curr_list_mut() {
+        curr_list
+            .contents
+            .iter_mut()
+            .for_each(|entry| entry.set_permanent_selected(false));
+    }
+}
+
+fn select_entry(entry: &mut JoshutoDirEntry, options: &SelectOption) {
+    if options.reverse {
+        entry.set_permanent_selected(false);
+    } else if options.toggle {
+        entry.set_permanent_selected(!entry.is_permanent_selected());
+    } else {
+        entry.set_permanent_selected(true);
+    }
+}
+
+fn select_without_pattern(context: &mut AppContext, options: &SelectOption) -> DiziResult {
+    if let Some(curr_list) = context.tab_context_mut().curr_tab_mut().curr_list_mut() {
+        if options.all {
+            curr_list
+                .contents
+                .iter_mut()
+                .for_each(|entry| select_entry(entry, options));
+        } else if let Some(index) = curr_list.get_index() {
+            select_entry(&mut curr_list.contents[index], options);
+            // move on to the next entry, so several entries can be selected in a row
+            let len = curr_list.len();
+            if index + 1 < len {
+                cursor_move::cursor_move(context, index + 1);
+            }
+        }
+    }
     Ok(())
 }
 
 fn select_with_pattern(
-    _context: &mut AppContext,
-    _pattern: &str,
-    _options: &SelectOption,
+    context: &mut AppContext,
+    pattern: &str,
+    options: &SelectOption,
 ) -> DiziResult {
+    let glob = GlobBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()?
+        .compile_matcher();
+
+    if let Some(curr_list) = context.tab_context_mut().curr_tab_mut().curr_list_mut() {
+        curr_list
+            .contents
+            .iter_mut()
+            .filter(|entry| glob.is_match(entry.file_name()))
+            .for_each(|entry| select_entry(entry, options));
+    }
     Ok(())
 }
@@ -0,0 +1,53 @@
+use std::path::Path;
+
+use dizi::error::DiziResult;
+
+use crate::commands::{cursor_move, view};
+use crate::config::option::WidgetType;
+use crate::context::{AppContext, Session};
+
+/// Views whose state can be brought back on the next launch
+fn is_restorable(widget: WidgetType) -> bool {
+    matches!(
+        widget,
+        WidgetType::FileBrowser | WidgetType::Playlist | WidgetType::Library
+    )
+}
+
+pub fn save_session(context: &AppContext, path: &Path) -> DiziResult {
+    let curr_tab = context.tab_context_ref().curr_tab_ref();
+    let widget = context.get_view_widget();
+    let session = Session {
+        cwd: curr_tab.cwd().to_path_buf(),
+        cursor: curr_tab
+            .curr_list_ref()
+            .and_then(|list| list.curr_entry_ref())
+            .map(|entry| entry.file_path_buf()),
+        view: is_restorable(widget).then(|| widget.to_string()),
+    };
+    session.save(path)
+}
+
+/// Puts the cursor and view back where the session left them,
+/// the directory itself is restored when the tab is created
+pub fn restore_session(context: &mut AppContext, session: &Session) -> DiziResult {
+    if let Some(cursor) = session.cursor.as_ref() {
+        let index = context
+            .tab_context_ref()
+            .curr_tab_ref()
+            .curr_list_ref()
+            .and_then(|list| list.iter().position(|entry| entry.file_path() == cursor));
+        if let Some(index) = index {
+            cursor_move::cursor_move_for_widget(context, WidgetType::FileBrowser, index);
+        }
+    }
+    let widget = session
+        .view
+        .as_deref()
+        .and_then(|s| s.parse::<WidgetType>().ok())
+        .filter(|widget| is_restorable(*widget));
+    if let Some(widget) = widget {
+        view::switch_view(context, widget)?;
+    }
+    Ok(())
+}
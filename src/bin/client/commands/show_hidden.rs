@@ -24,3 +24,19 @@ pub fn toggle_hidden(context: &mut AppContext) -> DiziResult {
     _toggle_hidden(context);
     reload::reload_dirlist(context)
 }
+
+pub fn toggle_audio_only(context: &mut AppContext) -> DiziResult {
+    let opposite = !context.config_ref().display_options_ref().audio_only();
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_audio_only(opposite);
+
+    for tab in context.tab_context_mut().iter_mut() {
+        tab.history_mut().depreciate_all_entries();
+        if let Some(s) = tab.curr_list_mut() {
+            s.depreciate();
+        }
+    }
+    reload::reload_dirlist(context)
+}
@@ -1,28 +1,69 @@
 use dizi::error::DiziResult;
+use termion::event::{Event, Key};
 
-use crate::config::option::SortType;
+use crate::config::option::{SortOption, SortType};
+use crate::config::{AppKeyMapping, KeyMapping};
 use crate::context::AppContext;
+use crate::event::process_event;
 use crate::history::DirectoryHistory;
+use crate::key_command::{AppExecute, Command, CommandKeybind};
+use crate::ui::AppBackend;
 
 use super::reload;
 
 pub fn set_sort(context: &mut AppContext, method: SortType) -> DiziResult {
-    context
-        .config_mut()
-        .sort_options_mut()
-        .set_sort_method(method);
-    for tab in context.tab_context_mut().iter_mut() {
-        tab.history_mut().depreciate_all_entries();
-    }
-    refresh(context)
+    update_sort_options(context, |options| options.set_sort_method(method))
 }
 
 pub fn toggle_reverse(context: &mut AppContext) -> DiziResult {
-    let reversed = !context.config_ref().sort_options_ref().reverse;
-    context.config_mut().sort_options_mut().reverse = reversed;
+    update_sort_options(context, |options| options.reverse = !options.reverse)
+}
+
+/// Lets the user pick a sort method with a single key press
+pub fn sort_menu(
+    context: &mut AppContext,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+) -> DiziResult {
+    let options = [
+        ('l', Command::Sort(SortType::Lexical)),
+        ('n', Command::Sort(SortType::Natural)),
+        ('m', Command::Sort(SortType::Mtime)),
+        ('s', Command::Sort(SortType::Size)),
+        ('e', Command::Sort(SortType::Ext)),
+        ('r', Command::SortReverse),
+    ];
+    let menu: KeyMapping = options
+        .into_iter()
+        .map(|(c, command)| {
+            (
+                Event::Key(Key::Char(c)),
+                CommandKeybind::SimpleKeybind(command),
+            )
+        })
+        .collect();
+
+    match process_event::get_input_while_composite(backend, context, &menu) {
+        Some(command) => command.execute(context, backend, keymap_t),
+        None => Ok(()),
+    }
+}
+
+/// Sort changes only apply to the current directory and are remembered
+/// for the rest of the session
+fn update_sort_options<F>(context: &mut AppContext, f: F) -> DiziResult
+where
+    F: FnOnce(&mut SortOption),
+{
+    let cwd = context.tab_context_ref().curr_tab_ref().cwd().to_path_buf();
+
+    let display_options = context.config_mut().display_options_mut();
+    let mut sort_options = display_options.sort_options_for(cwd.as_path()).clone();
+    f(&mut sort_options);
+    display_options.set_dir_sort_options(cwd.clone(), sort_options);
 
     for tab in context.tab_context_mut().iter_mut() {
-        tab.history_mut().depreciate_all_entries();
+        tab.history_mut().depreciate_entry(cwd.as_path());
     }
     refresh(context)
 }
@@ -0,0 +1,95 @@
+use std::thread;
+
+use dizi::error::DiziResult;
+
+use crate::config::option::WidgetType;
+use crate::context::{read_library, AppContext};
+use crate::event::AppEvent;
+
+pub fn toggle_view(context: &mut AppContext) -> DiziResult {
+    let new_widget = match context.get_view_widget() {
+        WidgetType::Playlist => WidgetType::FileBrowser,
+        _ => WidgetType::Playlist,
+    };
+    switch_view(context, new_widget)
+}
+
+pub fn switch_view(context: &mut AppContext, widget: WidgetType) -> DiziResult {
+    if widget == WidgetType::Library {
+        let library = context.library_context_ref();
+        if !library.is_loaded() && !library.is_loading() {
+            load_library(context);
+        }
+    }
+    context.set_view_widget(widget);
+    Ok(())
+}
+
+pub fn load_library(context: &mut AppContext) {
+    let event_tx = context.clone_event_tx();
+    let root = context.library_context_ref().root().to_path_buf();
+    context.library_context_mut().set_loading(true);
+
+    let _ = thread::spawn(move || {
+        let res = read_library(root.as_path());
+        let _ = event_tx.send(AppEvent::LibraryLoad {
+            root,
+            res: Box::new(res),
+        });
+    });
+}
+
+pub fn toggle_minimal(context: &mut AppContext) -> DiziResult {
+    let opposite = !context.config_ref().display_options_ref().minimal_mode();
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_minimal_mode(opposite);
+    Ok(())
+}
+
+pub fn toggle_remaining_time(context: &mut AppContext) -> DiziResult {
+    let opposite = !context
+        .config_ref()
+        .display_options_ref()
+        .show_remaining_time();
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_show_remaining_time(opposite);
+    Ok(())
+}
+
+pub fn toggle_visualizer(context: &mut AppContext) -> DiziResult {
+    let opposite = !context.config_ref().display_options_ref().show_visualizer();
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_show_visualizer(opposite);
+    Ok(())
+}
+
+/// Switches the playlist view between the file playlist and the songs of the
+/// directory being played
+pub fn toggle_directory_playlist(context: &mut AppContext) -> DiziResult {
+    let opposite = !context.server_state_ref().shows_directory_playlist();
+    context
+        .server_state_mut()
+        .set_show_directory_playlist(opposite);
+    let message = if opposite {
+        "Showing directory playlist"
+    } else {
+        "Showing file playlist"
+    };
+    context.message_queue_mut().push_info(message.to_string());
+    Ok(())
+}
+
+pub fn toggle_lyrics(context: &mut AppContext) -> DiziResult {
+    let opposite = !context.config_ref().display_options_ref().show_lyrics();
+    context
+        .config_mut()
+        .display_options_mut()
+        .set_show_lyrics(opposite);
+    Ok(())
+}
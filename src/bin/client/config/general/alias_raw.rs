@@ -0,0 +1,26 @@
+use std::collections::HashMap;
+
+use serde::Deserialize;
+
+use crate::config::option::CommandAliases;
+use crate::config::{parse_toml_to_config, TomlConfigFile};
+
+/// Only the `[alias]` table of client.toml
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct CommandAliasesRaw {
+    #[serde(default)]
+    pub alias: HashMap<String, String>,
+}
+
+impl From<CommandAliasesRaw> for CommandAliases {
+    fn from(raw: CommandAliasesRaw) -> Self {
+        Self::new(raw.alias)
+    }
+}
+
+impl TomlConfigFile for CommandAliases {
+    fn get_config(file_name: &str) -> Self {
+        // errors are already reported when the rest of client.toml is read
+        parse_toml_to_config::<CommandAliasesRaw, CommandAliases>(file_name).unwrap_or_default()
+    }
+}
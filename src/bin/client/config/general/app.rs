@@ -1,6 +1,6 @@
 use serde::Deserialize;
 
-use crate::config::option::{DisplayOption, SortOption};
+use crate::config::option::DisplayOption;
 use crate::config::{parse_toml_to_config, TomlConfigFile};
 
 use super::client::{ClientConfig, ClientConfigRaw};
@@ -44,13 +44,6 @@ impl AppConfig {
     pub fn display_options_mut(&mut self) -> &mut DisplayOption {
         &mut self.client_mut().display_options
     }
-
-    pub fn sort_options_ref(&self) -> &SortOption {
-        self.display_options_ref().sort_options_ref()
-    }
-    pub fn sort_options_mut(&mut self) -> &mut SortOption {
-        self.display_options_mut().sort_options_mut()
-    }
 }
 
 impl TomlConfigFile for AppConfig {
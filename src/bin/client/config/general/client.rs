@@ -1,30 +1,72 @@
 use std::convert::From;
-use std::path::{Path, PathBuf};
+use std::path::PathBuf;
 
 use serde::Deserialize;
 use shellexpand::tilde_with_context;
 
-use crate::config::option::DisplayOption;
+use crate::config::option::{ConfirmOption, DisplayOption, SearchOption, ServerOption};
+use crate::util::connection::ServerAddress;
 
+use super::confirm_raw::ConfirmOptionRaw;
 use super::display_raw::DisplayOptionRaw;
+use super::search_raw::SearchOptionRaw;
+use super::server_raw::ServerOptionRaw;
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_session_string() -> String {
+    "~/dizi-session.json".to_string()
+}
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct ClientConfigRaw {
     #[serde(default)]
     pub socket: String,
     #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub auth: bool,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
     pub home_dir: Option<String>,
+    #[serde(default = "default_true")]
+    pub restore_session: bool,
+    #[serde(default = "default_session_string")]
+    pub session: String,
+    #[serde(default)]
+    pub on_startup: Vec<String>,
+    #[serde(default)]
+    pub accessible: bool,
 
     #[serde(default, rename = "display")]
     pub display_options: DisplayOptionRaw,
+    #[serde(default, rename = "confirm")]
+    pub confirm_options: ConfirmOptionRaw,
+    #[serde(default, rename = "search")]
+    pub search_options: SearchOptionRaw,
+    #[serde(default, rename = "server")]
+    pub server_options: ServerOptionRaw,
 }
 
 impl std::default::Default for ClientConfigRaw {
     fn default() -> Self {
         Self {
             socket: "".to_string(),
+            address: None,
+            auth: false,
+            password: None,
             home_dir: None,
+            restore_session: true,
+            session: default_session_string(),
+            on_startup: Vec::new(),
+            accessible: false,
             display_options: DisplayOptionRaw::default(),
+            confirm_options: ConfirmOptionRaw::default(),
+            search_options: SearchOptionRaw::default(),
+            server_options: ServerOptionRaw::default(),
         }
     }
 }
@@ -35,11 +77,23 @@ impl From<ClientConfigRaw> for ClientConfig {
         let home_dir = raw.home_dir.map(|home_dir| {
             PathBuf::from(tilde_with_context(&home_dir, dirs_next::home_dir).as_ref())
         });
+        let session = raw
+            .restore_session
+            .then(|| PathBuf::from(tilde_with_context(&raw.session, dirs_next::home_dir).as_ref()));
 
         Self {
             socket,
+            address: raw.address,
+            auth: raw.auth,
+            password: raw.password,
             home_dir,
+            session,
+            on_startup: raw.on_startup,
+            accessible: raw.accessible,
             display_options: DisplayOption::from(raw.display_options),
+            confirm_options: ConfirmOption::from(raw.confirm_options),
+            search_options: SearchOption::from(raw.search_options),
+            server_options: ServerOption::from(raw.server_options),
         }
     }
 }
@@ -47,28 +101,66 @@ impl From<ClientConfigRaw> for ClientConfig {
 #[derive(Clone, Debug)]
 pub struct ClientConfig {
     pub socket: PathBuf,
+    // host:port of a remote server, used instead of the socket when set
+    pub address: Option<String>,
+    // whether the server asks for a password
+    pub auth: bool,
+    pub password: Option<String>,
     pub home_dir: Option<PathBuf>,
+    // where the last directory, cursor and view are kept, unset if not restored
+    pub session: Option<PathBuf>,
+    // commands run once the client is connected
+    pub on_startup: Vec<String>,
+    // announces changes as lines of text instead of drawing the tui
+    pub accessible: bool,
     pub display_options: DisplayOption,
+    pub confirm_options: ConfirmOption,
+    pub search_options: SearchOption,
+    pub server_options: ServerOption,
 }
 
 impl ClientConfig {
-    pub fn socket_ref(&self) -> &Path {
-        self.socket.as_path()
+    pub fn server_address(&self) -> ServerAddress {
+        match self.address.as_ref() {
+            Some(address) => ServerAddress::Tcp(address.clone()),
+            None => ServerAddress::Socket(self.socket.clone()),
+        }
     }
     pub fn display_options_ref(&self) -> &DisplayOption {
         &self.display_options
     }
+    pub fn confirm_options_ref(&self) -> &ConfirmOption {
+        &self.confirm_options
+    }
+    pub fn search_options_ref(&self) -> &SearchOption {
+        &self.search_options
+    }
+    pub fn server_options_ref(&self) -> &ServerOption {
+        &self.server_options
+    }
 }
 
 impl std::default::Default for ClientConfig {
     fn default() -> Self {
         let socket =
             PathBuf::from(tilde_with_context("~/dizi-server-socket", dirs_next::home_dir).as_ref());
+        let session = PathBuf::from(
+            tilde_with_context(&default_session_string(), dirs_next::home_dir).as_ref(),
+        );
 
         Self {
             socket,
+            address: None,
+            auth: false,
+            password: None,
             home_dir: None,
+            session: Some(session),
+            on_startup: Vec::new(),
+            accessible: false,
             display_options: DisplayOption::default(),
+            confirm_options: ConfirmOption::default(),
+            search_options: SearchOption::default(),
+            server_options: ServerOption::default(),
         }
     }
 }
@@ -0,0 +1,41 @@
+use serde::Deserialize;
+
+use crate::config::option::ConfirmOption;
+
+const fn default_true() -> bool {
+    true
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ConfirmOptionRaw {
+    #[serde(default = "default_true")]
+    pub playlist_clear: bool,
+    #[serde(default = "default_true")]
+    pub server_quit: bool,
+    #[serde(default = "default_true")]
+    pub delete_files: bool,
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+impl From<ConfirmOptionRaw> for ConfirmOption {
+    fn from(raw: ConfirmOptionRaw) -> Self {
+        Self {
+            playlist_clear: raw.playlist_clear,
+            server_quit: raw.server_quit,
+            delete_files: raw.delete_files,
+            commands: raw.commands,
+        }
+    }
+}
+
+impl std::default::Default for ConfirmOptionRaw {
+    fn default() -> Self {
+        Self {
+            playlist_clear: true,
+            server_quit: true,
+            delete_files: true,
+            commands: Vec::new(),
+        }
+    }
+}
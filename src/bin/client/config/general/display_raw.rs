@@ -1,15 +1,26 @@
+use std::collections::HashMap;
 use std::convert::From;
+use std::path::PathBuf;
 
 use serde::Deserialize;
+use shellexpand::tilde_with_context;
 
-use crate::config::option::DisplayOption;
+use crate::config::option::{
+    default_audio_extensions, DisplayOption, EntryFormat, IconStyle, LayoutComposition,
+    StatusFormat,
+};
 
+use super::layout_raw::AppLayout;
 use super::sort_raw::SortOptionRaw;
 
 const fn default_scroll_offset() -> usize {
     4
 }
 
+const fn default_true() -> bool {
+    true
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct DisplayOptionRaw {
     #[serde(default = "default_scroll_offset")]
@@ -18,20 +29,125 @@ pub struct DisplayOptionRaw {
     #[serde(default)]
     show_hidden: bool,
 
+    #[serde(default)]
+    audio_only: bool,
+
+    #[serde(default = "default_audio_extensions")]
+    audio_extensions: Vec<String>,
+
     #[serde(default)]
     show_icons: bool,
 
+    #[serde(default)]
+    icons: Option<String>,
+
+    #[serde(default = "default_true")]
+    show_visualizer: bool,
+
+    #[serde(default)]
+    show_lyrics: bool,
+
+    #[serde(default)]
+    show_waveform: bool,
+
+    #[serde(default = "default_true")]
+    terminal_title: bool,
+
+    #[serde(default)]
+    minimal_mode: bool,
+
+    #[serde(default)]
+    show_remaining_time: bool,
+
+    #[serde(default)]
+    show_song_tags: bool,
+
+    #[serde(default)]
+    follow_playing: bool,
+
     #[serde(default, rename = "sort")]
     sort_options: SortOptionRaw,
+
+    #[serde(default)]
+    status_format: Option<String>,
+
+    #[serde(default)]
+    entry_format: Option<String>,
+
+    #[serde(default)]
+    layout: Option<String>,
 }
 
 impl From<DisplayOptionRaw> for DisplayOption {
     fn from(raw: DisplayOptionRaw) -> Self {
+        let status_format = raw
+            .status_format
+            .and_then(|s| match StatusFormat::parse(s.as_str()) {
+                Ok(format) => Some(format),
+                Err(e) => {
+                    eprintln!("Failed to parse status_format: {}", e);
+                    None
+                }
+            });
+
+        let entry_format = raw
+            .entry_format
+            .and_then(|s| match EntryFormat::parse(s.as_str()) {
+                Ok(format) => Some(format),
+                Err(e) => {
+                    eprintln!("Failed to parse entry_format: {}", e);
+                    None
+                }
+            });
+
+        let layout = raw
+            .layout
+            .and_then(|s| match LayoutComposition::preset(s.as_str()) {
+                Some(layout) => Some(layout),
+                None => {
+                    let path = PathBuf::from(tilde_with_context(&s, dirs_next::home_dir).as_ref());
+                    match AppLayout::from_path(&path) {
+                        Ok(app_layout) => Some(app_layout.layout),
+                        Err(e) => {
+                            eprintln!("Failed to load layout '{}': {}", s, e);
+                            None
+                        }
+                    }
+                }
+            });
+
+        let icon_style = match raw.icons {
+            Some(s) => IconStyle::parse(s.as_str()).unwrap_or_else(|| {
+                eprintln!(
+                    "Unknown icons '{}', expected ascii, unicode or nerd_font",
+                    s
+                );
+                IconStyle::default()
+            }),
+            None => IconStyle::default(),
+        };
+
         Self {
             _show_hidden: raw.show_hidden,
+            _audio_only: raw.audio_only,
+            _audio_extensions: raw.audio_extensions,
             _show_icons: raw.show_icons,
+            _icon_style: icon_style,
+            _show_visualizer: raw.show_visualizer,
+            _show_lyrics: raw.show_lyrics,
+            _show_waveform: raw.show_waveform,
+            _terminal_title: raw.terminal_title,
+            _minimal_mode: raw.minimal_mode,
+            _show_remaining_time: raw.show_remaining_time,
+            _show_song_tags: raw.show_song_tags,
+            _follow_playing: raw.follow_playing,
             _sort_options: raw.sort_options.into(),
+            _dir_sort_options: HashMap::new(),
+            _dir_filters: HashMap::new(),
             _scroll_offset: raw.scroll_offset,
+            _status_format: status_format,
+            _entry_format: entry_format,
+            _layout: layout,
         }
     }
 }
@@ -40,9 +156,23 @@ impl std::default::Default for DisplayOptionRaw {
     fn default() -> Self {
         Self {
             show_hidden: false,
+            audio_only: false,
+            audio_extensions: default_audio_extensions(),
             show_icons: false,
+            icons: None,
+            show_visualizer: true,
+            show_lyrics: false,
+            show_waveform: false,
+            terminal_title: true,
+            minimal_mode: false,
+            show_remaining_time: false,
+            show_song_tags: false,
+            follow_playing: false,
             sort_options: SortOptionRaw::default(),
             scroll_offset: default_scroll_offset(),
+            status_format: None,
+            entry_format: None,
+            layout: None,
         }
     }
 }
@@ -1,5 +1,9 @@
 use serde::Deserialize;
 use std::convert::From;
+use std::fs;
+use std::path::Path;
+
+use dizi::error::DiziResult;
 
 use crate::config::option::LayoutComposition;
 use crate::config::{parse_json_to_config, JsonConfigFile};
@@ -41,6 +45,15 @@ impl std::default::Default for AppLayout {
     }
 }
 
+impl AppLayout {
+    pub fn from_path(path: &Path) -> DiziResult<Self> {
+        let file_contents = fs::read_to_string(path)?;
+        let raw = serde_json::from_str::<AppLayoutRaw>(&file_contents)?;
+        let layout = LayoutComposition::from(&raw.layout)?;
+        Ok(Self { layout })
+    }
+}
+
 impl From<AppLayoutRaw> for AppLayout {
     fn from(raw: AppLayoutRaw) -> Self {
         let res = LayoutComposition::from(&raw.layout);
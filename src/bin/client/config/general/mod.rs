@@ -1,8 +1,13 @@
+pub mod alias_raw;
 pub mod app;
 pub mod client;
+pub mod confirm_raw;
 pub mod display_raw;
 pub mod layout_raw;
+pub mod search_raw;
+pub mod server_raw;
 pub mod sort_raw;
+pub mod step_raw;
 
 pub use self::app::AppConfig;
 
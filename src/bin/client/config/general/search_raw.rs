@@ -0,0 +1,25 @@
+use serde::Deserialize;
+
+use crate::config::option::{CaseSensitivity, SearchOption};
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SearchOptionRaw {
+    #[serde(default)]
+    pub case_sensitivity: Option<String>,
+}
+
+impl From<SearchOptionRaw> for SearchOption {
+    fn from(raw: SearchOptionRaw) -> Self {
+        let case_sensitivity = match raw.case_sensitivity {
+            Some(s) => CaseSensitivity::parse(s.as_str()).unwrap_or_else(|| {
+                eprintln!(
+                    "Unknown case_sensitivity '{}', expected insensitive, sensitive or smart",
+                    s
+                );
+                CaseSensitivity::default()
+            }),
+            None => CaseSensitivity::default(),
+        };
+        Self { case_sensitivity }
+    }
+}
@@ -0,0 +1,60 @@
+use serde::Deserialize;
+use shellexpand::tilde_with_context;
+
+use crate::config::option::ServerOption;
+use crate::util::format;
+
+const fn default_true() -> bool {
+    true
+}
+
+fn default_path() -> String {
+    "dizi-server".to_string()
+}
+
+fn default_timeout() -> String {
+    "5s".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct ServerOptionRaw {
+    #[serde(default = "default_true")]
+    pub auto_start: bool,
+    #[serde(default = "default_path")]
+    pub path: String,
+    #[serde(default)]
+    pub args: Vec<String>,
+    // a duration such as `5s`
+    #[serde(default = "default_timeout")]
+    pub timeout: String,
+}
+
+impl From<ServerOptionRaw> for ServerOption {
+    fn from(raw: ServerOptionRaw) -> Self {
+        let default = Self::default();
+        let timeout = match format::parse_duration(&raw.timeout) {
+            Some(duration) => duration,
+            None => {
+                eprintln!("Invalid server timeout '{}', expected e.g. 5s", raw.timeout);
+                default.timeout
+            }
+        };
+        Self {
+            auto_start: raw.auto_start,
+            path: tilde_with_context(&raw.path, dirs_next::home_dir).to_string(),
+            args: raw.args,
+            timeout,
+        }
+    }
+}
+
+impl std::default::Default for ServerOptionRaw {
+    fn default() -> Self {
+        Self {
+            auto_start: true,
+            path: default_path(),
+            args: Vec::new(),
+            timeout: default_timeout(),
+        }
+    }
+}
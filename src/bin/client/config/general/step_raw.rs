@@ -0,0 +1,75 @@
+use serde::Deserialize;
+
+use crate::config::option::StepOption;
+use crate::config::{parse_toml_to_config, TomlConfigFile};
+use crate::util::format;
+
+const fn default_volume_step() -> usize {
+    1
+}
+
+fn default_seek_step() -> String {
+    "10s".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct StepOptionRaw {
+    #[serde(default = "default_volume_step")]
+    pub volume: usize,
+    // a duration such as `10s` or `1m30s`
+    #[serde(default = "default_seek_step")]
+    pub seek: String,
+}
+
+impl From<StepOptionRaw> for StepOption {
+    fn from(raw: StepOptionRaw) -> Self {
+        let default = Self::default();
+        let seek = match format::parse_duration(&raw.seek) {
+            Some(duration) => duration.as_secs() as usize,
+            None => {
+                eprintln!("Invalid seek step '{}', expected e.g. 10s", raw.seek);
+                default.seek
+            }
+        };
+        Self {
+            volume: raw.volume,
+            seek,
+        }
+    }
+}
+
+impl std::default::Default for StepOptionRaw {
+    fn default() -> Self {
+        Self {
+            volume: default_volume_step(),
+            seek: default_seek_step(),
+        }
+    }
+}
+
+/// Only the `[client.steps]` table of client.toml, the step sizes are
+/// needed while commands are parsed, before the rest of the config is around
+#[derive(Clone, Debug, Default, Deserialize)]
+struct StepConfigRaw {
+    #[serde(default)]
+    client: StepClientRaw,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct StepClientRaw {
+    #[serde(default)]
+    steps: StepOptionRaw,
+}
+
+impl From<StepConfigRaw> for StepOption {
+    fn from(raw: StepConfigRaw) -> Self {
+        Self::from(raw.client.steps)
+    }
+}
+
+impl TomlConfigFile for StepOption {
+    fn get_config(file_name: &str) -> Self {
+        // errors are already reported when the rest of client.toml is read
+        parse_toml_to_config::<StepConfigRaw, StepOption>(file_name).unwrap_or_default()
+    }
+}
@@ -4,8 +4,6 @@ use std::collections::{hash_map::Entry, HashMap};
 use std::convert::{AsMut, AsRef, From};
 
 use termion::event::Event;
-#[cfg(feature = "mouse")]
-use termion::event::MouseEvent;
 
 use dizi::error::DiziResult;
 use dizi::request::client::ClientRequest;
@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fs;
+
+use serde::Deserialize;
+
+use super::DEFAULT_CATALOG;
+use crate::config::{parse_toml_to_config, search_directories, TomlConfigFile};
+use crate::CONFIG_HIERARCHY;
+
+/// Only `locale` of the `[client]` table of client.toml
+#[derive(Clone, Debug, Default, Deserialize)]
+struct LocaleRaw {
+    #[serde(default)]
+    client: ClientLocaleRaw,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+struct ClientLocaleRaw {
+    #[serde(default)]
+    locale: Option<String>,
+}
+
+/// Catalog of the messages shown by the client, in English unless a
+/// translation is found for the user's locale
+#[derive(Clone, Debug)]
+pub struct AppMessages {
+    messages: HashMap<String, String>,
+}
+
+impl std::default::Default for AppMessages {
+    fn default() -> Self {
+        Self::parse(DEFAULT_CATALOG).unwrap_or_else(|| Self {
+            messages: HashMap::new(),
+        })
+    }
+}
+
+impl AppMessages {
+    /// Reads a catalog of `[section]` tables, keyed by `section.name`
+    fn parse(s: &str) -> Option<Self> {
+        let sections: HashMap<String, HashMap<String, String>> = toml::from_str(s).ok()?;
+        let messages = sections
+            .into_iter()
+            .flat_map(|(section, messages)| {
+                messages
+                    .into_iter()
+                    .map(move |(name, message)| (format!("{}.{}", section, name), message))
+            })
+            .collect();
+        Some(Self { messages })
+    }
+
+    /// Messages of `other` replace the ones here
+    fn merge(&mut self, other: Self) {
+        self.messages.extend(other.messages);
+    }
+
+    /// The message for `key`, or the key itself if there is none
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.messages.get(key).map(|s| s.as_str()).unwrap_or(key)
+    }
+
+    /// The message for `key` with every `{name}` replaced by its value
+    pub fn format(&self, key: &str, args: &[(&str, &dyn Display)]) -> String {
+        let mut message = self.get(key).to_string();
+        for (name, value) in args {
+            message = message.replace(&format!("{{{}}}", name), &value.to_string());
+        }
+        message
+    }
+}
+
+/// Catalog files to look for, most specific first, for a locale such as
+/// `pt_BR.UTF-8`
+fn catalog_names(locale: &str) -> Vec<String> {
+    let locale = locale.split(['.', '@']).next().unwrap_or_default();
+    if locale.is_empty() || locale == "C" || locale == "POSIX" {
+        return Vec::new();
+    }
+    let mut names = vec![format!("locale/{}.toml", locale)];
+    if let Some((language, _)) = locale.split_once('_') {
+        names.push(format!("locale/{}.toml", language));
+    }
+    names
+}
+
+/// `locale` of client.toml, otherwise the usual environment variables
+fn user_locale(file_name: &str) -> Option<String> {
+    let raw: LocaleRaw =
+        parse_toml_to_config::<LocaleRaw, LocaleRaw>(file_name).unwrap_or_default();
+    raw.client.locale.or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|s| !s.is_empty())
+    })
+}
+
+impl TomlConfigFile for AppMessages {
+    fn get_config(file_name: &str) -> Self {
+        let mut messages = Self::default();
+        let locale = match user_locale(file_name) {
+            Some(locale) => locale,
+            None => return messages,
+        };
+        // the language alone is the fallback for messages the region leaves out
+        for name in catalog_names(&locale).iter().rev() {
+            let translation = search_directories(name, &CONFIG_HIERARCHY)
+                .and_then(|path| fs::read_to_string(path).ok())
+                .and_then(|s| Self::parse(&s));
+            if let Some(translation) = translation {
+                messages.merge(translation);
+            }
+        }
+        messages
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn translation_falls_back_to_english() {
+        let mut messages = AppMessages::default();
+        messages.merge(AppMessages::parse("[tabs]\nlibrary = \"Bibliothek\"").unwrap());
+        assert_eq!(messages.get("tabs.library"), "Bibliothek");
+        assert_eq!(messages.get("tabs.playlist"), "Playlist");
+        assert_eq!(messages.get("tabs.missing"), "tabs.missing");
+    }
+
+    #[test]
+    fn format_fills_in_placeholders() {
+        let messages = AppMessages::default();
+        assert_eq!(
+            messages.format("footer.search", &[("position", &2), ("total", &5)]),
+            "Search: 2/5"
+        );
+    }
+
+    #[test]
+    fn catalog_names_of_locale() {
+        assert_eq!(
+            catalog_names("pt_BR.UTF-8"),
+            vec!["locale/pt_BR.toml", "locale/pt.toml"]
+        );
+        assert_eq!(catalog_names("de"), vec!["locale/de.toml"]);
+        assert!(catalog_names("C.UTF-8").is_empty());
+    }
+}
@@ -0,0 +1,5 @@
+mod app_messages;
+
+pub use self::app_messages::AppMessages;
+
+const DEFAULT_CATALOG: &str = include_str!("../../../../../config/locale/en.toml");
@@ -1,10 +1,12 @@
 pub mod general;
 pub mod keymap;
+pub mod locale;
 pub mod option;
 pub mod theme;
 
 pub use self::general::*;
 pub use self::keymap::*;
+pub use self::locale::*;
 pub use self::theme::*;
 
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
@@ -0,0 +1,54 @@
+use std::collections::HashMap;
+
+/// Short names for commands, set in the `[alias]` table of client.toml
+#[derive(Clone, Debug, Default)]
+pub struct CommandAliases {
+    aliases: HashMap<String, String>,
+}
+
+impl CommandAliases {
+    pub fn new(aliases: HashMap<String, String>) -> Self {
+        Self { aliases }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.aliases.keys().map(|s| s.as_str())
+    }
+
+    /// The command `s` stands for, with the arguments given to the alias
+    /// appended to the ones of the aliased command
+    pub fn resolve(&self, s: &str) -> Option<String> {
+        let (name, arg) = match s.find(' ') {
+            Some(i) => (&s[..i], s[i..].trim()),
+            None => (s, ""),
+        };
+        let command = self.aliases.get(name)?;
+        if arg.is_empty() {
+            Some(command.clone())
+        } else {
+            Some(format!("{} {}", command, arg))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resolve_alias_with_arguments() {
+        let aliases = CommandAliases::new(HashMap::from([
+            ("ff".to_string(), "player_fast_forward 30s".to_string()),
+            ("goto".to_string(), "playlist_goto".to_string()),
+        ]));
+        assert_eq!(
+            aliases.resolve("ff").as_deref(),
+            Some("player_fast_forward 30s")
+        );
+        assert_eq!(
+            aliases.resolve("goto 12").as_deref(),
+            Some("playlist_goto 12")
+        );
+        assert_eq!(aliases.resolve("toggle_view"), None);
+    }
+}
@@ -0,0 +1,27 @@
+/// Commands that ask before doing something that can't be undone
+#[derive(Clone, Debug)]
+pub struct ConfirmOption {
+    pub playlist_clear: bool,
+    pub server_quit: bool,
+    pub delete_files: bool,
+    // other commands to ask about, by name or, for server requests, by api path
+    pub commands: Vec<String>,
+}
+
+impl ConfirmOption {
+    /// Whether `name`, a command name or a request's api path, asks before running
+    pub fn requires(&self, name: &str) -> bool {
+        self.commands.iter().any(|command| command == name)
+    }
+}
+
+impl std::default::Default for ConfirmOption {
+    fn default() -> Self {
+        Self {
+            playlist_clear: true,
+            server_quit: true,
+            delete_files: true,
+            commands: Vec::new(),
+        }
+    }
+}
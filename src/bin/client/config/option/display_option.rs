@@ -1,13 +1,36 @@
+use std::collections::HashMap;
 use std::fs;
+use std::path::{Path, PathBuf};
 
-use crate::config::option::SortOption;
+use crate::config::option::{EntryFormat, IconStyle, LayoutComposition, SortOption, StatusFormat};
+use crate::fs::JoshutoDirEntry;
+use crate::util::audio::AUDIO_EXTENSIONS;
 
 #[derive(Clone, Debug)]
 pub struct DisplayOption {
     pub _show_hidden: bool,
+    pub _audio_only: bool,
+    pub _audio_extensions: Vec<String>,
     pub _show_icons: bool,
+    pub _icon_style: IconStyle,
+    pub _show_visualizer: bool,
+    pub _show_lyrics: bool,
+    pub _show_waveform: bool,
+    pub _terminal_title: bool,
+    pub _minimal_mode: bool,
+    pub _show_remaining_time: bool,
+    pub _show_song_tags: bool,
+    pub _follow_playing: bool,
     pub _sort_options: SortOption,
+    /// Sort options chosen for individual directories during this session
+    pub _dir_sort_options: HashMap<PathBuf, SortOption>,
+    /// Lowercase patterns listed directories are narrowed down to, see `filter`
+    pub _dir_filters: HashMap<PathBuf, String>,
     pub _scroll_offset: usize,
+    pub _status_format: Option<StatusFormat>,
+    pub _entry_format: Option<EntryFormat>,
+    /// Layout chosen in client.toml, overrides layout.json
+    pub _layout: Option<LayoutComposition>,
 }
 
 impl DisplayOption {
@@ -19,20 +42,128 @@ impl DisplayOption {
         self._show_hidden = show_hidden;
     }
 
+    pub fn audio_only(&self) -> bool {
+        self._audio_only
+    }
+
+    pub fn set_audio_only(&mut self, audio_only: bool) {
+        self._audio_only = audio_only;
+    }
+
+    /// Whether `entry` is shown when only audio files are listed.
+    /// Directories are always shown so they can still be browsed
+    pub fn is_audio_entry(&self, entry: &JoshutoDirEntry) -> bool {
+        entry.metadata.is_dir()
+            || entry
+                .ext()
+                .map(|ext| {
+                    self._audio_extensions
+                        .iter()
+                        .any(|audio_ext| audio_ext.eq_ignore_ascii_case(ext))
+                })
+                .unwrap_or(false)
+    }
+
     pub fn scroll_offset(&self) -> usize {
         self._scroll_offset
     }
 
+    pub fn status_format(&self) -> Option<&StatusFormat> {
+        self._status_format.as_ref()
+    }
+
+    pub fn entry_format(&self) -> Option<&EntryFormat> {
+        self._entry_format.as_ref()
+    }
+
+    pub fn layout_ref(&self) -> Option<&LayoutComposition> {
+        self._layout.as_ref()
+    }
+
     pub fn show_icons(&self) -> bool {
         self._show_icons
     }
 
+    pub fn icon_style(&self) -> IconStyle {
+        self._icon_style
+    }
+
+    pub fn show_song_tags(&self) -> bool {
+        self._show_song_tags
+    }
+
+    pub fn follow_playing(&self) -> bool {
+        self._follow_playing
+    }
+
+    pub fn set_follow_playing(&mut self, follow_playing: bool) {
+        self._follow_playing = follow_playing;
+    }
+
+    pub fn show_visualizer(&self) -> bool {
+        self._show_visualizer
+    }
+
+    pub fn set_show_visualizer(&mut self, show_visualizer: bool) {
+        self._show_visualizer = show_visualizer;
+    }
+
+    pub fn show_lyrics(&self) -> bool {
+        self._show_lyrics
+    }
+
+    pub fn set_show_lyrics(&mut self, show_lyrics: bool) {
+        self._show_lyrics = show_lyrics;
+    }
+
+    pub fn show_waveform(&self) -> bool {
+        self._show_waveform
+    }
+
+    pub fn terminal_title(&self) -> bool {
+        self._terminal_title
+    }
+
+    pub fn minimal_mode(&self) -> bool {
+        self._minimal_mode
+    }
+
+    pub fn set_minimal_mode(&mut self, minimal_mode: bool) {
+        self._minimal_mode = minimal_mode;
+    }
+
+    pub fn show_remaining_time(&self) -> bool {
+        self._show_remaining_time
+    }
+
+    pub fn set_show_remaining_time(&mut self, show_remaining_time: bool) {
+        self._show_remaining_time = show_remaining_time;
+    }
+
     pub fn sort_options_ref(&self) -> &SortOption {
         &self._sort_options
     }
 
-    pub fn sort_options_mut(&mut self) -> &mut SortOption {
-        &mut self._sort_options
+    /// Returns the sort options for `path`, falling back to the global ones
+    pub fn sort_options_for(&self, path: &Path) -> &SortOption {
+        self._dir_sort_options
+            .get(path)
+            .unwrap_or_else(|| self.sort_options_ref())
+    }
+
+    pub fn set_dir_sort_options(&mut self, path: PathBuf, sort_options: SortOption) {
+        self._dir_sort_options.insert(path, sort_options);
+    }
+
+    pub fn filter_for(&self, path: &Path) -> Option<&str> {
+        self._dir_filters.get(path).map(|s| s.as_str())
+    }
+
+    pub fn set_dir_filter(&mut self, path: PathBuf, filter: Option<String>) {
+        match filter {
+            Some(filter) => self._dir_filters.insert(path, filter),
+            None => self._dir_filters.remove(&path),
+        };
     }
 
     pub fn filter_func(&self) -> fn(&Result<fs::DirEntry, std::io::Error>) -> bool {
@@ -48,9 +179,25 @@ impl std::default::Default for DisplayOption {
     fn default() -> Self {
         Self {
             _show_hidden: false,
+            _audio_only: false,
+            _audio_extensions: default_audio_extensions(),
             _show_icons: false,
+            _icon_style: IconStyle::default(),
+            _show_visualizer: true,
+            _show_lyrics: false,
+            _show_waveform: false,
+            _terminal_title: true,
+            _minimal_mode: false,
+            _show_remaining_time: false,
+            _show_song_tags: false,
+            _follow_playing: false,
             _sort_options: SortOption::default(),
+            _dir_sort_options: HashMap::new(),
+            _dir_filters: HashMap::new(),
             _scroll_offset: 4,
+            _status_format: None,
+            _entry_format: None,
+            _layout: None,
         }
     }
 }
@@ -69,3 +216,11 @@ fn filter_hidden(result: &Result<fs::DirEntry, std::io::Error>) -> bool {
         }
     }
 }
+
+pub fn default_audio_extensions() -> Vec<String> {
+    AUDIO_EXTENSIONS
+        .into_iter()
+        .chain(dizi::song::optional_format_extensions())
+        .map(|s| s.to_string())
+        .collect()
+}
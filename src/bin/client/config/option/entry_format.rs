@@ -0,0 +1,136 @@
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+use crate::context::SongMetadata;
+use crate::util::format;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EntryToken {
+    Text(String),
+    Track,
+    Artist,
+    Title,
+    Album,
+    Duration,
+    FileName,
+}
+
+impl EntryToken {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "track" => Some(Self::Track),
+            "artist" => Some(Self::Artist),
+            "title" => Some(Self::Title),
+            "album" => Some(Self::Album),
+            "duration" => Some(Self::Duration),
+            "file" => Some(Self::FileName),
+            _ => None,
+        }
+    }
+}
+
+/// Template for songs in the file browser and playlist,
+/// such as `%track. %artist - %title (%duration)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EntryFormat {
+    tokens: Vec<EntryToken>,
+}
+
+impl EntryFormat {
+    pub fn parse(s: &str) -> DiziResult<Self> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                text.push('%');
+                continue;
+            }
+
+            let mut name = String::new();
+            while let Some(c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                name.push(*c);
+                chars.next();
+            }
+            let token = EntryToken::parse(&name).ok_or_else(|| {
+                DiziError::new(
+                    DiziErrorKind::ParseError,
+                    format!("Unknown entry format placeholder '%{}'", name),
+                )
+            })?;
+            if !text.is_empty() {
+                tokens.push(EntryToken::Text(std::mem::take(&mut text)));
+            }
+            tokens.push(token);
+        }
+        if !text.is_empty() {
+            tokens.push(EntryToken::Text(text));
+        }
+        Ok(Self { tokens })
+    }
+
+    /// Songs without a title are shown by their file name
+    pub fn render(&self, song: &SongMetadata, file_name: &str) -> String {
+        if song.title.is_none() {
+            return file_name.to_string();
+        }
+        let mut s = String::new();
+        for token in self.tokens.iter() {
+            match token {
+                EntryToken::Text(text) => s.push_str(text),
+                EntryToken::Track => s.push_str(song.track.as_deref().unwrap_or_default()),
+                EntryToken::Artist => s.push_str(song.artist.as_deref().unwrap_or_default()),
+                EntryToken::Title => s.push_str(song.title.as_deref().unwrap_or_default()),
+                EntryToken::Album => s.push_str(song.album.as_deref().unwrap_or_default()),
+                EntryToken::Duration => {
+                    if let Some(duration) = song.duration {
+                        s.push_str(&format::duration_to_string(duration));
+                    }
+                }
+                EntryToken::FileName => s.push_str(file_name),
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn song() -> SongMetadata {
+        SongMetadata {
+            duration: None,
+            track: Some("3".to_string()),
+            artist: Some("Artist".to_string()),
+            title: Some("Title".to_string()),
+            album: None,
+        }
+    }
+
+    #[test]
+    fn render_tags() {
+        let format = EntryFormat::parse("%track. %artist - %title").unwrap();
+        assert_eq!(format.render(&song(), "03.flac"), "3. Artist - Title");
+    }
+
+    #[test]
+    fn render_without_title() {
+        let format = EntryFormat::parse("%artist - %title").unwrap();
+        let song = SongMetadata {
+            title: None,
+            ..song()
+        };
+        assert_eq!(format.render(&song, "03.flac"), "03.flac");
+    }
+
+    #[test]
+    fn parse_unknown_placeholder() {
+        assert!(EntryFormat::parse("%volume").is_err());
+    }
+}
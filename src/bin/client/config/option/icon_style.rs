@@ -0,0 +1,155 @@
+use dizi::player::PlayerStatus;
+
+use crate::fs::{FileType, JoshutoDirEntry};
+use crate::util::audio::is_audio_file;
+
+const NERD_MUSIC: &str = "\u{f001}";
+
+/// Glyphs used for entries and player indicators, set by `icons` in client.toml
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IconStyle {
+    // for terminals without unicode support
+    Ascii,
+    #[default]
+    Unicode,
+    // requires a patched font from https://github.com/ryanoasis/nerd-fonts
+    NerdFont,
+}
+
+impl IconStyle {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "ascii" => Some(Self::Ascii),
+            "unicode" => Some(Self::Unicode),
+            "nerd_font" => Some(Self::NerdFont),
+            _ => None,
+        }
+    }
+
+    pub fn status(&self, status: PlayerStatus) -> &'static str {
+        match (self, status) {
+            (Self::Ascii, PlayerStatus::Playing) => ">",
+            (Self::Ascii, PlayerStatus::Paused) => "||",
+            (Self::Ascii, PlayerStatus::Stopped) => "[]",
+            (Self::Ascii, PlayerStatus::Loading) => "..",
+            (Self::Unicode, PlayerStatus::Playing) => "\u{25B6}",
+            (Self::Unicode, PlayerStatus::Paused) => "\u{2590}\u{258C}",
+            (Self::Unicode, PlayerStatus::Stopped) => "\u{25A0}",
+            (Self::Unicode, PlayerStatus::Loading) => "\u{2026}",
+            (Self::NerdFont, PlayerStatus::Playing) => "\u{f04b}",
+            (Self::NerdFont, PlayerStatus::Paused) => "\u{f04c}",
+            (Self::NerdFont, PlayerStatus::Stopped) => "\u{f04d}",
+            (Self::NerdFont, PlayerStatus::Loading) => "\u{f110}",
+        }
+    }
+
+    pub fn next(&self) -> &'static str {
+        match self {
+            Self::NerdFont => "\u{f051}",
+            _ => "[NEXT]",
+        }
+    }
+
+    pub fn repeat(&self) -> &'static str {
+        match self {
+            Self::NerdFont => "\u{f01e}",
+            _ => "[REPEAT]",
+        }
+    }
+
+    pub fn repeat_one(&self) -> &'static str {
+        match self {
+            Self::NerdFont => "\u{f0458}",
+            _ => "[REPEAT 1]",
+        }
+    }
+
+    pub fn single(&self) -> &'static str {
+        match self {
+            Self::NerdFont => "\u{f04d}",
+            _ => "[SINGLE]",
+        }
+    }
+
+    pub fn shuffle(&self) -> &'static str {
+        match self {
+            Self::NerdFont => "\u{f074}",
+            _ => "[SHUFFLE]",
+        }
+    }
+
+    /// Icon drawn before an entry's name when `show_icons` is set
+    pub fn entry_icon(&self, entry: &JoshutoDirEntry) -> &'static str {
+        let is_dir = *entry.metadata.file_type() == FileType::Directory;
+        match self {
+            Self::Ascii if is_dir => "+",
+            Self::Ascii if is_audio_file(entry.file_path()) => "~",
+            Self::Ascii => " ",
+            Self::NerdFont => nerd_font_icon(entry, is_dir),
+            _ if is_dir => "\u{25B8}",
+            _ if is_audio_file(entry.file_path()) => "\u{266A}",
+            _ => " ",
+        }
+    }
+}
+
+#[cfg(feature = "devicons")]
+fn nerd_font_icon(entry: &JoshutoDirEntry, is_dir: bool) -> &'static str {
+    use crate::util::devicons::*;
+
+    let name = entry.file_name();
+    if is_dir {
+        return DIR_NODE_EXACT_MATCHES
+            .get(name)
+            .copied()
+            .unwrap_or(DEFAULT_DIR);
+    }
+    if let Some(icon) = FILE_NODE_EXACT_MATCHES.get(name) {
+        return icon;
+    }
+    let ext = entry
+        .file_path()
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_lowercase());
+    match ext.as_deref().and_then(|ext| FILE_NODE_EXTENSIONS.get(ext)) {
+        Some(icon) => icon,
+        None if is_audio_file(entry.file_path()) => NERD_MUSIC,
+        None => DEFAULT_FILE,
+    }
+}
+
+#[cfg(not(feature = "devicons"))]
+fn nerd_font_icon(entry: &JoshutoDirEntry, is_dir: bool) -> &'static str {
+    if is_dir {
+        "\u{f07b}"
+    } else if is_audio_file(entry.file_path()) {
+        NERD_MUSIC
+    } else {
+        "\u{f15b}"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_icon_styles() {
+        assert_eq!(IconStyle::parse("ascii"), Some(IconStyle::Ascii));
+        assert_eq!(IconStyle::parse("nerd_font"), Some(IconStyle::NerdFont));
+        assert_eq!(IconStyle::parse("emoji"), None);
+    }
+
+    #[test]
+    fn ascii_status_is_ascii() {
+        for status in [
+            PlayerStatus::Playing,
+            PlayerStatus::Paused,
+            PlayerStatus::Stopped,
+            PlayerStatus::Loading,
+        ] {
+            assert!(IconStyle::Ascii.status(status).is_ascii());
+        }
+    }
+}
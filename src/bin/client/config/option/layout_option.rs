@@ -11,6 +11,11 @@ pub enum WidgetType {
     FileBrowser,
     MusicPlayer,
     Playlist,
+    Library,
+    MessageLog,
+    Visualizer,
+    UpNext,
+    Lyrics,
 }
 
 impl FromStr for WidgetType {
@@ -20,6 +25,11 @@ impl FromStr for WidgetType {
             "file_browser" => Ok(Self::FileBrowser),
             "music_player" => Ok(Self::MusicPlayer),
             "playlist" => Ok(Self::Playlist),
+            "library" => Ok(Self::Library),
+            "message_log" => Ok(Self::MessageLog),
+            "visualizer" => Ok(Self::Visualizer),
+            "up_next" => Ok(Self::UpNext),
+            "lyrics" => Ok(Self::Lyrics),
             s => Err(DiziError::new(
                 DiziErrorKind::ParseError,
                 format!("Unknown widget type: '{}'", s),
@@ -28,6 +38,22 @@ impl FromStr for WidgetType {
     }
 }
 
+impl std::fmt::Display for WidgetType {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let s = match self {
+            Self::FileBrowser => "file_browser",
+            Self::MusicPlayer => "music_player",
+            Self::Playlist => "playlist",
+            Self::Library => "library",
+            Self::MessageLog => "message_log",
+            Self::Visualizer => "visualizer",
+            Self::UpNext => "up_next",
+            Self::Lyrics => "lyrics",
+        };
+        write!(f, "{}", s)
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum LayoutComposition {
     Simple {
@@ -50,6 +76,14 @@ impl LayoutComposition {
             LayoutComposition::Composite { ratio, .. } => *ratio,
         }
     }
+    pub fn contains(&self, widget: WidgetType) -> bool {
+        match self {
+            LayoutComposition::Simple { widget: w, .. } => *w == widget,
+            LayoutComposition::Composite { widgets, .. } => {
+                widgets.iter().any(|w| w.contains(widget))
+            }
+        }
+    }
     pub fn from(raw: &LayoutCompositionRaw) -> DiziResult<Self> {
         match raw {
             LayoutCompositionRaw::Simple {
@@ -87,40 +121,96 @@ impl LayoutComposition {
             }
         }
     }
+
+    /// Built-in layouts that can be chosen with `layout` in client.toml
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            // just enough to browse and see what is playing
+            "compact" => Some(Self::horizontal(
+                1,
+                vec![
+                    Self::pane(WidgetType::FileBrowser, 1),
+                    Self::vertical(
+                        1,
+                        vec![
+                            Self::pane(WidgetType::MusicPlayer, 1),
+                            Self::pane(WidgetType::Playlist, 2),
+                        ],
+                    ),
+                ],
+            )),
+            // every pane, for large terminals
+            "full" => Some(Self::horizontal(
+                1,
+                vec![
+                    Self::pane(WidgetType::FileBrowser, 2),
+                    Self::vertical(
+                        2,
+                        vec![
+                            Self::pane(WidgetType::MusicPlayer, 2),
+                            Self::pane(WidgetType::Visualizer, 1),
+                            Self::pane(WidgetType::Playlist, 3),
+                        ],
+                    ),
+                    Self::vertical(
+                        1,
+                        vec![
+                            Self::pane(WidgetType::UpNext, 1),
+                            Self::pane(WidgetType::Lyrics, 2),
+                            Self::pane(WidgetType::MessageLog, 1),
+                        ],
+                    ),
+                ],
+            )),
+            _ => None,
+        }
+    }
+
+    fn pane(widget: WidgetType, ratio: usize) -> Self {
+        Self::Simple {
+            widget,
+            ratio,
+            border: true,
+            title: true,
+        }
+    }
+
+    fn horizontal(ratio: usize, widgets: Vec<Self>) -> Self {
+        Self::Composite {
+            direction: Direction::Horizontal,
+            widgets,
+            ratio,
+        }
+    }
+
+    fn vertical(ratio: usize, widgets: Vec<Self>) -> Self {
+        Self::Composite {
+            direction: Direction::Vertical,
+            widgets,
+            ratio,
+        }
+    }
 }
 
 impl std::default::Default for LayoutComposition {
     fn default() -> Self {
-        LayoutComposition::Composite {
-            direction: Direction::Horizontal,
-            ratio: 1,
-            widgets: vec![
-                LayoutComposition::Simple {
-                    widget: WidgetType::FileBrowser,
-                    ratio: 1,
-                    border: true,
-                    title: true,
-                },
-                LayoutComposition::Composite {
-                    direction: Direction::Vertical,
-                    ratio: 1,
-                    widgets: vec![
-                        LayoutComposition::Simple {
-                            widget: WidgetType::MusicPlayer,
-                            ratio: 1,
-                            border: true,
-                            title: true,
-                        },
-                        LayoutComposition::Simple {
-                            widget: WidgetType::Playlist,
-                            ratio: 1,
-                            border: true,
-                            title: true,
-                        },
+        Self::horizontal(
+            1,
+            vec![
+                Self::pane(WidgetType::FileBrowser, 1),
+                Self::vertical(
+                    1,
+                    vec![
+                        Self::pane(WidgetType::MusicPlayer, 2),
+                        Self::pane(WidgetType::Visualizer, 1),
+                        Self::pane(WidgetType::UpNext, 1),
+                        Self::pane(WidgetType::Lyrics, 2),
+                        Self::pane(WidgetType::Playlist, 3),
                     ],
-                },
+                ),
             ],
-        }
+        )
     }
 }
 
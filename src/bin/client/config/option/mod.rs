@@ -1,11 +1,27 @@
+pub mod command_aliases;
+pub mod confirm_option;
 pub mod display_option;
+pub mod entry_format;
+pub mod icon_style;
 pub mod layout_option;
+pub mod search_option;
 pub mod select_option;
+pub mod server_option;
 pub mod sort_option;
 pub mod sort_type;
+pub mod status_format;
+pub mod step_option;
 
+pub use self::command_aliases::*;
+pub use self::confirm_option::*;
 pub use self::display_option::*;
+pub use self::entry_format::*;
+pub use self::icon_style::*;
 pub use self::layout_option::*;
+pub use self::search_option::*;
 pub use self::select_option::*;
+pub use self::server_option::*;
 pub use self::sort_option::*;
 pub use self::sort_type::*;
+pub use self::status_format::*;
+pub use self::step_option::*;
@@ -0,0 +1,47 @@
+/// How `search_string` and `search_regex` treat case, set by `case_sensitivity` in client.toml
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum CaseSensitivity {
+    #[default]
+    Insensitive,
+    Sensitive,
+    // insensitive unless the pattern has an uppercase letter
+    Smart,
+}
+
+impl CaseSensitivity {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "insensitive" => Some(Self::Insensitive),
+            "sensitive" => Some(Self::Sensitive),
+            "smart" => Some(Self::Smart),
+            _ => None,
+        }
+    }
+
+    pub fn ignores_case(&self, pattern: &str) -> bool {
+        match self {
+            Self::Insensitive => true,
+            Self::Sensitive => false,
+            Self::Smart => !pattern.chars().any(|c| c.is_uppercase()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SearchOption {
+    pub case_sensitivity: CaseSensitivity,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn smart_case_follows_uppercase() {
+        let case = CaseSensitivity::Smart;
+        assert!(case.ignores_case("foo bar"));
+        assert!(!case.ignores_case("Foo"));
+        assert!(CaseSensitivity::Insensitive.ignores_case("Foo"));
+        assert!(!CaseSensitivity::Sensitive.ignores_case("foo"));
+    }
+}
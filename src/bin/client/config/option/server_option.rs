@@ -0,0 +1,22 @@
+use std::time;
+
+/// How the client starts a server on this machine when none is running
+#[derive(Clone, Debug)]
+pub struct ServerOption {
+    pub auto_start: bool,
+    pub path: String,
+    pub args: Vec<String>,
+    // how long to wait for the server's socket once started
+    pub timeout: time::Duration,
+}
+
+impl std::default::Default for ServerOption {
+    fn default() -> Self {
+        Self {
+            auto_start: true,
+            path: "dizi-server".to_string(),
+            args: Vec::new(),
+            timeout: time::Duration::from_secs(5),
+        }
+    }
+}
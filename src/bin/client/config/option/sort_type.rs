@@ -5,6 +5,8 @@ use std::time;
 
 use serde::Deserialize;
 
+use dizi::utils;
+
 use crate::config::option::SortOption;
 use crate::fs::JoshutoDirEntry;
 
@@ -22,7 +24,7 @@ impl SortType {
         match s {
             "lexical" => Some(SortType::Lexical),
             "mtime" => Some(SortType::Mtime),
-            "natural" => Some(SortType::Natural),
+            "natural" | "version" => Some(SortType::Natural),
             "size" => Some(SortType::Size),
             "ext" => Some(SortType::Ext),
             _ => None,
@@ -153,8 +155,6 @@ fn natural_sort(
     if sort_option.case_sensitive {
         alphanumeric_sort::compare_str(f1_name, f2_name)
     } else {
-        let f1_name = f1_name.to_lowercase();
-        let f2_name = f2_name.to_lowercase();
-        alphanumeric_sort::compare_str(f1_name, f2_name)
+        utils::natural_cmp(f1_name, f2_name)
     }
 }
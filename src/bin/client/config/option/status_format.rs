@@ -0,0 +1,189 @@
+use std::time;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::player::{PlayerState, RepeatMode};
+
+use crate::util::format;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum StatusToken {
+    Text(String),
+    Artist,
+    Title,
+    Album,
+    FileName,
+    Elapsed,
+    Remaining,
+    Duration,
+    Volume,
+    Status,
+    Flags,
+}
+
+impl StatusToken {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "artist" => Some(Self::Artist),
+            "title" => Some(Self::Title),
+            "album" => Some(Self::Album),
+            "file" => Some(Self::FileName),
+            "elapsed" => Some(Self::Elapsed),
+            "remaining" => Some(Self::Remaining),
+            "duration" => Some(Self::Duration),
+            "volume" => Some(Self::Volume),
+            "status" => Some(Self::Status),
+            "flags" => Some(Self::Flags),
+            _ => None,
+        }
+    }
+}
+
+/// Footer template such as `%artist - %title [%elapsed/%duration]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StatusFormat {
+    tokens: Vec<StatusToken>,
+}
+
+impl StatusFormat {
+    pub fn parse(s: &str) -> DiziResult<Self> {
+        let mut tokens = Vec::new();
+        let mut text = String::new();
+
+        let mut chars = s.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c != '%' {
+                text.push(c);
+                continue;
+            }
+            if chars.peek() == Some(&'%') {
+                chars.next();
+                text.push('%');
+                continue;
+            }
+
+            let mut name = String::new();
+            while let Some(c) = chars.peek().filter(|c| c.is_ascii_alphabetic()) {
+                name.push(*c);
+                chars.next();
+            }
+            let token = StatusToken::parse(&name).ok_or_else(|| {
+                DiziError::new(
+                    DiziErrorKind::ParseError,
+                    format!("Unknown status format placeholder '%{}'", name),
+                )
+            })?;
+            if !text.is_empty() {
+                tokens.push(StatusToken::Text(std::mem::take(&mut text)));
+            }
+            tokens.push(token);
+        }
+        if !text.is_empty() {
+            tokens.push(StatusToken::Text(text));
+        }
+        Ok(Self { tokens })
+    }
+
+    pub fn render(&self, player: &PlayerState) -> String {
+        let song = player.info.song.as_ref();
+        let tag = |key: &str| {
+            song.and_then(|song| song.music_metadata().standard_tags.get(key))
+                .cloned()
+                .unwrap_or_default()
+        };
+
+        let duration = player
+            .info
+            .total_duration()
+            .unwrap_or(time::Duration::from_secs(0));
+
+        let mut s = String::new();
+        for token in self.tokens.iter() {
+            match token {
+                StatusToken::Text(text) => s.push_str(text),
+                StatusToken::Artist => s.push_str(&tag("Artist")),
+                StatusToken::Album => s.push_str(&tag("Album")),
+                StatusToken::Title => {
+                    if let Some(song) = song {
+                        match song.music_metadata().standard_tags.get("TrackTitle") {
+                            Some(title) => s.push_str(title),
+                            None => s.push_str(song.file_name()),
+                        }
+                    }
+                }
+                StatusToken::FileName => {
+                    if let Some(song) = song {
+                        s.push_str(song.file_name());
+                    }
+                }
+                StatusToken::Elapsed => {
+                    s.push_str(&format::duration_to_string(player.info.elapsed))
+                }
+                StatusToken::Remaining => {
+                    s.push_str(&format::remaining_to_string(player.info.elapsed, duration))
+                }
+                StatusToken::Duration => s.push_str(&format::duration_to_string(duration)),
+                StatusToken::Volume => s.push_str(&player.info.volume.to_string()),
+                StatusToken::Status => s.push_str(&player.info.status.to_string()),
+                StatusToken::Flags => {
+                    s.push(if player.info.next { 'n' } else { '-' });
+                    s.push(match player.info.repeat {
+                        RepeatMode::Off => '-',
+                        RepeatMode::One => '1',
+                        RepeatMode::All => 'r',
+                    });
+                    s.push(if player.info.shuffle { 's' } else { '-' });
+                }
+            }
+        }
+        s
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_placeholders_and_text() {
+        let format = StatusFormat::parse("%artist - %title [%elapsed/%duration]").unwrap();
+        assert_eq!(
+            format.tokens,
+            vec![
+                StatusToken::Artist,
+                StatusToken::Text(" - ".to_string()),
+                StatusToken::Title,
+                StatusToken::Text(" [".to_string()),
+                StatusToken::Elapsed,
+                StatusToken::Text("/".to_string()),
+                StatusToken::Duration,
+                StatusToken::Text("]".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_escaped_percent() {
+        let format = StatusFormat::parse("vol:%volume%%").unwrap();
+        assert_eq!(
+            format.tokens,
+            vec![
+                StatusToken::Text("vol:".to_string()),
+                StatusToken::Volume,
+                StatusToken::Text("%".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_unknown_placeholder() {
+        assert!(StatusFormat::parse("%bitrate").is_err());
+        assert!(StatusFormat::parse("100%").is_err());
+    }
+
+    #[test]
+    fn render_without_song() {
+        let format = StatusFormat::parse("%status vol:%volume %flags").unwrap();
+        let player = PlayerState::default();
+        assert_eq!(format.render(&player), "stopped vol:50 n--");
+    }
+}
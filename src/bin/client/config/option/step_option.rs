@@ -0,0 +1,17 @@
+/// Step sizes of the volume and seek commands called without an amount
+#[derive(Clone, Debug)]
+pub struct StepOption {
+    // percent
+    pub volume: usize,
+    // seconds
+    pub seek: usize,
+}
+
+impl std::default::Default for StepOption {
+    fn default() -> Self {
+        Self {
+            volume: 1,
+            seek: 10,
+        }
+    }
+}
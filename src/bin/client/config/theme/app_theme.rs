@@ -13,6 +13,8 @@ pub struct AppThemeRaw {
     pub playing: AppStyleRaw,
     #[serde(default)]
     pub playlist: AppStyleRaw,
+    #[serde(default)]
+    pub selection: AppStyleRaw,
 
     #[serde(default)]
     pub regular: AppStyleRaw,
@@ -34,6 +36,7 @@ pub struct AppThemeRaw {
 pub struct AppTheme {
     pub playing: AppStyle,
     pub playlist: AppStyle,
+    pub selection: AppStyle,
 
     pub regular: AppStyle,
     pub directory: AppStyle,
@@ -48,6 +51,7 @@ impl From<AppThemeRaw> for AppTheme {
     fn from(raw: AppThemeRaw) -> Self {
         let playing = raw.playing.to_style_theme();
         let playlist = raw.playlist.to_style_theme();
+        let selection = raw.selection.to_style_theme();
 
         let executable = raw.executable.to_style_theme();
         let regular = raw.regular.to_style_theme();
@@ -67,6 +71,7 @@ impl From<AppThemeRaw> for AppTheme {
         Self {
             playing,
             playlist,
+            selection,
 
             executable,
             regular,
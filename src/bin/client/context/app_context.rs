@@ -1,15 +1,18 @@
 use ratatui::layout::Rect;
 use std::io;
-use std::os::unix::net::UnixStream;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::mpsc;
 
 use dizi::utils;
 
 use crate::config;
 use crate::config::option::WidgetType;
-use crate::context::{CommandLineContext, MessageQueue, ServerState, TabContext};
+use crate::context::{
+    CommandLineContext, LibraryContext, MacroContext, MessageQueue, MetadataCache, ServerState,
+    TabContext,
+};
 use crate::event::{AppEvent, Events};
+use crate::util::connection::ServerStream;
 use crate::util::search::SearchPattern;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -22,14 +25,46 @@ pub enum QuitType {
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct UiContext {
     pub layout: Vec<Rect>,
+    // how far the name at the cursor was scrolled
+    pub name_scroll: Option<NameScroll>,
+    // count typed before a command, e.g. the 10 of `10j`
+    pub count: Option<usize>,
+    // playlist entry being dragged with the mouse
+    pub drag: Option<PlaylistDrag>,
+}
+
+/// Moves the playlist entry at `from` to `to` once the mouse button is released
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PlaylistDrag {
+    pub from: usize,
+    pub to: usize,
+}
+
+/// Horizontal scroll of a long name, kept until the cursor moves to another entry
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct NameScroll {
+    pub path: PathBuf,
+    pub offset: usize,
+}
+
+impl NameScroll {
+    pub fn offset_for(&self, path: &Path) -> usize {
+        if self.path == path {
+            self.offset
+        } else {
+            0
+        }
+    }
 }
 
 pub struct AppContext {
     pub quit: QuitType,
     // event loop querying
     pub events: Events,
-    // server unix socket
-    pub stream: UnixStream,
+    // connection to the server
+    pub stream: ServerStream,
+    // whether the connection to the server was lost and is being retried
+    reconnecting: bool,
     pub view_widget: WidgetType,
     // app config
     config: config::AppConfig,
@@ -40,8 +75,14 @@ pub struct AppContext {
     commandline_context: CommandLineContext,
     // user interface context; data which is input to both, the UI rendering and the app state
     ui_context: UiContext,
+    // context related to the library view
+    library_context: LibraryContext,
+    // song metadata shown in the file browser
+    metadata_cache: MetadataCache,
     // context related to searching
     search_context: Option<SearchPattern>,
+    // recorded macros
+    macro_context: MacroContext,
     // message queue for displaying messages
     message_queue: MessageQueue,
     // server state
@@ -49,9 +90,11 @@ pub struct AppContext {
 }
 
 impl AppContext {
-    pub fn new(config: config::AppConfig, _cwd: PathBuf, stream: UnixStream) -> Self {
+    pub fn new(config: config::AppConfig, cwd: PathBuf, stream: ServerStream) -> Self {
         let events = Events::new();
 
+        let library_root = config.client_ref().home_dir.clone().unwrap_or(cwd);
+
         let mut commandline_context = CommandLineContext::new();
         commandline_context.history_mut().set_max_len(20);
 
@@ -59,18 +102,27 @@ impl AppContext {
             quit: QuitType::DoNot,
             config,
             stream,
+            reconnecting: false,
             view_widget: WidgetType::FileBrowser,
             events,
             commandline_context,
+            library_context: LibraryContext::new(library_root),
+            metadata_cache: MetadataCache::new(),
             search_context: None,
+            macro_context: MacroContext::new(),
             tab_context: TabContext::new(),
-            ui_context: UiContext { layout: vec![] },
+            ui_context: UiContext {
+                layout: vec![],
+                name_scroll: None,
+                count: None,
+                drag: None,
+            },
             message_queue: MessageQueue::new(),
             server_state: ServerState::new(),
         }
     }
 
-    pub fn clone_stream(&self) -> io::Result<UnixStream> {
+    pub fn clone_stream(&self) -> io::Result<ServerStream> {
         self.stream.try_clone()
     }
 
@@ -78,6 +130,13 @@ impl AppContext {
         utils::flush(&mut self.stream)
     }
 
+    pub fn is_reconnecting(&self) -> bool {
+        self.reconnecting
+    }
+    pub fn set_reconnecting(&mut self, reconnecting: bool) {
+        self.reconnecting = reconnecting;
+    }
+
     // event related
     pub fn poll_event(&self) -> Result<AppEvent, mpsc::RecvError> {
         self.events.next()
@@ -85,6 +144,9 @@ impl AppContext {
     pub fn flush_event(&self) {
         self.events.flush();
     }
+    pub fn set_ticking(&self, ticking: bool) {
+        self.events.set_ticking(ticking);
+    }
     pub fn clone_event_tx(&self) -> mpsc::Sender<AppEvent> {
         self.events.event_tx.clone()
     }
@@ -117,12 +179,36 @@ impl AppContext {
         &mut self.tab_context
     }
 
+    pub fn library_context_ref(&self) -> &LibraryContext {
+        &self.library_context
+    }
+    pub fn library_context_mut(&mut self) -> &mut LibraryContext {
+        &mut self.library_context
+    }
+
+    pub fn metadata_cache_ref(&self) -> &MetadataCache {
+        &self.metadata_cache
+    }
+    pub fn metadata_cache_mut(&mut self) -> &mut MetadataCache {
+        &mut self.metadata_cache
+    }
+
     pub fn get_search_context(&self) -> Option<&SearchPattern> {
         self.search_context.as_ref()
     }
     pub fn set_search_context(&mut self, pattern: SearchPattern) {
         self.search_context = Some(pattern);
     }
+    pub fn clear_search_context(&mut self) {
+        self.search_context = None;
+    }
+
+    pub fn macro_context_ref(&self) -> &MacroContext {
+        &self.macro_context
+    }
+    pub fn macro_context_mut(&mut self) -> &mut MacroContext {
+        &mut self.macro_context
+    }
 
     pub fn ui_context_ref(&self) -> &UiContext {
         &self.ui_context
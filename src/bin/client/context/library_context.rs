@@ -0,0 +1,105 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::util::audio::is_audio_file;
+
+#[derive(Clone, Debug, Default)]
+pub struct LibraryContext {
+    root: PathBuf,
+    songs: Vec<PathBuf>,
+    index: Option<usize>,
+    loading: bool,
+    loaded: bool,
+}
+
+impl LibraryContext {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            ..Self::default()
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        self.root.as_path()
+    }
+
+    pub fn songs_ref(&self) -> &[PathBuf] {
+        &self.songs
+    }
+
+    pub fn set_songs(&mut self, songs: Vec<PathBuf>) {
+        self.index = if songs.is_empty() { None } else { Some(0) };
+        self.songs = songs;
+        self.loading = false;
+        self.loaded = true;
+    }
+
+    pub fn len(&self) -> usize {
+        self.songs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.songs.is_empty()
+    }
+
+    pub fn is_loading(&self) -> bool {
+        self.loading
+    }
+    pub fn set_loading(&mut self, loading: bool) {
+        self.loading = loading;
+    }
+
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
+    pub fn get_index(&self) -> Option<usize> {
+        self.index
+    }
+    pub fn set_index(&mut self, index: Option<usize>) {
+        self.index = index;
+    }
+
+    pub fn curr_song_ref(&self) -> Option<&Path> {
+        self.index
+            .and_then(|i| self.songs.get(i))
+            .map(|p| p.as_path())
+    }
+
+    pub fn first_index_for_viewport(&self, viewport_height: usize) -> usize {
+        match self.index {
+            Some(index) => index / viewport_height * viewport_height,
+            None => 0,
+        }
+    }
+}
+
+/// Recursively collects every audio file under `root`, sorted by path
+pub fn read_library(root: &Path) -> io::Result<Vec<PathBuf>> {
+    let mut songs = Vec::new();
+    let mut directories = vec![root.to_path_buf()];
+
+    while let Some(dir) = directories.pop() {
+        let entries = match fs::read_dir(&dir) {
+            Ok(entries) => entries,
+            Err(e) if dir == root => return Err(e),
+            Err(_) => continue,
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let is_hidden = entry.file_name().to_string_lossy().starts_with('.');
+            if is_hidden {
+                continue;
+            }
+            if path.is_dir() {
+                directories.push(path);
+            } else if is_audio_file(&path) {
+                songs.push(path);
+            }
+        }
+    }
+    songs.sort();
+    Ok(songs)
+}
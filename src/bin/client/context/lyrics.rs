@@ -0,0 +1,158 @@
+use std::path::{Path, PathBuf};
+use std::time;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LyricsLine {
+    pub time: Option<time::Duration>,
+    pub text: String,
+}
+
+/// Lyrics of a song, synced when they come with LRC timestamps
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Lyrics {
+    lines: Vec<LyricsLine>,
+    synced: bool,
+}
+
+impl Lyrics {
+    /// Accepts plain text as well as LRC, e.g. `[01:02.50]some words`
+    pub fn parse(s: &str) -> Self {
+        let mut lines = Vec::new();
+        let mut plain = Vec::new();
+        for line in s.lines() {
+            let (times, text) = split_timestamps(line);
+            if times.is_empty() {
+                // ID tags such as `[ar:Artist]` are not part of the lyrics
+                if !is_id_tag(line) {
+                    plain.push(LyricsLine {
+                        time: None,
+                        text: line.trim_end().to_string(),
+                    });
+                }
+                continue;
+            }
+            for time in times {
+                lines.push(LyricsLine {
+                    time: Some(time),
+                    text: text.trim().to_string(),
+                });
+            }
+        }
+
+        if lines.is_empty() {
+            return Self {
+                lines: plain,
+                synced: false,
+            };
+        }
+        lines.sort_by_key(|line| line.time);
+        Self {
+            lines,
+            synced: true,
+        }
+    }
+
+    pub fn lines(&self) -> &[LyricsLine] {
+        &self.lines
+    }
+
+    /// Index of the line being sung at `elapsed`, only for synced lyrics
+    pub fn current_line(&self, elapsed: time::Duration) -> Option<usize> {
+        if !self.synced {
+            return None;
+        }
+        let index = self
+            .lines
+            .partition_point(|line| line.time.is_some_and(|time| time <= elapsed));
+        index.checked_sub(1)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum LyricsState {
+    Loading,
+    Missing,
+    Loaded(Lyrics),
+}
+
+/// Lyrics of the song they were asked for
+#[derive(Clone, Debug)]
+pub struct SongLyrics {
+    pub path: PathBuf,
+    pub state: LyricsState,
+}
+
+impl SongLyrics {
+    pub fn is_for(&self, path: &Path) -> bool {
+        self.path == path
+    }
+}
+
+/// Splits the leading `[mm:ss.xx]` timestamps off a line
+fn split_timestamps(line: &str) -> (Vec<time::Duration>, &str) {
+    let mut times = Vec::new();
+    let mut rest = line.trim_start();
+    while let Some(stripped) = rest.strip_prefix('[') {
+        let end = match stripped.find(']') {
+            Some(end) => end,
+            None => break,
+        };
+        match parse_timestamp(&stripped[..end]) {
+            Some(time) => times.push(time),
+            None => break,
+        }
+        rest = &stripped[end + 1..];
+    }
+    (times, rest)
+}
+
+fn parse_timestamp(s: &str) -> Option<time::Duration> {
+    let (minutes, seconds) = s.split_once(':')?;
+    let minutes: u64 = minutes.parse().ok()?;
+    // the fraction is separated by '.' or, in some files, by ':'
+    let seconds: f64 = seconds.replacen(':', ".", 1).parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    Some(time::Duration::from_secs(minutes * 60) + time::Duration::from_secs_f64(seconds))
+}
+
+fn is_id_tag(line: &str) -> bool {
+    let line = line.trim();
+    line.starts_with('[') && line.ends_with(']') && line.contains(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_synced_lyrics() {
+        let lyrics = Lyrics::parse("[ar:Someone]\n[00:12.50]first\n[00:05.00][00:20]second\n");
+        assert!(lyrics.synced);
+        let texts: Vec<&str> = lyrics.lines().iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(texts, vec!["second", "first", "second"]);
+        assert_eq!(
+            lyrics.lines()[1].time,
+            Some(time::Duration::from_millis(12_500))
+        );
+    }
+
+    #[test]
+    fn parse_plain_lyrics() {
+        let lyrics = Lyrics::parse("first\n\nsecond");
+        assert!(!lyrics.synced);
+        assert_eq!(lyrics.lines().len(), 3);
+        assert_eq!(lyrics.current_line(time::Duration::from_secs(10)), None);
+    }
+
+    #[test]
+    fn current_line_follows_elapsed() {
+        let lyrics = Lyrics::parse("[00:05.00]one\n[00:10.00]two\n[01:00.00]three");
+        let at = |secs| lyrics.current_line(time::Duration::from_secs(secs));
+        assert_eq!(at(0), None);
+        assert_eq!(at(5), Some(0));
+        assert_eq!(at(30), Some(1));
+        assert_eq!(at(90), Some(2));
+    }
+}
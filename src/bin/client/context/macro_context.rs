@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use crate::key_command::Command;
+
+/// Commands recorded into registers with `macro_record`, replayed with `macro_play`
+#[derive(Clone, Debug, Default)]
+pub struct MacroContext {
+    registers: HashMap<char, Vec<Command>>,
+    // register being recorded into and the commands recorded so far
+    recording: Option<(char, Vec<Command>)>,
+}
+
+impl MacroContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn recording_register(&self) -> Option<char> {
+        self.recording.as_ref().map(|(register, _)| *register)
+    }
+
+    pub fn start_recording(&mut self, register: char) {
+        self.recording = Some((register, Vec::new()));
+    }
+
+    /// Stores the recorded commands in their register, returning it and how many were recorded
+    pub fn stop_recording(&mut self) -> Option<(char, usize)> {
+        let (register, commands) = self.recording.take()?;
+        let len = commands.len();
+        self.registers.insert(register, commands);
+        Some((register, len))
+    }
+
+    /// Adds `command` to the macro being recorded, if any.
+    /// Macro commands are left out, so a macro can't replay itself
+    pub fn record(&mut self, command: &Command) {
+        let commands = match self.recording.as_mut() {
+            Some((_, commands)) => commands,
+            None => return,
+        };
+        match command {
+            Command::MacroRecord(_) | Command::MacroPlay(..) | Command::CommandLine(..) => {}
+            command => commands.push(command.clone()),
+        }
+    }
+
+    pub fn register_ref(&self, register: char) -> Option<&[Command]> {
+        self.registers
+            .get(&register)
+            .map(|commands| commands.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn record_into_register() {
+        let mut macros = MacroContext::new();
+        macros.record(&Command::ToggleView);
+        assert_eq!(macros.recording_register(), None);
+
+        macros.start_recording('a');
+        macros.record(&Command::CursorMoveDown(2));
+        macros.record(&Command::MacroPlay('a', 1));
+        macros.record(&Command::ToggleView);
+        assert_eq!(macros.stop_recording(), Some(('a', 2)));
+
+        let commands: Vec<String> = macros
+            .register_ref('a')
+            .unwrap()
+            .iter()
+            .map(|c| c.to_string())
+            .collect();
+        assert_eq!(commands, vec!["cursor_move_down 2", "toggle_view"]);
+        assert!(macros.register_ref('b').is_none());
+    }
+}
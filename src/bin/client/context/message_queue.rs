@@ -1,22 +1,59 @@
 use std::collections::VecDeque;
+use std::time;
 
+use chrono::{DateTime, Local};
 use ratatui::style::{Color, Style};
 
-#[derive(Clone, Debug, Default)]
+const MESSAGE_LOG_CAPACITY: usize = 256;
+
+// how long a notification is shown, the last part of it faded
+const NOTIFICATION_DURATION: time::Duration = time::Duration::from_secs(3);
+const NOTIFICATION_FADE: time::Duration = time::Duration::from_secs(1);
+
+#[derive(Clone, Debug)]
 pub struct Message {
     pub content: String,
     pub style: Style,
+    pub time: DateTime<Local>,
 }
 
 impl Message {
     pub fn new(content: String, style: Style) -> Self {
-        Self { content, style }
+        Self {
+            content,
+            style,
+            time: Local::now(),
+        }
+    }
+}
+
+/// A success or info message, shown for a few seconds
+#[derive(Clone, Debug)]
+pub struct Notification {
+    pub message: Message,
+    // notifications replaced by this one while they were still shown
+    pub suppressed: usize,
+    shown: time::Instant,
+}
+
+impl Notification {
+    fn is_expired(&self) -> bool {
+        self.shown.elapsed() >= NOTIFICATION_DURATION
+    }
+
+    pub fn is_fading(&self) -> bool {
+        self.shown.elapsed() >= NOTIFICATION_DURATION - NOTIFICATION_FADE
     }
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct MessageQueue {
+    // errors, shown until the next key press
     contents: VecDeque<Message>,
+    notification: Option<Notification>,
+    // every message pushed so far, kept for the message log view
+    history: VecDeque<Message>,
+    history_index: Option<usize>,
 }
 
 impl MessageQueue {
@@ -26,21 +63,38 @@ impl MessageQueue {
 
     pub fn push_success(&mut self, msg: String) {
         let message = Message::new(msg, Style::default().fg(Color::Green));
-        self.push_msg(message);
+        self.push_notification(message);
     }
 
     pub fn push_info(&mut self, msg: String) {
         let message = Message::new(msg, Style::default().fg(Color::Yellow));
-        self.push_msg(message);
+        self.push_notification(message);
     }
 
     pub fn push_error(&mut self, msg: String) {
         let message = Message::new(msg, Style::default().fg(Color::Red));
-        self.push_msg(message);
+        self.push_history(message.clone());
+        self.contents.push_back(message);
+    }
+
+    fn push_notification(&mut self, msg: Message) {
+        self.push_history(msg.clone());
+        let suppressed = match self.notification_ref() {
+            Some(notification) => notification.suppressed + 1,
+            None => 0,
+        };
+        self.notification = Some(Notification {
+            message: msg,
+            suppressed,
+            shown: time::Instant::now(),
+        });
     }
 
-    fn push_msg(&mut self, msg: Message) {
-        self.contents.push_back(msg);
+    fn push_history(&mut self, msg: Message) {
+        if self.history.len() >= MESSAGE_LOG_CAPACITY {
+            self.history.pop_front();
+        }
+        self.history.push_back(msg);
     }
 
     pub fn pop_front(&mut self) -> Option<Message> {
@@ -50,4 +104,44 @@ impl MessageQueue {
     pub fn current_message(&self) -> Option<&Message> {
         self.contents.front()
     }
+
+    /// The latest notification, until it expires
+    pub fn notification_ref(&self) -> Option<&Notification> {
+        self.notification.as_ref().filter(|n| !n.is_expired())
+    }
+
+    pub fn history_ref(&self) -> &VecDeque<Message> {
+        &self.history
+    }
+
+    pub fn get_history_index(&self) -> Option<usize> {
+        match self.history_index {
+            Some(i) if i < self.history.len() => Some(i),
+            // default to the most recent message
+            _ => self.history.len().checked_sub(1),
+        }
+    }
+    pub fn set_history_index(&mut self, index: Option<usize>) {
+        self.history_index = index;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn notifications_count_suppressed() {
+        let mut queue = MessageQueue::new();
+        queue.push_success("Added 42 songs to playlist".to_string());
+        queue.push_info("Volume 80%".to_string());
+        queue.push_error("Failed".to_string());
+
+        let notification = queue.notification_ref().unwrap();
+        assert_eq!(notification.message.content, "Volume 80%");
+        assert_eq!(notification.suppressed, 1);
+        // errors wait for a key press instead
+        assert_eq!(queue.current_message().unwrap().content, "Failed");
+        assert_eq!(queue.history_ref().len(), 3);
+    }
 }
@@ -0,0 +1,59 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::time;
+
+use dizi::song::DiziAudioFile;
+
+/// The subset of a song's metadata shown in the file browser
+#[derive(Clone, Debug, Default)]
+pub struct SongMetadata {
+    pub duration: Option<time::Duration>,
+    pub track: Option<String>,
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    pub album: Option<String>,
+}
+
+impl From<&DiziAudioFile> for SongMetadata {
+    fn from(song: &DiziAudioFile) -> Self {
+        let tags = &song.music_metadata().standard_tags;
+        Self {
+            duration: song.audio_metadata().total_duration,
+            track: tags.get("TrackNumber").cloned(),
+            artist: tags.get("Artist").cloned(),
+            title: tags.get("TrackTitle").cloned(),
+            album: tags.get("Album").cloned(),
+        }
+    }
+}
+
+/// Metadata of songs read in the background, kept for the whole session
+#[derive(Debug, Default)]
+pub struct MetadataCache {
+    entries: HashMap<PathBuf, SongMetadata>,
+    pending: HashSet<PathBuf>,
+}
+
+impl MetadataCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, path: &Path) -> Option<&SongMetadata> {
+        self.entries.get(path)
+    }
+
+    /// Whether `path` has been loaded or is currently being loaded
+    pub fn contains(&self, path: &Path) -> bool {
+        self.entries.contains_key(path) || self.pending.contains(path)
+    }
+
+    pub fn set_pending(&mut self, path: PathBuf) {
+        self.pending.insert(path);
+    }
+
+    pub fn insert(&mut self, path: PathBuf, metadata: SongMetadata) {
+        self.pending.remove(&path);
+        self.entries.insert(path, metadata);
+    }
+}
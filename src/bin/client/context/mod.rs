@@ -1,11 +1,21 @@
 mod app_context;
 mod commandline_context;
+mod library_context;
+mod lyrics;
+mod macro_context;
 mod message_queue;
+mod metadata_cache;
 mod server_state;
+mod session;
 mod tab_context;
 
 pub use self::app_context::*;
 pub use self::commandline_context::*;
+pub use self::library_context::*;
+pub use self::lyrics::*;
+pub use self::macro_context::*;
 pub use self::message_queue::*;
+pub use self::metadata_cache::*;
 pub use self::server_state::*;
+pub use self::session::*;
 pub use self::tab_context::*;
@@ -1,14 +1,249 @@
-use dizi::player::PlayerState;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time;
+
+use dizi::player::{PlayerState, PlayerStatus};
+use dizi::playlist::{FilePlaylist, PlaylistType};
+use dizi::song::DiziSongEntry;
+
+use crate::context::{Lyrics, LyricsState, SongLyrics};
 
 #[derive(Clone, Debug)]
 pub struct ServerState {
     pub player: PlayerState,
+    // latest frequency band levels sent by the server
+    pub spectrum: Vec<u8>,
+    // combined length of the songs in the playlist
+    playlist_duration: time::Duration,
+    // combined length of the songs played after the current one
+    upcoming_duration: time::Duration,
+    // directories this client asked the server to append recursively
+    scans: Vec<PathBuf>,
+    // last elapsed time reported by the server and when it was received
+    progress: (time::Duration, time::Instant),
+    // song ratings stored by the server
+    ratings: HashMap<PathBuf, u8>,
+    // lyrics of the playing song, once they have been asked for
+    lyrics: Option<SongLyrics>,
+    // peaks of the playing song, empty until the server answers
+    waveform: Option<(PathBuf, Vec<u8>)>,
+    // whether the playlist view shows the directory playlist instead of the file playlist
+    show_directory_playlist: bool,
 }
 
 impl ServerState {
     pub fn new() -> Self {
         Self {
             player: PlayerState::new(),
+            spectrum: Vec::new(),
+            playlist_duration: time::Duration::ZERO,
+            upcoming_duration: time::Duration::ZERO,
+            scans: Vec::new(),
+            progress: (time::Duration::ZERO, time::Instant::now()),
+            ratings: HashMap::new(),
+            lyrics: None,
+            waveform: None,
+            show_directory_playlist: false,
+        }
+    }
+
+    /// Sets the elapsed time as reported by the server
+    pub fn set_elapsed(&mut self, elapsed: time::Duration) {
+        self.player.info.elapsed = elapsed;
+        self.progress = (elapsed, time::Instant::now());
+    }
+
+    /// Advances the elapsed time from the last server update while playing,
+    /// so progress moves between broadcasts
+    pub fn interpolate_elapsed(&mut self) {
+        if self.player.info.status != PlayerStatus::Playing {
+            return;
+        }
+        let (elapsed, received) = self.progress;
+        let mut elapsed = elapsed + received.elapsed();
+        if let Some(total) = self.player.info.total_duration() {
+            elapsed = elapsed.min(total);
+        }
+        self.player.info.elapsed = elapsed;
+    }
+
+    pub fn is_scanning(&self) -> bool {
+        !self.scans.is_empty()
+    }
+
+    pub fn add_scan(&mut self, path: PathBuf) {
+        self.scans.push(path);
+    }
+
+    pub fn clear_scans(&mut self) {
+        self.scans.clear();
+    }
+
+    pub fn finish_scan(&mut self, path: &Path) {
+        if let Some(index) = self.scans.iter().position(|p| p == path) {
+            self.scans.remove(index);
+        }
+    }
+
+    /// Path of the playing song, whether it is played from the playlist or a directory
+    pub fn playing_path(&self) -> Option<&Path> {
+        match self.player.info.playlist_status {
+            PlaylistType::PlaylistFile => {
+                let playlist = &self.player.playlist;
+                playlist
+                    .get_playing_index()
+                    .and_then(|index| playlist.list_ref().get(index))
+                    .map(|entry| entry.file_path())
+            }
+            PlaylistType::DirectoryListing => {
+                self.player.info.song.as_ref().map(|song| song.file_path())
+            }
+        }
+    }
+
+    pub fn shows_directory_playlist(&self) -> bool {
+        self.show_directory_playlist
+    }
+
+    pub fn set_show_directory_playlist(&mut self, show: bool) {
+        self.show_directory_playlist = show;
+    }
+
+    /// Playlist shown in the playlist view
+    pub fn playlist_ref(&self) -> &FilePlaylist {
+        if self.show_directory_playlist {
+            &self.player.directory_playlist
+        } else {
+            &self.player.playlist
         }
     }
+
+    pub fn playlist_mut(&mut self) -> &mut FilePlaylist {
+        if self.show_directory_playlist {
+            &mut self.player.directory_playlist
+        } else {
+            &mut self.player.playlist
+        }
+    }
+
+    /// Marks the song at `path` as playing in the directory playlist
+    pub fn set_directory_playing(&mut self, path: &Path) {
+        let playlist = &mut self.player.directory_playlist;
+        let index = playlist
+            .list_ref()
+            .iter()
+            .position(|entry| entry.file_path() == path);
+        playlist.set_playing_index(index);
+    }
+
+    pub fn lyrics_ref(&self) -> Option<&SongLyrics> {
+        self.lyrics.as_ref()
+    }
+
+    pub fn set_lyrics_loading(&mut self, path: PathBuf) {
+        self.lyrics = Some(SongLyrics {
+            path,
+            state: LyricsState::Loading,
+        });
+    }
+
+    /// Ignores lyrics of songs other than the one they were last asked for
+    pub fn set_lyrics(&mut self, path: PathBuf, lyrics: Option<String>) {
+        if !self.lyrics.as_ref().is_some_and(|l| l.is_for(&path)) {
+            return;
+        }
+        let state = match lyrics {
+            Some(lyrics) => LyricsState::Loaded(Lyrics::parse(&lyrics)),
+            None => LyricsState::Missing,
+        };
+        self.lyrics = Some(SongLyrics { path, state });
+    }
+
+    /// The waveform asked for, if it is of the song at `path` and has arrived
+    pub fn waveform_of(&self, path: &Path) -> Option<&[u8]> {
+        match self.waveform.as_ref() {
+            Some((p, peaks)) if p == path && !peaks.is_empty() => Some(peaks),
+            _ => None,
+        }
+    }
+
+    pub fn is_waveform_for(&self, path: &Path) -> bool {
+        self.waveform.as_ref().is_some_and(|(p, _)| p == path)
+    }
+
+    pub fn set_waveform_loading(&mut self, path: PathBuf) {
+        self.waveform = Some((path, Vec::new()));
+    }
+
+    /// Ignores waveforms of songs other than the one it was last asked for
+    pub fn set_waveform(&mut self, path: PathBuf, peaks: Vec<u8>) {
+        if self.is_waveform_for(&path) {
+            self.waveform = Some((path, peaks));
+        }
+    }
+
+    pub fn ratings_ref(&self) -> &HashMap<PathBuf, u8> {
+        &self.ratings
+    }
+
+    pub fn set_ratings(&mut self, ratings: HashMap<PathBuf, u8>) {
+        self.ratings = ratings;
+    }
+
+    /// A rating of 0 removes the song's rating
+    pub fn set_rating(&mut self, path: PathBuf, rating: u8) {
+        if rating == 0 {
+            self.ratings.remove(&path);
+        } else {
+            self.ratings.insert(path, rating);
+        }
+    }
+
+    pub fn playlist_duration(&self) -> time::Duration {
+        self.playlist_duration
+    }
+
+    /// Time left until the end of the playlist, including the playing song
+    pub fn playlist_remaining(&self) -> time::Duration {
+        let current = match self.player.info.playlist_status {
+            PlaylistType::PlaylistFile => self
+                .player
+                .info
+                .total_duration()
+                .map(|duration| duration.saturating_sub(self.player.info.elapsed))
+                .unwrap_or_default(),
+            PlaylistType::DirectoryListing => time::Duration::ZERO,
+        };
+        self.upcoming_duration + current
+    }
+
+    /// Recomputes the playlist's length after it was replaced
+    pub fn reset_playlist_duration(&mut self) {
+        self.playlist_duration = total_duration(self.player.playlist.list_ref().iter());
+        self.update_upcoming_duration();
+    }
+
+    /// Only updates the total, call `update_upcoming_duration` once the playlist changed
+    pub fn add_playlist_duration(&mut self, entries: &[DiziSongEntry]) {
+        self.playlist_duration += total_duration(entries.iter());
+    }
+
+    pub fn remove_playlist_duration(&mut self, entry: &DiziSongEntry) {
+        if let Some(duration) = entry.duration() {
+            self.playlist_duration = self.playlist_duration.saturating_sub(duration);
+        }
+    }
+
+    /// Recomputes the length of the upcoming songs, for when the playing song
+    /// or the play order changed
+    pub fn update_upcoming_duration(&mut self) {
+        let playlist = &self.player.playlist;
+        let upcoming = playlist.up_next(playlist.len());
+        self.upcoming_duration =
+            total_duration(upcoming.into_iter().map(|i| &playlist.list_ref()[i]));
+    }
+}
+
+fn total_duration<'a>(entries: impl Iterator<Item = &'a DiziSongEntry>) -> time::Duration {
+    entries.filter_map(|entry| entry.duration()).sum()
 }
@@ -0,0 +1,45 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use dizi::error::DiziResult;
+
+/// Where the client was left, restored on the next launch
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Session {
+    pub cwd: PathBuf,
+    // entry at the cursor in the file browser
+    #[serde(default)]
+    pub cursor: Option<PathBuf>,
+    #[serde(default)]
+    pub view: Option<String>,
+}
+
+impl Session {
+    pub fn from_file(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let session = serde_json::from_str(&contents)?;
+        Ok(session)
+    }
+
+    pub fn save(&self, path: &Path) -> DiziResult {
+        let contents = serde_json::to_string(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_session_without_cursor() {
+        let session: Session = serde_json::from_str(r#"{"cwd":"/music"}"#).unwrap();
+        assert_eq!(session.cwd, PathBuf::from("/music"));
+        assert_eq!(session.cursor, None);
+        assert_eq!(session.view, None);
+    }
+}
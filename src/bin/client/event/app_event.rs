@@ -1,7 +1,9 @@
 use std::io;
 use std::path;
-use std::sync::mpsc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
 use std::thread;
+use std::time;
 
 use signal_hook::consts::signal;
 use signal_hook::iterator::exfiltrator::SignalOnly;
@@ -10,7 +12,9 @@ use signal_hook::iterator::SignalsInfo;
 use termion::event::Event;
 use termion::input::TermRead;
 
+use crate::context::SongMetadata;
 use crate::fs::JoshutoDirList;
+use crate::util::connection::ServerStream;
 
 #[derive(Debug)]
 pub enum AppEvent {
@@ -20,10 +24,27 @@ pub enum AppEvent {
         path: path::PathBuf,
         res: Box<io::Result<JoshutoDirList>>,
     },
+    // library thread events
+    LibraryLoad {
+        root: path::PathBuf,
+        res: Box<io::Result<Vec<path::PathBuf>>>,
+    },
+    // song metadata thread events
+    MetadataLoad {
+        path: path::PathBuf,
+        metadata: SongMetadata,
+    },
     Signal(i32),
     Server(String),
+    // the connection to the server was lost
+    ServerDisconnect,
+    ServerReconnect(ServerStream),
+    // redraws the ui while playing or while something is animating
+    Tick,
 }
 
+const TICK_INTERVAL: time::Duration = time::Duration::from_millis(100);
+
 #[derive(Debug, Default, Clone, Copy)]
 pub struct Config {}
 
@@ -31,6 +52,7 @@ pub struct Events {
     pub event_tx: mpsc::Sender<AppEvent>,
     event_rx: mpsc::Receiver<AppEvent>,
     pub input_tx: mpsc::Sender<()>,
+    ticking: Arc<AtomicBool>,
 }
 
 impl Events {
@@ -58,6 +80,17 @@ impl Events {
             }
         });
 
+        // tick thread, only sends ticks while enabled
+        let ticking = Arc::new(AtomicBool::new(false));
+        let event_tx2 = event_tx.clone();
+        let ticking2 = ticking.clone();
+        let _ = thread::spawn(move || loop {
+            thread::sleep(TICK_INTERVAL);
+            if ticking2.load(Ordering::Relaxed) && event_tx2.send(AppEvent::Tick).is_err() {
+                return;
+            }
+        });
+
         // input thread
         let event_tx2 = event_tx.clone();
         let _ = thread::spawn(move || {
@@ -76,6 +109,7 @@ impl Events {
             event_tx,
             event_rx,
             input_tx,
+            ticking,
         }
     }
 
@@ -87,6 +121,10 @@ impl Events {
         Ok(event)
     }
 
+    pub fn set_ticking(&self, ticking: bool) {
+        self.ticking.store(ticking, Ordering::Relaxed);
+    }
+
     pub fn flush(&self) {
         loop {
             if self.input_tx.send(()).is_ok() {
@@ -1,4 +1,5 @@
 pub mod app_event;
 pub mod process_event;
+pub mod process_mouse;
 
 pub use self::app_event::*;
@@ -1,15 +1,17 @@
 use std::io;
 use std::path;
+use std::time;
 
-use dizi::song::DiziSongEntry;
+use dizi::song::{DiziSongEntry, ReplayGainMode};
 use signal_hook::consts::signal;
 use termion::event::{Event, Key};
 
 use dizi::error::DiziResult;
-use dizi::player::PlayerStatus;
-use dizi::playlist::PlaylistType;
+use dizi::player::{PlayerStatus, RepeatMode};
+use dizi::playlist::{FilePlaylist, PlaylistType};
 use dizi::response::server::ServerBroadcastEvent;
 
+use crate::commands::goto;
 use crate::config::option::WidgetType;
 use crate::config::KeyMapping;
 use crate::context::{AppContext, QuitType};
@@ -18,6 +20,8 @@ use crate::fs::JoshutoDirList;
 use crate::key_command::{Command, CommandKeybind};
 use crate::ui;
 use crate::ui::views::TuiCommandMenu;
+use crate::util::format;
+use crate::MESSAGES_T;
 
 pub fn get_input_while_composite<'a>(
     backend: &mut ui::AppBackend,
@@ -64,7 +68,7 @@ pub fn process_server_event(context: &mut AppContext, s: &str) -> DiziResult {
         ServerBroadcastEvent::ServerError { msg } => {
             context
                 .message_queue_mut()
-                .push_error(format!("Server: {}", msg));
+                .push_error(MESSAGES_T.format("message.server_error", &[("msg", &msg)]));
         }
         ServerBroadcastEvent::ServerQuery { .. } => {}
         ServerBroadcastEvent::ServerQueryAll { .. } => {}
@@ -86,7 +90,11 @@ pub fn process_server_event(context: &mut AppContext, s: &str) -> DiziResult {
                     .unwrap_or_else(|| 0);
                 state.playlist.set_cursor_index(Some(new_cursor_index));
             }
+            set_directory_cursor(&mut state.directory_playlist);
+            let elapsed = state.info.elapsed;
             context.server_state_mut().player = state;
+            context.server_state_mut().set_elapsed(elapsed);
+            context.server_state_mut().reset_playlist_duration();
         }
         ServerBroadcastEvent::PlaylistOpen { mut state } => {
             if !state.playlist.is_empty() {
@@ -106,76 +114,148 @@ pub fn process_server_event(context: &mut AppContext, s: &str) -> DiziResult {
                     .unwrap_or_else(|| 0);
                 state.playlist.set_cursor_index(Some(new_cursor_index));
             }
+            set_directory_cursor(&mut state.directory_playlist);
             context.server_state_mut().player = state;
+            context.server_state_mut().reset_playlist_duration();
             let len = context.server_state_ref().player.playlist.len();
             context
                 .message_queue_mut()
-                .push_success(format!("Loaded {} songs to playlist", len));
+                .push_success(MESSAGES_T.format("message.playlist_loaded", &[("count", &len)]));
         }
         ServerBroadcastEvent::PlayerFilePlay { file: song } => {
-            context.server_state_mut().player.song = Some(song);
-            context.server_state_mut().player.status = PlayerStatus::Playing;
-            context.server_state_mut().player.playlist_status = PlaylistType::DirectoryListing;
+            context.server_state_mut().spectrum.clear();
+            context
+                .server_state_mut()
+                .set_directory_playing(song.file_path());
+            context.server_state_mut().player.info.song = Some(song);
+            context.server_state_mut().player.info.duration = None;
+            // until the server says it plays
+            context.server_state_mut().player.info.status = PlayerStatus::Loading;
+            context.server_state_mut().set_elapsed(time::Duration::ZERO);
+            context.server_state_mut().player.info.playlist_status = PlaylistType::DirectoryListing;
+            if context.config_ref().display_options_ref().follow_playing() {
+                goto::follow_playing(context)?;
+            }
         }
         ServerBroadcastEvent::PlayerPause => {
-            context.server_state_mut().player.status = PlayerStatus::Paused;
+            context.server_state_mut().spectrum.clear();
+            context.server_state_mut().player.info.status = PlayerStatus::Paused;
         }
         ServerBroadcastEvent::PlayerResume => {
-            context.server_state_mut().player.status = PlayerStatus::Playing;
+            // continue from where the progress stopped while paused
+            let elapsed = context.server_state_ref().player.info.elapsed;
+            context.server_state_mut().set_elapsed(elapsed);
+            context.server_state_mut().player.info.status = PlayerStatus::Playing;
         }
         ServerBroadcastEvent::PlayerStop => {
-            context.server_state_mut().player.status = PlayerStatus::Stopped;
+            context.server_state_mut().spectrum.clear();
+            context.server_state_mut().player.info.status = PlayerStatus::Stopped;
         }
         ServerBroadcastEvent::PlayerShuffle { on } => {
-            context.server_state_mut().player.shuffle = on;
-            let setting = "Shuffle";
-            let status = if on { "ON" } else { "OFF" };
+            context.server_state_mut().player.info.shuffle = on;
+            let status = MESSAGES_T.get(if on { "message.on" } else { "message.off" });
             context
                 .message_queue_mut()
-                .push_success(format!("{} {}", setting, status));
+                .push_success(MESSAGES_T.format("message.shuffle", &[("status", &status)]));
         }
-        ServerBroadcastEvent::PlayerRepeat { on } => {
-            context.server_state_mut().player.repeat = on;
-            let setting = "Repeat";
-            let status = if on { "ON" } else { "OFF" };
+        ServerBroadcastEvent::PlayerRepeat { mode } => {
+            context.server_state_mut().player.info.repeat = mode;
+            let message = MESSAGES_T.get(match mode {
+                RepeatMode::Off => "message.repeat_off",
+                RepeatMode::One => "message.repeat_one",
+                RepeatMode::All => "message.repeat_all",
+            });
             context
                 .message_queue_mut()
-                .push_success(format!("{} {}", setting, status));
+                .push_success(message.to_string());
         }
         ServerBroadcastEvent::PlayerNext { on } => {
-            context.server_state_mut().player.next = on;
-            let setting = "Next";
-            let status = if on { "ON" } else { "OFF" };
+            context.server_state_mut().player.info.next = on;
+            let status = MESSAGES_T.get(if on { "message.on" } else { "message.off" });
+            context
+                .message_queue_mut()
+                .push_success(MESSAGES_T.format("message.next", &[("status", &status)]));
+        }
+        ServerBroadcastEvent::PlayerSingle { on } => {
+            context.server_state_mut().player.info.single = on;
+            let status = MESSAGES_T.get(if on { "message.on" } else { "message.off" });
             context
                 .message_queue_mut()
-                .push_success(format!("{} {}", setting, status));
+                .push_success(MESSAGES_T.format("message.single", &[("status", &status)]));
+        }
+        ServerBroadcastEvent::PlayerReplayGain { mode } => {
+            context.server_state_mut().player.info.replaygain = mode;
+            let message = MESSAGES_T.get(match mode {
+                ReplayGainMode::Off => "message.replaygain_off",
+                ReplayGainMode::Track => "message.replaygain_track",
+                ReplayGainMode::Album => "message.replaygain_album",
+            });
+            context
+                .message_queue_mut()
+                .push_success(message.to_string());
+        }
+        ServerBroadcastEvent::PlayerEqState { bands } => {
+            let bands: Vec<String> = bands
+                .iter()
+                .map(|band| format!("{}:{:+}", band.frequency, band.gain))
+                .collect();
+            context
+                .message_queue_mut()
+                .push_info(MESSAGES_T.format("message.equalizer", &[("bands", &bands.join(" "))]));
+        }
+        ServerBroadcastEvent::QueueState { songs } => {
+            context
+                .message_queue_mut()
+                .push_info(MESSAGES_T.format("message.queue", &[("count", &songs.len())]));
         }
         ServerBroadcastEvent::PlayerVolumeUpdate { volume } => {
-            context.server_state_mut().player.volume = volume;
+            context.server_state_mut().player.info.volume = volume;
+            context
+                .message_queue_mut()
+                .push_info(MESSAGES_T.format("message.volume", &[("volume", &volume)]));
         }
-        ServerBroadcastEvent::PlayerProgressUpdate { elapsed } => {
-            context.server_state_mut().player.elapsed = elapsed;
+        ServerBroadcastEvent::PlayerSchedule { schedule } => {
+            let msg = match schedule {
+                Some(schedule) => MESSAGES_T.format(
+                    "message.schedule",
+                    &[
+                        ("playlist", &schedule.playlist.display()),
+                        ("time", &schedule.time),
+                    ],
+                ),
+                None => MESSAGES_T.get("message.no_schedule").to_string(),
+            };
+            context.message_queue_mut().push_info(msg);
+        }
+        ServerBroadcastEvent::PlayerProgressUpdate { elapsed, duration } => {
+            context.server_state_mut().set_elapsed(elapsed);
+            if duration.is_some() {
+                context.server_state_mut().player.info.duration = duration;
+            }
+        }
+        ServerBroadcastEvent::PlayerSpectrumUpdate { bands } => {
+            context.server_state_mut().spectrum = bands;
         }
         ServerBroadcastEvent::PlaylistSwapMove { index1, index2 } => {
             let playlist = &mut context.server_state_mut().player.playlist;
-            playlist.list_mut().swap(index1, index2);
-            playlist.set_cursor_index(Some(index2));
-            if let Some(playing_index) = playlist.get_playing_index() {
-                if playing_index == index1 {
-                    playlist.set_playing_index(Some(index2));
-                }
-                if playing_index == index2 {
-                    playlist.set_playing_index(Some(index1));
-                }
+            playlist.swap_songs(index1, index2);
+            context.server_state_mut().update_upcoming_duration();
+        }
+        ServerBroadcastEvent::PlaylistMove { index, to } => {
+            let playlist = &mut context.server_state_mut().player.playlist;
+            if index < playlist.len() && to < playlist.len() {
+                playlist.move_song(index, to);
             }
+            context.server_state_mut().update_upcoming_duration();
         }
         ServerBroadcastEvent::PlaylistClear => {
             let playlist_len = context.server_state_mut().player.playlist.len();
             context.server_state_mut().player.playlist.clear();
+            context.server_state_mut().reset_playlist_duration();
             context.set_view_widget(WidgetType::FileBrowser);
-            context
-                .message_queue_mut()
-                .push_success(format!("Removed {playlist_len} songs from playlist"));
+            context.message_queue_mut().push_success(
+                MESSAGES_T.format("message.playlist_removed", &[("count", &playlist_len)]),
+            );
         }
         ServerBroadcastEvent::PlaylistAppend { audio_files } => {
             let len = audio_files.len();
@@ -183,12 +263,15 @@ pub fn process_server_event(context: &mut AppContext, s: &str) -> DiziResult {
                 .into_iter()
                 .map(|s| DiziSongEntry::Loaded(s))
                 .collect();
-            context
-                .server_state_mut()
-                .player
-                .playlist
-                .list_mut()
-                .extend_from_slice(&entries);
+            context.server_state_mut().add_playlist_duration(&entries);
+            for entry in entries {
+                context
+                    .server_state_mut()
+                    .player
+                    .playlist
+                    .append_song(entry);
+            }
+            context.server_state_mut().update_upcoming_duration();
             if context
                 .server_state_ref()
                 .player
@@ -204,27 +287,111 @@ pub fn process_server_event(context: &mut AppContext, s: &str) -> DiziResult {
             }
             context
                 .message_queue_mut()
-                .push_success(format!("Added {len} songs to playlist"));
+                .push_success(MESSAGES_T.format("message.playlist_added", &[("count", &len)]));
         }
-        ServerBroadcastEvent::PlaylistRemove { index } => {
+        ServerBroadcastEvent::PlaylistInsert { index, audio_files } => {
+            let len = audio_files.len();
+            let entries: Vec<_> = audio_files.into_iter().map(DiziSongEntry::Loaded).collect();
+            context.server_state_mut().add_playlist_duration(&entries);
+            context
+                .server_state_mut()
+                .player
+                .playlist
+                .insert_songs(index, entries);
+            context.server_state_mut().update_upcoming_duration();
             context
+                .message_queue_mut()
+                .push_success(MESSAGES_T.format("message.playlist_added", &[("count", &len)]));
+        }
+        ServerBroadcastEvent::PlaylistRemove { index } => {
+            let entry = context
                 .server_state_mut()
                 .player
                 .playlist
                 .remove_song(index);
+            context.server_state_mut().remove_playlist_duration(&entry);
+            context.server_state_mut().update_upcoming_duration();
+        }
+        ServerBroadcastEvent::PlaylistRemoveMany { indices } => {
+            for index in indices.iter() {
+                let entry = context
+                    .server_state_mut()
+                    .player
+                    .playlist
+                    .remove_song(*index);
+                context.server_state_mut().remove_playlist_duration(&entry);
+            }
+            context.server_state_mut().update_upcoming_duration();
+            context.message_queue_mut().push_success(
+                MESSAGES_T.format("message.playlist_removed", &[("count", &indices.len())]),
+            );
+        }
+        ServerBroadcastEvent::PlaylistScanDone { path } => {
+            context.server_state_mut().finish_scan(&path);
+        }
+        ServerBroadcastEvent::PlaylistSave { path } => {
+            context.message_queue_mut().push_success(
+                MESSAGES_T.format("message.playlist_saved", &[("path", &path.display())]),
+            );
+        }
+        ServerBroadcastEvent::PlayerLyrics { path, lyrics } => {
+            context.server_state_mut().set_lyrics(path, lyrics);
+        }
+        ServerBroadcastEvent::Waveform { path, peaks } => {
+            context.server_state_mut().set_waveform(path, peaks);
+        }
+        ServerBroadcastEvent::PlayerChapters { chapters } => {
+            if chapters.is_empty() {
+                context
+                    .message_queue_mut()
+                    .push_info(MESSAGES_T.get("message.no_chapters").to_string());
+            }
+            for (i, chapter) in chapters.iter().enumerate() {
+                context.message_queue_mut().push_info(MESSAGES_T.format(
+                    "message.chapter",
+                    &[
+                        ("number", &(i + 1)),
+                        ("title", &chapter.title),
+                        ("start", &format::duration_to_string(chapter.start)),
+                    ],
+                ));
+            }
+        }
+        ServerBroadcastEvent::LibraryRating { path, rating } => {
+            context.server_state_mut().set_rating(path, rating);
+        }
+        ServerBroadcastEvent::LibraryRatings { ratings } => {
+            context.server_state_mut().set_ratings(ratings);
+        }
+        // answers `dizi ls`, the file browser lists this machine
+        ServerBroadcastEvent::FsList { .. } => {}
+        // the events sent along with it already update the player
+        ServerBroadcastEvent::TrackTransition { .. } => {}
+        ServerBroadcastEvent::PlaylistOrder { order } => {
+            context.server_state_mut().player.playlist.order = order;
+            context.server_state_mut().update_upcoming_duration();
+        }
+        ServerBroadcastEvent::PlaylistDirectory { mut playlist } => {
+            set_directory_cursor(&mut playlist);
+            context.server_state_mut().player.directory_playlist = playlist;
         }
         ServerBroadcastEvent::PlaylistPlay { index } => {
             let len = context.server_state_ref().player.playlist.len();
             if index < len {
                 let player = &mut context.server_state_mut().player;
-                player.status = PlayerStatus::Playing;
-                player.playlist_status = PlaylistType::PlaylistFile;
+                player.info.status = PlayerStatus::Loading;
+                player.info.playlist_status = PlaylistType::PlaylistFile;
                 let cursor_index = player.playlist.get_cursor_index();
                 let playing_index = player.playlist.get_playing_index();
                 if playing_index == cursor_index {
                     player.playlist.set_cursor_index(Some(index));
                 }
                 player.playlist.set_playing_index(Some(index));
+                context.server_state_mut().set_elapsed(time::Duration::ZERO);
+            }
+            context.server_state_mut().update_upcoming_duration();
+            if context.config_ref().display_options_ref().follow_playing() {
+                goto::follow_playing(context)?;
             }
         }
     }
@@ -234,6 +401,10 @@ pub fn process_server_event(context: &mut AppContext, s: &str) -> DiziResult {
 pub fn process_noninteractive(event: AppEvent, context: &mut AppContext) {
     match event {
         AppEvent::PreviewDir { path, res } => process_dir_preview(context, path, *res),
+        AppEvent::LibraryLoad { root, res } => process_library_load(context, root, *res),
+        AppEvent::MetadataLoad { path, metadata } => {
+            context.metadata_cache_mut().insert(path, metadata)
+        }
         AppEvent::Signal(signal::SIGWINCH) => {}
         _ => {}
     }
@@ -251,3 +422,35 @@ pub fn process_dir_preview(
         history.insert(dir_path, dirlist);
     }
 }
+
+pub fn process_library_load(
+    context: &mut AppContext,
+    root: path::PathBuf,
+    res: io::Result<Vec<path::PathBuf>>,
+) {
+    match res {
+        Ok(songs) => {
+            let len = songs.len();
+            context.library_context_mut().set_songs(songs);
+            context.message_queue_mut().push_success(MESSAGES_T.format(
+                "message.library_loaded",
+                &[("count", &len), ("root", &format!("{:?}", root))],
+            ));
+        }
+        Err(e) => {
+            context.library_context_mut().set_loading(false);
+            context.message_queue_mut().push_error(MESSAGES_T.format(
+                "message.library_failed",
+                &[("root", &format!("{:?}", root)), ("error", &e)],
+            ));
+        }
+    }
+}
+
+/// The server doesn't know about cursors, start at the playing song
+fn set_directory_cursor(playlist: &mut FilePlaylist) {
+    if !playlist.is_empty() {
+        let cursor_index = playlist.get_playing_index().unwrap_or(0);
+        playlist.set_cursor_index(Some(cursor_index));
+    }
+}
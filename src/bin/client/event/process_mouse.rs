@@ -0,0 +1,81 @@
+use ratatui::layout::Rect;
+use termion::event::{MouseButton, MouseEvent};
+
+use dizi::error::DiziResult;
+use dizi::request::client::ClientRequest;
+
+use crate::config::option::WidgetType;
+use crate::context::{AppContext, PlaylistDrag};
+use crate::ui::views;
+use crate::ui::widgets::TuiPlaylist;
+use crate::ui::AppBackend;
+use crate::util::request::send_client_request;
+
+/// Clicking a song of the playlist moves the cursor to it,
+/// dragging it drops it where the button is released
+pub fn process_mouse(
+    context: &mut AppContext,
+    backend: &AppBackend,
+    event: MouseEvent,
+) -> DiziResult {
+    let size = backend.terminal_ref().size()?;
+    let area = Rect::new(0, 0, size.width, size.height);
+    let playlist_area = match views::widget_area(context, area, WidgetType::Playlist) {
+        Some(rect) => rect,
+        None => {
+            context.ui_context_mut().drag = None;
+            return Ok(());
+        }
+    };
+
+    match event {
+        // termion counts from 1
+        MouseEvent::Press(MouseButton::Left, x, y) => {
+            context.ui_context_mut().drag = None;
+            let (x, y) = (x.saturating_sub(1), y.saturating_sub(1));
+            if x < playlist_area.left() || x >= playlist_area.right() {
+                return Ok(());
+            }
+            let playlist = context.server_state_ref().playlist_ref();
+            if let Some(index) = TuiPlaylist::index_at(playlist_area, playlist, y) {
+                context.set_view_widget(WidgetType::Playlist);
+                context
+                    .server_state_mut()
+                    .playlist_mut()
+                    .set_cursor_index(Some(index));
+                // the directory playlist follows the directory, it can't be reordered
+                if !context.server_state_ref().shows_directory_playlist() {
+                    context.ui_context_mut().drag = Some(PlaylistDrag {
+                        from: index,
+                        to: index,
+                    });
+                }
+            }
+        }
+        MouseEvent::Hold(_, y) => {
+            if let Some(drag) = context.ui_context_ref().drag {
+                let playlist = context.server_state_ref().playlist_ref();
+                let list_area = TuiPlaylist::list_area(playlist_area, playlist);
+                // dragging past the list drops the song at either end of the screen
+                let y = y
+                    .saturating_sub(1)
+                    .clamp(list_area.top(), list_area.bottom().saturating_sub(1));
+                if let Some(to) = TuiPlaylist::index_at(playlist_area, playlist, y)
+                    .or_else(|| playlist.len().checked_sub(1))
+                {
+                    context.ui_context_mut().drag = Some(PlaylistDrag { to, ..drag });
+                }
+            }
+        }
+        MouseEvent::Release(..) => {
+            if let Some(PlaylistDrag { from, to }) = context.ui_context_mut().drag.take() {
+                if from != to {
+                    let request = ClientRequest::PlaylistMoveTo { index: from, to };
+                    send_client_request(context, &request)?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
@@ -41,7 +41,7 @@ impl JoshutoDirList {
         let filter_func = options.filter_func();
         let mut contents = read_directory(path.as_path(), filter_func, options)?;
 
-        let sort_options = options.sort_options_ref();
+        let sort_options = options.sort_options_for(path.as_path());
         contents.sort_by(|f1, f2| sort_options.compare(f1, f2));
 
         let index = if contents.is_empty() { None } else { Some(0) };
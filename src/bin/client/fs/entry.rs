@@ -1,5 +1,7 @@
 use std::{fs, io, path};
 
+use dizi::archive::ArchiveEntry;
+
 use crate::config::option::DisplayOption;
 use crate::fs::metadata::JoshutoMetadata;
 
@@ -50,6 +52,24 @@ impl JoshutoDirEntry {
         })
     }
 
+    /// An entry listed inside an archive, found at `path`
+    pub fn from_archive_entry(path: path::PathBuf, entry: &ArchiveEntry) -> io::Result<Self> {
+        let ext = path
+            .extension()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string());
+        let metadata = JoshutoMetadata::from_archive_entry(&path, entry)?;
+        Ok(Self {
+            name: entry.name.clone(),
+            ext,
+            path,
+            metadata,
+            permanent_selected: false,
+            visual_mode_selected: false,
+            _marked: false,
+        })
+    }
+
     pub fn file_name(&self) -> &str {
         self.name.as_str()
     }
@@ -1,5 +1,7 @@
 use std::{fs, io, path, time};
 
+use dizi::archive::{self, ArchiveEntry};
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum FileType {
     Directory,
@@ -42,6 +44,11 @@ impl JoshutoMetadata {
         #[cfg(unix)]
         use std::os::unix::fs::MetadataExt;
 
+        if archive::is_archive_member(path) {
+            let entry = archive::entry(path)?;
+            return Self::from_archive_entry(path, &entry);
+        }
+
         let symlink_metadata = fs::symlink_metadata(path)?;
         let metadata = fs::metadata(path);
         let (_len, _modified, _permissions) = match metadata.as_ref() {
@@ -55,6 +62,8 @@ impl JoshutoMetadata {
 
         let (_file_type, _directory_size) = match metadata.as_ref() {
             Ok(m) if m.file_type().is_dir() => (FileType::Directory, None),
+            // archives are browsed into like directories
+            Ok(_) if archive::is_archive(path) => (FileType::Directory, None),
             _ => (FileType::File, None),
         };
 
@@ -96,6 +105,21 @@ impl JoshutoMetadata {
         })
     }
 
+    /// Members of an archive share the archive's times and permissions
+    pub fn from_archive_entry(path: &path::Path, entry: &ArchiveEntry) -> io::Result<Self> {
+        let (archive, _) = archive::split_archive_path(path)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "Not inside an archive"))?;
+        let mut metadata = Self::from(&archive)?;
+        metadata._len = entry.size;
+        metadata._file_type = if entry.is_dir {
+            FileType::Directory
+        } else {
+            FileType::File
+        };
+        metadata._link_type = LinkType::Normal;
+        Ok(metadata)
+    }
+
     pub fn len(&self) -> u64 {
         self._len
     }
@@ -3,6 +3,8 @@ use std::fs;
 use std::io;
 use std::path::{Path, PathBuf};
 
+use dizi::archive;
+
 use crate::config::option::DisplayOption;
 use crate::context::UiContext;
 use crate::fs::{JoshutoDirEntry, JoshutoDirList, JoshutoMetadata};
@@ -130,7 +132,7 @@ pub fn create_dirlist_with_history(
         }
     }
 
-    let sort_options = options.sort_options_ref();
+    let sort_options = options.sort_options_for(path);
     contents.sort_by(|f1, f2| sort_options.compare(f1, f2));
 
     let contents_len = contents.len();
@@ -186,10 +188,35 @@ pub fn read_directory<F>(
 where
     F: Fn(&Result<fs::DirEntry, io::Error>) -> bool,
 {
-    let results: Vec<JoshutoDirEntry> = fs::read_dir(path)?
-        .filter(filter_func)
-        .filter_map(|res| JoshutoDirEntry::from(&res.ok()?, path, options).ok())
+    let entries: Vec<JoshutoDirEntry> = if archive::split_archive_path(path).is_some() {
+        read_archive_directory(path, options)?
+    } else {
+        fs::read_dir(path)?
+            .filter(filter_func)
+            .filter_map(|res| JoshutoDirEntry::from(&res.ok()?, path, options).ok())
+            .collect()
+    };
+    let results = entries
+        .into_iter()
+        .filter(|entry| !options.audio_only() || options.is_audio_entry(entry))
+        .filter(|entry| match options.filter_for(path) {
+            Some(filter) => entry.file_name().to_lowercase().contains(filter),
+            None => true,
+        })
         .collect();
 
     Ok(results)
 }
+
+/// Lists an archive, or a directory inside one, like a directory on the file system
+fn read_archive_directory(
+    path: &Path,
+    options: &DisplayOption,
+) -> io::Result<Vec<JoshutoDirEntry>> {
+    let entries = archive::read_dir(path)?
+        .iter()
+        .filter(|entry| options.show_hidden() || !entry.name.starts_with('.'))
+        .filter_map(|entry| JoshutoDirEntry::from_archive_entry(path.join(&entry.name), entry).ok())
+        .collect();
+    Ok(entries)
+}
@@ -4,13 +4,24 @@ use dizi::request::client::ClientRequest;
 
 use crate::config::option::SelectOption;
 use crate::config::option::SortType;
+use crate::config::option::WidgetType;
 
 #[derive(Clone, Debug)]
 pub enum Command {
+    AddCwd,
+    AddRecursive,
     Close,
 
     ChangeDirectory(path::PathBuf),
     CommandLine(String, String),
+    // commands separated by `;`, run one after the other
+    Chain(Vec<Command>),
+
+    CopyPath,
+    CopyPlayingPath,
+
+    DeleteFiles,
+    RenameFile,
 
     CursorMoveUp(usize),
     CursorMoveDown(usize),
@@ -19,16 +30,33 @@ pub enum Command {
     CursorMovePageUp,
     CursorMovePageDown,
 
+    Filter(String),
+
+    // register to record into
+    MacroRecord(char),
+    // register to replay and how many times
+    MacroPlay(char, usize),
+
     GoToPlaying,
+    PlaylistGoTo(usize),
+    PlaylistSave(path::PathBuf),
+
+    Rate(u8),
+    RatePlaying(u8),
 
     OpenFile,
     ParentDirectory,
 
     ReloadDirList,
 
+    ScrollNameLeft(usize),
+    ScrollNameRight(usize),
+
     SearchGlob(String),
+    SearchRegex(String),
     SearchString(String),
-    SearchSkim,
+    // whether to search every song of the library instead of the current view
+    SearchSkim(bool),
     SearchNext,
     SearchPrev,
 
@@ -38,7 +66,16 @@ pub enum Command {
 
     Sort(SortType),
     SortReverse,
+    SortMenu,
 
+    SwitchView(WidgetType),
+    ToggleAudioOnly,
+    ToggleFollow,
     ToggleView,
     ToggleHiddenFiles,
+    ToggleMinimal,
+    ToggleRemainingTime,
+    ToggleVisualizer,
+    ToggleLyrics,
+    ToggleDirectoryPlaylist,
 }
@@ -1,6 +1,12 @@
 use rustyline::completion::Pair;
 
+use crate::ALIASES_T;
+
 pub const CMD_COMMAND_LINE: &str = ":";
+pub const CMD_CHAIN: &str = ";";
+
+// columns scrolled by `scroll_name_left` and `scroll_name_right` without an argument
+pub const SCROLL_NAME_COLUMNS: usize = 8;
 
 macro_rules! cmd_constants {
     ($( ($cmd_name:ident, $cmd_value:literal), )*) => {
@@ -15,20 +21,41 @@ macro_rules! cmd_constants {
 }
 
 cmd_constants![
+    (CMD_ADD_CWD, "add_cwd"),
+    (CMD_ADD_RECURSIVE, "add_recursive"),
     (CMD_CLOSE, "close"),
     (CMD_CHANGE_DIRECTORY, "cd"),
+    (CMD_COPY_PATH, "copy_path"),
+    (CMD_COPY_PLAYING_PATH, "copy_playing_path"),
+    (CMD_DELETE_FILES, "delete_files"),
     (CMD_CURSOR_MOVE_UP, "cursor_move_up"),
     (CMD_CURSOR_MOVE_DOWN, "cursor_move_down"),
     (CMD_CURSOR_MOVE_HOME, "cursor_move_home"),
     (CMD_CURSOR_MOVE_END, "cursor_move_end"),
     (CMD_CURSOR_MOVE_PAGEUP, "cursor_move_page_up"),
     (CMD_CURSOR_MOVE_PAGEDOWN, "cursor_move_page_down"),
+    (CMD_FILTER, "filter"),
     (CMD_GO_TO_PLAYING, "go_to_playing"),
     (CMD_OPEN_FILE, "open"),
+    (CMD_PLAYER_FAST_FORWARD, "player_fast_forward"),
+    (CMD_PLAYER_REWIND, "player_rewind"),
+    (CMD_PLAYER_VOLUME_DOWN, "player_volume_down"),
+    (CMD_PLAYER_VOLUME_UP, "player_volume_up"),
+    (CMD_PLAYLIST_GOTO, "playlist_goto"),
+    (CMD_PLAYLIST_SAVE, "playlist_save"),
     (CMD_PARENT_DIRECTORY, "cd .."),
+    (CMD_RATE, "rate"),
+    (CMD_RATE_PLAYING, "rate_playing"),
+    (CMD_RENAME_FILE, "rename"),
+    (CMD_SEEK, "seek"),
+    (CMD_SCROLL_NAME_LEFT, "scroll_name_left"),
+    (CMD_SCROLL_NAME_RIGHT, "scroll_name_right"),
     (CMD_RELOAD_DIRECTORY_LIST, "reload_dirlist"),
     (CMD_SEARCH_STRING, "search"),
+    (CMD_MACRO_RECORD, "macro_record"),
+    (CMD_MACRO_PLAY, "macro_play"),
     (CMD_SEARCH_GLOB, "search_glob"),
+    (CMD_SEARCH_REGEX, "search_regex"),
     (CMD_SEARCH_SKIM, "search_skim"),
     (CMD_SEARCH_NEXT, "search_next"),
     (CMD_SEARCH_PREV, "search_prev"),
@@ -36,13 +63,22 @@ cmd_constants![
     (CMD_SERVER_REQUEST, "server_request"),
     (CMD_SORT, "sort"),
     (CMD_SORT_REVERSE, "sort reverse"),
+    (CMD_SWITCH_VIEW, "switch_view"),
+    (CMD_TOGGLE_AUDIO_ONLY, "toggle_audio_only"),
+    (CMD_TOGGLE_FOLLOW, "toggle_follow"),
     (CMD_TOGGLE_HIDDEN, "toggle_hidden"),
+    (CMD_TOGGLE_MINIMAL, "toggle_minimal"),
+    (CMD_TOGGLE_REMAINING_TIME, "toggle_remaining_time"),
     (CMD_TOGGLE_VIEW, "toggle_view"),
+    (CMD_TOGGLE_VISUALIZER, "toggle_visualizer"),
+    (CMD_TOGGLE_LYRICS, "toggle_lyrics"),
+    (CMD_TOGGLE_DIRECTORY_PLAYLIST, "toggle_directory_playlist"),
 ];
 
 pub fn complete_command(partial_command: &str) -> Vec<Pair> {
     commands()
         .into_iter()
+        .chain(ALIASES_T.names())
         .filter(|command| command.starts_with(partial_command))
         .map(|command| Pair {
             display: command.to_string(),
@@ -4,10 +4,19 @@ use super::{AppCommand, Command};
 impl AppCommand for Command {
     fn command(&self) -> &'static str {
         match self {
+            Self::AddCwd => CMD_ADD_CWD,
+            Self::AddRecursive => CMD_ADD_RECURSIVE,
             Self::Close => CMD_CLOSE,
 
             Self::ChangeDirectory(_) => CMD_CHANGE_DIRECTORY,
             Self::CommandLine(_, _) => CMD_COMMAND_LINE,
+            Self::Chain(_) => CMD_CHAIN,
+
+            Self::CopyPath => CMD_COPY_PATH,
+            Self::CopyPlayingPath => CMD_COPY_PLAYING_PATH,
+
+            Self::DeleteFiles => CMD_DELETE_FILES,
+            Self::RenameFile => CMD_RENAME_FILE,
 
             Self::CursorMoveUp(_) => CMD_CURSOR_MOVE_UP,
             Self::CursorMoveDown(_) => CMD_CURSOR_MOVE_DOWN,
@@ -16,16 +25,28 @@ impl AppCommand for Command {
             Self::CursorMovePageUp => CMD_CURSOR_MOVE_PAGEUP,
             Self::CursorMovePageDown => CMD_CURSOR_MOVE_PAGEDOWN,
 
+            Self::Filter(_) => CMD_FILTER,
+
             Self::GoToPlaying => CMD_GO_TO_PLAYING,
+            Self::PlaylistGoTo(_) => CMD_PLAYLIST_GOTO,
+            Self::PlaylistSave(_) => CMD_PLAYLIST_SAVE,
+            Self::Rate(_) => CMD_RATE,
+            Self::RatePlaying(_) => CMD_RATE_PLAYING,
 
             Self::OpenFile => CMD_OPEN_FILE,
             Self::ParentDirectory => CMD_PARENT_DIRECTORY,
 
+            Self::ScrollNameLeft(_) => CMD_SCROLL_NAME_LEFT,
+            Self::ScrollNameRight(_) => CMD_SCROLL_NAME_RIGHT,
+
             Self::ReloadDirList => CMD_RELOAD_DIRECTORY_LIST,
 
             Self::SearchString(_) => CMD_SEARCH_STRING,
+            Self::MacroRecord(_) => CMD_MACRO_RECORD,
+            Self::MacroPlay(..) => CMD_MACRO_PLAY,
             Self::SearchGlob(_) => CMD_SEARCH_GLOB,
-            Self::SearchSkim => CMD_SEARCH_SKIM,
+            Self::SearchRegex(_) => CMD_SEARCH_REGEX,
+            Self::SearchSkim(_) => CMD_SEARCH_SKIM,
             Self::SearchNext => CMD_SEARCH_NEXT,
             Self::SearchPrev => CMD_SEARCH_PREV,
 
@@ -33,9 +54,18 @@ impl AppCommand for Command {
 
             Self::Sort(_) => CMD_SORT,
             Self::SortReverse => CMD_SORT_REVERSE,
+            Self::SortMenu => CMD_SORT,
 
             Self::ToggleHiddenFiles => CMD_TOGGLE_HIDDEN,
+            Self::SwitchView(_) => CMD_SWITCH_VIEW,
             Self::ToggleView => CMD_TOGGLE_VIEW,
+            Self::ToggleAudioOnly => CMD_TOGGLE_AUDIO_ONLY,
+            Self::ToggleFollow => CMD_TOGGLE_FOLLOW,
+            Self::ToggleMinimal => CMD_TOGGLE_MINIMAL,
+            Self::ToggleRemainingTime => CMD_TOGGLE_REMAINING_TIME,
+            Self::ToggleVisualizer => CMD_TOGGLE_VISUALIZER,
+            Self::ToggleLyrics => CMD_TOGGLE_LYRICS,
+            Self::ToggleDirectoryPlaylist => CMD_TOGGLE_DIRECTORY_PLAYLIST,
 
             Self::ServerRequest(request) => request.api_path(),
         }
@@ -7,9 +7,10 @@ use crate::config::AppKeyMapping;
 use crate::context::AppContext;
 use crate::ui::AppBackend;
 use crate::util::request::send_client_request;
+use crate::MESSAGES_T;
 use crate::{commands::*, ui::widgets::TuiPrompt};
 
-use super::{AppExecute, Command};
+use super::{AppCommand, AppExecute, Command};
 
 impl AppExecute for Command {
     fn execute(
@@ -18,13 +19,30 @@ impl AppExecute for Command {
         backend: &mut AppBackend,
         keymap_t: &AppKeyMapping,
     ) -> DiziResult {
+        if !confirm_command(context, backend, self) {
+            return Ok(());
+        }
         match self {
+            Self::AddCwd => playlist::add_cwd(context)?,
+            Self::AddRecursive => playlist::add_recursive(context)?,
             Self::ChangeDirectory(p) => {
                 change_directory::change_directory(context, p.as_path())?;
             }
             Self::CommandLine(p, s) => {
                 command_line::read_and_execute(context, backend, keymap_t, p.as_str(), s.as_str())?
             }
+            // the first error stops the rest of the chain
+            Self::Chain(commands) => {
+                for command in commands {
+                    command.execute(context, backend, keymap_t)?;
+                }
+            }
+
+            Self::CopyPath => clipboard::copy_path(context)?,
+            Self::CopyPlayingPath => clipboard::copy_playing_path(context)?,
+
+            Self::DeleteFiles => file_operations::delete_files(context, backend)?,
+            Self::RenameFile => file_operations::rename_file(context, backend)?,
 
             Self::CursorMoveUp(u) => cursor_move::up(context, *u)?,
             Self::CursorMoveDown(u) => cursor_move::down(context, *u)?,
@@ -33,7 +51,11 @@ impl AppExecute for Command {
             Self::CursorMovePageUp => cursor_move::page_up(context, backend)?,
             Self::CursorMovePageDown => cursor_move::page_down(context, backend)?,
 
+            Self::Filter(pattern) => filter::filter(context, pattern.as_str())?,
+
             Self::GoToPlaying => goto::goto_playing(context)?,
+            Self::PlaylistGoTo(i) => goto::goto_playlist_index(context, *i)?,
+            Self::PlaylistSave(p) => playlist::save(context, p.as_path())?,
 
             Self::ParentDirectory => change_directory::parent_directory(context)?,
 
@@ -41,9 +63,19 @@ impl AppExecute for Command {
 
             Self::ReloadDirList => reload::reload_dirlist(context)?,
 
+            Self::Rate(rating) => rating::rate(context, *rating)?,
+            Self::RatePlaying(rating) => rating::rate_playing(context, *rating)?,
+            Self::ScrollNameLeft(u) => scroll::scroll_name_left(context, *u)?,
+            Self::ScrollNameRight(u) => scroll::scroll_name_right(context, *u)?,
+
+            Self::MacroRecord(register) => macros::macro_record(context, *register)?,
+            Self::MacroPlay(register, times) => {
+                macros::macro_play(context, backend, keymap_t, *register, *times)?
+            }
             Self::SearchGlob(pattern) => search_glob::search_glob(context, pattern.as_str())?,
+            Self::SearchRegex(pattern) => search_regex::search_regex(context, pattern.as_str())?,
             Self::SearchString(pattern) => search_string::search_string(context, pattern.as_str())?,
-            Self::SearchSkim => search_skim::search_skim(context, backend)?,
+            Self::SearchSkim(library) => search_skim::search_skim(context, backend, *library)?,
             Self::SearchNext => search::search_next(context)?,
             Self::SearchPrev => search::search_prev(context)?,
 
@@ -54,16 +86,18 @@ impl AppExecute for Command {
             Self::ServerRequest(request) => execute_request(backend, context, request)?,
 
             Self::ToggleHiddenFiles => show_hidden::toggle_hidden(context)?,
-            Self::ToggleView => {
-                let new_widget = match context.get_view_widget() {
-                    WidgetType::FileBrowser => WidgetType::Playlist,
-                    WidgetType::Playlist => WidgetType::FileBrowser,
-                    s => s,
-                };
-                context.set_view_widget(new_widget);
-            }
+            Self::SwitchView(widget) => view::switch_view(context, *widget)?,
+            Self::ToggleView => view::toggle_view(context)?,
+            Self::ToggleAudioOnly => show_hidden::toggle_audio_only(context)?,
+            Self::ToggleFollow => goto::toggle_follow(context)?,
+            Self::ToggleMinimal => view::toggle_minimal(context)?,
+            Self::ToggleRemainingTime => view::toggle_remaining_time(context)?,
+            Self::ToggleVisualizer => view::toggle_visualizer(context)?,
+            Self::ToggleLyrics => view::toggle_lyrics(context)?,
+            Self::ToggleDirectoryPlaylist => view::toggle_directory_playlist(context)?,
             Self::Sort(t) => sort::set_sort(context, *t)?,
             Self::SortReverse => sort::toggle_reverse(context)?,
+            Self::SortMenu => sort::sort_menu(context, backend, keymap_t)?,
 
             Self::OpenFile => open_file::open(context)?,
         }
@@ -71,6 +105,20 @@ impl AppExecute for Command {
     }
 }
 
+/// Asks before running commands listed in `commands` of `[client.confirm]`
+fn confirm_command(context: &mut AppContext, backend: &mut AppBackend, command: &Command) -> bool {
+    let confirm_options = context.config_ref().client_ref().confirm_options_ref();
+    let name = match command {
+        Command::ServerRequest(request) => request.api_path(),
+        command => command.command(),
+    };
+    if !confirm_options.requires(name) {
+        return true;
+    }
+    let prompt_str = MESSAGES_T.format("prompt.run_command", &[("command", command)]);
+    TuiPrompt::new(&prompt_str).confirm(backend, context)
+}
+
 pub fn execute_request(
     backend: &mut AppBackend,
     context: &mut AppContext,
@@ -78,7 +126,111 @@ pub fn execute_request(
 ) -> DiziResult {
     match request {
         ClientRequest::ServerQuit => {
-            quit::server_quit(context)?;
+            quit::server_quit(context, backend)?;
+        }
+        ClientRequest::PlaylistClear => {
+            let len = context.server_state_ref().player.playlist.len();
+            if len == 0 {
+                return Ok(());
+            }
+            if context
+                .config_ref()
+                .client_ref()
+                .confirm_options_ref()
+                .playlist_clear
+            {
+                let prompt_str = MESSAGES_T.format("prompt.playlist_clear", &[("count", &len)]);
+                if !TuiPrompt::new(&prompt_str).confirm(backend, context) {
+                    return Ok(());
+                }
+            }
+            send_client_request(context, request)?;
+        }
+        ClientRequest::PlaylistAppend { path: None }
+            if context.get_view_widget() == WidgetType::Library =>
+        {
+            if let Some(path) = context.library_context_ref().curr_song_ref() {
+                let request = ClientRequest::PlaylistAppend {
+                    path: Some(path.to_path_buf()),
+                };
+                send_client_request(context, &request)?;
+            }
+        }
+        ClientRequest::PlaylistAppend { path: None }
+            if !selection::selected_paths(context).is_empty() =>
+        {
+            let request = ClientRequest::PlaylistAppendBatch {
+                paths: selection::selected_paths(context),
+                next: false,
+            };
+            send_client_request(context, &request)?;
+            selection::clear_selection(context);
+        }
+        ClientRequest::PlaylistAppendBatch { paths, next } if paths.is_empty() => {
+            let paths = if context.get_view_widget() == WidgetType::Library {
+                context
+                    .library_context_ref()
+                    .curr_song_ref()
+                    .map(|p| vec![p.to_path_buf()])
+                    .unwrap_or_default()
+            } else {
+                let selected = selection::selected_paths(context);
+                if selected.is_empty() {
+                    context
+                        .tab_context_ref()
+                        .curr_tab_ref()
+                        .curr_list_ref()
+                        .and_then(|s| s.curr_entry_ref())
+                        .map(|e| vec![e.file_path_buf()])
+                        .unwrap_or_default()
+                } else {
+                    selected
+                }
+            };
+            if !paths.is_empty() {
+                let request = ClientRequest::PlaylistAppendBatch { paths, next: *next };
+                send_client_request(context, &request)?;
+                selection::clear_selection(context);
+            }
+        }
+        // the directory at the cursor, or the current directory
+        ClientRequest::PlaylistAppendDir {
+            path: None,
+            recursive,
+        } => {
+            let curr_tab = context.tab_context_ref().curr_tab_ref();
+            let path = curr_tab
+                .curr_list_ref()
+                .and_then(|s| s.curr_entry_ref())
+                .filter(|entry| entry.metadata.is_dir())
+                .map(|entry| entry.file_path_buf())
+                .unwrap_or_else(|| curr_tab.cwd().to_path_buf());
+            let request = ClientRequest::playlist_append_dir(path, *recursive);
+            send_client_request(context, &request)?;
+        }
+        // the entry at the cursor, in front of the song at the playlist's cursor
+        ClientRequest::PlaylistInsert { path, index } if path.is_none() || index.is_none() => {
+            let path = match path {
+                Some(path) => Some(path.clone()),
+                None if context.get_view_widget() == WidgetType::Library => context
+                    .library_context_ref()
+                    .curr_song_ref()
+                    .map(|p| p.to_path_buf()),
+                None => context
+                    .tab_context_ref()
+                    .curr_tab_ref()
+                    .curr_list_ref()
+                    .and_then(|s| s.curr_entry_ref())
+                    .map(|e| e.file_path_buf()),
+            };
+            let playlist = &context.server_state_ref().player.playlist;
+            let index = index
+                .or_else(|| playlist.get_cursor_index())
+                .unwrap_or_else(|| playlist.len());
+            if let Some(path) = path {
+                let request = ClientRequest::playlist_insert(path, index);
+                send_client_request(context, &request)?;
+            }
         }
         ClientRequest::PlaylistAppend { path: None } => {
             let entry_file_path = context
@@ -98,7 +250,7 @@ pub fn execute_request(
                 }
 
                 let ch = {
-                    let prompt_str = format!("Add all songs in this directory? [Y/n]");
+                    let prompt_str = MESSAGES_T.get("prompt.add_directory").to_string();
                     let mut prompt = TuiPrompt::new(&prompt_str);
                     prompt.get_key(backend, context)
                 };
@@ -134,14 +286,30 @@ pub fn execute_request(
             }
         }
         ClientRequest::PlaylistPlay { index: None } => {
-            let playlist = &context.server_state_ref().player.playlist;
+            let server_state = context.server_state_ref();
+            if server_state.shows_directory_playlist() {
+                // songs of the directory playlist are played from their directory
+                let playlist = server_state.playlist_ref();
+                let path = playlist
+                    .get_cursor_index()
+                    .and_then(|index| playlist.list_ref().get(index))
+                    .map(|entry| entry.file_path().to_path_buf());
+                if path.is_some() {
+                    let request = ClientRequest::PlayerFilePlay { path };
+                    send_client_request(context, &request)?;
+                }
+                return Ok(());
+            }
+            let playlist = &server_state.player.playlist;
             if let Some(index) = playlist.get_cursor_index() {
                 let request = ClientRequest::PlaylistPlay { index: Some(index) };
                 send_client_request(context, &request)?;
             }
         }
         ClientRequest::PlaylistRemove { index: None } => {
-            if context.get_view_widget() != WidgetType::Playlist {
+            if context.get_view_widget() != WidgetType::Playlist
+                || context.server_state_ref().shows_directory_playlist()
+            {
                 return Ok(());
             }
             let playlist = &context.server_state_ref().player.playlist;
@@ -151,7 +319,9 @@ pub fn execute_request(
             }
         }
         ClientRequest::PlaylistMoveUp { index: None } => {
-            if context.get_view_widget() != WidgetType::Playlist {
+            if context.get_view_widget() != WidgetType::Playlist
+                || context.server_state_ref().shows_directory_playlist()
+            {
                 return Ok(());
             }
             let playlist = &context.server_state_ref().player.playlist;
@@ -161,7 +331,9 @@ pub fn execute_request(
             }
         }
         ClientRequest::PlaylistMoveDown { index: None } => {
-            if context.get_view_widget() != WidgetType::Playlist {
+            if context.get_view_widget() != WidgetType::Playlist
+                || context.server_state_ref().shows_directory_playlist()
+            {
                 return Ok(());
             }
             let playlist = &context.server_state_ref().player.playlist;
@@ -170,6 +342,31 @@ pub fn execute_request(
                 send_client_request(context, &request)?;
             }
         }
+        ClientRequest::QueuePush { path: None } => {
+            let path = match context.get_view_widget() {
+                WidgetType::Library => context
+                    .library_context_ref()
+                    .curr_song_ref()
+                    .map(|p| p.to_path_buf()),
+                WidgetType::Playlist => {
+                    let playlist = context.server_state_ref().playlist_ref();
+                    playlist
+                        .get_cursor_index()
+                        .and_then(|index| playlist.list_ref().get(index))
+                        .map(|entry| entry.file_path().to_path_buf())
+                }
+                _ => context
+                    .tab_context_ref()
+                    .curr_tab_ref()
+                    .curr_list_ref()
+                    .and_then(|s| s.curr_entry_ref())
+                    .map(|e| e.file_path().to_path_buf())
+                    .filter(|path| !path.is_dir()),
+            };
+            if path.is_some() {
+                send_client_request(context, &ClientRequest::QueuePush { path })?;
+            }
+        }
         request => {
             send_client_request(context, request)?;
         }
@@ -0,0 +1,66 @@
+use dizi::request::client::ClientRequest;
+
+use super::Command;
+
+// counts are capped, so a long run of digits can't overflow
+pub const MAX_COUNT: usize = 9999;
+
+impl Command {
+    /// The command repeated `count` times, for vim-style count prefixes
+    /// such as `10j`, commands without a step size are left as they are
+    pub fn with_count(&self, count: usize) -> Self {
+        match self {
+            Self::CursorMoveUp(n) => Self::CursorMoveUp(n * count),
+            Self::CursorMoveDown(n) => Self::CursorMoveDown(n * count),
+            Self::ScrollNameLeft(n) => Self::ScrollNameLeft(n * count),
+            Self::ScrollNameRight(n) => Self::ScrollNameRight(n * count),
+            Self::MacroPlay(register, n) => Self::MacroPlay(*register, n * count),
+            Self::ServerRequest(request) => {
+                let request = match request {
+                    ClientRequest::PlayerVolumeUp { amount } => ClientRequest::PlayerVolumeUp {
+                        amount: amount * count,
+                    },
+                    ClientRequest::PlayerVolumeDown { amount } => ClientRequest::PlayerVolumeDown {
+                        amount: amount * count,
+                    },
+                    ClientRequest::PlayerRewind { amount } => ClientRequest::PlayerRewind {
+                        amount: amount * count,
+                    },
+                    ClientRequest::PlayerFastForward { amount } => {
+                        ClientRequest::PlayerFastForward {
+                            amount: amount * count,
+                        }
+                    }
+                    request => request.clone(),
+                };
+                Self::ServerRequest(request)
+            }
+            Self::Chain(commands) => {
+                Self::Chain(commands.iter().map(|c| c.with_count(count)).collect())
+            }
+            command => command.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_multiplies_steps() {
+        assert!(matches!(
+            Command::CursorMoveDown(1).with_count(10),
+            Command::CursorMoveDown(10)
+        ));
+        let command = Command::ServerRequest(ClientRequest::PlayerFastForward { amount: 10 });
+        assert!(matches!(
+            command.with_count(5),
+            Command::ServerRequest(ClientRequest::PlayerFastForward { amount: 50 })
+        ));
+        assert!(matches!(
+            Command::ToggleView.with_count(3),
+            Command::ToggleView
+        ));
+    }
+}
@@ -5,15 +5,34 @@ impl std::fmt::Display for Command {
         match self {
             Self::ChangeDirectory(p) => write!(f, "{} {:?}", self.command(), p),
             Self::CommandLine(s, p) => write!(f, "{} {} {}", self.command(), s, p),
+            Self::Chain(commands) => {
+                let commands: Vec<String> = commands.iter().map(|c| c.to_string()).collect();
+                write!(f, "{}", commands.join("; "))
+            }
             Self::CursorMoveUp(i) => write!(f, "{} {}", self.command(), i),
             Self::CursorMoveDown(i) => write!(f, "{} {}", self.command(), i),
+            Self::Filter(s) => write!(f, "{} {}", self.command(), s),
+            Self::MacroRecord(register) => write!(f, "{} {}", self.command(), register),
+            Self::MacroPlay(register, 1) => write!(f, "{} {}", self.command(), register),
+            Self::MacroPlay(register, times) => {
+                write!(f, "{} {} {}", self.command(), register, times)
+            }
+            Self::PlaylistGoTo(i) => write!(f, "{} {}", self.command(), i),
+            Self::PlaylistSave(p) => write!(f, "{} {:?}", self.command(), p),
+            Self::Rate(i) => write!(f, "{} {}", self.command(), i),
+            Self::RatePlaying(i) => write!(f, "{} {}", self.command(), i),
+            Self::ScrollNameLeft(i) => write!(f, "{} {}", self.command(), i),
+            Self::ScrollNameRight(i) => write!(f, "{} {}", self.command(), i),
 
             Self::SearchGlob(s) => write!(f, "{} {}", self.command(), s),
+            Self::SearchRegex(s) => write!(f, "{} {}", self.command(), s),
             Self::SearchString(s) => write!(f, "{} {}", self.command(), s),
+            Self::SearchSkim(true) => write!(f, "{} --library", self.command()),
             Self::SelectFiles(pattern, options) => {
                 write!(f, "{} {} {}", self.command(), pattern, options)
             }
             Self::Sort(t) => write!(f, "{} {}", self.command(), t),
+            Self::SwitchView(w) => write!(f, "{} {}", self.command(), w),
             Self::ServerRequest(request) => write!(f, "{} {}", self.command(), request.api_path()),
             _ => write!(f, "{}", self.command()),
         }
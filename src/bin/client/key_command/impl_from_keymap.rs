@@ -1,17 +1,22 @@
 use std::path;
+use std::str::FromStr;
 
 use dirs_next::home_dir;
 use shellexpand::tilde_with_context;
 
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
 
 use crate::config::keymap::CommandKeymap;
 use crate::config::option::SelectOption;
 use crate::config::option::SortType;
+use crate::config::option::WidgetType;
 
-use crate::HOME_DIR;
+use crate::util::format;
+use crate::{ALIASES_T, HOME_DIR, STEPS_T};
 
 use super::constants::*;
+use super::impl_from_str::split_commands;
 use super::Command;
 
 macro_rules! simple_command_conversion_case {
@@ -29,6 +34,12 @@ impl Command {
             return Ok(Self::CommandLine(stripped.to_owned(), "".to_owned()));
         }
 
+        if split_commands(&keymap.command).len() > 1
+            || ALIASES_T.resolve(keymap.command.trim()).is_some()
+        {
+            return Self::from_str(&keymap.command);
+        }
+
         let (command, arg): (&str, &str) = match keymap.command.find(' ') {
             Some(i) => (&keymap.command[..i], keymap.command[i..].trim_start()),
             None => (&keymap.command, ""),
@@ -48,6 +59,12 @@ impl Command {
         }
 
         // client stuff
+        simple_command_conversion_case!(command, CMD_ADD_CWD, Self::AddCwd);
+        simple_command_conversion_case!(command, CMD_ADD_RECURSIVE, Self::AddRecursive);
+        simple_command_conversion_case!(command, CMD_COPY_PATH, Self::CopyPath);
+        simple_command_conversion_case!(command, CMD_COPY_PLAYING_PATH, Self::CopyPlayingPath);
+        simple_command_conversion_case!(command, CMD_DELETE_FILES, Self::DeleteFiles);
+        simple_command_conversion_case!(command, CMD_RENAME_FILE, Self::RenameFile);
         simple_command_conversion_case!(command, CMD_CLOSE, Self::Close);
         simple_command_conversion_case!(command, CMD_CURSOR_MOVE_HOME, Self::CursorMoveHome);
         simple_command_conversion_case!(command, CMD_CURSOR_MOVE_END, Self::CursorMoveEnd);
@@ -61,11 +78,25 @@ impl Command {
 
         simple_command_conversion_case!(command, CMD_OPEN_FILE, Self::OpenFile);
 
-        simple_command_conversion_case!(command, CMD_SEARCH_SKIM, Self::SearchSkim);
         simple_command_conversion_case!(command, CMD_SEARCH_NEXT, Self::SearchNext);
         simple_command_conversion_case!(command, CMD_SEARCH_PREV, Self::SearchPrev);
         simple_command_conversion_case!(command, CMD_TOGGLE_HIDDEN, Self::ToggleHiddenFiles);
         simple_command_conversion_case!(command, CMD_TOGGLE_VIEW, Self::ToggleView);
+        simple_command_conversion_case!(command, CMD_TOGGLE_AUDIO_ONLY, Self::ToggleAudioOnly);
+        simple_command_conversion_case!(command, CMD_TOGGLE_FOLLOW, Self::ToggleFollow);
+        simple_command_conversion_case!(command, CMD_TOGGLE_MINIMAL, Self::ToggleMinimal);
+        simple_command_conversion_case!(
+            command,
+            CMD_TOGGLE_REMAINING_TIME,
+            Self::ToggleRemainingTime
+        );
+        simple_command_conversion_case!(command, CMD_TOGGLE_VISUALIZER, Self::ToggleVisualizer);
+        simple_command_conversion_case!(command, CMD_TOGGLE_LYRICS, Self::ToggleLyrics);
+        simple_command_conversion_case!(
+            command,
+            CMD_TOGGLE_DIRECTORY_PLAYLIST,
+            Self::ToggleDirectoryPlaylist
+        );
 
         if command == CMD_CHANGE_DIRECTORY {
             match arg {
@@ -98,8 +129,124 @@ impl Command {
                     Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
                 },
             }
+        } else if command == CMD_SCROLL_NAME_LEFT {
+            match arg {
+                "" => Ok(Self::ScrollNameLeft(SCROLL_NAME_COLUMNS)),
+                arg => match arg.trim().parse::<usize>() {
+                    Ok(s) => Ok(Self::ScrollNameLeft(s)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_SCROLL_NAME_RIGHT {
+            match arg {
+                "" => Ok(Self::ScrollNameRight(SCROLL_NAME_COLUMNS)),
+                arg => match arg.trim().parse::<usize>() {
+                    Ok(s) => Ok(Self::ScrollNameRight(s)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_RATE || command == CMD_RATE_PLAYING {
+            let rating = match arg.trim().parse::<u8>() {
+                Ok(rating) => rating,
+                Err(e) => return Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+            };
+            if command == CMD_RATE {
+                Ok(Self::Rate(rating))
+            } else {
+                Ok(Self::RatePlaying(rating))
+            }
+        } else if command == CMD_PLAYER_REWIND || command == CMD_PLAYER_FAST_FORWARD {
+            let amount = match arg.trim() {
+                "" => STEPS_T.seek,
+                arg => match format::parse_duration(arg) {
+                    Some(duration) => duration.as_secs() as usize,
+                    None => {
+                        return Err(DiziError::new(
+                            DiziErrorKind::ParseError,
+                            format!("{}: Invalid duration '{}'", command, arg),
+                        ))
+                    }
+                },
+            };
+            if command == CMD_PLAYER_REWIND {
+                Ok(Self::ServerRequest(ClientRequest::PlayerRewind { amount }))
+            } else {
+                Ok(Self::ServerRequest(ClientRequest::PlayerFastForward {
+                    amount,
+                }))
+            }
+        } else if command == CMD_SEEK {
+            if let Some(percent) = arg.trim().strip_suffix('%') {
+                return match percent.trim().parse::<u8>() {
+                    Ok(percent) if percent <= 100 => {
+                        Ok(Self::ServerRequest(ClientRequest::seek_percent(percent)))
+                    }
+                    _ => Err(DiziError::new(
+                        DiziErrorKind::InvalidParameters,
+                        format!(
+                            "{}: Expected a percentage from 0 to 100, got '{}'",
+                            command, arg
+                        ),
+                    )),
+                };
+            }
+            match format::parse_duration(arg) {
+                Some(duration) => Ok(Self::ServerRequest(ClientRequest::PlayerSeek {
+                    position: duration.as_secs() as usize,
+                })),
+                None => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!(
+                        "{}: Expected a position such as 43:20 or 90, got '{}'",
+                        command, arg
+                    ),
+                )),
+            }
+        } else if command == CMD_PLAYER_VOLUME_UP || command == CMD_PLAYER_VOLUME_DOWN {
+            let amount = match arg.trim() {
+                "" => STEPS_T.volume,
+                arg => match arg.trim_end_matches('%').parse::<usize>() {
+                    Ok(amount) => amount,
+                    Err(e) => return Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            };
+            if command == CMD_PLAYER_VOLUME_UP {
+                Ok(Self::ServerRequest(ClientRequest::PlayerVolumeUp {
+                    amount,
+                }))
+            } else {
+                Ok(Self::ServerRequest(ClientRequest::PlayerVolumeDown {
+                    amount,
+                }))
+            }
+        } else if command == CMD_PLAYLIST_GOTO {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => match arg.trim().parse::<usize>() {
+                    Ok(s) => Ok(Self::PlaylistGoTo(s)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_PLAYLIST_SAVE {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => {
+                    let path_accepts_tilde = tilde_with_context(arg, home_dir);
+                    Ok(Self::PlaylistSave(path::PathBuf::from(
+                        path_accepts_tilde.as_ref(),
+                    )))
+                }
+            }
         } else if command == CMD_RELOAD_DIRECTORY_LIST {
             Ok(Self::ReloadDirList)
+        } else if command == CMD_FILTER {
+            Ok(Self::Filter(arg.trim().to_string()))
         } else if command == CMD_SEARCH_STRING {
             match arg {
                 "" => Err(DiziError::new(
@@ -108,6 +255,15 @@ impl Command {
                 )),
                 arg => Ok(Self::SearchString(arg.to_string())),
             }
+        } else if command == CMD_SEARCH_SKIM {
+            match arg.trim() {
+                "" => Ok(Self::SearchSkim(false)),
+                "--library" => Ok(Self::SearchSkim(true)),
+                arg => Err(DiziError::new(
+                    DiziErrorKind::UnrecognizedArgument,
+                    format!("{}: Unknown argument '{}'", command, arg),
+                )),
+            }
         } else if command == CMD_SEARCH_GLOB {
             match arg {
                 "" => Err(DiziError::new(
@@ -116,6 +272,35 @@ impl Command {
                 )),
                 arg => Ok(Self::SearchGlob(arg.to_string())),
             }
+        } else if command == CMD_MACRO_RECORD || command == CMD_MACRO_PLAY {
+            let mut args = arg.split_whitespace();
+            let register = match args.next().map(|s| (s.chars().next(), s.chars().count())) {
+                Some((Some(c), 1)) => c,
+                _ => {
+                    return Err(DiziError::new(
+                        DiziErrorKind::InvalidParameters,
+                        format!("{}: Expected a register, e.g. 'q'", command),
+                    ))
+                }
+            };
+            if command == CMD_MACRO_RECORD {
+                return Ok(Self::MacroRecord(register));
+            }
+            match args.next() {
+                None => Ok(Self::MacroPlay(register, 1)),
+                Some(times) => match times.parse::<usize>() {
+                    Ok(times) => Ok(Self::MacroPlay(register, times)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_SEARCH_REGEX {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => Ok(Self::SearchRegex(arg.to_string())),
+            }
         } else if command == CMD_SELECT_FILES {
             let mut options = SelectOption::default();
             let mut pattern = "";
@@ -141,6 +326,7 @@ impl Command {
             }
         } else if command == CMD_SORT {
             match arg {
+                "" => Ok(Self::SortMenu),
                 "reverse" => Ok(Self::SortReverse),
                 arg => match SortType::parse(arg) {
                     Some(s) => Ok(Self::Sort(s)),
@@ -150,6 +336,14 @@ impl Command {
                     )),
                 },
             }
+        } else if command == CMD_SWITCH_VIEW {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => Ok(Self::SwitchView(WidgetType::from_str(arg)?)),
+            }
         } else {
             Err(DiziError::new(
                 DiziErrorKind::UnrecognizedCommand,
@@ -1,14 +1,21 @@
 use std::path;
+use std::str::FromStr;
 
 use dirs_next::home_dir;
 use shellexpand::tilde_with_context;
 
 use dizi::error::{DiziError, DiziErrorKind};
+use dizi::request::client::ClientRequest;
 
 use crate::config::option::SelectOption;
 use crate::config::option::SortType;
+use crate::config::option::WidgetType;
 
-use crate::HOME_DIR;
+use crate::util::format;
+use crate::{ALIASES_T, HOME_DIR, STEPS_T};
+
+// aliases standing for other aliases are expanded this many times at most
+const MAX_ALIAS_DEPTH: usize = 8;
 
 use super::constants::*;
 use super::Command;
@@ -21,19 +28,74 @@ macro_rules! simple_command_conversion_case {
     };
 }
 
-impl std::str::FromStr for Command {
+/// Splits `s` on the `;` that are outside of quotes and json objects
+pub fn split_commands(s: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut start = 0;
+    let mut depth = 0;
+    let mut quoted = false;
+    for (i, c) in s.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '{' if !quoted => depth += 1,
+            '}' if !quoted => depth -= 1,
+            ';' if !quoted && depth == 0 => {
+                commands.push(s[start..i].trim());
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    commands.push(s[start..].trim());
+    commands.retain(|command| !command.is_empty());
+    commands
+}
+
+impl FromStr for Command {
     type Err = DiziError;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse_command(s, MAX_ALIAS_DEPTH)
+    }
+}
+
+impl Command {
+    fn parse_command(s: &str, alias_depth: usize) -> Result<Self, DiziError> {
         if let Some(stripped) = s.strip_prefix(':') {
             return Ok(Self::CommandLine(stripped.to_owned(), "".to_owned()));
         }
 
+        let commands = split_commands(s);
+        if commands.len() > 1 {
+            let commands = commands
+                .into_iter()
+                .map(|s| Self::parse_command(s, alias_depth))
+                .collect::<Result<Vec<Self>, DiziError>>()?;
+            return Ok(Self::Chain(commands));
+        }
+
+        // aliases take precedence over the built-in commands
+        if let Some(command) = ALIASES_T.resolve(s.trim()) {
+            if alias_depth == 0 {
+                return Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("Too many nested aliases in '{}'", s),
+                ));
+            }
+            return Self::parse_command(&command, alias_depth - 1);
+        }
+
         let (command, arg) = match s.find(' ') {
             Some(i) => (&s[..i], s[i..].trim_start()),
             None => (s, ""),
         };
 
+        simple_command_conversion_case!(command, CMD_ADD_CWD, Self::AddCwd);
+        simple_command_conversion_case!(command, CMD_ADD_RECURSIVE, Self::AddRecursive);
+        simple_command_conversion_case!(command, CMD_COPY_PATH, Self::CopyPath);
+        simple_command_conversion_case!(command, CMD_COPY_PLAYING_PATH, Self::CopyPlayingPath);
+        simple_command_conversion_case!(command, CMD_DELETE_FILES, Self::DeleteFiles);
+        simple_command_conversion_case!(command, CMD_RENAME_FILE, Self::RenameFile);
         simple_command_conversion_case!(command, CMD_CLOSE, Self::Close);
         simple_command_conversion_case!(command, CMD_CURSOR_MOVE_HOME, Self::CursorMoveHome);
         simple_command_conversion_case!(command, CMD_CURSOR_MOVE_END, Self::CursorMoveEnd);
@@ -48,11 +110,25 @@ impl std::str::FromStr for Command {
 
         simple_command_conversion_case!(command, CMD_OPEN_FILE, Self::OpenFile);
 
-        simple_command_conversion_case!(command, CMD_SEARCH_SKIM, Self::SearchSkim);
         simple_command_conversion_case!(command, CMD_SEARCH_NEXT, Self::SearchNext);
         simple_command_conversion_case!(command, CMD_SEARCH_PREV, Self::SearchPrev);
         simple_command_conversion_case!(command, CMD_TOGGLE_HIDDEN, Self::ToggleHiddenFiles);
         simple_command_conversion_case!(command, CMD_TOGGLE_VIEW, Self::ToggleView);
+        simple_command_conversion_case!(command, CMD_TOGGLE_AUDIO_ONLY, Self::ToggleAudioOnly);
+        simple_command_conversion_case!(command, CMD_TOGGLE_FOLLOW, Self::ToggleFollow);
+        simple_command_conversion_case!(command, CMD_TOGGLE_MINIMAL, Self::ToggleMinimal);
+        simple_command_conversion_case!(
+            command,
+            CMD_TOGGLE_REMAINING_TIME,
+            Self::ToggleRemainingTime
+        );
+        simple_command_conversion_case!(command, CMD_TOGGLE_VISUALIZER, Self::ToggleVisualizer);
+        simple_command_conversion_case!(command, CMD_TOGGLE_LYRICS, Self::ToggleLyrics);
+        simple_command_conversion_case!(
+            command,
+            CMD_TOGGLE_DIRECTORY_PLAYLIST,
+            Self::ToggleDirectoryPlaylist
+        );
 
         if command == CMD_CHANGE_DIRECTORY {
             match arg {
@@ -85,8 +161,124 @@ impl std::str::FromStr for Command {
                     Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
                 },
             }
+        } else if command == CMD_SCROLL_NAME_LEFT {
+            match arg {
+                "" => Ok(Self::ScrollNameLeft(SCROLL_NAME_COLUMNS)),
+                arg => match arg.trim().parse::<usize>() {
+                    Ok(s) => Ok(Self::ScrollNameLeft(s)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_SCROLL_NAME_RIGHT {
+            match arg {
+                "" => Ok(Self::ScrollNameRight(SCROLL_NAME_COLUMNS)),
+                arg => match arg.trim().parse::<usize>() {
+                    Ok(s) => Ok(Self::ScrollNameRight(s)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_RATE || command == CMD_RATE_PLAYING {
+            let rating = match arg.trim().parse::<u8>() {
+                Ok(rating) => rating,
+                Err(e) => return Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+            };
+            if command == CMD_RATE {
+                Ok(Self::Rate(rating))
+            } else {
+                Ok(Self::RatePlaying(rating))
+            }
+        } else if command == CMD_PLAYER_REWIND || command == CMD_PLAYER_FAST_FORWARD {
+            let amount = match arg.trim() {
+                "" => STEPS_T.seek,
+                arg => match format::parse_duration(arg) {
+                    Some(duration) => duration.as_secs() as usize,
+                    None => {
+                        return Err(DiziError::new(
+                            DiziErrorKind::ParseError,
+                            format!("{}: Invalid duration '{}'", command, arg),
+                        ))
+                    }
+                },
+            };
+            if command == CMD_PLAYER_REWIND {
+                Ok(Self::ServerRequest(ClientRequest::PlayerRewind { amount }))
+            } else {
+                Ok(Self::ServerRequest(ClientRequest::PlayerFastForward {
+                    amount,
+                }))
+            }
+        } else if command == CMD_SEEK {
+            if let Some(percent) = arg.trim().strip_suffix('%') {
+                return match percent.trim().parse::<u8>() {
+                    Ok(percent) if percent <= 100 => {
+                        Ok(Self::ServerRequest(ClientRequest::seek_percent(percent)))
+                    }
+                    _ => Err(DiziError::new(
+                        DiziErrorKind::InvalidParameters,
+                        format!(
+                            "{}: Expected a percentage from 0 to 100, got '{}'",
+                            command, arg
+                        ),
+                    )),
+                };
+            }
+            match format::parse_duration(arg) {
+                Some(duration) => Ok(Self::ServerRequest(ClientRequest::PlayerSeek {
+                    position: duration.as_secs() as usize,
+                })),
+                None => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!(
+                        "{}: Expected a position such as 43:20 or 90, got '{}'",
+                        command, arg
+                    ),
+                )),
+            }
+        } else if command == CMD_PLAYER_VOLUME_UP || command == CMD_PLAYER_VOLUME_DOWN {
+            let amount = match arg.trim() {
+                "" => STEPS_T.volume,
+                arg => match arg.trim_end_matches('%').parse::<usize>() {
+                    Ok(amount) => amount,
+                    Err(e) => return Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            };
+            if command == CMD_PLAYER_VOLUME_UP {
+                Ok(Self::ServerRequest(ClientRequest::PlayerVolumeUp {
+                    amount,
+                }))
+            } else {
+                Ok(Self::ServerRequest(ClientRequest::PlayerVolumeDown {
+                    amount,
+                }))
+            }
+        } else if command == CMD_PLAYLIST_GOTO {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => match arg.trim().parse::<usize>() {
+                    Ok(s) => Ok(Self::PlaylistGoTo(s)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_PLAYLIST_SAVE {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => {
+                    let path_accepts_tilde = tilde_with_context(arg, home_dir);
+                    Ok(Self::PlaylistSave(path::PathBuf::from(
+                        path_accepts_tilde.as_ref(),
+                    )))
+                }
+            }
         } else if command == CMD_RELOAD_DIRECTORY_LIST {
             Ok(Self::ReloadDirList)
+        } else if command == CMD_FILTER {
+            Ok(Self::Filter(arg.trim().to_string()))
         } else if command == CMD_SEARCH_STRING {
             match arg {
                 "" => Err(DiziError::new(
@@ -95,6 +287,15 @@ impl std::str::FromStr for Command {
                 )),
                 arg => Ok(Self::SearchString(arg.to_string())),
             }
+        } else if command == CMD_SEARCH_SKIM {
+            match arg.trim() {
+                "" => Ok(Self::SearchSkim(false)),
+                "--library" => Ok(Self::SearchSkim(true)),
+                arg => Err(DiziError::new(
+                    DiziErrorKind::UnrecognizedArgument,
+                    format!("{}: Unknown argument '{}'", command, arg),
+                )),
+            }
         } else if command == CMD_SEARCH_GLOB {
             match arg {
                 "" => Err(DiziError::new(
@@ -103,6 +304,35 @@ impl std::str::FromStr for Command {
                 )),
                 arg => Ok(Self::SearchGlob(arg.to_string())),
             }
+        } else if command == CMD_MACRO_RECORD || command == CMD_MACRO_PLAY {
+            let mut args = arg.split_whitespace();
+            let register = match args.next().map(|s| (s.chars().next(), s.chars().count())) {
+                Some((Some(c), 1)) => c,
+                _ => {
+                    return Err(DiziError::new(
+                        DiziErrorKind::InvalidParameters,
+                        format!("{}: Expected a register, e.g. 'q'", command),
+                    ))
+                }
+            };
+            if command == CMD_MACRO_RECORD {
+                return Ok(Self::MacroRecord(register));
+            }
+            match args.next() {
+                None => Ok(Self::MacroPlay(register, 1)),
+                Some(times) => match times.parse::<usize>() {
+                    Ok(times) => Ok(Self::MacroPlay(register, times)),
+                    Err(e) => Err(DiziError::new(DiziErrorKind::ParseError, e.to_string())),
+                },
+            }
+        } else if command == CMD_SEARCH_REGEX {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => Ok(Self::SearchRegex(arg.to_string())),
+            }
         } else if command == CMD_SELECT_FILES {
             let mut options = SelectOption::default();
             let mut pattern = "";
@@ -128,6 +358,7 @@ impl std::str::FromStr for Command {
             }
         } else if command == CMD_SORT {
             match arg {
+                "" => Ok(Self::SortMenu),
                 "reverse" => Ok(Self::SortReverse),
                 arg => match SortType::parse(arg) {
                     Some(s) => Ok(Self::Sort(s)),
@@ -137,6 +368,34 @@ impl std::str::FromStr for Command {
                     )),
                 },
             }
+        } else if command == CMD_SERVER_REQUEST {
+            // either the request's json or only its api path,
+            // for requests without parameters
+            let json = match arg.trim() {
+                "" => {
+                    return Err(DiziError::new(
+                        DiziErrorKind::InvalidParameters,
+                        format!("{}: Expected 1, got 0", command),
+                    ))
+                }
+                arg if arg.starts_with('{') => arg.to_string(),
+                arg => serde_json::json!({ "api": arg }).to_string(),
+            };
+            match serde_json::from_str::<ClientRequest>(&json) {
+                Ok(request) => Ok(Self::ServerRequest(request)),
+                Err(e) => Err(DiziError::new(
+                    DiziErrorKind::ParseError,
+                    format!("{}: {}", command, e),
+                )),
+            }
+        } else if command == CMD_SWITCH_VIEW {
+            match arg {
+                "" => Err(DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                )),
+                arg => Ok(Self::SwitchView(WidgetType::from_str(arg)?)),
+            }
         } else {
             Err(DiziError::new(
                 DiziErrorKind::UnrecognizedCommand,
@@ -145,3 +404,49 @@ impl std::str::FromStr for Command {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_chained_commands() {
+        assert_eq!(
+            split_commands("toggle_view; cursor_move_down 2 ;"),
+            vec!["toggle_view", "cursor_move_down 2"]
+        );
+        assert_eq!(
+            split_commands(r#"server_request {"api": "/server/query", "query": "a;b"}; close"#),
+            vec![
+                r#"server_request {"api": "/server/query", "query": "a;b"}"#,
+                "close"
+            ]
+        );
+    }
+
+    #[test]
+    fn parse_seek_position() {
+        let position = |s: &str| match Command::from_str(s) {
+            Ok(Command::ServerRequest(ClientRequest::PlayerSeek { position })) => Some(position),
+            _ => None,
+        };
+        assert_eq!(position("seek 43:20"), Some(2600));
+        assert_eq!(position("seek 90"), Some(90));
+        assert_eq!(position("seek"), None);
+        assert_eq!(position("seek soon"), None);
+        assert!(matches!(
+            Command::from_str("seek 30%"),
+            Ok(Command::ServerRequest(ClientRequest::PlayerSeekPercent {
+                percent: 30
+            }))
+        ));
+        assert!(Command::from_str("seek 130%").is_err());
+    }
+
+    #[test]
+    fn parse_chained_commands() {
+        let command = Command::from_str("toggle_view; cursor_move_down 2").unwrap();
+        assert_eq!(command.to_string(), "toggle_view; cursor_move_down 2");
+        assert!(Command::from_str("toggle_view; not_a_command").is_err());
+    }
+}
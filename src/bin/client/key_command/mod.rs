@@ -5,11 +5,13 @@ pub mod traits;
 
 mod impl_appcommand;
 mod impl_appexecute;
+mod impl_count;
 mod impl_display;
 mod impl_from_keymap;
 mod impl_from_str;
 
 pub use self::commands::*;
 pub use self::constants::*;
+pub use self::impl_count::MAX_COUNT;
 pub use self::keybind::*;
 pub use self::traits::*;
@@ -12,22 +12,28 @@ mod traits;
 mod ui;
 mod util;
 
-use std::os::unix::net::UnixStream;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::process;
 use std::thread;
 use std::time;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser, Subcommand};
 use lazy_static::lazy_static;
+use termion::input::TermRead;
 
-use dizi::error::DiziResult;
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
 
+use crate::commands::session;
+use crate::config::option::{CommandAliases, ServerOption, StepOption};
 use crate::config::{
-    AppConfig, AppKeyMapping, AppLayout, AppTheme, JsonConfigFile, TomlConfigFile,
+    AppConfig, AppKeyMapping, AppLayout, AppMessages, AppTheme, JsonConfigFile, TomlConfigFile,
 };
-use crate::context::AppContext;
+use crate::context::{AppContext, Session};
 use crate::tab::JoshutoTab;
+use crate::util::connection::{ServerAddress, ServerStream};
+use crate::util::request::send_client_request;
 
 const PROGRAM_NAME: &str = "dizi";
 const CONFIG_HOME: &str = "DIZI_CONFIG_HOME";
@@ -68,6 +74,9 @@ lazy_static! {
     };
 
     static ref THEME_T: AppTheme = AppTheme::get_config(THEME_FILE);
+    static ref MESSAGES_T: AppMessages = AppMessages::get_config(CONFIG_FILE);
+    static ref ALIASES_T: CommandAliases = CommandAliases::get_config(CONFIG_FILE);
+    static ref STEPS_T: StepOption = StepOption::get_config(CONFIG_FILE);
     static ref HOME_DIR: Option<PathBuf> = dirs_next::home_dir();
     static ref LAYOUT_T: AppLayout = AppLayout::get_config(LAYOUT_FILE);
 }
@@ -78,6 +87,10 @@ pub struct CommandArgs {
     #[arg(short = 'v', long = "version")]
     version: bool,
 
+    // host:port of a server on another machine
+    #[arg(long = "address")]
+    address: Option<String>,
+
     // query
     #[arg(short = 'Q', long = "query")]
     query: Option<String>,
@@ -98,22 +111,120 @@ pub struct CommandArgs {
     resume: bool,
     #[arg(long = "toggle-pause")]
     toggle_play: bool,
+
+    // runs a single command, as typed in the command line, and exits
+    #[arg(short = 'c', long = "command")]
+    command: Option<String>,
+    // prints the server's response to the command
+    #[arg(long = "print")]
+    print: bool,
+
+    // replaces the playlist with a playlist file before starting the frontend
+    #[arg(long = "playlist")]
+    playlist: Option<PathBuf>,
+    // starts playing the playlist given with --playlist
+    #[arg(long = "play", requires = "playlist")]
+    play: bool,
+
+    // shows only the spectrum of the playing song, without the file browser
+    #[arg(long = "visualizer")]
+    visualizer: bool,
+    // announces changes as plain lines of text, for screen readers
+    #[arg(long = "accessible")]
+    accessible: bool,
+
+    #[command(subcommand)]
+    subcommand: Option<SubCommand>,
 }
 
-fn start_server() -> DiziResult {
+#[derive(Clone, Debug, Subcommand)]
+pub enum SubCommand {
+    // same as --command, without the quotes
+    Cmd {
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+    // prints the player's state
+    Status {
+        // plain, json or a template such as "%artist - %title [%elapsed]"
+        #[arg(long = "format", default_value = "plain")]
+        format: run::StatusOutput,
+        // same as --format json
+        #[arg(long = "json")]
+        json: bool,
+    },
+    // prints a line for every change on the server until it quits
+    Idle {
+        // prints the server's events as json instead
+        #[arg(long = "json")]
+        json: bool,
+    },
+    // lists a directory of the server's machine
+    Ls {
+        path: Option<PathBuf>,
+        // prints the entries as json instead
+        #[arg(long = "json")]
+        json: bool,
+    },
+    // prints the completion script for a shell
+    Completions {
+        shell: util::completions::Shell,
+    },
+}
+
+fn start_server(options: &ServerOption) -> DiziResult {
     println!("Server is not running");
     println!("Starting server...");
-    process::Command::new("dizi-server")
+    process::Command::new(&options.path)
+        .args(&options.args)
         .stdout(process::Stdio::null())
         .stderr(process::Stdio::null())
-        .spawn()?;
+        .spawn()
+        .map_err(|e| {
+            DiziError::new(
+                DiziErrorKind::IoError(e.kind()),
+                format!("Failed to start '{}': {}", options.path, e),
+            )
+        })?;
     Ok(())
 }
 
-fn create_context(config: AppConfig, cwd: &Path, stream: UnixStream) -> AppContext {
+fn prompt_password(address: &ServerAddress) -> DiziResult<String> {
+    let mut stdout = io::stdout();
+    write!(stdout, "Password for {}: ", address)?;
+    stdout.flush()?;
+    let password = io::stdin().read_passwd(&mut stdout)?;
+    writeln!(stdout)?;
+    password.ok_or_else(|| {
+        DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "No password given".to_string(),
+        )
+    })
+}
+
+fn connect(config: &AppConfig) -> io::Result<ServerStream> {
+    let mut stream = ServerStream::connect(&config.client_ref().server_address())?;
+    if let Some(password) = config.client_ref().password.as_ref() {
+        stream.authenticate(password)?;
+    }
+    Ok(stream)
+}
+
+fn create_context(config: AppConfig, cwd: &Path, stream: ServerStream) -> AppContext {
     AppContext::new(config, cwd.to_path_buf(), stream)
 }
 
+/// Replaces the server's playlist with the playlist file at `path`
+fn open_playlist(context: &mut AppContext, path: PathBuf, play: bool) -> DiziResult {
+    send_client_request(context, &ClientRequest::playlist_clear())?;
+    send_client_request(context, &ClientRequest::playlist_open(path))?;
+    if play {
+        send_client_request(context, &ClientRequest::playlist_play(0))?;
+    }
+    Ok(())
+}
+
 fn run_app(args: CommandArgs) -> DiziResult {
     // print version
     if args.version {
@@ -122,22 +233,78 @@ fn run_app(args: CommandArgs) -> DiziResult {
         return Ok(());
     }
 
-    let config = AppConfig::get_config(CONFIG_FILE);
+    if let Some(SubCommand::Completions { shell }) = args.subcommand {
+        let mut command = CommandArgs::command();
+        print!("{}", util::completions::generate(shell, &mut command));
+        return Ok(());
+    }
+
+    let mut config = AppConfig::get_config(CONFIG_FILE);
+    if let Some(address) = args.address.clone() {
+        config.client_mut().address = Some(address);
+    }
+    if args.accessible {
+        config.client_mut().accessible = true;
+    }
+    if config.client_ref().auth && config.client_ref().password.is_none() {
+        let password = prompt_password(&config.client_ref().server_address())?;
+        config.client_mut().password = Some(password);
+    }
+    // relative to where dizi was started, not home_dir
+    let playlist = match args.playlist.as_ref() {
+        Some(path) if !path.is_file() => {
+            return Err(DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                format!("Playlist {:?} is not a file", path),
+            ));
+        }
+        Some(path) => Some(std::env::current_dir()?.join(path)),
+        None => None,
+    };
     if let Some(home_dir) = config.client_ref().home_dir.as_ref() {
         std::env::set_current_dir(home_dir)?;
     }
     let cwd = std::env::current_dir()?;
 
-    // query
-    if args.query_all {
+    let command = match args.subcommand.as_ref() {
+        Some(SubCommand::Cmd { command }) => Some(command.join(" ")),
+        _ => args.command.clone(),
+    };
+
+    if let Some(SubCommand::Status { format, json }) = args.subcommand {
+        // connect to stream
+        let stream = connect(&config)?;
+        let mut context = create_context(config, &cwd, stream);
+        let format = if json {
+            run::StatusOutput::Json
+        } else {
+            format
+        };
+        run::run_status(&mut context, format)?;
+    } else if let Some(SubCommand::Idle { json }) = args.subcommand {
         // connect to stream
-        let stream = UnixStream::connect(config.client_ref().socket_ref())?;
+        let stream = connect(&config)?;
+        let mut context = create_context(config, &cwd, stream);
+        run::run_idle(&mut context, json)?;
+    } else if let Some(SubCommand::Ls { path, json }) = args.subcommand {
+        // connect to stream
+        let stream = connect(&config)?;
+        let mut context = create_context(config, &cwd, stream);
+        run::run_ls(&mut context, path, json)?;
+    } else if let Some(command) = command {
+        // connect to stream
+        let stream = connect(&config)?;
+        let mut context = create_context(config, &cwd, stream);
+        run::run_command(&mut context, &command, args.print)?;
+    } else if args.query_all {
+        // connect to stream
+        let stream = connect(&config)?;
         let mut context = create_context(config, &cwd, stream);
         run::run_query_all(&mut context)?;
         return Ok(());
     } else if let Some(query) = args.query {
         // connect to stream
-        let stream = UnixStream::connect(config.client_ref().socket_ref())?;
+        let stream = connect(&config)?;
         let mut context = create_context(config, &cwd, stream);
         run::run_query(&mut context, query)?;
         return Ok(());
@@ -149,33 +316,68 @@ fn run_app(args: CommandArgs) -> DiziResult {
         || args.toggle_play
     {
         // connect to stream
-        let stream = UnixStream::connect(config.client_ref().socket_ref())?;
+        let stream = connect(&config)?;
         let mut context = create_context(config, &cwd, stream);
         run::run_control(&mut context, &args)?;
     } else {
         lazy_static::initialize(&HOME_DIR);
         lazy_static::initialize(&THEME_T);
-        lazy_static::initialize(&LAYOUT_T);
-
-        let mut stream = UnixStream::connect(config.client_ref().socket_ref());
-        if stream.is_err() {
-            start_server()?;
+        lazy_static::initialize(&MESSAGES_T);
+        if config
+            .client_ref()
+            .display_options_ref()
+            .layout_ref()
+            .is_none()
+        {
+            lazy_static::initialize(&LAYOUT_T);
         }
-        println!("Connecting to server ...");
-        for i in 1..11 {
-            stream = UnixStream::connect(config.client_ref().socket_ref());
-            if stream.is_ok() {
-                break;
+
+        let server_options = config.client_ref().server_options_ref().clone();
+        let mut stream = connect(&config);
+        // only a server on this machine can be started
+        if stream.is_err()
+            && server_options.auto_start
+            && config.client_ref().server_address().is_local()
+        {
+            start_server(&server_options)?;
+            println!("Connecting to server ...");
+            // wait for the server to create its socket
+            let start = time::Instant::now();
+            while stream.is_err() && start.elapsed() < server_options.timeout {
+                thread::sleep(time::Duration::from_millis(100));
+                stream = connect(&config);
             }
-            let wait_interval = time::Duration::from_millis(500);
-            thread::sleep(wait_interval);
-            println!("Retrying #{} ...", i);
         }
 
         match stream {
-            Err(_) => eprintln!("Error: Failed to connect to server after 10 retries"),
+            Err(e) => eprintln!(
+                "Error: Failed to connect to {}: {}",
+                config.client_ref().server_address(),
+                e
+            ),
             Ok(stream) => {
+                // resume in the directory the last session was left in
+                let last_session = config
+                    .client_ref()
+                    .session
+                    .as_ref()
+                    .and_then(|path| Session::from_file(path).ok())
+                    .filter(|session| session.cwd.is_dir());
+                let cwd = match last_session.as_ref() {
+                    Some(session) => {
+                        std::env::set_current_dir(&session.cwd)?;
+                        session.cwd.clone()
+                    }
+                    None => cwd,
+                };
                 let mut context = create_context(config, &cwd, stream);
+                if let Some(path) = playlist {
+                    open_playlist(&mut context, path, args.play)?;
+                }
+                if context.config_ref().client_ref().accessible {
+                    run::run_accessible(&mut context)?;
+                    return Ok(());
+                }
 
                 let keymap = AppKeyMapping::get_config(KEYMAP_FILE);
                 // eprintln!("keymap: {:#?}", keymap);
@@ -186,9 +388,24 @@ fn run_app(args: CommandArgs) -> DiziResult {
                     context.config_ref().display_options_ref(),
                 )?;
                 context.tab_context_mut().push_tab(tab);
+                if args.visualizer {
+                    let mut backend: ui::AppBackend = ui::AppBackend::new()?;
+                    run::run_visualizer(&mut backend, &mut context)?;
+                    return Ok(());
+                }
+                if let Some(last_session) = last_session.as_ref() {
+                    session::restore_session(&mut context, last_session)?;
+                }
 
                 let mut backend: ui::AppBackend = ui::AppBackend::new()?;
                 run::run_ui(&mut backend, &mut context, keymap)?;
+                drop(backend);
+
+                if let Some(path) = context.config_ref().client_ref().session.as_ref() {
+                    if let Err(err) = session::save_session(&context, path) {
+                        eprintln!("Failed to save session: {}", err);
+                    }
+                }
             }
         }
     }
@@ -1,2 +1,3 @@
 pub mod preview_default;
 pub mod preview_dir;
+pub mod preview_metadata;
@@ -0,0 +1,44 @@
+use std::path;
+use std::thread;
+
+use dizi::song::{DiziAudioFile, DiziFile};
+
+use crate::context::{AppContext, SongMetadata};
+use crate::event::AppEvent;
+use crate::util::audio::is_audio_file;
+
+/// Reads the metadata of the songs in the current directory that are not cached yet
+pub fn load_metadata(context: &mut AppContext) {
+    let paths: Vec<path::PathBuf> = match context.tab_context_ref().curr_tab_ref().curr_list_ref() {
+        Some(curr_list) => curr_list
+            .iter()
+            .map(|entry| entry.file_path())
+            .filter(|p| is_audio_file(p) && !context.metadata_cache_ref().contains(p))
+            .map(|p| p.to_path_buf())
+            .collect(),
+        None => return,
+    };
+    if paths.is_empty() {
+        return;
+    }
+
+    for p in paths.iter() {
+        context.metadata_cache_mut().set_pending(p.clone());
+    }
+
+    let event_tx = context.clone_event_tx();
+    let _ = thread::spawn(move || {
+        for path in paths {
+            // files that fail to load are cached without metadata
+            let metadata = DiziAudioFile::try_from(DiziFile::new(path.as_path()))
+                .map(|song| SongMetadata::from(&song))
+                .unwrap_or_default();
+            if event_tx
+                .send(AppEvent::MetadataLoad { path, metadata })
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+}
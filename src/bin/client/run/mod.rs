@@ -1,9 +1,21 @@
+pub mod run_accessible;
+pub mod run_command;
 pub mod run_control;
+pub mod run_idle;
+pub mod run_ls;
 pub mod run_query;
 pub mod run_query_all;
+pub mod run_status;
 pub mod run_ui;
+pub mod run_visualizer;
 
+pub use self::run_accessible::*;
+pub use self::run_command::*;
 pub use self::run_control::*;
+pub use self::run_idle::*;
+pub use self::run_ls::*;
 pub use self::run_query::*;
 pub use self::run_query_all::*;
+pub use self::run_status::*;
 pub use self::run_ui::*;
+pub use self::run_visualizer::*;
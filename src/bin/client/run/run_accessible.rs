@@ -0,0 +1,209 @@
+use termion::event::{Event, Key};
+
+use dizi::error::DiziResult;
+use dizi::player::{PlayerState, RepeatMode};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+use dizi::song::{DiziAudioFile, ReplayGainMode};
+
+use crate::context::AppContext;
+use crate::event::AppEvent;
+use crate::run::headless_request;
+use crate::util::connection;
+use crate::util::format;
+use crate::util::request::send_client_request;
+use crate::MESSAGES_T;
+
+const QUIT: &str = "quit";
+
+/// Line based frontend for screen readers and braille terminals, changes are
+/// announced one line at a time and commands are read a line at a time
+pub fn run_accessible(context: &mut AppContext) -> DiziResult {
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+    println!("{}", MESSAGES_T.get("announce.welcome"));
+    send_client_request(context, &ClientRequest::PlayerState)?;
+
+    let mut line = String::new();
+    loop {
+        match context.poll_event() {
+            // the terminal isn't raw, so keys only arrive once enter is pressed
+            Ok(AppEvent::Termion(Event::Key(key))) => {
+                match key {
+                    Key::Char('\n') => {
+                        let command = std::mem::take(&mut line);
+                        if command.trim() == QUIT {
+                            return Ok(());
+                        }
+                        if let Err(err) = run_line(context, command.trim()) {
+                            println!("{}", err);
+                        }
+                    }
+                    Key::Char(c) => line.push(c),
+                    Key::Ctrl('d') => return Ok(()),
+                    _ => {}
+                }
+                context.flush_event();
+            }
+            Ok(AppEvent::Server(message)) => {
+                let event = ServerBroadcastEvent::from_json(&message)?;
+                if let Some(announcement) = announcement(&event) {
+                    println!("{}", announcement);
+                }
+                if let ServerBroadcastEvent::ServerQuit = event {
+                    return Ok(());
+                }
+            }
+            Ok(AppEvent::ServerDisconnect) | Err(_) => {
+                println!("{}", MESSAGES_T.get("announce.disconnected"));
+                return Ok(());
+            }
+            Ok(_) => {}
+        }
+    }
+}
+
+/// An empty line asks for what is playing
+fn run_line(context: &mut AppContext, command: &str) -> DiziResult {
+    let request = match command {
+        "" => ClientRequest::PlayerState,
+        command => headless_request(command)?,
+    };
+    send_client_request(context, &request)
+}
+
+fn song_announcement(song: &DiziAudioFile) -> String {
+    let tags = &song.music_metadata().standard_tags;
+    let title = tags
+        .get("TrackTitle")
+        .map(|s| s.as_str())
+        .unwrap_or_else(|| song.file_name());
+    match tags.get("Artist") {
+        Some(artist) => MESSAGES_T.format(
+            "announce.playing_by",
+            &[("title", &title), ("artist", artist)],
+        ),
+        None => MESSAGES_T.format("announce.playing", &[("title", &title)]),
+    }
+}
+
+fn state_announcement(state: &PlayerState) -> String {
+    let song = match state.info.song.as_ref() {
+        Some(song) => song,
+        None => return MESSAGES_T.get("message.nothing_playing").to_string(),
+    };
+    let duration = state.info.total_duration().unwrap_or_default();
+    MESSAGES_T.format(
+        "announce.state",
+        &[
+            ("song", &song_announcement(song)),
+            ("status", &state.info.status.to_string()),
+            ("elapsed", &format::duration_to_string(state.info.elapsed)),
+            ("duration", &format::duration_to_string(duration)),
+            ("volume", &state.info.volume),
+        ],
+    )
+}
+
+fn on_off(on: bool) -> &'static str {
+    MESSAGES_T.get(if on { "message.on" } else { "message.off" })
+}
+
+/// A sentence describing a change, None for the ones not worth interrupting for
+fn announcement(event: &ServerBroadcastEvent) -> Option<String> {
+    let line = match event {
+        ServerBroadcastEvent::ServerQuit => MESSAGES_T.get("announce.server_quit").to_string(),
+        ServerBroadcastEvent::ServerError { msg } => {
+            MESSAGES_T.format("message.server_error", &[("msg", msg)])
+        }
+        ServerBroadcastEvent::PlayerState { state } => state_announcement(state),
+        ServerBroadcastEvent::PlayerFilePlay { file } => song_announcement(file),
+        ServerBroadcastEvent::TrackTransition { current, .. } => match current.as_deref() {
+            Some(song) => song_announcement(song),
+            None => MESSAGES_T.get("announce.stopped").to_string(),
+        },
+        ServerBroadcastEvent::PlayerPause => MESSAGES_T.get("announce.paused").to_string(),
+        ServerBroadcastEvent::PlayerResume => MESSAGES_T.get("announce.resumed").to_string(),
+        ServerBroadcastEvent::PlayerStop => MESSAGES_T.get("announce.stopped").to_string(),
+        ServerBroadcastEvent::PlayerRepeat { mode } => MESSAGES_T
+            .get(match mode {
+                RepeatMode::Off => "message.repeat_off",
+                RepeatMode::One => "message.repeat_one",
+                RepeatMode::All => "message.repeat_all",
+            })
+            .to_string(),
+        ServerBroadcastEvent::PlayerShuffle { on } => {
+            MESSAGES_T.format("message.shuffle", &[("status", &on_off(*on))])
+        }
+        ServerBroadcastEvent::PlayerNext { on } => {
+            MESSAGES_T.format("message.next", &[("status", &on_off(*on))])
+        }
+        ServerBroadcastEvent::PlayerSingle { on } => {
+            MESSAGES_T.format("message.single", &[("status", &on_off(*on))])
+        }
+        ServerBroadcastEvent::PlayerReplayGain { mode } => MESSAGES_T
+            .get(match mode {
+                ReplayGainMode::Off => "message.replaygain_off",
+                ReplayGainMode::Track => "message.replaygain_track",
+                ReplayGainMode::Album => "message.replaygain_album",
+            })
+            .to_string(),
+        ServerBroadcastEvent::PlayerVolumeUpdate { volume } => {
+            MESSAGES_T.format("message.volume", &[("volume", volume)])
+        }
+        ServerBroadcastEvent::PlayerSchedule { schedule } => match schedule {
+            Some(schedule) => MESSAGES_T.format(
+                "message.schedule",
+                &[
+                    ("playlist", &schedule.playlist.display()),
+                    ("time", &schedule.time),
+                ],
+            ),
+            None => MESSAGES_T.get("message.no_schedule").to_string(),
+        },
+        ServerBroadcastEvent::PlaylistOpen { state } => MESSAGES_T.format(
+            "message.playlist_loaded",
+            &[("count", &state.playlist.len())],
+        ),
+        ServerBroadcastEvent::PlaylistAppend { audio_files }
+        | ServerBroadcastEvent::PlaylistInsert { audio_files, .. } => {
+            MESSAGES_T.format("message.playlist_added", &[("count", &audio_files.len())])
+        }
+        ServerBroadcastEvent::PlaylistRemove { .. } => {
+            MESSAGES_T.format("message.playlist_removed", &[("count", &1)])
+        }
+        ServerBroadcastEvent::PlaylistRemoveMany { indices } => {
+            MESSAGES_T.format("message.playlist_removed", &[("count", &indices.len())])
+        }
+        ServerBroadcastEvent::PlaylistClear => {
+            MESSAGES_T.get("announce.playlist_cleared").to_string()
+        }
+        _ => return None,
+    };
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time;
+
+    use super::*;
+
+    #[test]
+    fn announcement_skips_progress() {
+        let event = ServerBroadcastEvent::PlayerPause;
+        assert_eq!(announcement(&event).as_deref(), Some("Paused"));
+        let event = ServerBroadcastEvent::PlayerProgressUpdate {
+            elapsed: time::Duration::from_secs(3),
+            duration: None,
+        };
+        assert_eq!(announcement(&event), None);
+    }
+
+    #[test]
+    fn announce_nothing_playing() {
+        let event = ServerBroadcastEvent::PlayerState {
+            state: PlayerState::default(),
+        };
+        assert_eq!(announcement(&event).as_deref(), Some("Nothing is playing"));
+    }
+}
@@ -0,0 +1,75 @@
+use std::str::FromStr;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+
+use crate::context::AppContext;
+use crate::event::AppEvent;
+use crate::key_command::{
+    AppCommand, Command, CMD_PLAYER_FAST_FORWARD, CMD_PLAYER_REWIND, CMD_PLAYER_VOLUME_DOWN,
+    CMD_PLAYER_VOLUME_UP, CMD_RATE_PLAYING, CMD_SEEK, CMD_SERVER_REQUEST,
+};
+use crate::util::connection;
+use crate::util::request::send_client_request;
+
+/// Commands accepted by `--command`
+pub const HEADLESS_COMMANDS: [&str; 7] = [
+    CMD_SERVER_REQUEST,
+    CMD_PLAYER_REWIND,
+    CMD_PLAYER_FAST_FORWARD,
+    CMD_SEEK,
+    CMD_PLAYER_VOLUME_UP,
+    CMD_PLAYER_VOLUME_DOWN,
+    CMD_RATE_PLAYING,
+];
+
+// how many ticks to wait for the server's response before giving up
+const RESPONSE_TICKS: usize = 20;
+
+/// The request a command stands for, if it doesn't need any client state
+pub fn headless_request(command: &str) -> DiziResult<ClientRequest> {
+    match Command::from_str(command)? {
+        Command::ServerRequest(request) => Ok(request),
+        Command::RatePlaying(rating) => Ok(ClientRequest::LibraryRate { path: None, rating }),
+        command => Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("'{}' can only be run from the client", command.command()),
+        )),
+    }
+}
+
+/// Sends a single command to the server without starting the tui,
+/// only commands that don't need any client state can be run this way
+pub fn run_command(context: &mut AppContext, command: &str, print: bool) -> DiziResult {
+    let request = headless_request(command)?;
+
+    if !print {
+        return send_client_request(context, &request);
+    }
+
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+    send_client_request(context, &request)?;
+    context.set_ticking(true);
+
+    let mut ticks = 0;
+    while ticks < RESPONSE_TICKS {
+        let message = match context.poll_event() {
+            Ok(AppEvent::Server(message)) => message,
+            Ok(AppEvent::Tick) => {
+                ticks += 1;
+                continue;
+            }
+            Ok(AppEvent::ServerDisconnect) | Err(_) => break,
+            Ok(_) => continue,
+        };
+        // sent while playing, not in response to anything
+        let event = ServerBroadcastEvent::from_json(&message)?;
+        if !event.is_periodic() {
+            event.into_result()?;
+            println!("{}", message);
+            break;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,156 @@
+use dizi::error::DiziResult;
+use dizi::response::server::ServerBroadcastEvent;
+use dizi::song::DiziAudioFile;
+
+use crate::context::AppContext;
+use crate::event::AppEvent;
+use crate::util::connection;
+
+/// Prints a line for every change broadcast by the server until it goes away
+pub fn run_idle(context: &mut AppContext, json: bool) -> DiziResult {
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+
+    loop {
+        let message = match context.poll_event() {
+            Ok(AppEvent::Server(message)) => message,
+            Ok(AppEvent::ServerDisconnect) | Err(_) => return Ok(()),
+            Ok(_) => continue,
+        };
+        let event: ServerBroadcastEvent = serde_json::from_str(&message)?;
+        if let Some(line) = idle_line(&event) {
+            if json {
+                println!("{}", message);
+            } else {
+                println!("{}", line);
+            }
+        }
+        if let ServerBroadcastEvent::ServerQuit = event {
+            return Ok(());
+        }
+    }
+}
+
+/// `name [details]` describing a change, None for periodic updates
+/// and for answers to other clients' queries
+fn idle_line(event: &ServerBroadcastEvent) -> Option<String> {
+    let line = match event {
+        ServerBroadcastEvent::ServerQuit => "server_quit".to_string(),
+        ServerBroadcastEvent::ServerError { msg } => format!("server_error {}", msg),
+        ServerBroadcastEvent::PlayerFilePlay { file } => {
+            format!("player_file_play {}", file.file_path().display())
+        }
+        ServerBroadcastEvent::PlayerPause => "player_pause".to_string(),
+        ServerBroadcastEvent::PlayerResume => "player_resume".to_string(),
+        ServerBroadcastEvent::PlayerStop => "player_stop".to_string(),
+        ServerBroadcastEvent::PlayerRepeat { mode } => format!("player_repeat {}", mode),
+        ServerBroadcastEvent::PlayerShuffle { on } => format!("player_shuffle {}", on),
+        ServerBroadcastEvent::PlayerNext { on } => format!("player_next {}", on),
+        ServerBroadcastEvent::PlayerSingle { on } => format!("player_single {}", on),
+        ServerBroadcastEvent::PlayerReplayGain { mode } => format!("player_replaygain {}", mode),
+        ServerBroadcastEvent::PlayerEqState { bands } => {
+            let gains: Vec<String> = bands.iter().map(|band| band.gain.to_string()).collect();
+            format!("player_eq {}", gains.join(" "))
+        }
+        ServerBroadcastEvent::PlayerVolumeUpdate { volume } => {
+            format!("player_volume {}", volume)
+        }
+        ServerBroadcastEvent::PlayerSchedule { schedule } => match schedule {
+            Some(schedule) => format!(
+                "player_schedule {} {}",
+                schedule.time,
+                schedule.playlist.display()
+            ),
+            None => "player_schedule none".to_string(),
+        },
+        ServerBroadcastEvent::TrackTransition {
+            previous, current, ..
+        } => {
+            let path = |song: Option<&DiziAudioFile>| match song {
+                Some(song) => song.file_path().display().to_string(),
+                None => "-".to_string(),
+            };
+            format!(
+                "track_transition {} -> {}",
+                path(previous.as_deref()),
+                path(current.as_deref())
+            )
+        }
+        ServerBroadcastEvent::PlaylistOpen { state } => {
+            format!("playlist_open {}", state.playlist.len())
+        }
+        ServerBroadcastEvent::PlaylistPlay { index } => format!("playlist_play {}", index),
+        ServerBroadcastEvent::PlaylistAppend { audio_files } => {
+            format!("playlist_append {}", audio_files.len())
+        }
+        ServerBroadcastEvent::PlaylistInsert { index, audio_files } => {
+            format!("playlist_insert {} {}", index, audio_files.len())
+        }
+        ServerBroadcastEvent::PlaylistRemove { index } => format!("playlist_remove {}", index),
+        ServerBroadcastEvent::PlaylistSwapMove { index1, index2 } => {
+            format!("playlist_swap {} {}", index1, index2)
+        }
+        ServerBroadcastEvent::PlaylistMove { index, to } => {
+            format!("playlist_move {} {}", index, to)
+        }
+        ServerBroadcastEvent::PlaylistClear => "playlist_clear".to_string(),
+        ServerBroadcastEvent::PlaylistRemoveMany { indices } => {
+            format!("playlist_remove_many {}", indices.len())
+        }
+        ServerBroadcastEvent::PlaylistScanDone { path } => {
+            format!("playlist_scan_done {}", path.display())
+        }
+        ServerBroadcastEvent::PlaylistOrder { .. } => "playlist_order".to_string(),
+        ServerBroadcastEvent::PlaylistDirectory { playlist } => {
+            format!("playlist_directory {}", playlist.len())
+        }
+        ServerBroadcastEvent::PlaylistSave { path } => {
+            format!("playlist_save {}", path.display())
+        }
+        ServerBroadcastEvent::QueueState { songs } => format!("queue {}", songs.len()),
+        ServerBroadcastEvent::LibraryRating { path, rating } => {
+            format!("library_rating {} {}", rating, path.display())
+        }
+        ServerBroadcastEvent::ServerQuery { .. }
+        | ServerBroadcastEvent::ServerQueryAll { .. }
+        | ServerBroadcastEvent::PlayerState { .. }
+        | ServerBroadcastEvent::PlayerProgressUpdate { .. }
+        | ServerBroadcastEvent::PlayerSpectrumUpdate { .. }
+        | ServerBroadcastEvent::PlayerLyrics { .. }
+        | ServerBroadcastEvent::PlayerChapters { .. }
+        | ServerBroadcastEvent::Waveform { .. }
+        | ServerBroadcastEvent::LibraryRatings { .. }
+        | ServerBroadcastEvent::FsList { .. } => return None,
+    };
+    Some(line)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time;
+
+    use super::*;
+
+    #[test]
+    fn idle_line_skips_progress() {
+        let event = ServerBroadcastEvent::PlayerVolumeUpdate { volume: 40 };
+        assert_eq!(idle_line(&event).as_deref(), Some("player_volume 40"));
+        let event = ServerBroadcastEvent::PlayerProgressUpdate {
+            elapsed: time::Duration::from_secs(3),
+            duration: None,
+        };
+        assert_eq!(idle_line(&event), None);
+    }
+
+    #[test]
+    fn idle_line_track_transition_to_nothing() {
+        let event = ServerBroadcastEvent::TrackTransition {
+            previous: None,
+            current: None,
+            next: None,
+        };
+        assert_eq!(
+            idle_line(&event).as_deref(),
+            Some("track_transition - -> -")
+        );
+    }
+}
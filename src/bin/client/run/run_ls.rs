@@ -0,0 +1,51 @@
+use std::path::PathBuf;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+
+use crate::context::AppContext;
+use crate::event::AppEvent;
+use crate::util::connection;
+use crate::util::request::send_client_request;
+
+/// Prints a directory of the server's machine, directories end with `/`
+pub fn run_ls(context: &mut AppContext, path: Option<PathBuf>, json: bool) -> DiziResult {
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+
+    let request = ClientRequest::FsList { path };
+    send_client_request(context, &request)?;
+
+    loop {
+        let message = match context.poll_event() {
+            Ok(AppEvent::Server(message)) => message,
+            Ok(AppEvent::ServerDisconnect) | Err(_) => {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    "Server closed the connection".to_string(),
+                ))
+            }
+            Ok(_) => continue,
+        };
+        match serde_json::from_str::<ServerBroadcastEvent>(&message)? {
+            ServerBroadcastEvent::FsList { entries, .. } => {
+                if json {
+                    println!("{}", serde_json::to_string(&entries)?);
+                    return Ok(());
+                }
+                for entry in entries {
+                    if entry.is_dir {
+                        println!("{}/", entry.path.display());
+                    } else {
+                        println!("{}", entry.path.display());
+                    }
+                }
+                return Ok(());
+            }
+            ServerBroadcastEvent::ServerError { msg } => {
+                return Err(DiziError::new(DiziErrorKind::Server, msg));
+            }
+            _ => {}
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::thread;
 
@@ -40,24 +41,15 @@ pub fn run_query_all(context: &mut AppContext) -> DiziResult {
         if let AppEvent::Server(message) = event {
             let server_broadcast_event: ServerBroadcastEvent = serde_json::from_str(&message)?;
             match server_broadcast_event {
-                ServerBroadcastEvent::ServerQueryAll { mut query_items } => {
-                    let mut items_sorted: Vec<(String, String)> = query_items.drain().collect();
-                    items_sorted.sort();
-                    for (key, val) in items_sorted {
-                        println!("{} = {}", key, val);
-                    }
+                ServerBroadcastEvent::ServerQueryAll { query_items } => {
+                    print_query_items(query_items);
                     break;
                 }
                 ServerBroadcastEvent::PlayerState { mut state } => {
                     if !state.playlist.is_empty() {
                         state.playlist.set_cursor_index(Some(0));
                     }
-                    let mut query_items = state.query_all();
-                    let mut items_sorted: Vec<(String, String)> = query_items.drain().collect();
-                    items_sorted.sort();
-                    for (key, val) in items_sorted {
-                        println!("{} = {}", key, val);
-                    }
+                    print_query_items(state.query_all());
                     break;
                 }
                 ServerBroadcastEvent::ServerError { msg } => {
@@ -70,3 +62,12 @@ pub fn run_query_all(context: &mut AppContext) -> DiziResult {
     }
     Ok(())
 }
+
+/// Prints `key = value` lines, sorted by key
+pub fn print_query_items(query_items: HashMap<String, String>) {
+    let mut items_sorted: Vec<(String, String)> = query_items.into_iter().collect();
+    items_sorted.sort();
+    for (key, val) in items_sorted {
+        println!("{} = {}", key, val);
+    }
+}
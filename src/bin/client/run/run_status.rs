@@ -0,0 +1,87 @@
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+
+use crate::config::option::StatusFormat;
+use crate::context::AppContext;
+use crate::event::AppEvent;
+use crate::util::connection;
+use crate::util::request::send_client_request;
+
+use super::print_query_items;
+
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum StatusOutput {
+    // `key = value` lines, same as --query-all
+    #[default]
+    Plain,
+    // the whole player state
+    Json,
+    // a single line such as `%artist - %title [%elapsed]`, see `status_format`
+    Template(StatusFormat),
+}
+
+impl std::str::FromStr for StatusOutput {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "plain" => Ok(Self::Plain),
+            "json" => Ok(Self::Json),
+            s => StatusFormat::parse(s)
+                .map(Self::Template)
+                .map_err(|e| e.to_string()),
+        }
+    }
+}
+
+pub fn run_status(context: &mut AppContext, format: StatusOutput) -> DiziResult {
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+
+    let request = ClientRequest::PlayerState;
+    send_client_request(context, &request)?;
+
+    loop {
+        let message = match context.poll_event() {
+            Ok(AppEvent::Server(message)) => message,
+            Ok(AppEvent::ServerDisconnect) | Err(_) => {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    "Server closed the connection".to_string(),
+                ))
+            }
+            Ok(_) => continue,
+        };
+        match serde_json::from_str::<ServerBroadcastEvent>(&message)? {
+            ServerBroadcastEvent::PlayerState { state } => {
+                match format {
+                    StatusOutput::Plain => print_query_items(state.query_all()),
+                    StatusOutput::Json => println!("{}", serde_json::to_string(&state)?),
+                    StatusOutput::Template(template) => println!("{}", template.render(&state)),
+                }
+                return Ok(());
+            }
+            ServerBroadcastEvent::ServerError { msg } => {
+                return Err(DiziError::new(DiziErrorKind::Server, msg));
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn parse_status_output() {
+        assert_eq!(StatusOutput::from_str("json"), Ok(StatusOutput::Json));
+        assert!(matches!(
+            StatusOutput::from_str("%artist - %title"),
+            Ok(StatusOutput::Template(_))
+        ));
+        assert!(StatusOutput::from_str("%bitrate").is_err());
+    }
+}
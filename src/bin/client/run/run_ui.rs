@@ -1,23 +1,29 @@
-use std::io::{BufRead, BufReader};
-use std::thread;
+use std::str::FromStr;
 
 use ratatui::layout::{Constraint, Rect};
-use termion::event::Event;
+use termion::event::{Event, Key};
 
 use dizi::error::DiziResult;
+use dizi::player::PlayerStatus;
 use dizi::request::client::ClientRequest;
 
 use crate::config::AppKeyMapping;
 use crate::context::{AppContext, QuitType};
 use crate::event::process_event;
+use crate::event::process_mouse;
 use crate::event::AppEvent;
-use crate::key_command::{AppExecute, Command, CommandKeybind};
-use crate::preview::preview_default;
+use crate::key_command::{AppExecute, Command, CommandKeybind, MAX_COUNT};
+use crate::preview::{preview_default, preview_metadata};
 use crate::traits::ToString;
 use crate::ui::views;
 use crate::ui::views::TuiView;
 use crate::ui::AppBackend;
+use crate::util::connection::{self, ServerStream};
 use crate::util::request::send_client_request;
+use crate::MESSAGES_T;
+
+// peaks asked for, the player squeezes them into its width
+const WAVEFORM_BUCKETS: usize = 512;
 
 pub fn run_ui(
     backend: &mut AppBackend,
@@ -28,20 +34,15 @@ pub fn run_ui(
 
     // server listener
     {
-        let stream = context.clone_stream()?;
-        let event_tx = context.clone_event_tx();
-
-        let _ = thread::spawn(move || {
-            let cursor = BufReader::new(stream);
-            for line in cursor.lines().flatten() {
-                let _ = event_tx.send(AppEvent::Server(line));
-            }
-        });
+        connection::listen(context.clone_stream()?, context.clone_event_tx());
 
         // request for server state
         let request = ClientRequest::PlayerState;
         send_client_request(context, &request)?;
+        let request = ClientRequest::LibraryRatings;
+        send_client_request(context, &request)?;
     }
+    run_startup_commands(context, backend, &keymap_t);
 
     while context.quit == QuitType::DoNot {
         // do the ui
@@ -54,9 +55,34 @@ pub fn run_ui(
             };
             // pre-calculate some ui attributes
             calculate_ui_context(context, area);
+            // fill in song metadata for the current directory
+            preview_metadata::load_metadata(context);
+            // ask for the playing song's lyrics while they are shown
+            if let Err(err) = request_lyrics(context) {
+                context.message_queue_mut().push_error(err.to_string());
+            }
+            if let Err(err) = request_waveform(context) {
+                context.message_queue_mut().push_error(err.to_string());
+            }
+            // move the progress along between server updates
+            let playing = !context.is_reconnecting()
+                && context.server_state_ref().player.info.status == PlayerStatus::Playing;
+            if playing {
+                context.server_state_mut().interpolate_elapsed();
+            }
+            // keep redrawing while playing, while a spinner is shown
+            // or until the notification is gone
+            let ticking = playing
+                || context.is_reconnecting()
+                || context.server_state_ref().is_scanning()
+                || context.message_queue_ref().notification_ref().is_some();
+            context.set_ticking(ticking);
 
             // render the ui
             backend.render(TuiView::new(context));
+            if context.config_ref().display_options_ref().terminal_title() {
+                backend.set_title(&terminal_title(context));
+            }
         }
 
         let event = match context.poll_event() {
@@ -65,7 +91,10 @@ pub fn run_ui(
         };
 
         match event {
-            AppEvent::Termion(Event::Mouse(_event)) => {
+            AppEvent::Termion(Event::Mouse(event)) => {
+                if let Err(e) = process_mouse::process_mouse(context, backend, event) {
+                    context.message_queue_mut().push_error(e.to_string());
+                }
                 context.flush_event();
             }
             AppEvent::Termion(key) => {
@@ -87,27 +116,41 @@ pub fn run_ui(
                             context.message_queue_mut().push_error(e.to_string());
                         }
                     }
-                    key => match keymap_t.as_ref().get(&key) {
-                        None => {
-                            context
-                                .message_queue_mut()
-                                .push_info(format!("Unmapped input: {}", key.to_string()));
-                        }
-                        Some(CommandKeybind::SimpleKeybind(command)) => {
-                            if let Err(e) = command.execute(context, backend, &keymap_t) {
-                                context.message_queue_mut().push_error(e.to_string());
+                    // digits start a count, unless they are bound and no count was typed yet
+                    Event::Key(Key::Char(c))
+                        if c.is_ascii_digit()
+                            && (context.ui_context_ref().count.is_some()
+                                || !keymap_t.as_ref().contains_key(&key)) =>
+                    {
+                        let digit = c.to_digit(10).unwrap_or(0) as usize;
+                        let count = context.ui_context_ref().count.unwrap_or(0);
+                        context.ui_context_mut().count = Some((count * 10 + digit).min(MAX_COUNT));
+                    }
+                    Event::Key(Key::Esc) if context.ui_context_ref().count.is_some() => {
+                        context.ui_context_mut().count = None;
+                    }
+                    key => {
+                        let count = context.ui_context_mut().count.take();
+                        match keymap_t.as_ref().get(&key) {
+                            None => {
+                                context.message_queue_mut().push_info(MESSAGES_T.format(
+                                    "message.unmapped_input",
+                                    &[("key", &key.to_string())],
+                                ));
                             }
-                        }
-                        Some(CommandKeybind::CompositeKeybind(m)) => {
-                            let cmd = process_event::get_input_while_composite(backend, context, m);
+                            Some(CommandKeybind::SimpleKeybind(command)) => {
+                                execute_with_count(context, backend, &keymap_t, command, count);
+                            }
+                            Some(CommandKeybind::CompositeKeybind(m)) => {
+                                let cmd =
+                                    process_event::get_input_while_composite(backend, context, m);
 
-                            if let Some(command) = cmd {
-                                if let Err(e) = command.execute(context, backend, &keymap_t) {
-                                    context.message_queue_mut().push_error(e.to_string());
+                                if let Some(command) = cmd {
+                                    execute_with_count(context, backend, &keymap_t, command, count);
                                 }
                             }
                         }
-                    },
+                    }
                 }
                 preview_default::load_preview(context, backend);
                 context.flush_event();
@@ -117,12 +160,124 @@ pub fn run_ui(
                     context.message_queue_mut().push_error(err.to_string());
                 }
             }
+            AppEvent::ServerDisconnect => {
+                context.set_reconnecting(true);
+                // the server won't finish the scans it was asked for
+                context.server_state_mut().clear_scans();
+                let address = context.config_ref().client_ref().server_address();
+                connection::reconnect(address, context.clone_event_tx());
+            }
+            AppEvent::ServerReconnect(stream) => match resume_connection(context, stream) {
+                Ok(_) => context
+                    .message_queue_mut()
+                    .push_success(MESSAGES_T.get("message.reconnected").to_string()),
+                Err(err) => context.message_queue_mut().push_error(err.to_string()),
+            },
             event => process_event::process_noninteractive(event, context),
         }
     }
     Ok(())
 }
 
+/// Runs the `on_startup` commands of the client config, in order.
+/// A command that fails is reported without stopping the ones after it
+fn run_startup_commands(
+    context: &mut AppContext,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+) {
+    let commands = context.config_ref().client_ref().on_startup.clone();
+    for s in commands.iter() {
+        let res =
+            Command::from_str(s).and_then(|command| command.execute(context, backend, keymap_t));
+        if let Err(e) = res {
+            context
+                .message_queue_mut()
+                .push_error(format!("on_startup '{}': {}", s, e));
+        }
+    }
+}
+
+fn execute_with_count(
+    context: &mut AppContext,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+    command: &Command,
+    count: Option<usize>,
+) {
+    let command = match count {
+        Some(count) => command.with_count(count),
+        None => command.clone(),
+    };
+    context.macro_context_mut().record(&command);
+    let res = command.execute(context, backend, keymap_t);
+    if let Err(e) = res {
+        context.message_queue_mut().push_error(e.to_string());
+    }
+}
+
+/// Starts listening to the new connection and asks for the server's state again
+fn resume_connection(context: &mut AppContext, mut stream: ServerStream) -> DiziResult {
+    if let Some(password) = context.config_ref().client_ref().password.as_ref() {
+        stream.authenticate(password)?;
+    }
+    context.stream = stream;
+    context.set_reconnecting(false);
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+
+    let request = ClientRequest::PlayerState;
+    send_client_request(context, &request)?;
+    let request = ClientRequest::LibraryRatings;
+    send_client_request(context, &request)
+}
+
+fn request_lyrics(context: &mut AppContext) -> DiziResult {
+    if !context.config_ref().display_options_ref().show_lyrics() || context.is_reconnecting() {
+        return Ok(());
+    }
+    let server_state = context.server_state_ref();
+    let path = match server_state.playing_path() {
+        Some(path) if !server_state.lyrics_ref().is_some_and(|l| l.is_for(path)) => {
+            path.to_path_buf()
+        }
+        _ => return Ok(()),
+    };
+    context.server_state_mut().set_lyrics_loading(path.clone());
+    let request = ClientRequest::PlayerLyrics { path: Some(path) };
+    send_client_request(context, &request)
+}
+
+fn request_waveform(context: &mut AppContext) -> DiziResult {
+    if !context.config_ref().display_options_ref().show_waveform() || context.is_reconnecting() {
+        return Ok(());
+    }
+    let server_state = context.server_state_ref();
+    let path = match server_state.playing_path() {
+        Some(path) if !server_state.is_waveform_for(path) => path.to_path_buf(),
+        _ => return Ok(()),
+    };
+    context
+        .server_state_mut()
+        .set_waveform_loading(path.clone());
+    let request = ClientRequest::waveform(Some(path), WAVEFORM_BUCKETS);
+    send_client_request(context, &request)
+}
+
+fn terminal_title(context: &AppContext) -> String {
+    match context.server_state_ref().player.info.song.as_ref() {
+        Some(song) => {
+            let title = song
+                .music_metadata()
+                .standard_tags
+                .get("TrackTitle")
+                .map(|s| s.as_str())
+                .unwrap_or_else(|| song.file_name());
+            MESSAGES_T.format("title.playing", &[("title", &title)])
+        }
+        None => MESSAGES_T.get("title.idle").to_string(),
+    }
+}
+
 fn calculate_ui_context(context: &mut AppContext, area: Rect) {
     let area = Rect {
         y: area.top() + 1,
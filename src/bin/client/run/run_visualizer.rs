@@ -0,0 +1,59 @@
+use termion::event::{Event, Key};
+
+use dizi::error::DiziResult;
+use dizi::player::PlayerStatus;
+use dizi::request::client::ClientRequest;
+
+use crate::context::{AppContext, QuitType};
+use crate::event::process_event;
+use crate::event::AppEvent;
+use crate::ui::views::TuiVisualizerView;
+use crate::ui::AppBackend;
+use crate::util::connection;
+use crate::util::request::send_client_request;
+
+/// Shows the spectrum of the playing song full screen until `q` is pressed,
+/// without the file browser
+pub fn run_visualizer(backend: &mut AppBackend, context: &mut AppContext) -> DiziResult {
+    let _ = context.flush_stream();
+
+    connection::listen(context.clone_stream()?, context.clone_event_tx());
+    let request = ClientRequest::PlayerState;
+    send_client_request(context, &request)?;
+
+    while context.quit == QuitType::DoNot {
+        // move the progress along between server updates
+        let playing = context.server_state_ref().player.info.status == PlayerStatus::Playing;
+        if playing {
+            context.server_state_mut().interpolate_elapsed();
+        }
+        context.set_ticking(playing);
+
+        backend.render(TuiVisualizerView::new(context));
+
+        let event = match context.poll_event() {
+            Ok(event) => event,
+            Err(_) => return Ok(()),
+        };
+        match event {
+            AppEvent::Termion(Event::Key(Key::Char('q')))
+            | AppEvent::Termion(Event::Key(Key::Esc))
+            | AppEvent::Termion(Event::Key(Key::Ctrl('c'))) => {
+                context.quit = QuitType::Normal;
+            }
+            AppEvent::Termion(_) => {
+                if context.message_queue_ref().current_message().is_some() {
+                    context.message_queue_mut().pop_front();
+                }
+            }
+            AppEvent::Server(message) => {
+                if let Err(err) = process_event::process_server_event(context, message.as_str()) {
+                    context.message_queue_mut().push_error(err.to_string());
+                }
+            }
+            AppEvent::ServerDisconnect => return Ok(()),
+            event => process_event::process_noninteractive(event, context),
+        }
+    }
+    Ok(())
+}
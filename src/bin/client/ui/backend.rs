@@ -41,6 +41,8 @@ pub type AppTerminal = ratatui::Terminal<TermionBackend<Screen>>;
 
 pub struct AppBackend {
     pub terminal: Option<AppTerminal>,
+    // last title given to the terminal
+    title: Option<String>,
 }
 
 impl AppBackend {
@@ -54,6 +56,7 @@ impl AppBackend {
         terminal.hide_cursor()?;
         Ok(Self {
             terminal: Some(terminal),
+            title: None,
         })
     }
 
@@ -67,6 +70,25 @@ impl AppBackend {
         });
     }
 
+    /// Sets the terminal's title, the one it had before is saved on the terminal's
+    /// title stack the first time and put back once the backend is dropped
+    pub fn set_title(&mut self, title: &str) {
+        if self.title.as_deref() == Some(title) {
+            return;
+        }
+        // control characters would end the escape sequence early
+        let title: String = title.chars().filter(|c| !c.is_control()).collect();
+        let push = if self.title.is_none() {
+            "\x1b[22;2t"
+        } else {
+            ""
+        };
+        let stdout = self.terminal_mut().backend_mut();
+        let _ = write!(stdout, "{}\x1b]2;{}\x07", push, title);
+        let _ = stdout.flush();
+        self.title = Some(title);
+    }
+
     pub fn terminal_ref(&self) -> &AppTerminal {
         self.terminal.as_ref().unwrap()
     }
@@ -86,3 +108,13 @@ impl AppBackend {
         Ok(())
     }
 }
+
+impl Drop for AppBackend {
+    fn drop(&mut self) {
+        if self.title.is_some() {
+            let mut stdout = stdout();
+            let _ = write!(stdout, "\x1b[23;2t");
+            let _ = stdout.flush();
+        }
+    }
+}
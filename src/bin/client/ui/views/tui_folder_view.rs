@@ -28,12 +28,22 @@ impl<'a> Widget for TuiFolderView<'a> {
 
         let config = self.context.config_ref();
         let display_options = config.display_options_ref();
-        let currently_playing = self.context.server_state_ref().player.song.as_ref();
+        let currently_playing = self.context.server_state_ref().player.info.song.as_ref();
+        let search_pattern = self.context.get_search_context();
 
         // render current view
         if let Some(list) = curr_list.as_ref() {
-            TuiDirListDetailed::new(list, display_options, currently_playing, self.focused)
-                .render(area, buf);
+            TuiDirListDetailed::new(
+                list,
+                display_options,
+                currently_playing,
+                search_pattern,
+                self.context.metadata_cache_ref(),
+                self.focused,
+            )
+            .name_scroll(self.context.ui_context_ref().name_scroll.as_ref())
+            .ratings(self.context.server_state_ref().ratings_ref())
+            .render(area, buf);
             let _rect = Rect {
                 x: 0,
                 y: area.height - 1,
@@ -40,12 +40,15 @@ pub struct CursorInfo {
     pub y: usize,
 }
 
+pub type TextFieldChangeHook<'a> = Box<dyn FnMut(&mut AppContext, &str) + 'a>;
+
 #[derive(Default)]
 pub struct TuiTextField<'a> {
     _prompt: &'a str,
     _prefix: &'a str,
     _suffix: &'a str,
     _menu_items: Vec<&'a str>,
+    _on_change: Option<TextFieldChangeHook<'a>>,
 }
 
 impl<'a> TuiTextField<'a> {
@@ -64,6 +67,12 @@ impl<'a> TuiTextField<'a> {
         self
     }
 
+    /// Called with the contents of the text field every time they change
+    pub fn on_change(&mut self, on_change: TextFieldChangeHook<'a>) -> &mut Self {
+        self._on_change = Some(on_change);
+        self
+    }
+
     pub fn get_input(
         &mut self,
         backend: &mut AppBackend,
@@ -157,6 +166,7 @@ impl<'a> TuiTextField<'a> {
             if let Ok(event) = context.poll_event() {
                 match event {
                     AppEvent::Termion(Event::Key(key)) => {
+                        let prev_input = line_buffer.as_str().to_string();
                         let dirty = match key {
                             Key::Backspace => line_buffer.backspace(1),
                             Key::Delete => line_buffer.delete(1).is_some(),
@@ -256,6 +266,11 @@ impl<'a> TuiTextField<'a> {
                         if dirty {
                             completion_tracker.take();
                         }
+                        if line_buffer.as_str() != prev_input {
+                            if let Some(on_change) = self._on_change.as_mut() {
+                                on_change(context, line_buffer.as_str());
+                            }
+                        }
                         context.flush_event();
                     }
                     AppEvent::Termion(_) => {
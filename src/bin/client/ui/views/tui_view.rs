@@ -6,7 +6,10 @@ use ratatui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
 
 use crate::config::option::{LayoutComposition, WidgetType};
 use crate::context::AppContext;
-use crate::ui::widgets::{TuiFooter, TuiPlayer, TuiPlaylist, TuiTopBar};
+use crate::ui::widgets::{
+    TuiFooter, TuiLibrary, TuiLyrics, TuiMessageLog, TuiMinimal, TuiNotification, TuiPlayer,
+    TuiPlaylist, TuiTabs, TuiTopBar, TuiUpNext, TuiVisualizer,
+};
 
 use crate::LAYOUT_T;
 
@@ -28,18 +31,27 @@ impl<'a> TuiView<'a> {
 
 impl<'a> Widget for TuiView<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        if area.height < 2 || area.width < 2 {
+        // the minimal view fits in a single line
+        let display_options = self.context.config_ref().display_options_ref();
+        if display_options.minimal_mode() {
+            TuiMinimal::new(&self.context.server_state_ref().player)
+                .status_format(display_options.status_format())
+                .show_remaining(display_options.show_remaining_time())
+                .icon_style(display_options.icon_style())
+                .render(area, buf);
             return;
         }
 
-        let default_layout = [Constraint::Ratio(1, 1)];
-        let layout_rect = Layout::default()
-            .direction(Direction::Horizontal)
-            .vertical_margin(1)
-            .constraints(default_layout)
-            .split(area);
+        if area.height < 2 || area.width < 2 {
+            return;
+        }
 
-        render_widget(self.context, &LAYOUT_T.layout, layout_rect[0], buf);
+        render_widget(
+            self.context,
+            current_layout(self.context),
+            layout_area(area),
+            buf,
+        );
 
         if let Some(msg) = self.context.message_queue_ref().current_message() {
             let rect = Rect {
@@ -60,10 +72,43 @@ impl<'a> Widget for TuiView<'a> {
                 width: area.width,
                 height: 1,
             };
-            TuiFooter::new(&self.context.server_state_ref().player).render(rect, buf);
+            TuiFooter::new(&self.context.server_state_ref().player)
+                .status_format(
+                    self.context
+                        .config_ref()
+                        .display_options_ref()
+                        .status_format(),
+                )
+                .search_matches(search_matches(self.context))
+                .filter(
+                    self.context
+                        .config_ref()
+                        .display_options_ref()
+                        .filter_for(self.context.tab_context_ref().curr_tab_ref().cwd()),
+                )
+                .selected(selected_count(self.context))
+                .count(self.context.ui_context_ref().count)
+                .recording(self.context.macro_context_ref().recording_register())
+                .scanning(self.context.server_state_ref().is_scanning())
+                .reconnecting(self.context.is_reconnecting())
+                .render(rect, buf);
+        }
+
+        // notifications sit on the bottom border, just above the footer
+        if let Some(notification) = self.context.message_queue_ref().notification_ref() {
+            if area.height >= 3 {
+                let rect = Rect {
+                    x: 0,
+                    y: area.height - 2,
+                    width: area.width,
+                    height: 1,
+                };
+                TuiNotification::new(notification).render(rect, buf);
+            }
         }
 
-        let topbar_width = area.width;
+        let tabs_width = (TuiTabs::width() as u16).min(area.width / 2);
+        let topbar_width = area.width - tabs_width;
         let rect = Rect {
             x: 0,
             y: 0,
@@ -72,6 +117,14 @@ impl<'a> Widget for TuiView<'a> {
         };
         let cwd = self.context.tab_context_ref().curr_tab_ref().cwd();
         TuiTopBar::new(cwd).render(rect, buf);
+
+        let rect = Rect {
+            x: topbar_width,
+            y: 0,
+            width: tabs_width,
+            height: 1,
+        };
+        TuiTabs::new(self.context.get_view_widget()).render(rect, buf);
     }
 }
 
@@ -97,6 +150,7 @@ pub fn render_widget(
             border,
             title: _,
         } => {
+            let widget = &shown_widget(context, *widget);
             let focused = current_view_widget == *widget;
 
             let border_style = if focused {
@@ -118,10 +172,56 @@ pub fn render_widget(
             match widget {
                 WidgetType::FileBrowser => TuiFolderView::new(context, focused).render(rect, buf),
                 WidgetType::MusicPlayer => {
-                    TuiPlayer::new(&context.server_state_ref().player).render(rect, buf)
+                    let display_options = context.config_ref().display_options_ref();
+                    let server_state = context.server_state_ref();
+                    let waveform = server_state
+                        .playing_path()
+                        .and_then(|path| server_state.waveform_of(path))
+                        .filter(|_| display_options.show_waveform());
+                    TuiPlayer::new(&server_state.player)
+                        .show_remaining(display_options.show_remaining_time())
+                        .icon_style(display_options.icon_style())
+                        .waveform(waveform)
+                        .render(rect, buf)
                 }
                 WidgetType::Playlist => {
-                    TuiPlaylist::new(&context.server_state_ref().player, focused).render(rect, buf)
+                    let server_state = context.server_state_ref();
+                    TuiPlaylist::new(&server_state.player, focused)
+                        .directory_playlist(server_state.shows_directory_playlist())
+                        .durations(
+                            server_state.playlist_duration(),
+                            server_state.playlist_remaining(),
+                        )
+                        .entry_format(context.config_ref().display_options_ref().entry_format())
+                        .name_scroll(context.ui_context_ref().name_scroll.as_ref())
+                        .ratings(server_state.ratings_ref())
+                        .drop_index(context.ui_context_ref().drag.map(|drag| drag.to))
+                        .render(rect, buf)
+                }
+                WidgetType::Library => {
+                    let currently_playing = context
+                        .server_state_ref()
+                        .player
+                        .info
+                        .song
+                        .as_ref()
+                        .map(|s| s.file_path());
+                    TuiLibrary::new(context.library_context_ref(), currently_playing, focused)
+                        .render(rect, buf)
+                }
+                WidgetType::MessageLog => {
+                    TuiMessageLog::new(context.message_queue_ref(), focused).render(rect, buf)
+                }
+                WidgetType::Visualizer => {
+                    TuiVisualizer::new(&context.server_state_ref().spectrum).render(rect, buf)
+                }
+                WidgetType::UpNext => {
+                    TuiUpNext::new(&context.server_state_ref().player).render(rect, buf)
+                }
+                WidgetType::Lyrics => {
+                    let server_state = context.server_state_ref();
+                    TuiLyrics::new(server_state.lyrics_ref(), server_state.player.info.elapsed)
+                        .render(rect, buf)
                 }
             }
         }
@@ -130,21 +230,117 @@ pub fn render_widget(
             widgets,
             ratio: _,
         } => {
-            let widget_sizes: Vec<usize> = widgets.iter().map(|w| w.ratio()).collect();
-            let widget_size_sum = widget_sizes.iter().map(|n| *n as u32).sum();
-            let constraints: Vec<Constraint> = widget_sizes
-                .iter()
-                .map(|n| Constraint::Ratio(*n as u32, widget_size_sum))
-                .collect();
-
-            let layout_rect = Layout::default()
-                .direction(direction.clone())
-                .constraints(constraints)
-                .split(area);
-            for (widget, rect) in widgets.iter().zip(layout_rect.iter()) {
-                render_widget(context, widget, *rect, buf);
+            for (widget, rect) in split_composite(context, direction, widgets, area) {
+                render_widget(context, widget, rect, buf);
+            }
+        }
+    }
+}
+
+/// Where the contents of `target` are drawn on a screen of size `area`,
+/// inside its border, None when it is not shown
+pub fn widget_area(context: &AppContext, area: Rect, target: WidgetType) -> Option<Rect> {
+    if context.config_ref().display_options_ref().minimal_mode() {
+        return None;
+    }
+    find_widget_area(context, current_layout(context), layout_area(area), target)
+}
+
+fn find_widget_area(
+    context: &AppContext,
+    layout: &LayoutComposition,
+    area: Rect,
+    target: WidgetType,
+) -> Option<Rect> {
+    if area.height < 2 || area.width < 2 {
+        return None;
+    }
+    match layout {
+        LayoutComposition::Simple { widget, border, .. } => {
+            if shown_widget(context, *widget) != target {
+                None
+            } else if *border {
+                Some(Block::default().borders(Borders::ALL).inner(area))
+            } else {
+                Some(area)
             }
         }
+        LayoutComposition::Composite {
+            direction, widgets, ..
+        } => split_composite(context, direction, widgets, area)
+            .into_iter()
+            .find_map(|(widget, rect)| find_widget_area(context, widget, rect, target)),
+    }
+}
+
+// leaves the first and last lines for the top bar and the footer
+fn layout_area(area: Rect) -> Rect {
+    let default_layout = [Constraint::Ratio(1, 1)];
+    Layout::default()
+        .direction(Direction::Horizontal)
+        .vertical_margin(1)
+        .constraints(default_layout)
+        .split(area)[0]
+}
+
+fn split_composite<'a>(
+    context: &AppContext,
+    direction: &Direction,
+    widgets: &'a [LayoutComposition],
+    area: Rect,
+) -> Vec<(&'a LayoutComposition, Rect)> {
+    let widgets: Vec<&LayoutComposition> =
+        widgets.iter().filter(|w| !is_hidden(context, w)).collect();
+    let widget_sizes: Vec<usize> = widgets.iter().map(|w| w.ratio()).collect();
+    let widget_size_sum = widget_sizes.iter().map(|n| *n as u32).sum();
+    let constraints: Vec<Constraint> = widget_sizes
+        .iter()
+        .map(|n| Constraint::Ratio(*n as u32, widget_size_sum))
+        .collect();
+
+    let layout_rect = Layout::default()
+        .direction(direction.clone())
+        .constraints(constraints)
+        .split(area);
+    widgets
+        .into_iter()
+        .zip(layout_rect.iter().copied())
+        .collect()
+}
+
+// views without a pane of their own are shown in the file browser's pane
+fn shown_widget(context: &AppContext, widget: WidgetType) -> WidgetType {
+    let current_view_widget = context.get_view_widget();
+    match widget {
+        WidgetType::FileBrowser
+            if current_view_widget != WidgetType::MusicPlayer
+                && !current_layout(context).contains(current_view_widget) =>
+        {
+            current_view_widget
+        }
+        widget => widget,
+    }
+}
+
+fn current_layout(context: &AppContext) -> &LayoutComposition {
+    context
+        .config_ref()
+        .display_options_ref()
+        .layout_ref()
+        .unwrap_or_else(|| &LAYOUT_T.layout)
+}
+
+fn is_hidden(context: &AppContext, layout: &LayoutComposition) -> bool {
+    match layout {
+        LayoutComposition::Simple {
+            widget: WidgetType::Visualizer,
+            ..
+        } => !context.config_ref().display_options_ref().show_visualizer(),
+        LayoutComposition::Simple {
+            widget: WidgetType::Lyrics,
+            ..
+        } => !context.config_ref().display_options_ref().show_lyrics(),
+        _ => false,
     }
 }
 
@@ -165,3 +361,32 @@ pub fn calculate_layout_with_borders(area: Rect, constraints: &[Constraint]) ->
 
     vec![inner1, layout_rect[1], inner3]
 }
+
+/// Returns the position of the cursor among the entries matching the current
+/// search, counting from 1 (0 when the cursor is not on a match), and the number of matches
+fn search_matches(context: &AppContext) -> Option<(usize, usize)> {
+    let pattern = context.get_search_context()?;
+    let curr_list = context.tab_context_ref().curr_tab_ref().curr_list_ref()?;
+    let curr_index = curr_list.get_index();
+
+    let mut position = 0;
+    let mut total = 0;
+    for (i, entry) in curr_list.iter().enumerate() {
+        if pattern.is_match(entry.file_name()) {
+            total += 1;
+            if Some(i) == curr_index {
+                position = total;
+            }
+        }
+    }
+    Some((position, total))
+}
+
+fn selected_count(context: &AppContext) -> usize {
+    context
+        .tab_context_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .map(|list| list.iter().filter(|entry| entry.is_selected()).count())
+        .unwrap_or(0)
+}
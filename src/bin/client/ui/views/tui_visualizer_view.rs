@@ -0,0 +1,54 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::text::Span;
+use ratatui::widgets::{Paragraph, Widget, Wrap};
+
+use crate::context::AppContext;
+use crate::ui::widgets::{TuiMinimal, TuiVisualizer};
+
+/// The whole screen of `dizi --visualizer`:
+/// the playing song on the first line and the spectrum below it
+pub struct TuiVisualizerView<'a> {
+    pub context: &'a AppContext,
+}
+
+impl<'a> TuiVisualizerView<'a> {
+    pub fn new(context: &'a AppContext) -> Self {
+        Self { context }
+    }
+}
+
+impl<'a> Widget for TuiVisualizerView<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 2 || area.width < 1 {
+            return;
+        }
+        let display_options = self.context.config_ref().display_options_ref();
+        let server_state = self.context.server_state_ref();
+
+        let rect = Rect { height: 1, ..area };
+        TuiMinimal::new(&server_state.player)
+            .status_format(display_options.status_format())
+            .icon_style(display_options.icon_style())
+            .render(rect, buf);
+
+        let rect = Rect {
+            y: area.y + 1,
+            height: area.height - 1,
+            ..area
+        };
+        TuiVisualizer::new(&server_state.spectrum).render(rect, buf);
+
+        if let Some(msg) = self.context.message_queue_ref().current_message() {
+            let rect = Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            };
+            let text = Span::styled(msg.content.as_str(), msg.style);
+            Paragraph::new(text)
+                .wrap(Wrap { trim: true })
+                .render(rect, buf);
+        }
+    }
+}
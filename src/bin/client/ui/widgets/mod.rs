@@ -1,17 +1,35 @@
 mod tui_dirlist_detailed;
 mod tui_footer;
+mod tui_library;
+mod tui_lyrics;
 mod tui_menu;
+mod tui_message_log;
+mod tui_minimal;
+mod tui_notification;
 mod tui_player;
 mod tui_playlist;
 mod tui_prompt;
+mod tui_tabs;
 mod tui_text;
 mod tui_topbar;
+mod tui_up_next;
+mod tui_visualizer;
+mod tui_volume;
 
 pub use self::tui_dirlist_detailed::*;
 pub use self::tui_footer::*;
+pub use self::tui_library::*;
+pub use self::tui_lyrics::*;
 pub use self::tui_menu::*;
+pub use self::tui_message_log::*;
+pub use self::tui_minimal::*;
+pub use self::tui_notification::*;
 pub use self::tui_player::*;
 pub use self::tui_playlist::*;
 pub use self::tui_prompt::*;
+pub use self::tui_tabs::*;
 pub use self::tui_text::*;
 pub use self::tui_topbar::*;
+pub use self::tui_up_next::*;
+pub use self::tui_visualizer::*;
+pub use self::tui_volume::*;
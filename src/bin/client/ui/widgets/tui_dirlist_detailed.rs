@@ -1,4 +1,6 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
 
 use dizi::song::DiziAudioFile;
 use ratatui::buffer::Buffer;
@@ -8,9 +10,11 @@ use ratatui::widgets::Widget;
 use unicode_width::UnicodeWidthStr;
 
 use crate::config::option::DisplayOption;
+use crate::context::{MetadataCache, NameScroll, SongMetadata};
 use crate::fs::{FileType, JoshutoDirEntry, JoshutoDirList, LinkType};
 use crate::util::format;
-use crate::util::string::UnicodeTruncate;
+use crate::util::search::SearchPattern;
+use crate::util::string::{self, UnicodeTruncate};
 use crate::util::style;
 
 const MIN_LEFT_LABEL_WIDTH: i32 = 15;
@@ -21,23 +25,44 @@ pub struct TuiDirListDetailed<'a> {
     dirlist: &'a JoshutoDirList,
     display_options: &'a DisplayOption,
     currently_playing: Option<&'a DiziAudioFile>,
+    search_pattern: Option<&'a SearchPattern>,
+    metadata_cache: &'a MetadataCache,
     focused: bool,
+    name_scroll: Option<&'a NameScroll>,
+    ratings: Option<&'a HashMap<PathBuf, u8>>,
 }
 impl<'a> TuiDirListDetailed<'a> {
     pub fn new(
         dirlist: &'a JoshutoDirList,
         display_options: &'a DisplayOption,
         currently_playing: Option<&'a DiziAudioFile>,
+        search_pattern: Option<&'a SearchPattern>,
+        metadata_cache: &'a MetadataCache,
         focused: bool,
     ) -> Self {
         Self {
             dirlist,
             display_options,
             currently_playing,
+            search_pattern,
+            metadata_cache,
             focused,
+            name_scroll: None,
+            ratings: None,
         }
     }
 
+    /// Scrolls the name at the cursor, see `scroll_name_right`
+    pub fn name_scroll(mut self, name_scroll: Option<&'a NameScroll>) -> Self {
+        self.name_scroll = name_scroll;
+        self
+    }
+
+    pub fn ratings(mut self, ratings: &'a HashMap<PathBuf, u8>) -> Self {
+        self.ratings = Some(ratings);
+        self
+    }
+
     fn draw_listing(&self, area: &Rect, buf: &mut Buffer) {
         let x = area.left();
         let y = area.top();
@@ -64,28 +89,75 @@ impl<'a> TuiDirListDetailed<'a> {
             .for_each(|(i, entry)| {
                 let ix = skip_dist + i;
 
+                let is_match = self
+                    .search_pattern
+                    .map(|pattern| pattern.is_match(entry.file_name()))
+                    .unwrap_or(false);
+
+                let entry_style = if entry.is_selected() {
+                    style::selection_style()
+                } else {
+                    style::entry_style(entry)
+                };
+
                 let style = if self.focused && ix == curr_index {
-                    style::entry_style(entry).add_modifier(Modifier::REVERSED)
+                    entry_style.add_modifier(Modifier::REVERSED)
                 } else if let Some(song) = self.currently_playing {
                     if song.file_path() == entry.file_path() {
                         style::playing_style()
                     } else {
-                        style::entry_style(entry)
+                        entry_style
                     }
                 } else {
-                    style::entry_style(entry)
+                    entry_style
+                };
+                let style = if is_match {
+                    style::search_match_style(style)
+                } else {
+                    style
                 };
 
                 buf.set_string(x, y + i as u16, space_fill.as_str(), style);
 
-                let line_number_string = "".to_string();
+                let song = self.metadata_cache.get(entry.file_path());
+                // the tags are part of the name when it is formatted
+                let name = song
+                    .zip(self.display_options.entry_format())
+                    .map(|(song, format)| format.render(song, entry.file_name()));
+                let song_string = match (song, name.is_some()) {
+                    (Some(song), false) => song_label(song, self.display_options.show_song_tags()),
+                    _ => String::new(),
+                };
+                let song_string = match self.ratings.and_then(|r| r.get(entry.file_path())) {
+                    Some(rating) => {
+                        format!("{}  {}", format::rating_to_string(*rating), song_string)
+                    }
+                    None => song_string,
+                };
+                let scroll = match self.name_scroll {
+                    Some(scroll) if self.focused && ix == curr_index => {
+                        scroll.offset_for(entry.file_path())
+                    }
+                    _ => 0,
+                };
+                // only file names keep their extension when trimmed
+                let is_file_name = name.is_none() && scroll == 0;
+                let name = name.as_deref().unwrap_or_else(|| entry.file_name());
+                let name = string::skip_width(name, scroll);
+                let name = if self.display_options.show_icons() {
+                    let icon = self.display_options.icon_style().entry_icon(entry);
+                    format!("{} {}", icon, name)
+                } else {
+                    name.to_string()
+                };
                 print_entry(
                     buf,
                     entry,
+                    (name.as_str(), is_file_name),
+                    song_string.as_str(),
                     style,
                     (x + 1, y + i as u16),
                     drawing_width - 1,
-                    line_number_string,
                 );
             });
     }
@@ -103,10 +175,11 @@ impl<'a> Widget for TuiDirListDetailed<'a> {
 fn print_entry(
     buf: &mut Buffer,
     entry: &JoshutoDirEntry,
+    (name, is_file_name): (&str, bool),
+    song_string: &str,
     style: Style,
     (x, y): (u16, u16),
     drawing_width: usize,
-    index: String,
 ) {
     let size_string = match entry.metadata.file_type() {
         FileType::Directory => entry
@@ -120,21 +193,16 @@ fn print_entry(
         LinkType::Normal => "",
         LinkType::Symlink(_, _) => "-> ",
     };
-    let left_label_original = entry.file_name();
-    let right_label_original = format!(" {}{} ", symlink_string, size_string);
+    let left_label_original = name;
+    let right_label_original = format!(" {}{}{} ", song_string, symlink_string, size_string);
 
     let (left_label, right_label) = factor_labels_for_entry(
         left_label_original,
         right_label_original.as_str(),
         drawing_width,
+        is_file_name,
     );
 
-    let index_width = index.width();
-    // draw_index
-    buf.set_stringn(x, y, index, index_width, Style::default());
-
-    let drawing_width = drawing_width - index_width as usize;
-    let x = x + index_width as u16;
     // Drawing labels
     buf.set_stringn(x, y, left_label, drawing_width, style);
     buf.set_stringn(
@@ -146,10 +214,28 @@ fn print_entry(
     );
 }
 
+fn song_label(song: &SongMetadata, show_song_tags: bool) -> String {
+    let mut label = String::new();
+    if show_song_tags {
+        match (song.artist.as_ref(), song.title.as_ref()) {
+            (Some(artist), Some(title)) => label.push_str(&format!("{} - {}  ", artist, title)),
+            (None, Some(title)) => label.push_str(&format!("{}  ", title)),
+            (Some(artist), None) => label.push_str(&format!("{}  ", artist)),
+            (None, None) => {}
+        }
+    }
+    if let Some(duration) = song.duration {
+        label.push_str(&format::duration_to_string(duration));
+        label.push_str("  ");
+    }
+    label
+}
+
 fn factor_labels_for_entry<'a>(
     left_label_original: &'a str,
     right_label_original: &'a str,
     drawing_width: usize,
+    is_file_name: bool,
 ) -> (String, &'a str) {
     let left_label_original_width = left_label_original.width();
     let right_label_original_width = right_label_original.width();
@@ -164,7 +250,7 @@ fn factor_labels_for_entry<'a>(
     } else if left_width_remainder < MIN_LEFT_LABEL_WIDTH {
         (
             if left_label_original.width() as i32 <= left_width_remainder {
-                trim_file_label(left_label_original, drawing_width)
+                trim_label(left_label_original, drawing_width, is_file_name)
             } else {
                 left_label_original.to_string()
             },
@@ -172,12 +258,29 @@ fn factor_labels_for_entry<'a>(
         )
     } else {
         (
-            trim_file_label(left_label_original, left_width_remainder as usize),
+            trim_label(
+                left_label_original,
+                left_width_remainder as usize,
+                is_file_name,
+            ),
             right_label_original,
         )
     }
 }
 
+/// Only file names keep their extension when trimmed
+fn trim_label(label: &str, drawing_width: usize, is_file_name: bool) -> String {
+    if is_file_name {
+        trim_file_label(label, drawing_width)
+    } else if drawing_width < 1 {
+        "".to_string()
+    } else {
+        let mut truncated = label.trunc(drawing_width - 1);
+        truncated.push_str(ELLIPSIS);
+        truncated
+    }
+}
+
 pub fn trim_file_label(name: &str, drawing_width: usize) -> String {
     // pre-condition: string name is longer than width
     let (stem, extension) = match name.rfind('.') {
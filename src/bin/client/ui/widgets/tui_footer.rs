@@ -1,3 +1,5 @@
+use std::time;
+
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
@@ -6,47 +8,231 @@ use ratatui::widgets::{Paragraph, Widget};
 
 use dizi::player::PlayerState;
 
+use crate::config::option::StatusFormat;
+use crate::MESSAGES_T;
+
+use super::TuiVolume;
+
+const SPINNER_FRAMES: [&str; 10] = [
+    "\u{280B}", "\u{2819}", "\u{2839}", "\u{2838}", "\u{283C}", "\u{2834}", "\u{2826}", "\u{2827}",
+    "\u{2807}", "\u{280F}",
+];
+
 pub struct TuiFooter<'a> {
     player_state: &'a PlayerState,
+    status_format: Option<&'a StatusFormat>,
+    search_matches: Option<(usize, usize)>,
+    filter: Option<&'a str>,
+    selected: usize,
+    count: Option<usize>,
+    recording: Option<char>,
+    scanning: bool,
+    reconnecting: bool,
 }
 
 impl<'a> TuiFooter<'a> {
     pub fn new(player_state: &'a PlayerState) -> Self {
-        Self { player_state }
+        Self {
+            player_state,
+            status_format: None,
+            search_matches: None,
+            filter: None,
+            selected: 0,
+            count: None,
+            recording: None,
+            scanning: false,
+            reconnecting: false,
+        }
+    }
+
+    /// Replaces the default footer text with a user defined template
+    pub fn status_format(mut self, status_format: Option<&'a StatusFormat>) -> Self {
+        self.status_format = status_format;
+        self
+    }
+
+    /// Shows `(position, total)` of the search matches in the current directory
+    pub fn search_matches(mut self, search_matches: Option<(usize, usize)>) -> Self {
+        self.search_matches = search_matches;
+        self
+    }
+
+    /// Shows the pattern the current directory is filtered by, if any
+    pub fn filter(mut self, filter: Option<&'a str>) -> Self {
+        self.filter = filter;
+        self
+    }
+
+    /// Shows how many entries are selected, if any
+    pub fn selected(mut self, selected: usize) -> Self {
+        self.selected = selected;
+        self
+    }
+
+    /// Shows the count typed so far for the next command
+    pub fn count(mut self, count: Option<usize>) -> Self {
+        self.count = count;
+        self
+    }
+
+    /// Shows the register a macro is being recorded into
+    pub fn recording(mut self, recording: Option<char>) -> Self {
+        self.recording = recording;
+        self
+    }
+
+    /// Shows a spinner while the server is looking for songs to append
+    pub fn scanning(mut self, scanning: bool) -> Self {
+        self.scanning = scanning;
+        self
+    }
+
+    /// Shows a spinner while the connection to the server is being retried
+    pub fn reconnecting(mut self, reconnecting: bool) -> Self {
+        self.reconnecting = reconnecting;
+        self
     }
 }
 
 impl<'a> Widget for TuiFooter<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
-        let text = vec![
-            Span::styled(
-                format!("Audio system: {}", self.player_state.audio_host),
-                Style::default().fg(Color::Green),
-            ),
-            Span::raw("  "),
-            Span::raw(format!(
-                "Channels: {}",
-                self.player_state
+        let mut text = match self.status_format {
+            Some(status_format) => vec![Span::raw(status_format.render(self.player_state))],
+            None => {
+                let metadata = self
+                    .player_state
+                    .info
                     .song
                     .as_ref()
-                    .map(|song| song.audio_metadata())
+                    .map(|song| song.audio_metadata());
+                let unknown = || MESSAGES_T.get("footer.unknown").to_string();
+                let channels = metadata
                     .and_then(|metadata| metadata.channels)
                     .map(|s| s.to_string())
-                    .unwrap_or_else(|| "UNKNOWN".to_string())
-            )),
-            Span::raw("  "),
-            Span::raw(format!(
-                "Sample Rate: {} Hz",
-                self.player_state
-                    .song
-                    .as_ref()
-                    .map(|song| song.audio_metadata())
+                    .unwrap_or_else(unknown);
+                let sample_rate = metadata
                     .and_then(|metadata| metadata.sample_rate)
                     .map(|s| s.to_string())
-                    .unwrap_or_else(|| "UNKNOWN".to_string())
-            )),
-        ];
+                    .unwrap_or_else(unknown);
+                let mut spans = vec![
+                    Span::styled(
+                        MESSAGES_T.format(
+                            "footer.audio_system",
+                            &[("host", &self.player_state.info.audio_host)],
+                        ),
+                        Style::default().fg(Color::Green),
+                    ),
+                    Span::raw("  "),
+                    Span::raw(MESSAGES_T.format("footer.channels", &[("channels", &channels)])),
+                    Span::raw("  "),
+                    Span::raw(MESSAGES_T.format("footer.sample_rate", &[("rate", &sample_rate)])),
+                ];
+                if let Some(output) = self.player_state.info.output.as_ref() {
+                    let resampled = match output.resampled_from {
+                        Some(_) => MESSAGES_T.get("footer.resampled"),
+                        None => "",
+                    };
+                    spans.push(Span::raw("  "));
+                    spans.push(Span::raw(format!(
+                        "{}{}",
+                        MESSAGES_T.format(
+                            "footer.output",
+                            &[
+                                ("channels", &output.channels),
+                                ("rate", &output.sample_rate),
+                                ("format", &output.sample_format),
+                            ],
+                        ),
+                        resampled
+                    )));
+                }
+                spans
+            }
+        };
+        if let Some((position, total)) = self.search_matches {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                MESSAGES_T.format(
+                    "footer.search",
+                    &[("position", &position), ("total", &total)],
+                ),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        if let Some(filter) = self.filter {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                MESSAGES_T.format("footer.filter", &[("filter", &filter)]),
+                Style::default().fg(Color::Yellow),
+            ));
+        }
+
+        if self.reconnecting {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                format!(
+                    "{} {}",
+                    spinner_frame(),
+                    MESSAGES_T.get("footer.reconnecting")
+                ),
+                Style::default().fg(Color::Red),
+            ));
+        }
 
-        Paragraph::new(Line::from(text)).render(area, buf);
+        if self.scanning {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                format!("{} {}", spinner_frame(), MESSAGES_T.get("footer.scanning")),
+                Style::default().fg(Color::Cyan),
+            ));
+        }
+
+        if self.selected > 0 {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                MESSAGES_T.format("footer.selected", &[("count", &self.selected)]),
+                Style::default().fg(Color::LightYellow),
+            ));
+        }
+
+        if let Some(count) = self.count {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                MESSAGES_T.format("footer.count", &[("count", &count)]),
+                Style::default().fg(Color::LightCyan),
+            ));
+        }
+
+        if let Some(register) = self.recording {
+            text.push(Span::raw("  "));
+            text.push(Span::styled(
+                MESSAGES_T.format("footer.recording", &[("register", &register)]),
+                Style::default().fg(Color::LightRed),
+            ));
+        }
+
+        // keep the volume gauge on the right edge
+        let volume_width = (TuiVolume::width() as u16).min(area.width);
+        let text_rect = Rect {
+            width: area.width - volume_width,
+            ..area
+        };
+        let volume_rect = Rect {
+            x: area.x + text_rect.width,
+            width: volume_width,
+            ..area
+        };
+
+        Paragraph::new(Line::from(text)).render(text_rect, buf);
+        TuiVolume::new(self.player_state.info.volume).render(volume_rect, buf);
     }
 }
+
+fn spinner_frame() -> &'static str {
+    let millis = time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    SPINNER_FRAMES[(millis / 100) as usize % SPINNER_FRAMES.len()]
+}
@@ -0,0 +1,81 @@
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+
+use crate::context::LibraryContext;
+use crate::util::style;
+
+pub struct TuiLibrary<'a> {
+    library: &'a LibraryContext,
+    currently_playing: Option<&'a Path>,
+    focused: bool,
+}
+
+impl<'a> TuiLibrary<'a> {
+    pub fn new(
+        library: &'a LibraryContext,
+        currently_playing: Option<&'a Path>,
+        focused: bool,
+    ) -> Self {
+        Self {
+            library,
+            currently_playing,
+            focused,
+        }
+    }
+}
+
+impl<'a> Widget for TuiLibrary<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+
+        let x = area.left();
+        let y = area.top();
+        let drawing_width = area.width as usize;
+
+        if self.library.is_empty() {
+            let (msg, style) = if self.library.is_loading() {
+                ("loading...", Style::default().fg(Color::Yellow))
+            } else {
+                ("empty", Style::default().bg(Color::Red).fg(Color::White))
+            };
+            buf.set_stringn(x, y, msg, drawing_width, style);
+            return;
+        }
+
+        let curr_index = self.library.get_index();
+        let skip_dist = self.library.first_index_for_viewport(area.height as usize);
+        let space_fill = " ".repeat(drawing_width);
+
+        self.library
+            .songs_ref()
+            .iter()
+            .enumerate()
+            .skip(skip_dist)
+            .take(area.height as usize)
+            .enumerate()
+            .for_each(|(offset, (i, path))| {
+                let style = if self.focused && Some(i) == curr_index {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else if self.currently_playing == Some(path.as_path()) {
+                    style::playing_style()
+                } else {
+                    Style::default()
+                };
+
+                let label = path
+                    .strip_prefix(self.library.root())
+                    .unwrap_or(path)
+                    .to_string_lossy();
+
+                let y = y + offset as u16;
+                buf.set_string(x, y, space_fill.as_str(), style);
+                buf.set_stringn(x + 1, y, label, drawing_width - 1, style);
+            });
+    }
+}
@@ -0,0 +1,74 @@
+use std::time;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+
+use crate::context::{LyricsState, SongLyrics};
+use crate::MESSAGES_T;
+
+/// Lyrics of the playing song, keeping the current line of synced lyrics
+/// in the middle of the pane
+pub struct TuiLyrics<'a> {
+    lyrics: Option<&'a SongLyrics>,
+    elapsed: time::Duration,
+}
+
+impl<'a> TuiLyrics<'a> {
+    pub fn new(lyrics: Option<&'a SongLyrics>, elapsed: time::Duration) -> Self {
+        Self { lyrics, elapsed }
+    }
+}
+
+impl<'a> Widget for TuiLyrics<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+
+        let x = area.left();
+        let y = area.top();
+        let drawing_width = area.width as usize - 1;
+
+        let lyrics = match self.lyrics.map(|l| &l.state) {
+            Some(LyricsState::Loaded(lyrics)) => lyrics,
+            state => {
+                let msg = match state {
+                    Some(LyricsState::Loading) => MESSAGES_T.get("player.loading_lyrics"),
+                    _ => MESSAGES_T.get("player.no_lyrics"),
+                };
+                let style = Style::default().fg(Color::DarkGray);
+                buf.set_stringn(x + 1, y, msg, drawing_width, style);
+                return;
+            }
+        };
+
+        let height = area.height as usize;
+        let current = lyrics.current_line(self.elapsed);
+        let skip_dist = current
+            .map(|index| index.saturating_sub(height / 2))
+            .unwrap_or(0);
+
+        let current_style = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let past_style = Style::default().fg(Color::DarkGray);
+
+        lyrics
+            .lines()
+            .iter()
+            .enumerate()
+            .skip(skip_dist)
+            .take(height)
+            .for_each(|(index, line)| {
+                let style = match current {
+                    Some(current) if index == current => current_style,
+                    Some(current) if index < current => past_style,
+                    _ => Style::default(),
+                };
+                let offset = (index - skip_dist) as u16;
+                buf.set_stringn(x + 1, y + offset, &line.text, drawing_width, style);
+            });
+    }
+}
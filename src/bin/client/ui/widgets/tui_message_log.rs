@@ -0,0 +1,57 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::Modifier;
+use ratatui::widgets::Widget;
+
+use crate::context::MessageQueue;
+
+pub struct TuiMessageLog<'a> {
+    message_queue: &'a MessageQueue,
+    focused: bool,
+}
+
+impl<'a> TuiMessageLog<'a> {
+    pub fn new(message_queue: &'a MessageQueue, focused: bool) -> Self {
+        Self {
+            message_queue,
+            focused,
+        }
+    }
+}
+
+impl<'a> Widget for TuiMessageLog<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+
+        let x = area.left();
+        let y = area.top();
+        let drawing_width = area.width as usize;
+        let height = area.height as usize;
+
+        let history = self.message_queue.history_ref();
+        let curr_index = self.message_queue.get_history_index();
+        let skip_dist = curr_index.map(|i| i / height * height).unwrap_or(0);
+        let space_fill = " ".repeat(drawing_width);
+
+        history
+            .iter()
+            .enumerate()
+            .skip(skip_dist)
+            .take(height)
+            .enumerate()
+            .for_each(|(offset, (i, msg))| {
+                let style = if self.focused && Some(i) == curr_index {
+                    msg.style.add_modifier(Modifier::REVERSED)
+                } else {
+                    msg.style
+                };
+
+                let y = y + offset as u16;
+                let line = format!("{} {}", msg.time.format("%H:%M:%S"), msg.content);
+                buf.set_string(x, y, space_fill.as_str(), style);
+                buf.set_stringn(x + 1, y, line.as_str(), drawing_width - 1, style);
+            });
+    }
+}
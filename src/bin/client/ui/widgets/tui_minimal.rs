@@ -0,0 +1,126 @@
+use std::time;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+use dizi::player::PlayerState;
+
+use crate::config::option::{IconStyle, StatusFormat};
+use crate::util::format;
+
+use super::TuiVolume;
+
+/// The playing song, its progress and the volume, one line each
+pub struct TuiMinimal<'a> {
+    player: &'a PlayerState,
+    status_format: Option<&'a StatusFormat>,
+    show_remaining: bool,
+    icon_style: IconStyle,
+}
+
+impl<'a> TuiMinimal<'a> {
+    pub fn new(player: &'a PlayerState) -> Self {
+        Self {
+            player,
+            status_format: None,
+            show_remaining: false,
+            icon_style: IconStyle::default(),
+        }
+    }
+
+    pub fn show_remaining(mut self, show_remaining: bool) -> Self {
+        self.show_remaining = show_remaining;
+        self
+    }
+
+    pub fn status_format(mut self, status_format: Option<&'a StatusFormat>) -> Self {
+        self.status_format = status_format;
+        self
+    }
+
+    pub fn icon_style(mut self, icon_style: IconStyle) -> Self {
+        self.icon_style = icon_style;
+        self
+    }
+
+    fn now_playing(&self) -> String {
+        if let Some(status_format) = self.status_format {
+            return status_format.render(self.player);
+        }
+        let status = self.icon_style.status(self.player.info.status);
+        match self.player.info.song.as_ref() {
+            Some(song) => {
+                let tags = &song.music_metadata().standard_tags;
+                let title = tags
+                    .get("TrackTitle")
+                    .map(|s| s.as_str())
+                    .unwrap_or_else(|| song.file_name());
+                match tags.get("Artist") {
+                    Some(artist) => format!("{} {} - {}", status, artist, title),
+                    None => format!("{} {}", status, title),
+                }
+            }
+            None => format!("{} Nothing playing", status),
+        }
+    }
+}
+
+impl<'a> Widget for TuiMinimal<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 1 || area.height < 1 {
+            return;
+        }
+        let x = area.left();
+        let y = area.top();
+        let width = area.width as usize;
+
+        buf.set_stringn(x, y, self.now_playing(), width, Style::default());
+        if area.height < 2 {
+            return;
+        }
+
+        let elapsed = self.player.info.elapsed;
+        let total = self
+            .player
+            .info
+            .total_duration()
+            .unwrap_or(time::Duration::from_secs(0));
+        let played = if self.show_remaining {
+            format::remaining_to_string(elapsed, total)
+        } else {
+            format::duration_to_string(elapsed)
+        };
+        let times = format!("{} / {} ", played, format::duration_to_string(total));
+        buf.set_stringn(x, y + 1, times.as_str(), width, Style::default());
+
+        let bar_width = width.saturating_sub(times.len());
+        if bar_width > 0 && total.as_secs() > 0 {
+            let filled = (elapsed.as_secs().min(total.as_secs()) as usize * bar_width)
+                / total.as_secs() as usize;
+            let bar = format!(
+                "{}{}",
+                "\u{2501}".repeat(filled),
+                "\u{2500}".repeat(bar_width - filled)
+            );
+            buf.set_stringn(
+                x + times.len() as u16,
+                y + 1,
+                bar,
+                bar_width,
+                Style::default().fg(Color::Blue),
+            );
+        }
+        if area.height < 3 {
+            return;
+        }
+
+        let rect = Rect {
+            y: y + 2,
+            height: 1,
+            ..area
+        };
+        TuiVolume::new(self.player.info.volume).render(rect, buf);
+    }
+}
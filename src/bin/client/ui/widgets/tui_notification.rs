@@ -0,0 +1,48 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::{Clear, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::context::Notification;
+
+/// Latest notification drawn over the right end of a line,
+/// e.g. ` Added 42 songs to playlist (+2) `
+pub struct TuiNotification<'a> {
+    notification: &'a Notification,
+}
+
+impl<'a> TuiNotification<'a> {
+    pub fn new(notification: &'a Notification) -> Self {
+        Self { notification }
+    }
+}
+
+impl<'a> Widget for TuiNotification<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+
+        let mut text = format!(" {} ", self.notification.message.content);
+        if self.notification.suppressed > 0 {
+            text.push_str(&format!("(+{}) ", self.notification.suppressed));
+        }
+        let width = (text.width() as u16).min(area.width);
+
+        let style = if self.notification.is_fading() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            self.notification.message.style
+        };
+
+        let rect = Rect {
+            x: area.right() - width,
+            y: area.top(),
+            width,
+            height: 1,
+        };
+        Clear.render(rect, buf);
+        buf.set_stringn(rect.x, rect.y, text, width as usize, style);
+    }
+}
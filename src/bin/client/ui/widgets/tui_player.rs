@@ -7,15 +7,44 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Paragraph, Widget, Wrap};
 
-use dizi::player::{PlayerState, PlayerStatus};
+use dizi::chapter;
+use dizi::player::{PlayerState, RepeatMode};
+use dizi::song::{AudioMetadata, DiziAudioFile};
+
+use crate::config::option::IconStyle;
+use crate::MESSAGES_T;
 
 pub struct TuiPlayer<'a> {
     player: &'a PlayerState,
+    show_remaining: bool,
+    icon_style: IconStyle,
+    waveform: Option<&'a [u8]>,
 }
 
 impl<'a> TuiPlayer<'a> {
     pub fn new(player: &'a PlayerState) -> Self {
-        Self { player }
+        Self {
+            player,
+            show_remaining: false,
+            icon_style: IconStyle::default(),
+            waveform: None,
+        }
+    }
+
+    pub fn show_remaining(mut self, show_remaining: bool) -> Self {
+        self.show_remaining = show_remaining;
+        self
+    }
+
+    pub fn icon_style(mut self, icon_style: IconStyle) -> Self {
+        self.icon_style = icon_style;
+        self
+    }
+
+    /// Draws the progress bar over the song's peaks
+    pub fn waveform(mut self, waveform: Option<&'a [u8]>) -> Self {
+        self.waveform = waveform;
+        self
     }
 }
 
@@ -25,7 +54,7 @@ impl<'a> Widget for TuiPlayer<'a> {
             return;
         }
 
-        let song = &self.player.song;
+        let song = &self.player.info.song;
         {
             let song_name = match song {
                 Some(song) => match song.music_metadata().standard_tags.get("TrackTitle") {
@@ -34,12 +63,20 @@ impl<'a> Widget for TuiPlayer<'a> {
                 },
                 None => " ".to_string(),
             };
+            let song_name = match song
+                .as_ref()
+                .and_then(|song| chapter_line(song, self.player))
+            {
+                Some(chapter) => format!("{}\n{}", song_name, chapter),
+                None => song_name,
+            };
 
+            // leave room for the technical info line on short widgets
             let p_rect = Rect {
                 x: area.x,
                 y: area.y,
                 width: area.width,
-                height: 4,
+                height: (area.height - 4).clamp(1, 4),
             };
             Paragraph::new(song_name)
                 .style(Style::default())
@@ -47,16 +84,31 @@ impl<'a> Widget for TuiPlayer<'a> {
                 .render(p_rect, buf);
         }
 
+        if area.height >= 6 {
+            let info = match song {
+                Some(song) => technical_info(song.audio_metadata()),
+                None => " ".to_string(),
+            };
+            let rect = Rect {
+                y: area.y + area.height - 4,
+                height: 1,
+                ..area
+            };
+            Paragraph::new(info)
+                .style(Style::default().fg(Color::DarkGray))
+                .render(rect, buf);
+        }
+
         {
             let on_style = Style::default().fg(Color::Green);
             let off_style = Style::default().fg(Color::Black);
 
-            let playlist_file_style = match self.player.playlist_status {
+            let playlist_file_style = match self.player.info.playlist_status {
                 PlaylistType::PlaylistFile => on_style,
                 PlaylistType::DirectoryListing => off_style,
             };
 
-            let playlist_directory_style = match self.player.playlist_status {
+            let playlist_directory_style = match self.player.info.playlist_status {
                 PlaylistType::PlaylistFile => off_style,
                 PlaylistType::DirectoryListing => on_style,
             };
@@ -66,7 +118,10 @@ impl<'a> Widget for TuiPlayer<'a> {
                 .add_modifier(Modifier::BOLD);
             let text = Line::from(vec![
                 Span::styled(
-                    format!("Volume: {}%  ", self.player.volume,),
+                    format!(
+                        "{}  ",
+                        MESSAGES_T.format("player.volume", &[("volume", &self.player.info.volume)])
+                    ),
                     player_status_style,
                 ),
                 Span::styled("[PLAYLIST] ", playlist_file_style),
@@ -81,18 +136,25 @@ impl<'a> Widget for TuiPlayer<'a> {
             Paragraph::new(text).render(rect, buf);
         }
 
-        let duration_elapsed = self.player.elapsed;
+        let duration_elapsed = self.player.info.elapsed;
+        let total_duration = self
+            .player
+            .info
+            .total_duration()
+            .unwrap_or(time::Duration::from_secs(0));
         let duration_played_str = {
-            let total_secs = duration_elapsed.as_secs();
+            let shown = if self.show_remaining {
+                total_duration.saturating_sub(duration_elapsed)
+            } else {
+                duration_elapsed
+            };
+            let total_secs = shown.as_secs();
             let minutes = total_secs / 60;
             let hours = total_secs / 3600;
             let seconds = total_secs % 60;
-            format!("{:02}:{:02}:{:02}", hours, minutes, seconds)
+            let sign = if self.show_remaining { "-" } else { "" };
+            format!("{}{:02}:{:02}:{:02}", sign, hours, minutes, seconds)
         };
-        let total_duration = song
-            .as_ref()
-            .and_then(|song| song.audio_metadata().total_duration)
-            .unwrap_or(time::Duration::from_secs(0));
         let total_duration_str = {
             let total_secs = total_duration.as_secs();
             let minutes = total_secs / 60;
@@ -104,33 +166,41 @@ impl<'a> Widget for TuiPlayer<'a> {
             let on_style = Style::default().fg(Color::Yellow);
             let off_style = Style::default().fg(Color::Black);
 
-            let next_style = match self.player.next {
+            let next_style = match self.player.info.next {
                 true => on_style,
                 false => off_style,
             };
-            let repeat_style = match self.player.repeat {
+            let repeat_style = match self.player.info.repeat {
+                RepeatMode::Off => off_style,
+                RepeatMode::One | RepeatMode::All => on_style,
+            };
+            let shuffle_style = match self.player.info.shuffle {
                 true => on_style,
                 false => off_style,
             };
-            let shuffle_style = match self.player.shuffle {
+            let single_style = match self.player.info.single {
                 true => on_style,
                 false => off_style,
             };
 
-            let player_status = match self.player.status {
-                PlayerStatus::Playing => "\u{25B6}  ",
-                PlayerStatus::Stopped => "\u{2588}\u{2588}",
-                PlayerStatus::Paused => "\u{2590} \u{258C}",
-            };
+            let player_status = self.icon_style.status(self.player.info.status);
+            let icons = &self.icon_style;
 
             let text = Line::from(vec![
                 Span::raw(format!(
-                    "{} {} / {}   ",
+                    "{:<3} {} / {}   ",
                     player_status, duration_played_str, total_duration_str
                 )),
-                Span::styled("[NEXT] ", next_style),
-                Span::styled("[REPEAT] ", repeat_style),
-                Span::styled("[SHUFFLE] ", shuffle_style),
+                Span::styled(format!("{} ", icons.next()), next_style),
+                Span::styled(
+                    match self.player.info.repeat {
+                        RepeatMode::One => format!("{} ", icons.repeat_one()),
+                        _ => format!("{} ", icons.repeat()),
+                    },
+                    repeat_style,
+                ),
+                Span::styled(format!("{} ", icons.shuffle()), shuffle_style),
+                Span::styled(format!("{} ", icons.single()), single_style),
             ]);
 
             let rect = Rect {
@@ -149,6 +219,26 @@ impl<'a> Widget for TuiPlayer<'a> {
                 let progress_bar_width =
                     (secs as f32 / total_duration as f32 * area.width as f32) as usize;
 
+                if let Some(peaks) = self.waveform {
+                    let played = Style::default().fg(Color::Blue);
+                    let unplayed = Style::default().fg(Color::DarkGray);
+                    let bar = waveform_bar(peaks, area.width as usize);
+                    for (i, c) in bar.chars().enumerate() {
+                        let style = if i < progress_bar_width {
+                            played
+                        } else {
+                            unplayed
+                        };
+                        buf.set_string(
+                            area.x + i as u16,
+                            area.y + area.height - 1,
+                            c.to_string(),
+                            style,
+                        );
+                    }
+                    return;
+                }
+
                 let progress_bar_space = " ".repeat(progress_bar_width);
                 let style = Style::default().bg(Color::Blue);
                 buf.set_stringn(
@@ -162,3 +252,72 @@ impl<'a> Widget for TuiPlayer<'a> {
         }
     }
 }
+
+/// `peaks` squeezed or stretched into `width` block characters, the loudest of those
+/// falling in a column giving its height
+fn waveform_bar(peaks: &[u8], width: usize) -> String {
+    const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+    if peaks.is_empty() {
+        return String::new();
+    }
+    (0..width)
+        .map(|column| {
+            let start = column * peaks.len() / width;
+            let end = ((column + 1) * peaks.len() / width).max(start + 1);
+            let peak = peaks[start..end].iter().max().cloned().unwrap_or(0);
+            BLOCKS[peak as usize * BLOCKS.len() / 256]
+        })
+        .collect()
+}
+
+/// The playing chapter of songs that have chapters
+fn chapter_line(song: &DiziAudioFile, player: &PlayerState) -> Option<String> {
+    let chapters = song.chapters();
+    let index = chapter::chapter_at(chapters, player.info.elapsed)?;
+    Some(MESSAGES_T.format(
+        "player.chapter",
+        &[
+            ("number", &(index + 1)),
+            ("count", &chapters.len()),
+            ("title", &chapters[index].title),
+        ],
+    ))
+}
+
+/// e.g. `FLAC · 44.1 kHz · 16 bit · 2 ch · 1011 kbps`, leaving out whatever is unknown
+fn technical_info(metadata: &AudioMetadata) -> String {
+    let mut parts = Vec::new();
+    if let Some(codec) = metadata.codec.as_ref() {
+        parts.push(codec.clone());
+    }
+    if let Some(sample_rate) = metadata.sample_rate {
+        parts.push(format!("{} kHz", sample_rate as f32 / 1000.0));
+    }
+    if let Some(bit_depth) = metadata.bit_depth {
+        parts.push(format!("{} bit", bit_depth));
+    }
+    if let Some(channels) = metadata.channels {
+        parts.push(format!("{} ch", channels));
+    }
+    if let Some(bitrate) = metadata.bitrate {
+        parts.push(format!("{} kbps", bitrate));
+    }
+    if parts.is_empty() {
+        MESSAGES_T.get("player.unknown_format").to_string()
+    } else {
+        parts.join(" \u{00B7} ")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn waveform_bar_fits_width() {
+        let peaks = [0, 255, 128, 64];
+        assert_eq!(waveform_bar(&peaks, 2), "█▅");
+        assert_eq!(waveform_bar(&peaks, 8), "▁▁██▅▅▃▃");
+        assert_eq!(waveform_bar(&[], 4), "");
+    }
+}
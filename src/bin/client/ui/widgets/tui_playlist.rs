@@ -1,16 +1,23 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::time;
 
 use dizi::song::DiziSongEntry;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
-use ratatui::style::{Modifier, Style};
+use ratatui::style::{Color, Modifier, Style};
 use ratatui::widgets::Widget;
 
 use unicode_width::UnicodeWidthStr;
 
 use dizi::player::PlayerState;
+use dizi::playlist::FilePlaylist;
 
-use crate::util::string::UnicodeTruncate;
+use crate::config::option::EntryFormat;
+use crate::context::{NameScroll, SongMetadata};
+use crate::util::format;
+use crate::util::string::{self, UnicodeTruncate};
 use crate::util::style;
 
 const MIN_LEFT_LABEL_WIDTH: i32 = 15;
@@ -19,19 +26,166 @@ const ELLIPSIS: &str = "…";
 
 pub struct TuiPlaylist<'a> {
     player: &'a PlayerState,
+    // either the file playlist or the directory playlist
+    playlist: &'a FilePlaylist,
+    is_directory: bool,
     focused: bool,
+    // total and remaining length of the playlist
+    durations: Option<(time::Duration, time::Duration)>,
+    entry_format: Option<&'a EntryFormat>,
+    name_scroll: Option<&'a NameScroll>,
+    ratings: Option<&'a HashMap<PathBuf, u8>>,
+    // where the song being dragged with the mouse would be dropped
+    drop_index: Option<usize>,
 }
 
 impl<'a> TuiPlaylist<'a> {
     pub fn new(player: &'a PlayerState, focused: bool) -> Self {
-        Self { player, focused }
+        Self {
+            player,
+            playlist: &player.playlist,
+            is_directory: false,
+            focused,
+            durations: None,
+            entry_format: None,
+            name_scroll: None,
+            ratings: None,
+            drop_index: None,
+        }
+    }
+
+    /// Shows the songs of the directory being played instead of the file playlist
+    pub fn directory_playlist(mut self, show: bool) -> Self {
+        if show {
+            self.playlist = &self.player.directory_playlist;
+        } else {
+            self.playlist = &self.player.playlist;
+        }
+        self.is_directory = show;
+        self
+    }
+
+    pub fn durations(mut self, total: time::Duration, remaining: time::Duration) -> Self {
+        self.durations = Some((total, remaining));
+        self
+    }
+
+    pub fn entry_format(mut self, entry_format: Option<&'a EntryFormat>) -> Self {
+        self.entry_format = entry_format;
+        self
+    }
+
+    /// Scrolls the name at the cursor, see `scroll_name_right`
+    pub fn name_scroll(mut self, name_scroll: Option<&'a NameScroll>) -> Self {
+        self.name_scroll = name_scroll;
+        self
+    }
+
+    pub fn ratings(mut self, ratings: &'a HashMap<PathBuf, u8>) -> Self {
+        self.ratings = Some(ratings);
+        self
+    }
+
+    pub fn drop_index(mut self, index: Option<usize>) -> Self {
+        self.drop_index = index;
+        self
+    }
+
+    /// Area the songs of `playlist` are listed in, without the statistics line
+    pub fn list_area(area: Rect, playlist: &FilePlaylist) -> Rect {
+        if area.height >= 3 && !playlist.is_empty() {
+            Rect {
+                height: area.height - 1,
+                ..area
+            }
+        } else {
+            area
+        }
+    }
+
+    /// Index of the song listed on row `y` of the screen
+    pub fn index_at(area: Rect, playlist: &FilePlaylist, y: u16) -> Option<usize> {
+        let area = Self::list_area(area, playlist);
+        if y < area.top() || y >= area.bottom() {
+            return None;
+        }
+        let skip_dist = playlist.first_index_for_viewport(area.height as usize);
+        let index = skip_dist + (y - area.top()) as usize;
+        (index < playlist.len()).then_some(index)
+    }
+
+    fn draw_drop_indicator(&self, area: &Rect, buf: &mut Buffer) {
+        let index = match self.drop_index {
+            Some(index) => index,
+            None => return,
+        };
+        let skip_dist = self.playlist.first_index_for_viewport(area.height as usize);
+        if index < skip_dist || index >= skip_dist + area.height as usize {
+            return;
+        }
+        let rect = Rect {
+            y: area.top() + (index - skip_dist) as u16,
+            height: 1,
+            ..*area
+        };
+        buf.set_style(rect, Style::default().add_modifier(Modifier::UNDERLINED));
+        buf.set_string(area.left(), rect.y, ">", Style::default().fg(Color::Yellow));
+    }
+
+    fn entry_name(&self, entry: &DiziSongEntry) -> Option<String> {
+        match (entry, self.entry_format) {
+            (DiziSongEntry::Loaded(song), Some(format)) => {
+                Some(format.render(&SongMetadata::from(song), entry.file_name()))
+            }
+            // until the server reads the song, the title its playlist file gave it
+            (DiziSongEntry::Unloaded(file), _) => file.title.clone(),
+            _ => None,
+        }
+    }
+
+    /// Left and right labels of the entry at `index`, the name scrolled by `scroll` columns
+    fn entry_labels(&self, index: usize, entry: &DiziSongEntry, scroll: usize) -> EntryLabels {
+        let name = self.entry_name(entry);
+        let is_file_name = name.is_none() && scroll == 0;
+        let name = name.unwrap_or_else(|| entry.file_name().to_string());
+        let name = string::skip_width(&name, scroll);
+        let right = match self.ratings.and_then(|r| r.get(entry.file_path())) {
+            Some(rating) => format!(" {} ", format::rating_to_string(*rating)),
+            None => String::new(),
+        };
+        EntryLabels {
+            left: format!("{:03} \u{02503} {}", index + 1, name),
+            right,
+            is_file_name,
+        }
+    }
+
+    fn draw_statistics(&self, area: &Rect, buf: &mut Buffer) {
+        let mut text = format!("{} tracks", self.playlist.len());
+        if self.is_directory {
+            text.push_str(" \u{00B7} directory");
+        } else if let Some((total, remaining)) = self.durations {
+            text.push_str(&format!(
+                " \u{00B7} total {} \u{00B7} {} remaining",
+                format::duration_to_short_string(total),
+                format::duration_to_short_string(remaining)
+            ));
+        }
+        let style = Style::default().fg(Color::DarkGray);
+        buf.set_stringn(
+            area.left() + 1,
+            area.top(),
+            text,
+            area.width as usize - 1,
+            style,
+        );
     }
 
     fn draw_playlist(&self, area: &Rect, buf: &mut Buffer) {
         let x = area.left();
         let y = area.top();
 
-        let playlist = &self.player.playlist;
+        let playlist = self.playlist;
 
         let drawing_width = area.width as usize;
         let skip_dist = playlist.first_index_for_viewport(area.height as usize);
@@ -46,10 +200,10 @@ impl<'a> TuiPlaylist<'a> {
             .enumerate()
             .take(area.height as usize)
             .for_each(|(offset, (i, entry))| {
+                let labels = self.entry_labels(i, entry, 0);
                 print_entry(
                     buf,
-                    entry,
-                    i,
+                    &labels,
                     style,
                     (x + 1, y + offset as u16),
                     drawing_width - 1,
@@ -62,7 +216,7 @@ impl<'a> TuiPlaylist<'a> {
             return;
         }
 
-        let playlist = &self.player.playlist;
+        let playlist = self.playlist;
         let skip_dist = playlist.first_index_for_viewport(area.height as usize);
         let curr_index = playlist.get_cursor_index();
 
@@ -82,10 +236,14 @@ impl<'a> TuiPlaylist<'a> {
                 let space_fill = " ".repeat(drawing_width);
                 buf.set_string(x, y + screen_index as u16, space_fill.as_str(), style);
 
+                let scroll = self
+                    .name_scroll
+                    .map(|scroll| scroll.offset_for(song.file_path()))
+                    .unwrap_or(0);
+                let labels = self.entry_labels(curr_index, song, scroll);
                 print_entry(
                     buf,
-                    song,
-                    curr_index,
+                    &labels,
                     style,
                     (x + 1, y + screen_index as u16),
                     drawing_width - 1,
@@ -98,7 +256,7 @@ impl<'a> TuiPlaylist<'a> {
         let x = area.left();
         let y = area.top();
 
-        let playlist = &self.player.playlist;
+        let playlist = self.playlist;
         let drawing_width = area.width as usize;
         let skip_dist = playlist.first_index_for_viewport(area.height as usize);
 
@@ -118,10 +276,10 @@ impl<'a> TuiPlaylist<'a> {
                 let space_fill = " ".repeat(drawing_width);
                 buf.set_string(x, y + screen_index as u16, space_fill.as_str(), style);
 
+                let labels = self.entry_labels(playing_index, song, 0);
                 print_entry(
                     buf,
-                    song,
-                    playing_index,
+                    &labels,
                     style,
                     (x + 1, y + screen_index as u16),
                     drawing_width - 1,
@@ -136,25 +294,44 @@ impl<'a> Widget for TuiPlaylist<'a> {
         if area.width < 4 || area.height < 1 {
             return;
         }
+        // keep the last line for the playlist's statistics
+        let list_area = Self::list_area(area, self.playlist);
+        if list_area.height < area.height {
+            let stats_area = Rect {
+                y: area.y + area.height - 1,
+                height: 1,
+                ..area
+            };
+            self.draw_statistics(&stats_area, buf);
+        }
+        let area = list_area;
         self.draw_playlist(&area, buf);
         self.draw_selected_entry(&area, buf);
         self.draw_currently_playing(&area, buf);
+        self.draw_drop_indicator(&area, buf);
     }
 }
 
+struct EntryLabels {
+    left: String,
+    right: String,
+    // only file names keep their extension when trimmed
+    is_file_name: bool,
+}
+
 fn print_entry(
     buf: &mut Buffer,
-    entry: &DiziSongEntry,
-    index: usize,
+    labels: &EntryLabels,
     style: Style,
     (x, y): (u16, u16),
     drawing_width: usize,
 ) {
-    let left_label_original = format!("{:03} \u{02503} {}", index + 1, entry.file_name());
-    let right_label_original = "";
-
-    let (left_label, right_label) =
-        factor_labels_for_entry(&left_label_original, right_label_original, drawing_width);
+    let (left_label, right_label) = factor_labels_for_entry(
+        &labels.left,
+        &labels.right,
+        drawing_width,
+        labels.is_file_name,
+    );
 
     let right_width = right_label.width();
     buf.set_stringn(x, y, left_label, drawing_width, style);
@@ -171,6 +348,7 @@ fn factor_labels_for_entry<'a>(
     left_label_original: &'a str,
     right_label_original: &'a str,
     drawing_width: usize,
+    is_file_name: bool,
 ) -> (String, &'a str) {
     let left_label_original_width = left_label_original.width();
     let right_label_original_width = right_label_original.width();
@@ -185,7 +363,7 @@ fn factor_labels_for_entry<'a>(
     } else if left_width_remainder < MIN_LEFT_LABEL_WIDTH {
         (
             if left_label_original.width() as i32 <= left_width_remainder {
-                trim_file_label(left_label_original, drawing_width)
+                trim_label(left_label_original, drawing_width, is_file_name)
             } else {
                 left_label_original.to_string()
             },
@@ -193,12 +371,29 @@ fn factor_labels_for_entry<'a>(
         )
     } else {
         (
-            trim_file_label(left_label_original, left_width_remainder as usize),
+            trim_label(
+                left_label_original,
+                left_width_remainder as usize,
+                is_file_name,
+            ),
             right_label_original,
         )
     }
 }
 
+/// Only file names keep their extension when trimmed
+fn trim_label(label: &str, drawing_width: usize, is_file_name: bool) -> String {
+    if is_file_name {
+        trim_file_label(label, drawing_width)
+    } else if drawing_width < 1 {
+        "".to_string()
+    } else {
+        let mut truncated = label.trunc(drawing_width - 1);
+        truncated.push_str(ELLIPSIS);
+        truncated
+    }
+}
+
 pub fn trim_file_label(name: &str, drawing_width: usize) -> String {
     // pre-condition: string name is longer than width
     let (stem, extension) = match name.rfind('.') {
@@ -241,7 +436,7 @@ mod test_factor_labels {
         let right = "right";
         assert_eq!(
             ("".to_string(), ""),
-            factor_labels_for_entry(left, right, 0)
+            factor_labels_for_entry(left, right, 0, true)
         );
     }
 
@@ -251,7 +446,7 @@ mod test_factor_labels {
         let right = "right";
         assert_eq!(
             (left.to_string(), right),
-            factor_labels_for_entry(left, right, 20)
+            factor_labels_for_entry(left, right, 20, true)
         );
     }
 
@@ -261,7 +456,7 @@ mod test_factor_labels {
         let right = "right";
         assert_eq!(
             (left.to_string(), right),
-            factor_labels_for_entry(left, right, 12)
+            factor_labels_for_entry(left, right, 12, true)
         );
     }
 
@@ -272,7 +467,7 @@ mod test_factor_labels {
         assert!(left.chars().count() as i32 == MIN_LEFT_LABEL_WIDTH);
         assert_eq!(
             ("foobarbazfo.ext".to_string(), ""),
-            factor_labels_for_entry(left, right, MIN_LEFT_LABEL_WIDTH as usize)
+            factor_labels_for_entry(left, right, MIN_LEFT_LABEL_WIDTH as usize, true)
         );
     }
 
@@ -286,7 +481,8 @@ mod test_factor_labels {
             factor_labels_for_entry(
                 left,
                 right,
-                MIN_LEFT_LABEL_WIDTH as usize + right.chars().count()
+                MIN_LEFT_LABEL_WIDTH as usize + right.chars().count(),
+                true
             )
         );
     }
@@ -300,7 +496,16 @@ mod test_factor_labels {
         assert!(left.chars().count() as i32 > MIN_LEFT_LABEL_WIDTH);
         assert_eq!(
             ("foooooobaaaaaaarbaaaa…".to_string(), right),
-            factor_labels_for_entry(left, right, left.chars().count())
+            factor_labels_for_entry(left, right, left.chars().count(), true)
+        );
+    }
+
+    #[test]
+    fn formatted_label_is_trimmed_without_keeping_extension() {
+        let left = "3. Artist - Title (3:45)";
+        assert_eq!(
+            ("3. Artist - Title (…".to_string(), ""),
+            factor_labels_for_entry(left, "", 20, false)
         );
     }
 }
@@ -19,6 +19,14 @@ impl<'a> TuiPrompt<'a> {
         Self { prompt }
     }
 
+    /// Asks a yes/no question, anything but `y` is a no
+    pub fn confirm(&mut self, backend: &mut AppBackend, context: &mut AppContext) -> bool {
+        matches!(
+            self.get_key(backend, context),
+            Key::Char('y') | Key::Char('Y')
+        )
+    }
+
     pub fn get_key(&mut self, backend: &mut AppBackend, context: &mut AppContext) -> Key {
         let terminal = backend.terminal_mut();
 
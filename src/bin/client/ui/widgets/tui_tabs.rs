@@ -0,0 +1,59 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Tabs, Widget};
+use unicode_width::UnicodeWidthStr;
+
+use crate::config::option::WidgetType;
+use crate::MESSAGES_T;
+
+const TAB_DIVIDER: &str = "│";
+
+pub const TAB_VIEWS: [(WidgetType, &str); 4] = [
+    (WidgetType::FileBrowser, "tabs.browser"),
+    (WidgetType::Playlist, "tabs.playlist"),
+    (WidgetType::Library, "tabs.library"),
+    (WidgetType::MessageLog, "tabs.log"),
+];
+
+pub struct TuiTabs {
+    selected: WidgetType,
+}
+
+impl TuiTabs {
+    pub fn new(selected: WidgetType) -> Self {
+        Self { selected }
+    }
+
+    /// Number of columns needed to draw every tab
+    pub fn width() -> usize {
+        let labels_width: usize = TAB_VIEWS
+            .iter()
+            .map(|(_, key)| MESSAGES_T.get(key).width() + 2)
+            .sum();
+        labels_width + (TAB_VIEWS.len() - 1) * TAB_DIVIDER.width()
+    }
+}
+
+impl Widget for TuiTabs {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let titles: Vec<&str> = TAB_VIEWS
+            .iter()
+            .map(|(_, key)| MESSAGES_T.get(key))
+            .collect();
+        let selected = TAB_VIEWS
+            .iter()
+            .position(|(w, _)| *w == self.selected)
+            .unwrap_or(0);
+
+        let highlight_style = Style::default()
+            .fg(Color::LightBlue)
+            .add_modifier(Modifier::REVERSED);
+
+        Tabs::new(titles)
+            .select(selected)
+            .highlight_style(highlight_style)
+            .divider(TAB_DIVIDER)
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,57 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+use dizi::player::PlayerState;
+use dizi::playlist::PlaylistType;
+
+use crate::util::style;
+use crate::MESSAGES_T;
+
+/// Songs of the playlist that are played after the current one, in play order
+pub struct TuiUpNext<'a> {
+    player: &'a PlayerState,
+}
+
+impl<'a> TuiUpNext<'a> {
+    pub fn new(player: &'a PlayerState) -> Self {
+        Self { player }
+    }
+}
+
+impl<'a> Widget for TuiUpNext<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+
+        let x = area.left();
+        let y = area.top();
+        let drawing_width = area.width as usize - 1;
+
+        if self.player.info.playlist_status == PlaylistType::DirectoryListing {
+            let style = Style::default().fg(Color::DarkGray);
+            buf.set_stringn(
+                x + 1,
+                y,
+                MESSAGES_T.get("player.playing_from_directory"),
+                drawing_width,
+                style,
+            );
+            return;
+        }
+
+        let playlist = &self.player.playlist;
+        let style = style::playlist_style();
+        playlist
+            .up_next(area.height as usize)
+            .into_iter()
+            .enumerate()
+            .for_each(|(offset, index)| {
+                let entry = &playlist.list_ref()[index];
+                let label = format!("{:03} \u{02503} {}", index + 1, entry.file_name());
+                buf.set_stringn(x + 1, y + offset as u16, label, drawing_width, style);
+            });
+    }
+}
@@ -0,0 +1,51 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style};
+use ratatui::widgets::Widget;
+
+// partially filled cells, from 1/8th to a full block
+const BAR_SYMBOLS: [&str; 8] = [
+    "\u{2581}", "\u{2582}", "\u{2583}", "\u{2584}", "\u{2585}", "\u{2586}", "\u{2587}", "\u{2588}",
+];
+
+pub struct TuiVisualizer<'a> {
+    bands: &'a [u8],
+}
+
+impl<'a> TuiVisualizer<'a> {
+    pub fn new(bands: &'a [u8]) -> Self {
+        Self { bands }
+    }
+}
+
+impl<'a> Widget for TuiVisualizer<'a> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 1 || area.height < 1 || self.bands.is_empty() {
+            return;
+        }
+
+        let style = Style::default().fg(Color::Cyan);
+        let width = area.width as usize;
+        let height = area.height as usize;
+
+        for column in 0..width {
+            // stretch or squash the bands to fit the width of the widget
+            let band = column * self.bands.len() / width;
+            let level = self.bands[band] as usize;
+
+            // height of the bar in eighths of a cell
+            let bar_height = level * height * 8 / u8::MAX as usize;
+
+            for row in 0..height {
+                let filled = bar_height.saturating_sub(row * 8);
+                if filled == 0 {
+                    break;
+                }
+                let symbol = BAR_SYMBOLS[filled.min(8) - 1];
+                let x = area.x + column as u16;
+                let y = area.y + area.height - 1 - row as u16;
+                buf.set_string(x, y, symbol, style);
+            }
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+
+const GAUGE_CELLS: usize = 10;
+
+/// Compact volume gauge, e.g. `vol ██████░░░░  60%`
+pub struct TuiVolume {
+    volume: usize,
+}
+
+impl TuiVolume {
+    pub fn new(volume: usize) -> Self {
+        Self { volume }
+    }
+
+    /// Number of cells needed to draw the gauge
+    pub const fn width() -> usize {
+        // "vol " + gauge + " 100%"
+        4 + GAUGE_CELLS + 5
+    }
+}
+
+impl Widget for TuiVolume {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 1 || area.height < 1 {
+            return;
+        }
+        let x = area.left();
+        let y = area.top();
+        let width = area.width as usize;
+
+        if self.volume == 0 {
+            let style = Style::default().fg(Color::Red).add_modifier(Modifier::BOLD);
+            buf.set_stringn(x, y, "vol MUTED", width, style);
+            return;
+        }
+
+        let volume = self.volume.min(100);
+        let filled = (volume * GAUGE_CELLS).div_ceil(100);
+        let text = format!(
+            "vol {}{} {:>3}%",
+            "\u{2588}".repeat(filled),
+            "\u{2591}".repeat(GAUGE_CELLS - filled),
+            self.volume
+        );
+        buf.set_stringn(x, y, text, width, Style::default().fg(Color::Green));
+    }
+}
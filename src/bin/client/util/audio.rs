@@ -0,0 +1,17 @@
+use std::path::Path;
+
+pub const AUDIO_EXTENSIONS: [&str; 12] = [
+    "aac", "aiff", "alac", "flac", "m4a", "mka", "mp3", "mp4", "ogg", "opus", "wav", "webm",
+];
+
+pub fn is_audio_file(path: &Path) -> bool {
+    if dizi::song::is_optional_format(path) {
+        return true;
+    }
+    match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => AUDIO_EXTENSIONS
+            .iter()
+            .any(|audio_ext| audio_ext.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
@@ -0,0 +1,86 @@
+use std::env;
+use std::io::{self, Write};
+use std::process::{Command, Stdio};
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+const BASE64_CHARS: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Copies `text` to the system clipboard.
+///
+/// The text is sent to the terminal with an OSC 52 sequence, which also works over ssh.
+/// Since not every terminal supports it, it is handed to `wl-copy` or `xclip` as well
+/// when a display server is running.
+pub fn copy(text: &str) -> DiziResult {
+    let mut stdout = io::stdout();
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))?;
+    stdout.flush()?;
+
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        pipe_to("wl-copy", &[], text)
+    } else if env::var_os("DISPLAY").is_some() {
+        pipe_to("xclip", &["-selection", "clipboard"], text)
+    } else {
+        Ok(())
+    }
+}
+
+fn pipe_to(program: &str, args: &[&str], text: &str) -> DiziResult {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| {
+            DiziError::new(
+                DiziErrorKind::ClipboardError,
+                format!("Failed to run {}: {}", program, e),
+            )
+        })?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if !status.success() {
+        return Err(DiziError::new(
+            DiziErrorKind::ClipboardError,
+            format!("{} exited with {}", program, status),
+        ));
+    }
+    Ok(())
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut s = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (b[0] as u32) << 16 | (b[1] as u32) << 8 | b[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                s.push(BASE64_CHARS[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                s.push('=');
+            }
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"/music/a.flac"), "L211c2ljL2EuZmxhYw==");
+    }
+}
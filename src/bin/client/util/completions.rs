@@ -0,0 +1,269 @@
+use clap::{Arg, Command};
+
+use crate::run::HEADLESS_COMMANDS;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, clap::ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+#[derive(Clone, Debug)]
+struct Flag {
+    short: Option<char>,
+    long: Option<String>,
+    // None if the flag takes no value, empty if any value is accepted
+    values: Option<Vec<String>>,
+}
+
+impl Flag {
+    fn names(&self) -> Vec<String> {
+        let mut names = Vec::new();
+        names.extend(self.short.map(|c| format!("-{}", c)));
+        names.extend(self.long.as_ref().map(|s| format!("--{}", s)));
+        names
+    }
+}
+
+/// Flags and values of one (sub)command, as needed by the completion scripts
+#[derive(Clone, Debug)]
+struct CompletionSpec {
+    name: String,
+    flags: Vec<Flag>,
+    // values of the positional arguments
+    values: Vec<String>,
+}
+
+impl CompletionSpec {
+    fn from_command(command: &Command) -> Self {
+        let mut flags = Vec::new();
+        let mut values = Vec::new();
+        for arg in command.get_arguments() {
+            if arg.is_positional() {
+                values.extend(arg_values(arg));
+            } else {
+                flags.push(Flag {
+                    short: arg.get_short(),
+                    long: arg.get_long().map(|s| s.to_string()),
+                    values: arg.get_action().takes_values().then(|| arg_values(arg)),
+                });
+            }
+        }
+        Self {
+            name: command.get_name().to_string(),
+            flags,
+            values,
+        }
+    }
+
+    fn words(&self) -> Vec<String> {
+        let mut words = self.values.clone();
+        words.extend(self.flags.iter().flat_map(|flag| flag.names()));
+        words
+    }
+}
+
+/// Commands are completed to the ones that can be run without the tui
+fn arg_values(arg: &Arg) -> Vec<String> {
+    if arg.get_id() == "command" {
+        return HEADLESS_COMMANDS.iter().map(|s| s.to_string()).collect();
+    }
+    arg.get_possible_values()
+        .iter()
+        .map(|value| value.get_name().to_string())
+        .collect()
+}
+
+/// Generates the completion script of `command` for `shell`
+pub fn generate(shell: Shell, command: &mut Command) -> String {
+    command.build();
+    let bin = command.get_name().to_string();
+    let root = CompletionSpec::from_command(command);
+    let subcommands: Vec<CompletionSpec> = command
+        .get_subcommands()
+        .map(CompletionSpec::from_command)
+        .collect();
+    match shell {
+        Shell::Bash => generate_bash(&bin, &root, &subcommands),
+        Shell::Zsh => generate_zsh(&bin, &root, &subcommands),
+        Shell::Fish => generate_fish(&bin, &root, &subcommands),
+    }
+}
+
+fn generate_bash(bin: &str, root: &CompletionSpec, subcommands: &[CompletionSpec]) -> String {
+    let names: Vec<&str> = subcommands.iter().map(|s| s.name.as_str()).collect();
+    let mut root_words = root.words();
+    root_words.extend(names.iter().map(|s| s.to_string()));
+
+    let mut s = String::new();
+    s.push_str(&format!("_{}() {{\n", bin));
+    s.push_str("    local cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    s.push_str("    local prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    s.push_str("    local subcommand=\"\"\n");
+    s.push_str("    for word in \"${COMP_WORDS[@]:1:COMP_CWORD-1}\"; do\n");
+    s.push_str("        case \"$word\" in\n");
+    s.push_str(&format!(
+        "            {}) subcommand=\"$word\"; break ;;\n",
+        names.join("|")
+    ));
+    s.push_str("        esac\n");
+    s.push_str("    done\n\n");
+
+    // values of the flag being completed
+    s.push_str("    case \"$prev\" in\n");
+    for spec in std::iter::once(root).chain(subcommands) {
+        for flag in spec.flags.iter() {
+            if let Some(values) = flag.values.as_ref() {
+                s.push_str(&format!(
+                    "        {})\n            COMPREPLY=($(compgen -W \"{}\" -- \"$cur\"))\n            return ;;\n",
+                    flag.names().join("|"),
+                    values.join(" ")
+                ));
+            }
+        }
+    }
+    s.push_str("    esac\n\n");
+
+    s.push_str("    case \"$subcommand\" in\n");
+    for spec in subcommands {
+        s.push_str(&format!(
+            "        {}) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;\n",
+            spec.name,
+            spec.words().join(" ")
+        ));
+    }
+    s.push_str(&format!(
+        "        *) COMPREPLY=($(compgen -W \"{}\" -- \"$cur\")) ;;\n",
+        root_words.join(" ")
+    ));
+    s.push_str("    esac\n");
+    s.push_str("}\n\n");
+    s.push_str(&format!("complete -F _{} {}\n", bin, bin));
+    s
+}
+
+fn zsh_flag(flag: &Flag) -> String {
+    let names = flag.names();
+    let value = match flag.values.as_ref() {
+        None => String::new(),
+        Some(values) if values.is_empty() => ": :".to_string(),
+        Some(values) => format!(": :({})", values.join(" ")),
+    };
+    if names.len() > 1 {
+        format!("'({})'{{{}}}'{}'", names.join(" "), names.join(","), value)
+    } else {
+        format!("'{}{}'", names.join(""), value)
+    }
+}
+
+fn generate_zsh(bin: &str, root: &CompletionSpec, subcommands: &[CompletionSpec]) -> String {
+    let names: Vec<&str> = subcommands.iter().map(|s| s.name.as_str()).collect();
+
+    let mut s = String::new();
+    s.push_str(&format!("#compdef {}\n\n", bin));
+    s.push_str(&format!("_{}() {{\n", bin));
+    s.push_str("    local state line\n");
+    s.push_str("    _arguments -C \\\n");
+    for flag in root.flags.iter() {
+        s.push_str(&format!("        {} \\\n", zsh_flag(flag)));
+    }
+    s.push_str(&format!("        '1: :({})' \\\n", names.join(" ")));
+    s.push_str("        '*:: :->args'\n\n");
+    s.push_str("    case $state in\n");
+    s.push_str("        args)\n");
+    s.push_str("            case $line[1] in\n");
+    for spec in subcommands {
+        let mut args: Vec<String> = spec.flags.iter().map(zsh_flag).collect();
+        if !spec.values.is_empty() {
+            args.push(format!("'*: :({})'", spec.values.join(" ")));
+        }
+        s.push_str(&format!(
+            "                {}) _arguments {} ;;\n",
+            spec.name,
+            args.join(" ")
+        ));
+    }
+    s.push_str("            esac\n");
+    s.push_str("            ;;\n");
+    s.push_str("    esac\n");
+    s.push_str("}\n\n");
+    s.push_str(&format!("_{} \"$@\"\n", bin));
+    s
+}
+
+fn fish_flag(bin: &str, condition: &str, flag: &Flag) -> String {
+    let mut s = format!("complete -c {} -n \"{}\"", bin, condition);
+    if let Some(c) = flag.short {
+        s.push_str(&format!(" -s {}", c));
+    }
+    if let Some(long) = flag.long.as_ref() {
+        s.push_str(&format!(" -l {}", long));
+    }
+    if let Some(values) = flag.values.as_ref() {
+        s.push_str(" -r");
+        if !values.is_empty() {
+            s.push_str(&format!(" -a \"{}\"", values.join(" ")));
+        }
+    }
+    s.push('\n');
+    s
+}
+
+fn generate_fish(bin: &str, root: &CompletionSpec, subcommands: &[CompletionSpec]) -> String {
+    let names: Vec<&str> = subcommands.iter().map(|s| s.name.as_str()).collect();
+
+    let mut s = String::new();
+    s.push_str(&format!("complete -c {} -f\n", bin));
+    let condition = "__fish_use_subcommand";
+    s.push_str(&format!(
+        "complete -c {} -n \"{}\" -a \"{}\"\n",
+        bin,
+        condition,
+        names.join(" ")
+    ));
+    for flag in root.flags.iter() {
+        s.push_str(&fish_flag(bin, condition, flag));
+    }
+    for spec in subcommands {
+        let condition = format!("__fish_seen_subcommand_from {}", spec.name);
+        if !spec.values.is_empty() {
+            s.push_str(&format!(
+                "complete -c {} -n \"{}\" -a \"{}\"\n",
+                bin,
+                condition,
+                spec.values.join(" ")
+            ));
+        }
+        for flag in spec.flags.iter() {
+            s.push_str(&fish_flag(bin, &condition, flag));
+        }
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_command() -> Command {
+        Command::new("dizi")
+            .arg(Arg::new("command").short('c').long("command"))
+            .subcommand(
+                Command::new("status").arg(
+                    Arg::new("format")
+                        .long("format")
+                        .value_parser(["plain", "json"]),
+                ),
+            )
+    }
+
+    #[test]
+    fn bash_completes_flag_values() {
+        let script = generate(Shell::Bash, &mut test_command());
+        assert!(script.contains("-c|--command)"));
+        assert!(script.contains(&HEADLESS_COMMANDS.join(" ")));
+        assert!(script.contains("--format)\n            COMPREPLY=($(compgen -W \"plain json\""));
+        assert!(script.ends_with("complete -F _dizi dizi\n"));
+    }
+}
@@ -0,0 +1,125 @@
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::thread;
+use std::time;
+
+use dizi::request::client::ClientRequest;
+use dizi::utils;
+
+use crate::event::AppEvent;
+
+const RECONNECT_INTERVAL_MIN: time::Duration = time::Duration::from_millis(500);
+const RECONNECT_INTERVAL_MAX: time::Duration = time::Duration::from_secs(8);
+
+/// Where the server can be reached
+#[derive(Clone, Debug)]
+pub enum ServerAddress {
+    Socket(PathBuf),
+    // host:port of a server on another machine
+    Tcp(String),
+}
+
+impl ServerAddress {
+    pub fn is_local(&self) -> bool {
+        matches!(self, Self::Socket(_))
+    }
+}
+
+impl std::fmt::Display for ServerAddress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Socket(path) => write!(f, "{}", path.display()),
+            Self::Tcp(address) => write!(f, "{}", address),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ServerStream {
+    Unix(UnixStream),
+    Tcp(TcpStream),
+}
+
+impl ServerStream {
+    pub fn connect(address: &ServerAddress) -> io::Result<Self> {
+        match address {
+            ServerAddress::Socket(path) => UnixStream::connect(path).map(Self::Unix),
+            ServerAddress::Tcp(address) => TcpStream::connect(address).map(Self::Tcp),
+        }
+    }
+
+    pub fn try_clone(&self) -> io::Result<Self> {
+        match self {
+            Self::Unix(stream) => stream.try_clone().map(Self::Unix),
+            Self::Tcp(stream) => stream.try_clone().map(Self::Tcp),
+        }
+    }
+
+    /// Sends the password, must be the first request on a new connection
+    pub fn authenticate(&mut self, password: &str) -> io::Result<()> {
+        let request = ClientRequest::ClientAuth {
+            password: password.to_string(),
+        };
+        let json = serde_json::to_string(&request)?;
+        self.write_all(json.as_bytes())?;
+        utils::flush(self)
+    }
+}
+
+impl Read for ServerStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Self::Unix(stream) => stream.read(buf),
+            Self::Tcp(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for ServerStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Unix(stream) => stream.write(buf),
+            Self::Tcp(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Unix(stream) => stream.flush(),
+            Self::Tcp(stream) => stream.flush(),
+        }
+    }
+}
+
+/// Forwards every line sent by the server, followed by a
+/// `ServerDisconnect` once the connection is closed
+pub fn listen(stream: ServerStream, event_tx: mpsc::Sender<AppEvent>) {
+    let _ = thread::spawn(move || {
+        let cursor = BufReader::new(stream);
+        for line in cursor.lines().map_while(Result::ok) {
+            if event_tx.send(AppEvent::Server(line)).is_err() {
+                return;
+            }
+        }
+        let _ = event_tx.send(AppEvent::ServerDisconnect);
+    });
+}
+
+/// Tries to connect to the server until it succeeds, waiting twice as long
+/// after every failed attempt
+pub fn reconnect(address: ServerAddress, event_tx: mpsc::Sender<AppEvent>) {
+    let _ = thread::spawn(move || {
+        let mut wait_interval = RECONNECT_INTERVAL_MIN;
+        loop {
+            thread::sleep(wait_interval);
+            if let Ok(stream) = ServerStream::connect(&address) {
+                let _ = event_tx.send(AppEvent::ServerReconnect(stream));
+                return;
+            }
+            wait_interval = (wait_interval * 2).min(RECONNECT_INTERVAL_MAX);
+        }
+    });
+}
@@ -1,3 +1,7 @@
+use std::time;
+
+const MAX_RATING: u8 = 5;
+
 pub fn file_size_to_string(file_size: u64) -> String {
     const FILE_UNITS: [&str; 6] = ["B", "K", "M", "G", "T", "E"];
     const CONV_RATE: f64 = 1024.0;
@@ -17,3 +21,111 @@ pub fn file_size_to_string(file_size: u64) -> String {
         format!("{:>4.2} {}", file_size, FILE_UNITS[index])
     }
 }
+
+pub fn duration_to_string(duration: time::Duration) -> String {
+    let total_secs = duration.as_secs();
+    let hours = total_secs / 3600;
+    let minutes = (total_secs / 60) % 60;
+    let seconds = total_secs % 60;
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Coarse duration for summaries, e.g. `2h41m`
+pub fn duration_to_short_string(duration: time::Duration) -> String {
+    let total_secs = duration.as_secs();
+    let hours = total_secs / 3600;
+    let minutes = (total_secs / 60) % 60;
+    if hours > 0 {
+        format!("{}h{:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m", minutes)
+    } else {
+        format!("{}s", total_secs)
+    }
+}
+
+/// Time left until `total`, e.g. `-03:12`
+pub fn remaining_to_string(elapsed: time::Duration, total: time::Duration) -> String {
+    format!("-{}", duration_to_string(total.saturating_sub(elapsed)))
+}
+
+/// Rating as stars, e.g. `★★★☆☆` for 3
+pub fn rating_to_string(rating: u8) -> String {
+    let rating = rating.min(MAX_RATING) as usize;
+    format!(
+        "{}{}",
+        "\u{2605}".repeat(rating),
+        "\u{2606}".repeat(MAX_RATING as usize - rating)
+    )
+}
+
+/// Parses durations such as `90`, `90s`, `1m30s`, `1h`, `1:30` or `1:02:03`
+pub fn parse_duration(s: &str) -> Option<time::Duration> {
+    let s = s.trim();
+    if s.is_empty() {
+        return None;
+    }
+    if s.contains(':') {
+        if s.split(':').count() > 3 {
+            return None;
+        }
+        let mut secs: u64 = 0;
+        for part in s.split(':') {
+            secs = secs.checked_mul(60)?.checked_add(part.parse().ok()?)?;
+        }
+        return Some(time::Duration::from_secs(secs));
+    }
+
+    let mut secs: u64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+        let unit = match c {
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        let n: u64 = number.parse().ok()?;
+        number.clear();
+        secs = secs.checked_add(n.checked_mul(unit)?)?;
+    }
+    // a plain number, or the seconds of `1m30`
+    if !number.is_empty() {
+        secs = secs.checked_add(number.parse().ok()?)?;
+    }
+    Some(time::Duration::from_secs(secs))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_durations() {
+        let secs = |s| parse_duration(s).map(|d| d.as_secs());
+        assert_eq!(secs("10"), Some(10));
+        assert_eq!(secs("10s"), Some(10));
+        assert_eq!(secs("1m30s"), Some(90));
+        assert_eq!(secs("1m30"), Some(90));
+        assert_eq!(secs("1h"), Some(3600));
+        assert_eq!(secs("1:30"), Some(90));
+        assert_eq!(secs("1:02:03"), Some(3723));
+    }
+
+    #[test]
+    fn parse_invalid_durations() {
+        assert_eq!(parse_duration(""), None);
+        assert_eq!(parse_duration("10x"), None);
+        assert_eq!(parse_duration("m"), None);
+        assert_eq!(parse_duration("1:"), None);
+        assert_eq!(parse_duration("1:2:3:4"), None);
+    }
+}
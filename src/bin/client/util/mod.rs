@@ -1,10 +1,15 @@
 #[cfg(feature = "devicons")]
 pub mod devicons;
 
+pub mod audio;
+pub mod clipboard;
+pub mod completions;
+pub mod connection;
 pub mod format;
 pub mod keyparse;
 pub mod request;
 pub mod search;
 pub mod string;
 pub mod style;
+pub mod trash;
 pub mod unix;
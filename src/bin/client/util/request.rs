@@ -1,11 +1,18 @@
 use std::io::Write;
 
-use dizi::error::DiziResult;
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
 use dizi::request::client::ClientRequest;
 
 use crate::context::AppContext;
+use crate::MESSAGES_T;
 
 pub fn send_client_request(context: &mut AppContext, request: &ClientRequest) -> DiziResult {
+    if context.is_reconnecting() {
+        return Err(DiziError::new(
+            DiziErrorKind::Server,
+            MESSAGES_T.get("message.not_connected").to_string(),
+        ));
+    }
     let json = serde_json::to_string(&request)?;
 
     context.stream.write_all(json.as_bytes())?;
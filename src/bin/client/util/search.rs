@@ -1,7 +1,42 @@
 use globset::GlobMatcher;
+use regex::Regex;
+
+use crate::config::option::CaseSensitivity;
 
 #[derive(Clone, Debug)]
 pub enum SearchPattern {
     Glob(GlobMatcher),
-    String(String),
+    // the pattern is stored in lowercase when case is ignored
+    String { pattern: String, ignore_case: bool },
+    Regex(Regex),
+}
+
+impl SearchPattern {
+    pub fn string(pattern: &str, case_sensitivity: CaseSensitivity) -> Self {
+        let ignore_case = case_sensitivity.ignores_case(pattern);
+        let pattern = if ignore_case {
+            pattern.to_lowercase()
+        } else {
+            pattern.to_string()
+        };
+        Self::String {
+            pattern,
+            ignore_case,
+        }
+    }
+
+    pub fn is_match(&self, file_name: &str) -> bool {
+        match self {
+            Self::Glob(glob) => glob.is_match(file_name),
+            Self::String {
+                pattern,
+                ignore_case: true,
+            } => file_name.to_lowercase().contains(pattern.as_str()),
+            Self::String {
+                pattern,
+                ignore_case: false,
+            } => file_name.contains(pattern.as_str()),
+            Self::Regex(regex) => regex.is_match(file_name),
+        }
+    }
 }
@@ -31,6 +31,18 @@ impl UnicodeTruncate for str {
     }
 }
 
+/// Drops the first `width` columns of `s`, for scrolling long names
+pub fn skip_width(s: &str, width: usize) -> &str {
+    let mut skipped = 0;
+    for (i, grapheme) in s.grapheme_indices(true) {
+        if skipped >= width {
+            return &s[i..];
+        }
+        skipped += grapheme.width();
+    }
+    ""
+}
+
 #[cfg(test)]
 mod tests_trunc {
     use super::UnicodeTruncate;
@@ -65,3 +77,21 @@ mod tests_trunc {
         assert_eq!(String::from("a🌕").trunc(2), String::from("a"));
     }
 }
+
+#[cfg(test)]
+mod tests_skip_width {
+    use super::skip_width;
+
+    #[test]
+    fn skip_columns_of_ascii() {
+        assert_eq!(skip_width("foobar.flac", 3), "bar.flac");
+        assert_eq!(skip_width("foo", 0), "foo");
+        assert_eq!(skip_width("foo", 5), "");
+    }
+
+    #[test]
+    fn skip_fullwidth_character_as_a_whole() {
+        assert_eq!(skip_width("🌕🌕a", 2), "🌕a");
+        assert_eq!(skip_width("🌕🌕a", 3), "a");
+    }
+}
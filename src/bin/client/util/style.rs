@@ -1,4 +1,4 @@
-use ratatui::style::Style;
+use ratatui::style::{Color, Modifier, Style};
 
 use crate::fs::{FileType, JoshutoDirEntry, LinkType};
 use crate::util::unix;
@@ -19,6 +19,17 @@ pub fn playlist_style() -> Style {
         .add_modifier(THEME_T.playlist.modifier)
 }
 
+pub fn selection_style() -> Style {
+    Style::default()
+        .fg(THEME_T.selection.fg)
+        .bg(THEME_T.selection.bg)
+        .add_modifier(THEME_T.selection.modifier)
+}
+
+pub fn search_match_style(style: Style) -> Style {
+    style.fg(Color::Yellow).add_modifier(Modifier::UNDERLINED)
+}
+
 pub fn entry_style(entry: &JoshutoDirEntry) -> Style {
     let metadata = &entry.metadata;
     let filetype = &metadata.file_type();
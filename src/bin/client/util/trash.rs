@@ -0,0 +1,171 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Moves `path` to the user's trash, following the freedesktop.org trash specification.
+///
+/// Only works for files on the same filesystem as the trash directory.
+/// Symlinks are trashed themselves, not the files they point to
+pub fn trash(path: &Path) -> io::Result<()> {
+    trash_into(&trash_dir()?, path)
+}
+
+fn trash_into(trash_dir: &Path, path: &Path) -> io::Result<()> {
+    let files_dir = trash_dir.join("files");
+    let info_dir = trash_dir.join("info");
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Cannot trash this path"))?;
+    // only the parent is resolved, so a symlink is moved rather than what it links to
+    let parent = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => fs::canonicalize(parent)?,
+        _ => std::env::current_dir()?,
+    };
+    let path = parent.join(file_name);
+    fs::symlink_metadata(&path)?;
+
+    fs::create_dir_all(&files_dir)?;
+    fs::create_dir_all(&info_dir)?;
+
+    let info = format!(
+        "[Trash Info]\nPath={}\nDeletionDate={}\n",
+        percent_encode(&path),
+        chrono::Local::now().format("%Y-%m-%dT%H:%M:%S")
+    );
+    // the name is claimed by creating its info file, which fails if someone
+    // else got to it first, so two trashings never share a name
+    let file_name = file_name.to_string_lossy().to_string();
+    let mut trash_name = file_name.clone();
+    let mut i = 1;
+    let info_path = loop {
+        let info_path = info_dir.join(info_name(&trash_name));
+        if fs::symlink_metadata(files_dir.join(&trash_name)).is_err() {
+            let created = fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&info_path);
+            match created {
+                Ok(mut file) => {
+                    if let Err(e) = file.write_all(info.as_bytes()) {
+                        let _ = fs::remove_file(&info_path);
+                        return Err(e);
+                    }
+                    break info_path;
+                }
+                Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {}
+                Err(e) => return Err(e),
+            }
+        }
+        i += 1;
+        trash_name = format!("{}.{}", file_name, i);
+    };
+
+    if let Err(e) = fs::rename(&path, files_dir.join(&trash_name)) {
+        let _ = fs::remove_file(&info_path);
+        return Err(e);
+    }
+    Ok(())
+}
+
+/// `path` escaped as the trash specification asks for `Path=`, keeping its slashes
+fn percent_encode(path: &Path) -> String {
+    let mut encoded = String::new();
+    for b in path.as_os_str().as_encoded_bytes() {
+        match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(*b as char)
+            }
+            b => encoded.push_str(&format!("%{:02X}", b)),
+        }
+    }
+    encoded
+}
+
+fn info_name(trash_name: &str) -> String {
+    format!("{}.trashinfo", trash_name)
+}
+
+fn trash_dir() -> io::Result<PathBuf> {
+    let data_dir = dirs_next::data_dir()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::NotFound, "No data directory found"))?;
+    Ok(data_dir.join("Trash"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_path() {
+        assert_eq!(
+            percent_encode(Path::new("/music/Artist - Song #1.flac")),
+            "/music/Artist%20-%20Song%20%231.flac"
+        );
+        assert_eq!(percent_encode(Path::new("/é")), "/%C3%A9");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn trashes_symlink() {
+        let dir = std::env::temp_dir().join(format!("dizi-trash-{}", std::process::id()));
+        let music = dir.join("music");
+        let links = dir.join("links");
+        let trash_dir = dir.join("Trash");
+        fs::create_dir_all(&music).unwrap();
+        fs::create_dir_all(&links).unwrap();
+        let song = music.join("song.flac");
+        fs::write(&song, b"song").unwrap();
+        let link = links.join("linked song.flac");
+        std::os::unix::fs::symlink(&song, &link).unwrap();
+
+        trash_into(&trash_dir, &link).unwrap();
+
+        // the link went to the trash, the file it pointed to stayed
+        assert!(song.exists());
+        assert!(fs::symlink_metadata(&link).is_err());
+        let trashed = trash_dir.join("files").join("linked song.flac");
+        assert!(fs::symlink_metadata(&trashed)
+            .unwrap()
+            .file_type()
+            .is_symlink());
+        let info =
+            fs::read_to_string(trash_dir.join("info").join("linked song.flac.trashinfo")).unwrap();
+        let links = fs::canonicalize(&links).unwrap();
+        assert!(info.contains(&format!(
+            "Path={}/linked%20song.flac\n",
+            percent_encode(&links)
+        )));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn trashes_same_name_twice() {
+        let dir = std::env::temp_dir().join(format!("dizi-trash-twice-{}", std::process::id()));
+        let trash_dir = dir.join("Trash");
+        let info_dir = trash_dir.join("info");
+        fs::create_dir_all(&info_dir).unwrap();
+        // a name claimed by someone that hasn't moved their file yet
+        fs::write(info_dir.join("song.flac.2.trashinfo"), b"").unwrap();
+
+        for album in ["a", "b"] {
+            let album = dir.join(album);
+            fs::create_dir_all(&album).unwrap();
+            fs::write(album.join("song.flac"), album.to_string_lossy().as_bytes()).unwrap();
+            trash_into(&trash_dir, &album.join("song.flac")).unwrap();
+        }
+
+        let files_dir = trash_dir.join("files");
+        let a = fs::read_to_string(files_dir.join("song.flac")).unwrap();
+        let b = fs::read_to_string(files_dir.join("song.flac.3")).unwrap();
+        assert!(a.ends_with("a") && b.ends_with("b"));
+        let info = fs::read_to_string(info_dir.join("song.flac.3.trashinfo")).unwrap();
+        assert!(info.contains("/b/song.flac\n"));
+        assert!(fs::read(info_dir.join("song.flac.2.trashinfo"))
+            .unwrap()
+            .is_empty());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+use std::f32::consts::PI;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use dizi::error::DiziResult;
+use dizi::player::{EqBand, EQ_FREQUENCIES, EQ_MAX_GAIN};
+
+pub const EQ_BANDS: usize = EQ_FREQUENCIES.len();
+
+// octave wide bands
+const Q: f32 = 1.414;
+
+/// Gains of the bands saved by `save_gains`, flat if there are none.
+/// They are kept within `EQ_MAX_GAIN` like set ones, bands that aren't
+/// numbers are left flat
+pub fn load_gains(path: &Path) -> io::Result<[f32; EQ_BANDS]> {
+    let contents = fs::read_to_string(path)?;
+    let gains: Vec<f32> = serde_json::from_str(&contents)?;
+    let mut loaded = [0.0; EQ_BANDS];
+    for (gain, saved) in loaded.iter_mut().zip(gains) {
+        if saved.is_finite() {
+            *gain = saved.clamp(-EQ_MAX_GAIN, EQ_MAX_GAIN);
+        }
+    }
+    Ok(loaded)
+}
+
+pub fn save_gains(path: &Path, gains: &[f32; EQ_BANDS]) -> DiziResult {
+    let contents = serde_json::to_string(gains)?;
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+pub fn bands(gains: &[f32; EQ_BANDS]) -> Vec<EqBand> {
+    EQ_FREQUENCIES
+        .iter()
+        .zip(gains)
+        .map(|(frequency, gain)| EqBand {
+            frequency: *frequency,
+            gain: *gain,
+        })
+        .collect()
+}
+
+/// A peaking filter
+#[derive(Clone, Copy, Debug, Default)]
+struct Biquad {
+    b0: f32,
+    b1: f32,
+    b2: f32,
+    a1: f32,
+    a2: f32,
+}
+
+impl Biquad {
+    /// Coefficients from the Audio EQ Cookbook, normalized by a0
+    fn peaking(frequency: f32, gain: f32, sample_rate: u32) -> Self {
+        let a = 10f32.powf(gain / 40.0);
+        let w0 = 2.0 * PI * frequency / sample_rate as f32;
+        let alpha = w0.sin() / (2.0 * Q);
+        let a0 = 1.0 + alpha / a;
+        Self {
+            b0: (1.0 + alpha * a) / a0,
+            b1: -2.0 * w0.cos() / a0,
+            b2: (1.0 - alpha * a) / a0,
+            a1: -2.0 * w0.cos() / a0,
+            a2: (1.0 - alpha / a) / a0,
+        }
+    }
+}
+
+/// Ten band equalizer run over the interleaved samples of each callback buffer
+#[derive(Clone, Debug)]
+pub struct Equalizer {
+    channels: usize,
+    sample_rate: u32,
+    filters: [Biquad; EQ_BANDS],
+    // bands that change the sound, those set to 0 dB are skipped
+    active: [bool; EQ_BANDS],
+    // the two previous states of each filter for each channel, transposed direct form II
+    state: Vec<[f32; 2]>,
+}
+
+impl Equalizer {
+    pub fn new(channels: u16, sample_rate: u32, gains: &[f32; EQ_BANDS]) -> Self {
+        let channels = channels.max(1) as usize;
+        let mut equalizer = Self {
+            channels,
+            sample_rate,
+            filters: [Biquad::default(); EQ_BANDS],
+            active: [false; EQ_BANDS],
+            state: vec![[0.0; 2]; EQ_BANDS * channels],
+        };
+        equalizer.set_gains(gains);
+        equalizer
+    }
+
+    /// Bands at or above the Nyquist frequency of the output are left out.
+    /// Called from the output callback, so nothing is allocated and bands
+    /// that stay on keep their state, which would click if it were reset
+    pub fn set_gains(&mut self, gains: &[f32; EQ_BANDS]) {
+        let nyquist = self.sample_rate as f32 / 2.0;
+        for (band, (frequency, gain)) in EQ_FREQUENCIES.iter().zip(gains).enumerate() {
+            let active = *gain != 0.0 && *frequency < nyquist;
+            if active {
+                self.filters[band] = Biquad::peaking(*frequency, *gain, self.sample_rate);
+                if !self.active[band] {
+                    // left over from the last time the band was on
+                    self.state[band * self.channels..(band + 1) * self.channels].fill([0.0; 2]);
+                }
+            }
+            self.active[band] = active;
+        }
+    }
+
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if !self.active.contains(&true) {
+            return;
+        }
+        for frame in samples.chunks_mut(self.channels) {
+            for (channel, sample) in frame.iter_mut().enumerate() {
+                let mut x = *sample;
+                for (band, filter) in self.filters.iter().enumerate() {
+                    if !self.active[band] {
+                        continue;
+                    }
+                    let state = &mut self.state[band * self.channels + channel];
+                    let y = filter.b0 * x + state[0];
+                    state[0] = filter.b1 * x - filter.a1 * y + state[1];
+                    state[1] = filter.b2 * x - filter.a2 * y;
+                    x = y;
+                }
+                *sample = x;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loaded_gains_are_clamped() {
+        let path = std::env::temp_dir().join(format!("dizi-eq-{}.json", std::process::id()));
+        fs::write(&path, "[1000, -1000, 3.5, 1e39]").unwrap();
+        let gains = load_gains(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+        assert_eq!(gains[..4], [EQ_MAX_GAIN, -EQ_MAX_GAIN, 3.5, 0.0]);
+        assert_eq!(gains[4..], [0.0; EQ_BANDS - 4]);
+    }
+
+    #[test]
+    fn flat_leaves_samples() {
+        let mut equalizer = Equalizer::new(2, 44100, &[0.0; EQ_BANDS]);
+        let mut samples = [0.5, -0.5, 0.25, -0.25];
+        equalizer.process(&mut samples);
+        assert_eq!(samples, [0.5, -0.5, 0.25, -0.25]);
+    }
+
+    #[test]
+    fn changing_gains_keeps_state() {
+        let mut gains = [0.0; EQ_BANDS];
+        gains[5] = 6.0;
+        let mut equalizer = Equalizer::new(2, 44100, &gains);
+        let mut samples = [1.0, 1.0, 0.0, 0.0];
+        equalizer.process(&mut samples);
+        let state = equalizer.state.clone();
+        assert_ne!(state[10], [0.0; 2]);
+
+        gains[5] = 3.0;
+        gains[0] = -3.0;
+        equalizer.set_gains(&gains);
+        assert_eq!(equalizer.state, state);
+        assert!(equalizer.active[0] && equalizer.active[5]);
+
+        equalizer.set_gains(&[0.0; EQ_BANDS]);
+        assert!(!equalizer.active.contains(&true));
+    }
+
+    #[test]
+    fn bands_above_nyquist_are_off() {
+        let equalizer = Equalizer::new(1, 8000, &[6.0; EQ_BANDS]);
+        for (frequency, active) in EQ_FREQUENCIES.iter().zip(equalizer.active) {
+            assert_eq!(active, *frequency < 4000.0);
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use std::ffi::CString;
+use std::os::raw::{c_char, c_double, c_float, c_int, c_void};
+use std::path::Path;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+const FLUID_FAILED: c_int = -1;
+const FLUID_PLAYER_PLAYING: c_int = 1;
+
+// frames rendered per call into fluidsynth
+const RENDER_FRAMES: usize = 4096;
+
+#[link(name = "fluidsynth")]
+extern "C" {
+    fn new_fluid_settings() -> *mut c_void;
+    fn delete_fluid_settings(settings: *mut c_void);
+    fn fluid_settings_setnum(settings: *mut c_void, name: *const c_char, val: c_double) -> c_int;
+    fn fluid_settings_setint(settings: *mut c_void, name: *const c_char, val: c_int) -> c_int;
+    fn fluid_settings_setstr(
+        settings: *mut c_void,
+        name: *const c_char,
+        str: *const c_char,
+    ) -> c_int;
+    fn new_fluid_synth(settings: *mut c_void) -> *mut c_void;
+    fn delete_fluid_synth(synth: *mut c_void);
+    fn fluid_synth_sfload(
+        synth: *mut c_void,
+        filename: *const c_char,
+        reset_presets: c_int,
+    ) -> c_int;
+    fn fluid_synth_write_float(
+        synth: *mut c_void,
+        len: c_int,
+        lout: *mut c_void,
+        loff: c_int,
+        lincr: c_int,
+        rout: *mut c_void,
+        roff: c_int,
+        rincr: c_int,
+    ) -> c_int;
+    fn new_fluid_player(synth: *mut c_void) -> *mut c_void;
+    fn delete_fluid_player(player: *mut c_void);
+    fn fluid_player_add_mem(player: *mut c_void, buffer: *const c_void, len: usize) -> c_int;
+    fn fluid_player_play(player: *mut c_void) -> c_int;
+    fn fluid_player_get_status(player: *mut c_void) -> c_int;
+}
+
+fn c_string(s: &str) -> CString {
+    CString::new(s).expect("no nul bytes in names")
+}
+
+fn c_path(path: &Path) -> DiziResult<CString> {
+    CString::new(path.to_string_lossy().as_bytes()).map_err(|_| {
+        DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("Invalid path {:?}", path),
+        )
+    })
+}
+
+/// A FluidSynth synthesizer driven by its midi file player,
+/// timed by the samples written instead of the system clock
+struct Synth {
+    settings: *mut c_void,
+    synth: *mut c_void,
+    player: *mut c_void,
+}
+
+impl Synth {
+    fn new(soundfont: &Path, sample_rate: u32) -> DiziResult<Self> {
+        let soundfont = c_path(soundfont)?;
+        unsafe {
+            let settings = new_fluid_settings();
+            if settings.is_null() {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    "Failed to create fluidsynth settings".to_string(),
+                ));
+            }
+            fluid_settings_setnum(
+                settings,
+                c_string("synth.sample-rate").as_ptr(),
+                sample_rate as c_double,
+            );
+            fluid_settings_setint(settings, c_string("synth.lock-memory").as_ptr(), 0);
+            fluid_settings_setstr(
+                settings,
+                c_string("player.timing-source").as_ptr(),
+                c_string("sample").as_ptr(),
+            );
+            let mut synth = Self {
+                settings,
+                synth: new_fluid_synth(settings),
+                player: std::ptr::null_mut(),
+            };
+            if synth.synth.is_null() {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    "Failed to create fluidsynth synthesizer".to_string(),
+                ));
+            }
+            if fluid_synth_sfload(synth.synth, soundfont.as_ptr(), 1) == FLUID_FAILED {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    format!("Failed to load soundfont {:?}", soundfont),
+                ));
+            }
+            synth.player = new_fluid_player(synth.synth);
+            if synth.player.is_null() {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    "Failed to create fluidsynth player".to_string(),
+                ));
+            }
+            Ok(synth)
+        }
+    }
+
+    /// Interleaved stereo samples of the whole file, along with
+    /// a second after its last note for the notes still ringing
+    fn render(&mut self, path: &Path, sample_rate: u32) -> DiziResult<Vec<f32>> {
+        // read here, so midi files in archives play too
+        let data = dizi::archive::read_file(path)?;
+        unsafe {
+            if fluid_player_add_mem(self.player, data.as_ptr() as *const c_void, data.len())
+                == FLUID_FAILED
+                || fluid_player_play(self.player) == FLUID_FAILED
+            {
+                return Err(DiziError::new(
+                    DiziErrorKind::UnrecognizedFormat,
+                    format!("Failed to play midi file {:?}", path),
+                ));
+            }
+        }
+
+        let mut samples = Vec::new();
+        let mut buffer: Vec<c_float> = vec![0.0; RENDER_FRAMES * 2];
+        let mut tail_frames = sample_rate as usize;
+        loop {
+            let playing = unsafe { fluid_player_get_status(self.player) } == FLUID_PLAYER_PLAYING;
+            if !playing {
+                if tail_frames == 0 {
+                    break;
+                }
+                tail_frames = tail_frames.saturating_sub(RENDER_FRAMES);
+            }
+            let data = buffer.as_mut_ptr() as *mut c_void;
+            let res = unsafe {
+                fluid_synth_write_float(self.synth, RENDER_FRAMES as c_int, data, 0, 2, data, 1, 2)
+            };
+            if res == FLUID_FAILED {
+                break;
+            }
+            samples.extend_from_slice(&buffer);
+        }
+        Ok(samples)
+    }
+}
+
+impl Drop for Synth {
+    fn drop(&mut self) {
+        unsafe {
+            if !self.player.is_null() {
+                delete_fluid_player(self.player);
+            }
+            if !self.synth.is_null() {
+                delete_fluid_synth(self.synth);
+            }
+            delete_fluid_settings(self.settings);
+        }
+    }
+}
+
+/// Renders a midi file with `soundfont` into interleaved stereo samples at `sample_rate`
+pub fn render(path: &Path, soundfont: &Path, sample_rate: u32) -> DiziResult<Vec<f32>> {
+    let mut synth = Synth::new(soundfont, sample_rate)?;
+    synth.render(path, sample_rate)
+}
@@ -1,3 +1,10 @@
 pub mod device;
+pub mod equalizer;
+#[cfg(feature = "midi")]
+pub mod midi;
 pub mod request;
+pub mod resample;
+pub mod spectrum;
 pub mod symphonia;
+pub mod volume;
+pub mod waveform;
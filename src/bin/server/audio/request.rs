@@ -2,15 +2,30 @@ use std::time::Duration;
 
 use dizi::song::DiziAudioFile;
 
+use crate::audio::equalizer::EQ_BANDS;
+
 #[derive(Clone, Debug)]
 pub enum PlayerRequest {
-    Play { song: DiziAudioFile, volume: f32 },
+    Play {
+        song: Box<DiziAudioFile>,
+        volume: f32,
+        // the song's ReplayGain factor
+        gain: f32,
+        // answered along with the result, so results of songs played over are told apart
+        generation: u64,
+    },
     Pause,
     Resume,
     Stop,
-    SetVolume { volume: f32 },
-    FastForward { offset: Duration },
-    Rewind { offset: Duration },
+    SetVolume {
+        volume: f32,
+    },
+    // ReplayGain factor of the playing song
+    SetGain(f32),
+    // gains of the equalizer's bands in dB
+    SetEqualizer([f32; EQ_BANDS]),
+    // moves playback to a position in the song, the decoder seeks there
+    SeekTo(Duration),
     //    AddListener(ServerEventSender),
     //    ClearListeners,
 }
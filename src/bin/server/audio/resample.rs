@@ -0,0 +1,222 @@
+use std::f64::consts::PI;
+
+// zero crossings of the filter on each side of a sample, at the lower rate
+const TAPS: usize = 16;
+// positions between two input frames the filter is worked out at
+const PHASES: usize = 256;
+
+/// Converts interleaved samples from one sample rate to another, a chunk at
+/// a time, by windowed sinc interpolation. Downsampling lowers the cutoff to
+/// the new rate's Nyquist frequency, so nothing above it folds back as aliasing.
+/// The frames around the next output frame are kept to interpolate across chunks
+#[derive(Clone, Debug)]
+pub struct Resampler {
+    channels: u16,
+    from: u32,
+    to: u32,
+    // input frames the filter reaches on each side of an output frame, widened
+    // with the ratio when downsampling so it always reaches past the next one
+    taps: usize,
+    // position of the next output frame, in frames of `pending`
+    position: f64,
+    pending: Vec<f32>,
+    // `2 * taps` coefficients for each of `PHASES + 1` fractions of a frame
+    filter: Vec<f32>,
+}
+
+impl Resampler {
+    pub fn new(channels: u16, from: u32, to: u32) -> Self {
+        let mut resampler = Self {
+            channels: channels.max(1),
+            from,
+            to,
+            taps: TAPS * (from as usize).div_ceil(to.max(1) as usize).max(1),
+            position: 0.0,
+            pending: Vec::new(),
+            filter: Vec::new(),
+        };
+        if resampler.resamples() {
+            resampler.filter = filter(from, to, resampler.taps);
+            resampler.reset();
+        }
+        resampler
+    }
+
+    fn resamples(&self) -> bool {
+        self.from != self.to && self.from != 0 && self.to != 0
+    }
+
+    pub fn channels(&self) -> u16 {
+        self.channels
+    }
+
+    pub fn output_rate(&self) -> u32 {
+        self.to
+    }
+
+    /// Forgets the samples before a seek
+    pub fn reset(&mut self) {
+        // silence before the first frame, so it is interpolated like the rest
+        self.pending.clear();
+        self.pending
+            .resize((self.taps - 1) * self.channels as usize, 0.0);
+        self.position = (self.taps - 1) as f64;
+    }
+
+    pub fn process(&mut self, samples: Vec<f32>) -> Vec<f32> {
+        if !self.resamples() {
+            return samples;
+        }
+        let channels = self.channels as usize;
+        let taps = self.taps;
+        self.pending.extend(samples);
+        let frames = self.pending.len() / channels;
+        let step = self.from as f64 / self.to as f64;
+
+        let mut resampled =
+            Vec::with_capacity((frames as f64 / step) as usize * channels + channels);
+        while self.position as usize + taps < frames {
+            let index = self.position as usize;
+            let phase = ((self.position - index as f64) * PHASES as f64).round() as usize;
+            let coefficients = &self.filter[phase * 2 * taps..(phase + 1) * 2 * taps];
+            // the frames from taps - 1 before to taps after the position
+            let first = index + 1 - taps;
+            for channel in 0..channels {
+                let sample: f32 = coefficients
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| c * self.pending[(first + i) * channels + channel])
+                    .sum();
+                resampled.push(sample);
+            }
+            self.position += step;
+        }
+        // frames the filter won't reach anymore aren't needed
+        let consumed = (self.position as usize + 1)
+            .saturating_sub(taps)
+            .min(frames);
+        self.pending.drain(..consumed * channels);
+        self.position -= consumed as f64;
+        resampled
+    }
+}
+
+/// Lanczos windowed sinc filter, scaled to keep the volume the same
+fn filter(from: u32, to: u32, taps: usize) -> Vec<f32> {
+    let cutoff = (to as f64 / from as f64).min(1.0);
+    let mut filter = Vec::with_capacity((PHASES + 1) * 2 * taps);
+    for phase in 0..=PHASES {
+        let fraction = phase as f64 / PHASES as f64;
+        let start = filter.len();
+        for i in 0..2 * taps {
+            // distance from the output frame, in input frames
+            let x = (i as f64 - (taps - 1) as f64) - fraction;
+            let window = sinc(x / taps as f64);
+            filter.push((cutoff * sinc(cutoff * x) * window) as f32);
+        }
+        let sum: f32 = filter[start..].iter().sum();
+        if sum != 0.0 {
+            for c in filter[start..].iter_mut() {
+                *c /= sum;
+            }
+        }
+    }
+    filter
+}
+
+fn sinc(x: f64) -> f64 {
+    if x == 0.0 {
+        1.0
+    } else {
+        (PI * x).sin() / (PI * x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // interleaved stereo with a different ramp on each channel
+    fn stereo(frames: usize) -> Vec<f32> {
+        (0..frames)
+            .flat_map(|i| [(i % 100) as f32 / 100.0, -((i % 50) as f32) / 50.0])
+            .collect()
+    }
+
+    fn resample_in_chunks(resampler: &mut Resampler, samples: &[f32], chunk: usize) -> Vec<f32> {
+        samples
+            .chunks(chunk * resampler.channels() as usize)
+            .flat_map(|chunk| resampler.process(chunk.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn equal_rates_pass_through() {
+        let samples = stereo(1000);
+        let mut resampler = Resampler::new(2, 44100, 44100);
+        assert_eq!(resample_in_chunks(&mut resampler, &samples, 7), samples);
+    }
+
+    #[test]
+    fn output_length_follows_the_ratio() {
+        let frames = 20_000;
+        let samples = stereo(frames);
+        for (from, to) in [
+            (44100, 48000),
+            (48000, 44100),
+            (96000, 8000),
+            (384000, 8000),
+        ] {
+            for chunk in [1, 7, 100, 4096] {
+                let mut resampler = Resampler::new(2, from, to);
+                let resampled = resample_in_chunks(&mut resampler, &samples, chunk);
+                assert_eq!(resampled.len() % 2, 0);
+                let expected = frames as f64 * to as f64 / from as f64;
+                // the frames the filter looks ahead aren't out yet
+                let delay = resampler.taps as f64 * to as f64 / from as f64 + 2.0;
+                let actual = (resampled.len() / 2) as f64;
+                assert!(
+                    actual <= expected + 1.0 && actual >= expected - delay,
+                    "{} -> {} in chunks of {}: {} frames, expected {}",
+                    from,
+                    to,
+                    chunk,
+                    actual,
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn dc_keeps_unity_gain() {
+        for (from, to) in [(44100, 48000), (48000, 44100), (384000, 8000)] {
+            let mut resampler = Resampler::new(1, from, to);
+            let resampled = resample_in_chunks(&mut resampler, &[0.5; 50_000], 333);
+            // past the silence the first frames are interpolated with
+            let settled = &resampled[resampled.len() / 2..];
+            assert!(!settled.is_empty());
+            for sample in settled {
+                assert!(
+                    (sample - 0.5).abs() < 1e-3,
+                    "{} -> {}: {}",
+                    from,
+                    to,
+                    sample
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn reset_forgets_earlier_samples() {
+        let samples = stereo(5000);
+        let mut fresh = Resampler::new(2, 48000, 44100);
+        let expected = fresh.process(samples.clone());
+
+        let mut resampler = Resampler::new(2, 48000, 44100);
+        resampler.process(vec![1.0; 3000]);
+        resampler.reset();
+        assert_eq!(resampler.process(samples), expected);
+    }
+}
@@ -0,0 +1,63 @@
+use std::f32::consts::PI;
+
+pub const SPECTRUM_BANDS: usize = 32;
+/// Number of mono frames analysed for each spectrum update
+pub const SPECTRUM_WINDOW: usize = 1024;
+/// Spectrum updates sent per second while playing
+pub const SPECTRUM_RATE: u32 = 15;
+
+const MIN_FREQUENCY: f32 = 50.0;
+const MAX_FREQUENCY: f32 = 16000.0;
+// levels below this are drawn as silence
+const DB_FLOOR: f32 = -70.0;
+
+/// Computes the level of `SPECTRUM_BANDS` logarithmically spaced frequency bands
+/// of `samples`, scaled to `0..=255`.
+///
+/// Each band is measured with the Goertzel algorithm, which is cheaper than a
+/// full FFT when only a handful of frequencies are needed.
+pub fn compute_spectrum(samples: &[f32], sample_rate: u32) -> Vec<u8> {
+    let len = samples.len();
+    if len == 0 {
+        return vec![0; SPECTRUM_BANDS];
+    }
+
+    // hann window to reduce spectral leakage
+    let windowed: Vec<f32> = samples
+        .iter()
+        .enumerate()
+        .map(|(i, s)| {
+            let w = 0.5 - 0.5 * (2.0 * PI * i as f32 / (len - 1).max(1) as f32).cos();
+            s * w
+        })
+        .collect();
+
+    let max_frequency = MAX_FREQUENCY.min(sample_rate as f32 / 2.0);
+    let ratio = (max_frequency / MIN_FREQUENCY).powf(1.0 / SPECTRUM_BANDS as f32);
+
+    (0..SPECTRUM_BANDS)
+        .map(|band| {
+            let frequency = MIN_FREQUENCY * ratio.powf(band as f32 + 0.5);
+            let magnitude = goertzel(&windowed, frequency, sample_rate);
+            // a full scale sine wave has a magnitude of len / 4 after windowing
+            let normalized = magnitude / (len as f32 / 4.0);
+            let db = 20.0 * (normalized + f32::EPSILON).log10();
+            let level = ((db - DB_FLOOR) / -DB_FLOOR).clamp(0.0, 1.0);
+            (level * u8::MAX as f32) as u8
+        })
+        .collect()
+}
+
+fn goertzel(samples: &[f32], frequency: f32, sample_rate: u32) -> f32 {
+    let coeff = 2.0 * (2.0 * PI * frequency / sample_rate as f32).cos();
+
+    let mut s_prev = 0.0;
+    let mut s_prev2 = 0.0;
+    for sample in samples {
+        let s = sample + coeff * s_prev - s_prev2;
+        s_prev2 = s_prev;
+        s_prev = s;
+    }
+    let power = s_prev * s_prev + s_prev2 * s_prev2 - coeff * s_prev * s_prev2;
+    power.max(0.0).sqrt()
+}
@@ -0,0 +1,292 @@
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread::{self, Thread};
+use std::time::Duration;
+
+// how long the decoder sleeps on a full buffer before looking at it again
+const FULL_WAIT: Duration = Duration::from_millis(10);
+
+/// What the decoder should do after handing over its samples
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Feed {
+    Continue,
+    // throw away what is being decoded and start again from this sample
+    Seek(usize),
+    Close,
+}
+
+/// Samples decoded ahead of the output callback. The decoder waits when it is
+/// `capacity` samples ahead, so a song is never in memory all at once.
+///
+/// The decoder is the only writer and the output callback the only reader,
+/// so the callback only ever touches atomics and never waits on the decoder
+#[derive(Debug)]
+pub struct RingBuffer {
+    // bits of the samples, `head` and `tail` wrap around it
+    samples: Box<[AtomicU32]>,
+    // samples written so far, only moved by the decoder
+    head: AtomicUsize,
+    // samples read so far, only moved by the callback
+    tail: AtomicUsize,
+    // samples before this were decoded before the last seek and are skipped
+    discard: AtomicUsize,
+    // index in the song, counting every channel, of the next sample played
+    position: AtomicUsize,
+    // seeks made, and those the decoder has started decoding from
+    seeks: AtomicUsize,
+    seeks_taken: AtomicUsize,
+    seek_position: AtomicUsize,
+    // the decoder reached the end of the song
+    finished: AtomicBool,
+    // playback stopped, the decoder can stop decoding
+    closed: AtomicBool,
+    // woken when a seek is made or playback stops
+    decoder: OnceLock<Thread>,
+    channels: usize,
+    // samples played per second, counting every channel
+    rate: usize,
+    // length of the song in samples, if it is known before it is decoded
+    len: Option<usize>,
+    // bits of the ReplayGain factor samples are played at
+    gain: AtomicU32,
+}
+
+impl RingBuffer {
+    pub fn new(capacity: usize, channels: u16, sample_rate: u32, len: Option<usize>) -> Self {
+        let channels = channels.max(1) as usize;
+        Self {
+            samples: (0..capacity.max(1)).map(|_| AtomicU32::new(0)).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+            discard: AtomicUsize::new(0),
+            position: AtomicUsize::new(0),
+            seeks: AtomicUsize::new(0),
+            seeks_taken: AtomicUsize::new(0),
+            seek_position: AtomicUsize::new(0),
+            finished: AtomicBool::new(false),
+            closed: AtomicBool::new(false),
+            decoder: OnceLock::new(),
+            channels,
+            rate: (channels * sample_rate as usize).max(1),
+            len,
+            gain: AtomicU32::new(1f32.to_bits()),
+        }
+    }
+
+    /// Adds decoded samples, waiting while the buffer is full
+    pub fn push(&self, mut samples: &[f32]) -> Feed {
+        self.decoder.get_or_init(thread::current);
+        let capacity = self.samples.len();
+        let mut head = self.head.load(Ordering::Relaxed);
+        loop {
+            if self.closed.load(Ordering::Acquire) {
+                return Feed::Close;
+            }
+            if let Some(position) = self.take_seek() {
+                return Feed::Seek(position);
+            }
+            if samples.is_empty() {
+                return Feed::Continue;
+            }
+            // skipped samples are free to write over
+            let start = self
+                .tail
+                .load(Ordering::Acquire)
+                .max(self.discard.load(Ordering::Relaxed));
+            let space = capacity - (head - start);
+            if space == 0 {
+                // the callback doesn't wake the decoder, it is only polled
+                thread::park_timeout(FULL_WAIT);
+                continue;
+            }
+            let (now, later) = samples.split_at(space.min(samples.len()));
+            for sample in now {
+                self.samples[head % capacity].store(sample.to_bits(), Ordering::Relaxed);
+                head += 1;
+            }
+            self.head.store(head, Ordering::Release);
+            samples = later;
+        }
+    }
+
+    /// Marks the end of the song, then waits for a seek back into it
+    /// or for playback to stop
+    pub fn finish(&self) -> Feed {
+        self.decoder.get_or_init(thread::current);
+        self.finished.store(true, Ordering::Release);
+        loop {
+            if self.closed.load(Ordering::Acquire) {
+                return Feed::Close;
+            }
+            if let Some(position) = self.take_seek() {
+                return Feed::Seek(position);
+            }
+            thread::park();
+        }
+    }
+
+    /// A seek the decoder hasn't started decoding from yet, after which
+    /// everything it decoded so far is skipped
+    fn take_seek(&self) -> Option<usize> {
+        let seeks = self.seeks.load(Ordering::Acquire);
+        if seeks == self.seeks_taken.load(Ordering::Relaxed) {
+            return None;
+        }
+        let position = self.seek_position.load(Ordering::Relaxed);
+        self.finished.store(false, Ordering::Relaxed);
+        self.discard
+            .store(self.head.load(Ordering::Relaxed), Ordering::Relaxed);
+        self.seeks_taken.store(seeks, Ordering::Release);
+        Some(position)
+    }
+
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.wake_decoder();
+    }
+
+    fn wake_decoder(&self) {
+        if let Some(decoder) = self.decoder.get() {
+            decoder.unpark();
+        }
+    }
+
+    /// Fills `output` with the next samples, returning how many there were and
+    /// where playback is after them, or None once the whole song was played
+    pub fn pop(&self, output: &mut [f32]) -> Option<(usize, usize)> {
+        if self.seeks.load(Ordering::Acquire) != self.seeks_taken.load(Ordering::Acquire) {
+            // nothing is decoded from the seek yet
+            return Some((0, self.position.load(Ordering::Relaxed)));
+        }
+        // before `head`, which is written first
+        let finished = self.finished.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        let mut tail = self
+            .tail
+            .load(Ordering::Relaxed)
+            .max(self.discard.load(Ordering::Relaxed));
+        if finished && head == tail {
+            return None;
+        }
+        let capacity = self.samples.len();
+        let count = output.len().min(head - tail);
+        for out in output[..count].iter_mut() {
+            *out = f32::from_bits(self.samples[tail % capacity].load(Ordering::Relaxed));
+            tail += 1;
+        }
+        self.tail.store(tail, Ordering::Release);
+        let position = self.position.fetch_add(count, Ordering::Relaxed) + count;
+        Some((count, position))
+    }
+
+    /// What the output callback multiplies samples by, changing as soon as it is set
+    pub fn gain(&self) -> f32 {
+        f32::from_bits(self.gain.load(Ordering::Relaxed))
+    }
+
+    pub fn set_gain(&self, gain: f32) {
+        self.gain.store(gain.to_bits(), Ordering::Relaxed);
+    }
+
+    /// Length of the song in samples, if it was known before it was decoded
+    pub fn song_len(&self) -> Option<usize> {
+        self.len
+    }
+
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_secs((self.position.load(Ordering::Relaxed) / self.rate) as u64)
+    }
+
+    /// Starts playback again from `position`, once the decoder has caught up.
+    /// Stays a second before the end, so moving past it doesn't skip the song
+    pub fn seek(&self, position: Duration) {
+        let position = (position.as_secs_f64() * self.rate as f64) as usize;
+        let position = match self.len {
+            Some(len) if position >= len => len.saturating_sub(self.rate),
+            _ => position,
+        };
+        // whole frames only, so channels don't swap places
+        let position = position - position % self.channels;
+        self.seek_position.store(position, Ordering::Relaxed);
+        self.position.store(position, Ordering::Relaxed);
+        self.seeks.fetch_add(1, Ordering::Release);
+        self.wake_decoder();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::Arc;
+
+    #[test]
+    fn pop_what_was_pushed() {
+        let buffer = RingBuffer::new(8, 1, 4, None);
+        assert_eq!(buffer.push(&[1.0, 2.0, 3.0, 4.0]), Feed::Continue);
+
+        let mut output = [0.0; 3];
+        assert_eq!(buffer.pop(&mut output), Some((3, 3)));
+        assert_eq!(output, [1.0, 2.0, 3.0]);
+        assert_eq!(buffer.pop(&mut output), Some((1, 4)));
+        assert_eq!(output[0], 4.0);
+        assert_eq!(buffer.pop(&mut output), Some((0, 4)));
+    }
+
+    #[test]
+    fn decoder_waits_while_full() {
+        let buffer = Arc::new(RingBuffer::new(4, 1, 4, None));
+        let decoder = {
+            let buffer = Arc::clone(&buffer);
+            thread::spawn(move || {
+                let samples: Vec<f32> = (0..64).map(|i| i as f32).collect();
+                buffer.push(&samples)
+            })
+        };
+        let mut played = Vec::new();
+        let mut output = [0.0; 3];
+        while played.len() < 64 {
+            let (count, position) = buffer.pop(&mut output).unwrap();
+            played.extend_from_slice(&output[..count]);
+            assert_eq!(position, played.len());
+        }
+        assert_eq!(decoder.join().unwrap(), Feed::Continue);
+        assert!(played.iter().enumerate().all(|(i, s)| *s == i as f32));
+    }
+
+    #[test]
+    fn seek_skips_what_was_decoded() {
+        let buffer = RingBuffer::new(8, 2, 2, None);
+        buffer.push(&[1.0; 6]);
+        buffer.seek(Duration::from_secs(3));
+
+        // nothing plays until the decoder starts again from the seek
+        let mut output = [0.0; 4];
+        assert_eq!(buffer.pop(&mut output), Some((0, 12)));
+        assert_eq!(buffer.push(&[1.0]), Feed::Seek(12));
+        assert_eq!(buffer.push(&[2.0; 8]), Feed::Continue);
+        assert_eq!(buffer.pop(&mut output), Some((4, 16)));
+        assert_eq!(output, [2.0; 4]);
+    }
+
+    #[test]
+    fn finish_then_close() {
+        let buffer = Arc::new(RingBuffer::new(8, 1, 4, None));
+        let decoder = {
+            let buffer = Arc::clone(&buffer);
+            thread::spawn(move || {
+                buffer.push(&[1.0, 2.0]);
+                buffer.finish()
+            })
+        };
+        let mut output = [0.0; 4];
+        let mut played = 0;
+        while let Some((count, _)) = buffer.pop(&mut output) {
+            played += count;
+            thread::yield_now();
+        }
+        assert_eq!(played, 2);
+        buffer.close();
+        assert_eq!(decoder.join().unwrap(), Feed::Close);
+    }
+}
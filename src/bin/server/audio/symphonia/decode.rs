@@ -1,21 +1,25 @@
 use std::iter::Iterator;
-use std::sync::{mpsc, Arc, RwLock};
+use std::sync::{mpsc, Arc};
 use std::time::Duration;
 
 use symphonia::core::audio::SampleBuffer;
 use symphonia::core::codecs::Decoder;
 use symphonia::core::errors::Error as SymphoniaError;
-use symphonia::core::formats::{FormatReader, Packet};
+use symphonia::core::formats::{FormatReader, Packet, SeekMode, SeekTo};
 
 use cpal::traits::{DeviceTrait, StreamTrait};
-use cpal::{Stream, StreamConfig};
+use cpal::StreamConfig;
 
 use dizi::error::{DiziError, DiziResult};
-use symphonia::core::units::TimeBase;
+use symphonia::core::units::{Time, TimeBase};
 
-use crate::audio::request::PlayerRequest;
+use crate::audio::equalizer::Equalizer;
+use crate::audio::resample::Resampler;
+use crate::audio::spectrum::{SPECTRUM_RATE, SPECTRUM_WINDOW};
 
-use super::stream::StreamEvent;
+use super::buffer::{Feed, RingBuffer};
+use super::output::remix;
+use super::stream::{OutputOptions, OutputRequest, StreamEvent};
 
 pub struct PacketReader {
     format: Box<dyn FormatReader>,
@@ -56,6 +60,11 @@ impl PacketDecoder {
         Self { decoder }
     }
 
+    /// Forgets the packets decoded before a seek
+    pub fn reset(&mut self) {
+        self.decoder.reset();
+    }
+
     pub fn decode<T>(&mut self, packet: Packet) -> DiziResult<Vec<T>>
     where
         T: symphonia::core::sample::Sample
@@ -98,123 +107,355 @@ impl PacketDecoder {
     }
 }
 
+/// Songs decoded a packet at a time while they are played
+pub trait Source: Send {
+    /// The next interleaved samples, None at the end of the song
+    fn next_samples(&mut self) -> Option<DiziResult<Vec<f32>>>;
+    fn seek(&mut self, time: Duration) -> DiziResult;
+}
+
+/// A song symphonia reads
+pub struct SongDecoder {
+    format: Box<dyn FormatReader>,
+    track_id: u32,
+    decoder: PacketDecoder,
+    time_base: Option<TimeBase>,
+    channels: usize,
+    sample_rate: u32,
+    // samples before where a seek was asked to, an accurate seek can land a bit early
+    skip: usize,
+}
+
+impl SongDecoder {
+    pub fn new(
+        format: Box<dyn FormatReader>,
+        track_id: u32,
+        decoder: Box<dyn Decoder>,
+        channels: u16,
+        sample_rate: u32,
+    ) -> Self {
+        let time_base = decoder.codec_params().time_base;
+        Self {
+            format,
+            track_id,
+            decoder: PacketDecoder::new(decoder),
+            time_base,
+            channels: channels.max(1) as usize,
+            sample_rate,
+            skip: 0,
+        }
+    }
+}
+
+impl Source for SongDecoder {
+    fn next_samples(&mut self) -> Option<DiziResult<Vec<f32>>> {
+        loop {
+            let packet = match self.format.next_packet() {
+                Ok(packet) => packet,
+                Err(_) => return None,
+            };
+            if packet.track_id() != self.track_id {
+                continue;
+            }
+            let mut samples = match self.decoder.decode::<f32>(packet) {
+                Ok(samples) => samples,
+                Err(err) => return Some(Err(err)),
+            };
+            if self.skip > 0 {
+                let skipped = self.skip.min(samples.len());
+                samples.drain(..skipped);
+                self.skip -= skipped;
+            }
+            return Some(Ok(samples));
+        }
+    }
+
+    fn seek(&mut self, time: Duration) -> DiziResult {
+        let seeked = self.format.seek(
+            SeekMode::Accurate,
+            SeekTo::Time {
+                time: Time::from(time),
+                track_id: Some(self.track_id),
+            },
+        )?;
+        self.decoder.reset();
+        // timestamps count frames unless the track says otherwise
+        let early = seeked.required_ts.saturating_sub(seeked.actual_ts);
+        let frames = match self.time_base {
+            Some(time_base) => {
+                let time = time_base.calc_time(early);
+                ((time.seconds as f64 + time.frac) * self.sample_rate as f64) as usize
+            }
+            None => early as usize,
+        };
+        self.skip = frames * self.channels;
+        Ok(())
+    }
+}
+
+// samples handed over at a time by songs rendered in one go
+#[cfg(any(feature = "openmpt", feature = "midi"))]
+const RENDERED_CHUNK: usize = 4096;
+
+/// A song rendered whole before it is played, such as tracker modules and midi
+#[cfg(any(feature = "openmpt", feature = "midi"))]
+pub struct RenderedSong {
+    samples: Vec<f32>,
+    index: usize,
+    // samples per second, counting every channel
+    rate: usize,
+    channels: usize,
+}
+
+#[cfg(any(feature = "openmpt", feature = "midi"))]
+impl RenderedSong {
+    pub fn new(samples: Vec<f32>, channels: u16, sample_rate: u32) -> Self {
+        let channels = channels.max(1) as usize;
+        Self {
+            samples,
+            index: 0,
+            rate: channels * sample_rate as usize,
+            channels,
+        }
+    }
+}
+
+#[cfg(any(feature = "openmpt", feature = "midi"))]
+impl Source for RenderedSong {
+    fn next_samples(&mut self) -> Option<DiziResult<Vec<f32>>> {
+        if self.index >= self.samples.len() {
+            return None;
+        }
+        let end = (self.index + RENDERED_CHUNK).min(self.samples.len());
+        let samples = self.samples[self.index..end].to_vec();
+        self.index = end;
+        Some(Ok(samples))
+    }
+
+    fn seek(&mut self, time: Duration) -> DiziResult {
+        let index = (time.as_secs_f64() * self.rate as f64) as usize;
+        self.index = index - index % self.channels;
+        Ok(())
+    }
+}
+
+/// Decodes `source` into `buffer` as fast as it is played, until playback stops.
+/// Samples are remixed from `channels` and resampled on the way in
+pub fn decode_loop(
+    mut source: Box<dyn Source>,
+    channels: u16,
+    mut resampler: Resampler,
+    buffer: Arc<RingBuffer>,
+) {
+    let output_channels = resampler.channels();
+    let output_rate = (output_channels as usize * resampler.output_rate() as usize).max(1);
+    let mut feed = Feed::Continue;
+    loop {
+        feed = match feed {
+            Feed::Close => return,
+            Feed::Seek(position) => {
+                resampler.reset();
+                let time = Duration::from_secs_f64(position as f64 / output_rate as f64);
+                match source.seek(time) {
+                    Ok(()) => Feed::Continue,
+                    Err(err) => {
+                        tracing::error!("Failed to seek: {}", err);
+                        buffer.finish()
+                    }
+                }
+            }
+            Feed::Continue => match source.next_samples() {
+                Some(Ok(samples)) => {
+                    let samples = remix(samples, channels, output_channels);
+                    buffer.push(&resampler.process(samples))
+                }
+                Some(Err(err)) => {
+                    tracing::error!("Failed to decode: {}", err);
+                    buffer.finish()
+                }
+                None => buffer.finish(),
+            },
+        };
+    }
+}
+
 pub fn stream_loop<T>(
     stream_tx: mpsc::Sender<StreamEvent>,
     device: &cpal::Device,
     config: &StreamConfig,
-    samples: Vec<T>,
-    volume: f32,
-    volume_change: fn(T, f32) -> T,
-) -> DiziResult<(Stream, mpsc::Sender<PlayerRequest>)>
+    buffer: Arc<RingBuffer>,
+    options: OutputOptions,
+) -> DiziResult<(cpal::Stream, mpsc::Sender<OutputRequest>)>
 where
-    T: symphonia::core::sample::Sample
-        + cpal::Sample
-        + cpal::SizedSample
-        + std::marker::Send
-        + 'static
-        + symphonia::core::conv::FromSample<i8>
-        + symphonia::core::conv::FromSample<i16>
-        + symphonia::core::conv::FromSample<i32>
-        + symphonia::core::conv::FromSample<u8>
-        + symphonia::core::conv::FromSample<u16>
-        + symphonia::core::conv::FromSample<u32>
-        + symphonia::core::conv::FromSample<f32>
-        + symphonia::core::conv::FromSample<f64>
-        + symphonia::core::conv::FromSample<symphonia::core::sample::i24>
-        + symphonia::core::conv::FromSample<symphonia::core::sample::u24>,
+    T: cpal::SizedSample + cpal::FromSample<f32> + Send + 'static,
 {
     let err_fn = |err| {
         tracing::error!("A playback error has occured! {}", err);
     };
 
-    let time_base = TimeBase {
-        numer: 1,
-        denom: config.sample_rate.0 * config.channels as u32,
-    };
-
-    let samples_count = samples.len();
+    let channels = config.channels.max(1) as usize;
+    let sample_rate = config.sample_rate.0;
+    let rate = (channels * sample_rate as usize).max(1);
+    let spectrum_interval = (sample_rate / SPECTRUM_RATE) as usize;
+    // mono mix of the most recently played frames, for the visualizer
+    let mut spectrum_samples: Vec<f32> = Vec::with_capacity(SPECTRUM_WINDOW * 2);
+    let mut frames_since_spectrum = 0;
+    // copies of the window are sent in these and handed back once the
+    // spectrum is computed, so the callback doesn't allocate one each time
+    let (spare_tx, spare_rx) = mpsc::channel();
+    for _ in 0..2 {
+        let _ = spare_tx.send(Vec::with_capacity(SPECTRUM_WINDOW));
+    }
 
-    // all vars that the stream will update while its streaming
-    let frame_index = Arc::new(RwLock::new(0_usize));
-    let volume = Arc::new(RwLock::new(volume));
-    let playback_duration = Arc::new(RwLock::new(0));
+    // only the output callback reads and updates these,
+    // so they are owned by it instead of being shared behind locks
+    let OutputOptions {
+        mut volume,
+        visualizer,
+        crossfade,
+        fade,
+        equalizer,
+    } = options;
+    let mut equalizer = Equalizer::new(config.channels, sample_rate, &equalizer);
+    let mut playback_duration = 0;
+    // grows to the size of the largest buffer asked for, then stays
+    let mut played: Vec<f32> = Vec::new();
+    let mut fading: Vec<f32> = Vec::new();
+    let mut current = buffer;
+    // the song fading out while `current` fades in
+    let mut outgoing: Option<Arc<RingBuffer>> = None;
+    // the crossfade in samples, counting every channel
+    let fade_len = (crossfade.as_secs_f64() * rate as f64) as usize;
+    let mut ending_sent = false;
+    // the output starts silent and fades in, by `fade_step` every frame
+    let fade_frames = (fade.as_secs_f64() * sample_rate as f64) as usize;
+    let fade_step = 1.0 / fade_frames.max(1) as f32;
+    let mut fade_level = if fade_frames == 0 { 1.0 } else { 0.0 };
+    let mut fade_target = 1.0;
 
     let _ = stream_tx.send(StreamEvent::Progress(Duration::from_secs(0)));
 
-    // if stream_tx is None, then we've already sent a StreamEnded message
-    // and we don't need to send another one
-    let mut stream_tx = Some(stream_tx);
+    // StreamEnded is only sent once for each song
+    let mut ended_sent = false;
 
     let (playback_loop_tx, playback_loop_rx) = mpsc::channel();
 
     let stream = device.build_output_stream(
         config,
         move |data: &mut [T], _: &cpal::OutputCallbackInfo| {
-            let process_message = |msg: PlayerRequest| match msg {
-                PlayerRequest::SetVolume { volume: new_volume } => {
-                    let mut current_volume = volume.write().unwrap();
-                    *current_volume = new_volume;
-                }
-                PlayerRequest::FastForward { offset } => {
-                    let mut sample_offset = frame_index.write().unwrap();
-                    *sample_offset += time_base.denom as usize * offset.as_secs() as usize;
-                    if *sample_offset >= samples_count {
-                        *sample_offset = samples_count - time_base.denom as usize;
+            while let Ok(msg) = playback_loop_rx.try_recv() {
+                match msg {
+                    OutputRequest::SetVolume(new_volume) => volume = new_volume,
+                    OutputRequest::SetEqualizer(gains) => equalizer.set_gains(&gains),
+                    OutputRequest::FadeIn(next) => {
+                        let previous = std::mem::replace(&mut current, next);
+                        if let Some(previous) = outgoing.replace(previous) {
+                            previous.close();
+                        }
+                        ending_sent = false;
+                        ended_sent = false;
                     }
+                    OutputRequest::FadeTo(level) => fade_target = level,
                 }
-                PlayerRequest::Rewind { offset } => {
-                    let mut sample_offset = frame_index.write().unwrap();
-                    if *sample_offset < time_base.denom as usize * offset.as_secs() as usize {
-                        *sample_offset = 0;
-                    } else {
-                        *sample_offset -= time_base.denom as usize * offset.as_secs() as usize;
+            }
+
+            played.resize(data.len(), 0.0);
+            let (mut count, position) = match current.pop(&mut played) {
+                Some(popped) => popped,
+                None => {
+                    data.fill(T::EQUILIBRIUM);
+                    if !ended_sent {
+                        ended_sent = true;
+                        let _ = stream_tx.send(StreamEvent::StreamEnded(Arc::clone(&current)));
                     }
+                    return;
                 }
-                _ => {}
             };
-
-            if let Ok(msg) = playback_loop_rx.try_recv() {
-                process_message(msg);
+            apply_gain(&mut played[..count], current.gain());
+            if let Some(fading_out) = outgoing.as_ref() {
+                fading.resize(data.len(), 0.0);
+                match fading_out.pop(&mut fading) {
+                    Some((fading_count, fading_position)) => {
+                        apply_gain(&mut fading[..fading_count], fading_out.gain());
+                        // only songs of a known length reach their crossfade
+                        let start = fading_position - fading_count;
+                        let remaining = fading_out
+                            .song_len()
+                            .map_or(0, |len| len.saturating_sub(start));
+                        played[count..fading_count.max(count)].fill(0.0);
+                        count = count.max(fading_count);
+                        crossfade_samples(
+                            &mut played,
+                            &fading[..fading_count],
+                            remaining,
+                            fade_len,
+                        );
+                    }
+                    None => {
+                        fading_out.close();
+                        outgoing = None;
+                    }
+                }
+            } else if fade_len > 0 && !ending_sent {
+                let ending = current
+                    .song_len()
+                    .is_some_and(|len| len.saturating_sub(position) <= fade_len);
+                if ending {
+                    ending_sent = true;
+                    let _ = stream_tx.send(StreamEvent::Ending);
+                }
             }
 
-            // if sample_offset is greater than samples_count, then we've reached the end
-            let sample_offset = { *frame_index.read().unwrap() };
-            if sample_offset >= samples_count {
-                if let Some(stream_tx) = stream_tx.take() {
-                    let _ = stream_tx.send(StreamEvent::StreamEnded);
-                }
-                return;
+            equalizer.process(&mut played[..count]);
+            if fade_level != fade_target || fade_level != 1.0 {
+                fade_level = apply_fade(
+                    &mut played[..count],
+                    channels,
+                    fade_level,
+                    fade_target,
+                    fade_step,
+                );
             }
+            let played = &played[..count];
+            let (output, rest) = data.split_at_mut(count);
+            apply_volume(output, played, volume);
+            // the decoder fell behind, or the song ended part way through this buffer
+            rest.fill(T::EQUILIBRIUM);
 
-            let current_volume = { *volume.read().unwrap() };
-            let mut i = 0;
-            for d in data.iter_mut() {
-                if sample_offset + i >= samples_count {
-                    let mut offset = frame_index.write().unwrap();
-                    *offset = samples_count + 1;
-                    break;
+            if visualizer {
+                spectrum_samples.extend(played.chunks(channels).map(|frame| {
+                    let sum: f32 = frame.iter().sum();
+                    sum / channels as f32
+                }));
+                if spectrum_samples.len() > SPECTRUM_WINDOW {
+                    let excess = spectrum_samples.len() - SPECTRUM_WINDOW;
+                    spectrum_samples.drain(..excess);
+                }
+                frames_since_spectrum += count / channels;
+                if frames_since_spectrum >= spectrum_interval
+                    && spectrum_samples.len() == SPECTRUM_WINDOW
+                {
+                    // skipped while both copies are still being read
+                    if let Ok(mut samples) = spare_rx.try_recv() {
+                        frames_since_spectrum = 0;
+                        samples.clear();
+                        samples.extend_from_slice(&spectrum_samples);
+                        let _ = stream_tx.send(StreamEvent::Samples {
+                            samples,
+                            sample_rate,
+                            spare: spare_tx.clone(),
+                        });
+                    }
                 }
-                *d = volume_change(samples[sample_offset + i], current_volume);
-                i += 1;
             }
-            // new offset
-            let new_sample_offset = {
-                let mut sample_offset = frame_index.write().unwrap();
-                *sample_offset += i;
-                *sample_offset
-            };
-            // new duration
-            let next_duration = time_base.calc_time(new_sample_offset as u64).seconds;
-            let prev_duration = { *playback_duration.read().unwrap() };
 
             // update duration if seconds changed
-            if prev_duration != next_duration {
-                let new_duration = Duration::from_secs(next_duration);
-                if let Some(stream_tx) = stream_tx.as_ref() {
-                    let _ = stream_tx.send(StreamEvent::Progress(new_duration));
-                }
-                let mut duration = playback_duration.write().unwrap();
-                *duration = new_duration.as_secs();
+            let next_duration = (position / rate) as u64;
+            if playback_duration != next_duration {
+                playback_duration = next_duration;
+                let _ = stream_tx.send(StreamEvent::Progress(Duration::from_secs(
+                    playback_duration,
+                )));
             }
         },
         err_fn,
@@ -223,3 +464,49 @@ where
     stream.play()?;
     Ok((stream, playback_loop_tx))
 }
+
+/// Applies a song's ReplayGain, each song keeping its own through a crossfade
+fn apply_gain(samples: &mut [f32], gain: f32) {
+    if gain != 1.0 {
+        for sample in samples.iter_mut() {
+            *sample *= gain;
+        }
+    }
+}
+
+/// Mixes the song fading out over the start of the one fading in, `remaining`
+/// being how many samples of it were left before `fading`
+fn crossfade_samples(played: &mut [f32], fading: &[f32], remaining: usize, fade_len: usize) {
+    let fade_len = fade_len.max(1) as f32;
+    for (i, (out, sample)) in played.iter_mut().zip(fading).enumerate() {
+        let gain = (remaining.saturating_sub(i) as f32 / fade_len).min(1.0);
+        *out = *out * (1.0 - gain) + sample * gain;
+    }
+}
+
+/// Moves every frame's level `step` closer to `target`, starting from `level`,
+/// and returns the level reached
+fn apply_fade(samples: &mut [f32], channels: usize, mut level: f32, target: f32, step: f32) -> f32 {
+    for frame in samples.chunks_mut(channels) {
+        level = if level < target {
+            (level + step).min(target)
+        } else {
+            (level - step).max(target)
+        };
+        for sample in frame.iter_mut() {
+            *sample *= level;
+        }
+    }
+    level
+}
+
+/// Writes `samples` to `output` at `volume`, in the output's sample format.
+/// A plain loop over two slices of the same length, which the compiler vectorizes
+fn apply_volume<T>(output: &mut [T], samples: &[f32], volume: f32)
+where
+    T: cpal::Sample + cpal::FromSample<f32>,
+{
+    for (out, sample) in output.iter_mut().zip(samples) {
+        *out = T::from_sample(sample * volume);
+    }
+}
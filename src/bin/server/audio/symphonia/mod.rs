@@ -1,3 +1,5 @@
+pub mod buffer;
 pub mod decode;
+pub mod output;
 pub mod player;
 pub mod stream;
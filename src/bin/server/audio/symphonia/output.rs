@@ -0,0 +1,66 @@
+use cpal::traits::DeviceTrait;
+use cpal::{SampleFormat, SampleRate, SupportedStreamConfig, SupportedStreamConfigRange};
+
+/// Picks the device config closest to the song's, so it is played as decoded where possible.
+/// Matching the sample rate counts the most since anything else needs resampling,
+/// then matching the channels, then f32 which the samples are decoded to
+pub fn choose_output_config(
+    device: &cpal::Device,
+    channels: u16,
+    sample_rate: u32,
+    default: &SupportedStreamConfig,
+) -> SupportedStreamConfig {
+    let ranges: Vec<SupportedStreamConfigRange> = match device.supported_output_configs() {
+        Ok(ranges) => ranges.collect(),
+        Err(err) => {
+            tracing::debug!("Failed to get supported output configs: {}", err);
+            return default.clone();
+        }
+    };
+
+    let supports_rate = |range: &SupportedStreamConfigRange| {
+        range.min_sample_rate().0 <= sample_rate && sample_rate <= range.max_sample_rate().0
+    };
+    let best = ranges.into_iter().max_by_key(|range| {
+        (
+            supports_rate(range),
+            range.channels() == channels,
+            // more channels than the song are better than dropping some
+            range.channels() >= channels,
+            range.sample_format() == SampleFormat::F32,
+            range.sample_format() == default.sample_format(),
+        )
+    });
+    match best {
+        Some(range) => {
+            let rate = sample_rate.clamp(range.min_sample_rate().0, range.max_sample_rate().0);
+            range.with_sample_rate(SampleRate(rate))
+        }
+        None => default.clone(),
+    }
+}
+
+/// Maps interleaved samples of `from` channels to `to` channels. Mono is copied
+/// to every channel, channels are averaged into mono and missing channels are left silent
+pub fn remix(samples: Vec<f32>, from: u16, to: u16) -> Vec<f32> {
+    let (from, to) = (from.max(1) as usize, to.max(1) as usize);
+    if from == to {
+        return samples;
+    }
+    let mut remixed = Vec::with_capacity(samples.len() / from * to);
+    for frame in samples.chunks_exact(from) {
+        if to == 1 {
+            remixed.push(frame.iter().sum::<f32>() / from as f32);
+            continue;
+        }
+        for channel in 0..to {
+            let sample = if from == 1 {
+                frame[0]
+            } else {
+                frame.get(channel).copied().unwrap_or(0.0)
+            };
+            remixed.push(sample);
+        }
+    }
+    remixed
+}
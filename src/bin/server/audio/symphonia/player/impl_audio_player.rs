@@ -1,35 +1,58 @@
+use std::collections::VecDeque;
 use std::path;
 use std::time;
 
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
-use dizi::player::{PlayerState, PlayerStatus};
+use dizi::player::{PlayerInfo, PlayerState, PlayerStatus, RepeatMode, EQ_MAX_GAIN};
 use dizi::playlist::PlaylistType;
 use dizi::song::DiziAudioFile;
-use dizi::song::DiziSongEntry;
+use dizi::song::{DiziSongEntry, ReplayGainMode};
 
+use crate::audio::equalizer::EQ_BANDS;
 use crate::audio::request::PlayerRequest;
 use crate::context::PlaylistContext;
 use crate::playlist::DiziPlaylist;
 use crate::traits::{AudioPlayer, DiziPlaylistTrait};
-use crate::util::mimetype::{get_mimetype, is_mimetype_audio, is_mimetype_video};
+use crate::util::mimetype::{get_mimetype, is_mimetype_audio, is_mimetype_video, is_playable};
 
 use super::SymphoniaPlayer;
 
 impl AudioPlayer for SymphoniaPlayer {
+    fn player_info(&self) -> PlayerInfo {
+        let mut info = self.state.clone();
+        info.playlist_status = self.playlist_context.current_playlist_type;
+        info
+    }
+
     fn player_state(&self) -> PlayerState {
-        let mut state = self.state.clone();
-        state.playlist = self.playlist_context.file_playlist.to_file_playlist();
-        state.playlist_status = self.playlist_context.current_playlist_type;
-        state
+        PlayerState {
+            info: self.player_info(),
+            playlist: self.playlist_context.file_playlist.to_file_playlist(),
+            directory_playlist: self.playlist_context.directory_playlist.to_file_playlist(),
+        }
     }
 
     fn play_directory(&mut self, path: &path::Path) -> DiziResult {
-        let mimetype = get_mimetype(path)?;
-        if !is_mimetype_audio(&mimetype) && !is_mimetype_video(&mimetype) {
-            return Err(DiziError::new(
-                DiziErrorKind::NotAudioFile,
-                format!("File mimetype is not of type audio: '{}'", mimetype),
-            ));
+        // `file` can't look inside archives
+        if dizi::archive::is_archive_member(path) {
+            if !is_playable(path)? {
+                return Err(DiziError::new(
+                    DiziErrorKind::NotAudioFile,
+                    format!("Not an audio file: {:?}", path),
+                ));
+            }
+        } else {
+            let mimetype = get_mimetype(path)?;
+            // nor does it always know the formats of optional features
+            if !is_mimetype_audio(&mimetype)
+                && !is_mimetype_video(&mimetype)
+                && !dizi::song::is_optional_format(path)
+            {
+                return Err(DiziError::new(
+                    DiziErrorKind::NotAudioFile,
+                    format!("File mimetype is not of type audio: '{}'", mimetype),
+                ));
+            }
         }
 
         let shuffle_enabled = self.shuffle_enabled();
@@ -172,12 +195,14 @@ impl AudioPlayer for SymphoniaPlayer {
 
         self.player_stream_res().recv()??;
         self.state.status = PlayerStatus::Stopped;
+        self.state.output = None;
         Ok(())
     }
 
     fn toggle_play(&mut self) -> DiziResult<PlayerStatus> {
         match self.state.status {
-            PlayerStatus::Playing => {
+            // pausing a song still loading pauses it once it plays
+            PlayerStatus::Playing | PlayerStatus::Loading => {
                 self.pause()?;
                 Ok(PlayerStatus::Paused)
             }
@@ -189,13 +214,17 @@ impl AudioPlayer for SymphoniaPlayer {
         }
     }
     fn fast_forward(&mut self, offset: time::Duration) -> DiziResult {
-        self.player_stream_req()
-            .send(PlayerRequest::FastForward { offset })?;
-        Ok(())
+        self.seek(self.state.elapsed + offset)
     }
     fn rewind(&mut self, offset: time::Duration) -> DiziResult {
+        self.seek(self.state.elapsed.saturating_sub(offset))
+    }
+    fn seek(&mut self, position: time::Duration) -> DiziResult {
         self.player_stream_req()
-            .send(PlayerRequest::Rewind { offset })?;
+            .send(PlayerRequest::SeekTo(position))?;
+        // seeks in quick succession move on from each other,
+        // before the stream lets the server know where playback is
+        self.state.elapsed = position;
         Ok(())
     }
 
@@ -203,8 +232,9 @@ impl AudioPlayer for SymphoniaPlayer {
         self.state.volume
     }
     fn set_volume(&mut self, volume: usize) -> DiziResult {
+        let volume = volume.min(self.volume_max);
         self.player_stream_req().send(PlayerRequest::SetVolume {
-            volume: volume as f32 / 100.0,
+            volume: self.volume_curve.amplitude(volume),
         })?;
 
         self.player_stream_res().recv()??;
@@ -214,18 +244,24 @@ impl AudioPlayer for SymphoniaPlayer {
     fn next_enabled(&self) -> bool {
         self.state.next
     }
-    fn repeat_enabled(&self) -> bool {
+    fn repeat_mode(&self) -> RepeatMode {
         self.state.repeat
     }
     fn shuffle_enabled(&self) -> bool {
         self.state.shuffle
     }
+    fn single_enabled(&self) -> bool {
+        self.state.single
+    }
 
     fn set_next(&mut self, next: bool) {
         self.state.next = next;
     }
-    fn set_repeat(&mut self, repeat: bool) {
-        self.state.repeat = repeat;
+    fn set_repeat_mode(&mut self, mode: RepeatMode) {
+        self.state.repeat = mode;
+    }
+    fn set_single(&mut self, single: bool) {
+        self.state.single = single;
     }
     fn set_shuffle(&mut self, shuffle: bool) {
         self.state.shuffle = shuffle;
@@ -239,10 +275,62 @@ impl AudioPlayer for SymphoniaPlayer {
         }
     }
 
+    fn replaygain_mode(&self) -> ReplayGainMode {
+        self.state.replaygain
+    }
+    fn set_replaygain_mode(&mut self, mode: ReplayGainMode) -> DiziResult {
+        let gain = self
+            .current_song_ref()
+            .map_or(1.0, |song| song.replay_gain().factor(mode));
+        self.player_stream_req()
+            .send(PlayerRequest::SetGain(gain))?;
+
+        self.player_stream_res().recv()??;
+        self.state.replaygain = mode;
+        Ok(())
+    }
+
+    fn eq_gains(&self) -> [f32; EQ_BANDS] {
+        self.equalizer
+    }
+    fn set_eq_band(&mut self, band: usize, gain: f32) -> DiziResult {
+        if band >= EQ_BANDS || !gain.is_finite() {
+            return Err(DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                format!("No equalizer band {} to set to {} dB", band, gain),
+            ));
+        }
+        let mut gains = self.equalizer;
+        gains[band] = gain.clamp(-EQ_MAX_GAIN, EQ_MAX_GAIN);
+        self.player_stream_req()
+            .send(PlayerRequest::SetEqualizer(gains))?;
+
+        self.player_stream_res().recv()??;
+        self.equalizer = gains;
+        Ok(())
+    }
+
     fn set_elapsed(&mut self, elapsed: time::Duration) {
         self.state.elapsed = elapsed;
     }
 
+    fn queue_ref(&self) -> &VecDeque<DiziAudioFile> {
+        &self.queue
+    }
+    fn queue_mut(&mut self) -> &mut VecDeque<DiziAudioFile> {
+        &mut self.queue
+    }
+    fn play_queued(&mut self) -> DiziResult<bool> {
+        match self.queue.pop_front() {
+            Some(song) => {
+                // the playlist stays where it was, to carry on from after the queue
+                self.play(&song)?;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
     fn current_song_ref(&self) -> Option<&DiziAudioFile> {
         self.state.song.as_ref()
     }
@@ -1,5 +1,6 @@
 mod impl_audio_player;
 
+use std::collections::VecDeque;
 use std::path::PathBuf;
 use std::sync::mpsc;
 use std::thread::{self, JoinHandle};
@@ -7,13 +8,15 @@ use std::thread::{self, JoinHandle};
 use cpal::traits::HostTrait;
 
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
-use dizi::player::{PlayerState, PlayerStatus};
+use dizi::player::{PlayerInfo, PlayerStatus};
 use dizi::playlist::PlaylistType;
 use dizi::song::DiziAudioFile;
 
 use crate::audio::device::get_default_host;
+use crate::audio::equalizer::{self, EQ_BANDS};
 use crate::audio::request::PlayerRequest;
 use crate::audio::symphonia::stream::PlayerStream;
+use crate::audio::volume::VolumeCurve;
 use crate::config;
 use crate::context::PlaylistContext;
 use crate::events::ServerEventSender;
@@ -22,12 +25,24 @@ use crate::traits::AudioPlayer;
 
 #[derive(Debug)]
 pub struct SymphoniaPlayer {
-    pub state: PlayerState,
+    pub state: PlayerInfo,
     pub playlist_context: PlaylistContext,
 
     pub player_req_tx: mpsc::Sender<PlayerRequest>,
     pub player_res_rx: mpsc::Receiver<DiziResult>,
 
+    // gains of the equalizer's bands in dB
+    pub equalizer: [f32; EQ_BANDS],
+
+    // bumped with every song played, only the latest one's load result is applied
+    pub load_generation: u64,
+
+    pub volume_max: usize,
+    pub volume_curve: VolumeCurve,
+
+    // songs to play before the playlist moves on, forgotten when the server quits
+    pub queue: VecDeque<DiziAudioFile>,
+
     pub _stream_handle: JoinHandle<DiziResult>,
 }
 
@@ -43,9 +58,26 @@ impl SymphoniaPlayer {
         let (player_req_tx, player_req_rx) = mpsc::channel();
         let (player_res_tx, player_res_rx) = mpsc::channel();
 
+        let visualizer = config_t.server_ref().player_ref().visualizer;
+        let crossfade = config_t.server_ref().player_ref().crossfade;
+        let fade = config_t.server_ref().player_ref().fade;
+        let gains =
+            equalizer::load_gains(config_t.server_ref().equalizer_ref()).unwrap_or([0.0; EQ_BANDS]);
+        #[cfg(feature = "midi")]
+        let soundfont = config_t.server_ref().midi_ref().soundfont.clone();
         let stream_handle: JoinHandle<DiziResult> = thread::spawn(move || {
-            let mut stream =
-                PlayerStream::new(event_tx, player_res_tx, player_req_rx, audio_device)?;
+            let mut stream = PlayerStream::new(
+                event_tx,
+                player_res_tx,
+                player_req_rx,
+                audio_device,
+                visualizer,
+                crossfade,
+                gains,
+            )?;
+            stream.set_fade(fade);
+            #[cfg(feature = "midi")]
+            stream.set_soundfont(soundfont);
             stream.listen_for_events()?;
             Ok(())
         });
@@ -61,13 +93,14 @@ impl SymphoniaPlayer {
             .unwrap_or_default(),
             ..Default::default()
         };
-        let state = PlayerState {
+        let state = PlayerInfo {
             next: player_config.next,
             repeat: player_config.repeat,
             shuffle: player_config.shuffle,
-            volume: config_t.server_ref().player_ref().volume,
+            replaygain: player_config.replaygain,
+            volume: player_config.volume.min(player_config.volume_max),
             audio_host: audio_host.id().name().to_lowercase(),
-            ..PlayerState::default()
+            ..PlayerInfo::default()
         };
 
         Ok(Self {
@@ -75,6 +108,11 @@ impl SymphoniaPlayer {
             playlist_context,
             player_req_tx,
             player_res_rx,
+            equalizer: gains,
+            load_generation: 0,
+            volume_max: player_config.volume_max,
+            volume_curve: player_config.volume_curve,
+            queue: VecDeque::new(),
             _stream_handle: stream_handle,
         })
     }
@@ -86,18 +124,22 @@ impl SymphoniaPlayer {
         &self.player_res_rx
     }
 
+    /// Starts opening the song without waiting for it,
+    /// `ServerEvent::PlayerLoaded` follows once it plays or fails to
     fn play(&mut self, song: &DiziAudioFile) -> DiziResult {
         tracing::debug!("Song: {:#?}", song);
 
+        self.load_generation += 1;
         self.player_stream_req().send(PlayerRequest::Play {
-            song: song.clone(),
-            volume: self.get_volume() as f32 / 100.0,
+            song: Box::new(song.clone()),
+            volume: self.volume_curve.amplitude(self.get_volume()),
+            gain: song.replay_gain().factor(self.state.replaygain),
+            generation: self.load_generation,
         })?;
 
-        self.player_stream_res().recv()??;
-
-        self.state.status = PlayerStatus::Playing;
+        self.state.status = PlayerStatus::Loading;
         self.state.song = Some(song.clone());
+        self.state.duration = song.audio_metadata.total_duration;
         Ok(())
     }
 
@@ -1,24 +1,63 @@
-use std::sync::mpsc;
+#[cfg(feature = "midi")]
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time::Duration;
 
-use dizi::song::DiziAudioFile;
-use symphonia::core::codecs::DecoderOptions;
+use dizi::song::{track_duration, DiziAudioFile};
+use symphonia::core::codecs::{Decoder, DecoderOptions};
+use symphonia::core::formats::FormatReader;
 
 use cpal::traits::{DeviceTrait, StreamTrait};
 use cpal::Stream;
 
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::player::OutputConfig;
+#[cfg(feature = "openmpt")]
+use dizi::tracker;
 
+#[cfg(feature = "midi")]
+use crate::audio::midi;
 use crate::audio::request::PlayerRequest;
-use crate::events::{ServerEvent, ServerEventSender};
+use crate::events::{LoadedSong, ServerEvent, ServerEventSender};
 
-use super::decode::{stream_loop, PacketDecoder, PacketReader};
-#[derive(Clone, Copy, Debug)]
+use crate::audio::equalizer::EQ_BANDS;
+use crate::audio::resample::Resampler;
+use crate::audio::spectrum::compute_spectrum;
+
+use super::buffer::RingBuffer;
+#[cfg(any(feature = "openmpt", feature = "midi"))]
+use super::decode::RenderedSong;
+use super::decode::{decode_loop, stream_loop, PacketDecoder, PacketReader, SongDecoder, Source};
+use super::output::choose_output_config;
 
+// how far ahead of playback songs are decoded
+const BUFFER_SECS: usize = 2;
+
+#[derive(Clone, Debug)]
 pub enum StreamEvent {
     Progress(Duration),
-    StreamEnded,
+    Samples {
+        samples: Vec<f32>,
+        sample_rate: u32,
+        // where `samples` goes back to once it is read
+        spare: mpsc::Sender<Vec<f32>>,
+    },
+    // the song is within the crossfade of its end
+    Ending,
+    // the song in this buffer played to its end
+    StreamEnded(Arc<RingBuffer>),
+}
+
+/// Requests to the output callback
+#[derive(Clone, Debug)]
+pub enum OutputRequest {
+    SetVolume(f32),
+    SetEqualizer([f32; EQ_BANDS]),
+    // fades the song out over the start of this one
+    FadeIn(Arc<RingBuffer>),
+    // fades the output to this level, 0 being silent and 1 full
+    FadeTo(f32),
 }
 
 #[derive(Clone, Debug)]
@@ -81,17 +120,66 @@ impl PlayerStreamEventListener {
     }
 }
 
+/// How the output callback plays songs
+#[derive(Clone, Copy, Debug)]
+pub struct OutputOptions {
+    pub volume: f32,
+    pub visualizer: bool,
+    pub crossfade: Duration,
+    // how long fading in and out to keep starts and stops from clicking
+    pub fade: Duration,
+    // gains of the equalizer's bands in dB
+    pub equalizer: [f32; EQ_BANDS],
+}
+
 pub struct PlayerStreamState {
     pub stream: Stream,
-    pub playback_loop_tx: mpsc::Sender<PlayerRequest>,
+    pub playback_loop_tx: mpsc::Sender<OutputRequest>,
+    // where playback is, shared with the output callback and the decoder
+    pub buffer: Arc<RingBuffer>,
+    pub output: OutputConfig,
+    // the song fading out, if the current one faded in over it
+    pub outgoing: Option<Arc<RingBuffer>>,
+    // the song reached its crossfade, the next one fades in when played
+    pub ending: bool,
+    pub paused: bool,
+}
+
+impl PlayerStreamState {
+    /// Fades the output to silence and waits for it to be played,
+    /// so cutting it off afterwards doesn't click
+    fn fade_out(&self, fade: Duration) {
+        if self.paused || fade.is_zero() {
+            return;
+        }
+        let _ = self.playback_loop_tx.send(OutputRequest::FadeTo(0.0));
+        thread::sleep(fade);
+    }
+}
+
+impl Drop for PlayerStreamState {
+    fn drop(&mut self) {
+        // lets the decoder threads finish
+        self.buffer.close();
+        if let Some(outgoing) = self.outgoing.as_ref() {
+            outgoing.close();
+        }
+    }
 }
 
 pub struct PlayerStream {
     event_tx: ServerEventSender,
     event_poller: PlayerStreamEventListener,
     device: cpal::Device,
+    // used for songs that don't tell their channels or sample rate
     stream_config: cpal::SupportedStreamConfig,
     state: Option<PlayerStreamState>,
+    visualizer: bool,
+    crossfade: Duration,
+    fade: Duration,
+    equalizer: [f32; EQ_BANDS],
+    #[cfg(feature = "midi")]
+    soundfont: Option<PathBuf>,
 }
 
 impl PlayerStream {
@@ -100,6 +188,9 @@ impl PlayerStream {
         player_res_tx: mpsc::Sender<DiziResult>,
         player_req_rx: mpsc::Receiver<PlayerRequest>,
         device: cpal::Device,
+        visualizer: bool,
+        crossfade: Duration,
+        equalizer: [f32; EQ_BANDS],
     ) -> DiziResult<Self> {
         let event_poller = PlayerStreamEventListener::new(player_res_tx, player_req_rx);
 
@@ -117,38 +208,65 @@ impl PlayerStream {
             device,
             stream_config,
             state: None,
+            visualizer,
+            crossfade,
+            fade: Duration::ZERO,
+            equalizer,
+            #[cfg(feature = "midi")]
+            soundfont: None,
         })
     }
 
+    #[cfg(feature = "midi")]
+    pub fn set_soundfont(&mut self, soundfont: Option<PathBuf>) {
+        self.soundfont = soundfont;
+    }
+
+    pub fn set_fade(&mut self, fade: Duration) {
+        self.fade = fade;
+    }
+
     pub fn pause(&mut self) -> DiziResult {
-        if let Some(state) = self.state.as_ref() {
-            state.stream.pause()?;
+        let fade = self.fade;
+        if let Some(state) = self.state.as_mut() {
+            if !state.paused {
+                state.fade_out(fade);
+                state.stream.pause()?;
+                state.paused = true;
+            }
         }
         Ok(())
     }
     pub fn resume(&mut self) -> DiziResult {
-        if let Some(state) = self.state.as_ref() {
+        if let Some(state) = self.state.as_mut() {
+            let _ = state.playback_loop_tx.send(OutputRequest::FadeTo(1.0));
             state.stream.play()?;
+            state.paused = false;
         }
         Ok(())
     }
     pub fn stop(&mut self) -> DiziResult {
-        self.state.take();
+        if let Some(state) = self.state.take() {
+            state.fade_out(self.fade);
+        }
         Ok(())
     }
-    pub fn fast_forward(&mut self, offset: Duration) -> DiziResult {
+    /// Throws away what was decoded ahead and has the decoder
+    /// seek the song's format reader to `position`
+    pub fn seek(&mut self, position: Duration) -> DiziResult {
         if let Some(state) = self.state.as_ref() {
-            state
-                .playback_loop_tx
-                .send(PlayerRequest::FastForward { offset })?;
+            state.buffer.seek(position);
+            self.send_progress()?;
         }
         Ok(())
     }
-    pub fn rewind(&mut self, offset: Duration) -> DiziResult {
+
+    /// Lets the server know where playback moved to,
+    /// the output callback doesn't while the stream is paused
+    fn send_progress(&self) -> DiziResult {
         if let Some(state) = self.state.as_ref() {
-            state
-                .playback_loop_tx
-                .send(PlayerRequest::Rewind { offset })?;
+            self.event_tx
+                .send(ServerEvent::PlayerProgressUpdate(state.buffer.elapsed()))?;
         }
         Ok(())
     }
@@ -157,7 +275,7 @@ impl PlayerStream {
         if let Some(state) = self.state.as_ref() {
             let _ = state
                 .playback_loop_tx
-                .send(PlayerRequest::SetVolume { volume });
+                .send(OutputRequest::SetVolume(volume));
         }
     }
 
@@ -173,18 +291,42 @@ impl PlayerStream {
 
     fn process_player_req(&mut self, req: PlayerRequest) -> DiziResult {
         match req {
-            PlayerRequest::Play { song, volume } => {
-                let stream_res = self.play(song, volume);
+            PlayerRequest::Play {
+                song,
+                volume,
+                gain,
+                generation,
+            } => {
+                if self.state.as_ref().is_some_and(|state| state.ending) {
+                    let loaded = self.fade_in(&song, volume, gain);
+                    if loaded.is_err() {
+                        self.state = None;
+                    }
+                    self.event_tx
+                        .send(ServerEvent::PlayerLoaded { generation, loaded })?;
+                    return Ok(());
+                }
+                // fades out the song being played over
+                self.stop()?;
+                // answered through the server's events,
+                // so the server doesn't wait while the song is decoded
+                let stream_res = self.play(*song, volume, gain);
                 match stream_res {
-                    Ok(stream_res) => {
-                        let (stream, playback_loop_tx) = stream_res;
-                        self.state = Some(PlayerStreamState {
-                            stream,
-                            playback_loop_tx,
-                        });
-                        self.event_poller.player_res().send(Ok(()))?;
+                    Ok((state, loaded)) => {
+                        self.state = Some(state);
+                        self.event_tx.send(ServerEvent::PlayerLoaded {
+                            generation,
+                            loaded: Ok(loaded),
+                        })?;
+                    }
+                    Err(e) => {
+                        // the server already moved on from the previous song
+                        self.state = None;
+                        self.event_tx.send(ServerEvent::PlayerLoaded {
+                            generation,
+                            loaded: Err(e),
+                        })?;
                     }
-                    Err(e) => self.event_poller.player_res().send(Err(e))?,
                 };
             }
             PlayerRequest::Pause => {
@@ -203,11 +345,23 @@ impl PlayerStream {
                 self.set_volume(volume);
                 self.event_poller.player_res().send(Ok(()))?;
             }
-            PlayerRequest::FastForward { offset } => {
-                self.fast_forward(offset)?;
+            PlayerRequest::SetEqualizer(gains) => {
+                self.equalizer = gains;
+                if let Some(state) = self.state.as_ref() {
+                    let _ = state
+                        .playback_loop_tx
+                        .send(OutputRequest::SetEqualizer(gains));
+                }
+                self.event_poller.player_res().send(Ok(()))?;
+            }
+            PlayerRequest::SetGain(gain) => {
+                if let Some(state) = self.state.as_ref() {
+                    state.buffer.set_gain(gain);
+                }
+                self.event_poller.player_res().send(Ok(()))?;
             }
-            PlayerRequest::Rewind { offset } => {
-                self.rewind(offset)?;
+            PlayerRequest::SeekTo(position) => {
+                self.seek(position)?;
             }
         }
         Ok(())
@@ -215,194 +369,307 @@ impl PlayerStream {
 
     fn process_stream_event(&mut self, event: StreamEvent) -> DiziResult {
         match event {
-            StreamEvent::StreamEnded => {
-                self.stop()?;
-                self.event_tx.send(ServerEvent::PlayerDone)?;
+            StreamEvent::StreamEnded(buffer) => {
+                // the song ended before the next one faded in over it,
+                // which was already played and is the one playing now
+                let playing = self
+                    .state
+                    .as_ref()
+                    .is_some_and(|state| Arc::ptr_eq(&state.buffer, &buffer));
+                if playing {
+                    // nothing is left to fade out
+                    self.state = None;
+                    self.event_tx.send(ServerEvent::PlayerDone)?;
+                }
+            }
+            StreamEvent::Ending => {
+                if let Some(state) = self.state.as_mut() {
+                    state.ending = true;
+                }
+                self.event_tx.send(ServerEvent::PlayerEnding)?;
             }
             StreamEvent::Progress(duration) => {
                 self.event_tx
                     .send(ServerEvent::PlayerProgressUpdate(duration))?;
             }
+            StreamEvent::Samples {
+                samples,
+                sample_rate,
+                spare,
+            } => {
+                let bands = compute_spectrum(&samples, sample_rate);
+                let _ = spare.send(samples);
+                self.event_tx
+                    .send(ServerEvent::PlayerSpectrumUpdate(bands))?;
+            }
         }
         Ok(())
     }
 
+    /// Opens the song to be decoded while it plays, along with the sample rate
+    /// it is decoded at. Tracker modules and midi files are rendered whole first
+    #[cfg_attr(
+        not(any(feature = "openmpt", feature = "midi")),
+        allow(unused_variables)
+    )]
+    fn open(
+        &self,
+        audio_file: &DiziAudioFile,
+        channels: u16,
+        sample_rate: u32,
+        output_rate: u32,
+    ) -> DiziResult<(Box<dyn Source>, u32)> {
+        let path = audio_file.file_path();
+        // these render at any rate, so they never need resampling
+        #[cfg(feature = "openmpt")]
+        if tracker::is_tracker_file(path) {
+            let samples = tracker::decode(path, output_rate)?;
+            let song = RenderedSong::new(samples, channels, output_rate);
+            return Ok((Box::new(song), output_rate));
+        }
+        #[cfg(feature = "midi")]
+        if dizi::midi::is_midi_file(path) {
+            let soundfont = self.soundfont.as_deref().ok_or_else(|| {
+                DiziError::new(
+                    DiziErrorKind::InvalidParameters,
+                    "No soundfont set to play midi files with".to_string(),
+                )
+            })?;
+            let samples = midi::render(path, soundfont, output_rate)?;
+            let song = RenderedSong::new(samples, channels, output_rate);
+            return Ok((Box::new(song), output_rate));
+        }
+
+        let (format, decoder) = open_song(audio_file)?;
+        let track_id = audio_file.audio_metadata.track_id;
+        let song = SongDecoder::new(format, track_id, decoder, channels, sample_rate);
+        Ok((Box::new(song), sample_rate))
+    }
+
+    /// Channels and sample rate of a song, the output's for songs that don't tell
+    fn song_config(&self, audio_file: &DiziAudioFile) -> (u16, u32) {
+        let channels = audio_file
+            .audio_metadata
+            .channels
+            .map(|c| c as u16)
+            .unwrap_or_else(|| self.stream_config.channels());
+        let sample_rate = audio_file
+            .audio_metadata
+            .sample_rate
+            .unwrap_or_else(|| self.stream_config.sample_rate().0);
+        (channels, sample_rate)
+    }
+
+    /// Starts decoding a song into a buffer, in the output's channels and sample rate
+    fn decode(
+        &self,
+        audio_file: &DiziAudioFile,
+        output_channels: u16,
+        output_rate: u32,
+        gain: f32,
+    ) -> DiziResult<(Arc<RingBuffer>, LoadedSong)> {
+        let (channels, sample_rate) = self.song_config(audio_file);
+        let (source, sample_rate) = self.open(audio_file, channels, sample_rate, output_rate)?;
+        let duration = audio_file.audio_metadata.total_duration;
+        let rate = output_channels as usize * output_rate as usize;
+        let buffer = Arc::new(RingBuffer::new(
+            rate * BUFFER_SECS,
+            output_channels,
+            output_rate,
+            duration.map(|duration| (duration.as_secs_f64() * rate as f64) as usize),
+        ));
+        buffer.set_gain(gain);
+        let resampler = Resampler::new(output_channels, sample_rate, output_rate);
+        let decoder_buffer = Arc::clone(&buffer);
+        let _ = thread::spawn(move || decode_loop(source, channels, resampler, decoder_buffer));
+
+        let loaded = LoadedSong {
+            duration,
+            output: OutputConfig {
+                channels: output_channels,
+                sample_rate: output_rate,
+                sample_format: String::new(),
+                resampled_from: (sample_rate != output_rate).then_some(sample_rate),
+            },
+        };
+        Ok((buffer, loaded))
+    }
+
+    /// Plays a song over the end of the one ending, in the same stream
+    /// so the two can be mixed. It is resampled to the stream's sample rate
+    fn fade_in(
+        &mut self,
+        audio_file: &DiziAudioFile,
+        volume: f32,
+        gain: f32,
+    ) -> DiziResult<LoadedSong> {
+        let (channels, sample_rate) = match self.state.as_ref() {
+            Some(state) => (state.output.channels, state.output.sample_rate),
+            None => {
+                return Err(DiziError::new(
+                    DiziErrorKind::Server,
+                    "Nothing playing to fade out".to_string(),
+                ))
+            }
+        };
+        let (buffer, mut loaded) = self.decode(audio_file, channels, sample_rate, gain)?;
+        if let Some(state) = self.state.as_mut() {
+            loaded.output.sample_format = state.output.sample_format.clone();
+            let _ = state
+                .playback_loop_tx
+                .send(OutputRequest::SetVolume(volume));
+            let _ = state
+                .playback_loop_tx
+                .send(OutputRequest::FadeIn(Arc::clone(&buffer)));
+            let outgoing = std::mem::replace(&mut state.buffer, buffer);
+            if let Some(previous) = state.outgoing.replace(outgoing) {
+                previous.close();
+            }
+            state.ending = false;
+            state.output = loaded.output.clone();
+            // the ending song may have been paused
+            let _ = state.playback_loop_tx.send(OutputRequest::FadeTo(1.0));
+            state.stream.play()?;
+            state.paused = false;
+        }
+        Ok(loaded)
+    }
+
     pub fn play(
         &self,
         audio_file: DiziAudioFile,
         volume: f32,
-    ) -> DiziResult<(Stream, mpsc::Sender<PlayerRequest>)> {
-        let track_id = audio_file.audio_metadata.track_id;
+        gain: f32,
+    ) -> DiziResult<(PlayerStreamState, LoadedSong)> {
+        let (channels, sample_rate) = self.song_config(&audio_file);
 
-        let probe_result = audio_file.file.get_probe_result()?;
+        let output_config =
+            choose_output_config(&self.device, channels, sample_rate, &self.stream_config);
+        let audio_config = output_config.config();
+        let output_rate = audio_config.sample_rate.0;
 
-        let codec_params = probe_result
-            .format
-            .default_track()
-            .map(|t| &t.codec_params)
-            .ok_or_else(|| {
-                let error_msg = "Failed to get default track codec_params";
-                tracing::error!("{error_msg}");
-                DiziError::new(DiziErrorKind::Symphonia, error_msg.to_string())
-            })?;
+        tracing::debug!("audio_config: {:#?}", audio_config);
 
-        // Use the default options for the decoder.
-        let dec_opts: DecoderOptions = Default::default();
-
-        // Create a decoder for the track.
-        let decoder = symphonia::default::get_codecs().make(&codec_params, &dec_opts)?;
-
-        let audio_config = cpal::StreamConfig {
-            channels: audio_file
-                .audio_metadata
-                .channels
-                .map(|c| c as u16)
-                .unwrap_or_else(|| self.stream_config.channels()),
-            sample_rate: cpal::SampleRate(
-                audio_file
-                    .audio_metadata
-                    .sample_rate
-                    .unwrap_or_else(|| self.stream_config.sample_rate().0),
+        let stream_tx = self.event_poller.stream_tx.clone();
+
+        let (buffer, mut loaded) =
+            self.decode(&audio_file, audio_config.channels, output_rate, gain)?;
+        loaded.output.sample_format = output_config.sample_format().to_string();
+
+        let device = &self.device;
+        let options = OutputOptions {
+            volume,
+            visualizer: self.visualizer,
+            crossfade: self.crossfade,
+            fade: self.fade,
+            equalizer: self.equalizer,
+        };
+        let (stream, playback_loop_tx) = match output_config.sample_format() {
+            cpal::SampleFormat::U8 => stream_loop::<u8>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+            cpal::SampleFormat::U16 => stream_loop::<u16>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+            cpal::SampleFormat::U32 => stream_loop::<u32>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+            cpal::SampleFormat::I8 => stream_loop::<i8>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+            cpal::SampleFormat::I16 => stream_loop::<i16>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+            cpal::SampleFormat::I32 => stream_loop::<i32>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+            cpal::SampleFormat::F32 => stream_loop::<f32>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
             ),
-            buffer_size: cpal::BufferSize::Default,
+            _ => stream_loop::<f64>(
+                stream_tx,
+                device,
+                &audio_config,
+                Arc::clone(&buffer),
+                options,
+            ),
+        }?;
+        let state = PlayerStreamState {
+            stream,
+            playback_loop_tx,
+            buffer,
+            output: loaded.output.clone(),
+            outgoing: None,
+            ending: false,
+            paused: false,
         };
+        Ok((state, loaded))
+    }
+}
 
-        tracing::debug!("audio_config: {:#?}", audio_config);
+/// The format reader and decoder of a song symphonia reads
+fn open_song(audio_file: &DiziAudioFile) -> DiziResult<(Box<dyn FormatReader>, Box<dyn Decoder>)> {
+    let probe_result = audio_file.file.get_probe_result()?;
+
+    let codec_params = probe_result
+        .format
+        .default_track()
+        .map(|t| &t.codec_params)
+        .ok_or_else(|| {
+            let error_msg = "Failed to get default track codec_params";
+            tracing::error!("{error_msg}");
+            DiziError::new(DiziErrorKind::Symphonia, error_msg.to_string())
+        })?;
 
-        let stream_tx = self.event_poller.stream_tx.clone();
+    // Use the default options for the decoder.
+    let dec_opts: DecoderOptions = Default::default();
 
-        let packet_reader = PacketReader::new(probe_result.format, track_id);
-        let mut packet_decoder = PacketDecoder::new(decoder);
+    // Create a decoder for the track.
+    let decoder = symphonia::default::get_codecs().make(codec_params, &dec_opts)?;
+    Ok((probe_result.format, decoder))
+}
 
-        match self.stream_config.sample_format() {
-            cpal::SampleFormat::U8 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<u8>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<u8>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| ((packet as f32) * volume) as u8,
-                )?;
-                Ok(res)
-            }
-            cpal::SampleFormat::U16 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<u16>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<u16>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| ((packet as f32) * volume) as u16,
-                )?;
-                Ok(res)
-            }
-            cpal::SampleFormat::U32 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<u32>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<u32>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| ((packet as f32) * volume) as u32,
-                )?;
-                Ok(res)
-            }
-            cpal::SampleFormat::I8 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<i8>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<i8>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| ((packet as f32) * volume) as i8,
-                )?;
-                Ok(res)
-            }
-            cpal::SampleFormat::I16 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<i16>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<i16>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| ((packet as f32) * volume) as i16,
-                )?;
-                Ok(res)
-            }
-            cpal::SampleFormat::I32 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<i32>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<i32>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| ((packet as f32) * volume) as i32,
-                )?;
-                Ok(res)
-            }
-            cpal::SampleFormat::F32 => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<f32>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<f32>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| packet * volume,
-                )?;
-                Ok(res)
-            }
-            _ => {
-                let mut samples = Vec::new();
-                for packet in packet_reader {
-                    let packet_sample = packet_decoder.decode::<f64>(packet)?;
-                    samples.extend(packet_sample);
-                }
-                let res = stream_loop::<f64>(
-                    stream_tx,
-                    &self.device,
-                    &audio_config,
-                    samples,
-                    volume,
-                    |packet, volume| (packet * volume as f64) as f64,
-                )?;
-                Ok(res)
-            }
-        }
+/// Decodes the whole of a song symphonia reads, along with its length if the container knows it
+pub fn decode_song(audio_file: &DiziAudioFile) -> DiziResult<(Vec<f32>, Option<Duration>)> {
+    let track_id = audio_file.audio_metadata.track_id;
+    let (format, decoder) = open_song(audio_file)?;
+    let duration = track_duration(decoder.codec_params());
+
+    let packet_reader = PacketReader::new(format, track_id);
+    let mut packet_decoder = PacketDecoder::new(decoder);
+
+    let mut samples: Vec<f32> = Vec::new();
+    for packet in packet_reader {
+        let packet_sample = packet_decoder.decode::<f32>(packet)?;
+        samples.extend(packet_sample);
     }
+    Ok((samples, duration))
 }
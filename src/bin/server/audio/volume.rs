@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+// how far below full volume the logarithmic curve starts, in dB
+const LOG_RANGE_DB: f32 = 60.0;
+
+/// How the volume's percentage maps to what samples are multiplied by
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeCurve {
+    #[default]
+    Linear,
+    // even steps in dB, closest to how loud it sounds
+    Logarithmic,
+    // between the two, quiet at low percentages without falling off as quickly
+    Cubic,
+}
+
+impl VolumeCurve {
+    /// The factor samples are played at for `volume` out of 100
+    pub fn amplitude(self, volume: usize) -> f32 {
+        let volume = volume.min(100) as f32 / 100.0;
+        match self {
+            Self::Linear => volume,
+            Self::Logarithmic if volume == 0.0 => 0.0,
+            Self::Logarithmic => 10f32.powf((volume - 1.0) * LOG_RANGE_DB / 20.0),
+            Self::Cubic => volume * volume * volume,
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::Path;
+
+use dizi::error::DiziResult;
+use dizi::song::{DiziAudioFile, DiziFile};
+
+use crate::audio::symphonia::stream::decode_song;
+
+/// Peaks kept of each song, requests for fewer are downsampled from them
+pub const WAVEFORM_RESOLUTION: usize = 1024;
+
+/// Loudest sample of `WAVEFORM_RESOLUTION` even stretches of interleaved `samples`,
+/// scaled to `0..=255` relative to the loudest of them
+pub fn compute_waveform(samples: &[f32], channels: u16) -> Vec<u8> {
+    let channels = channels.max(1) as usize;
+    let frames = samples.len() / channels;
+    if frames == 0 {
+        return Vec::new();
+    }
+    let chunk = frames.div_ceil(WAVEFORM_RESOLUTION) * channels;
+    let peaks: Vec<f32> = samples
+        .chunks(chunk)
+        .map(|chunk| chunk.iter().fold(0.0, |peak: f32, s| peak.max(s.abs())))
+        .collect();
+    let loudest = peaks.iter().cloned().fold(0.0, f32::max);
+    if loudest <= 0.0 {
+        return vec![0; peaks.len()];
+    }
+    peaks
+        .iter()
+        .map(|peak| (peak / loudest * 255.0).round() as u8)
+        .collect()
+}
+
+/// Takes the loudest of each group of `peaks` to make `buckets` of them,
+/// there are never more buckets than peaks
+pub fn downsample(peaks: &[u8], buckets: usize) -> Vec<u8> {
+    if buckets == 0 || buckets >= peaks.len() {
+        return peaks.to_vec();
+    }
+    (0..buckets)
+        .map(|bucket| {
+            let start = bucket * peaks.len() / buckets;
+            let end = (bucket + 1) * peaks.len() / buckets;
+            peaks[start..end].iter().max().cloned().unwrap_or(0)
+        })
+        .collect()
+}
+
+/// Decodes the song at `path`, for songs that weren't played yet
+pub fn read_waveform(path: &Path) -> DiziResult<Vec<u8>> {
+    #[cfg(feature = "openmpt")]
+    if dizi::tracker::is_tracker_file(path) {
+        // rendered in stereo
+        let samples = dizi::tracker::decode(path, 44100)?;
+        return Ok(compute_waveform(&samples, 2));
+    }
+    let audio_file = DiziAudioFile::try_from(DiziFile::new(path))?;
+    let (samples, _) = decode_song(&audio_file)?;
+    let channels = audio_file.audio_metadata.channels.unwrap_or(2) as u16;
+    Ok(compute_waveform(&samples, channels))
+}
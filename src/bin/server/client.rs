@@ -1,6 +1,6 @@
 use std::io::{BufRead, BufReader, Write};
 use std::os::unix::net::UnixStream;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use std::thread;
 
 use dizi::error::DiziResult;
@@ -13,7 +13,7 @@ use crate::events::{ClientRequestSender, ServerBroadcastEventReceiver};
 #[derive(Clone, Debug)]
 pub enum ClientMessage {
     Client(String),
-    Server(Box<ServerBroadcastEvent>),
+    Server(Arc<ServerBroadcastEvent>),
 }
 
 pub fn handle_client(
@@ -29,7 +29,7 @@ pub fn handle_client(
     let _ = thread::spawn(move || {
         while let Ok(server_event) = server_event_rx.recv() {
             if event_tx_clone
-                .send(ClientMessage::Server(Box::new(server_event)))
+                .send(ClientMessage::Server(server_event))
                 .is_err()
             {
                 return;
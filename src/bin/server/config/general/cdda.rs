@@ -0,0 +1,55 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use shellexpand::tilde_with_context;
+
+fn default_rip_dir_string() -> String {
+    "~/.cache/dizi/cdda".to_string()
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CddaOptionRaw {
+    #[serde(default)]
+    pub device: Option<String>,
+    #[serde(default = "default_rip_dir_string")]
+    pub rip_dir: String,
+    #[serde(default)]
+    pub cddb_server: Option<String>,
+}
+
+impl std::default::Default for CddaOptionRaw {
+    fn default() -> Self {
+        Self {
+            device: None,
+            rip_dir: default_rip_dir_string(),
+            cddb_server: None,
+        }
+    }
+}
+
+impl From<CddaOptionRaw> for CddaOption {
+    fn from(crude: CddaOptionRaw) -> Self {
+        let rip_dir = tilde_with_context(&crude.rip_dir, dirs_next::home_dir);
+        Self {
+            device: crude.device,
+            rip_dir: PathBuf::from(rip_dir.as_ref()),
+            cddb_server: crude.cddb_server,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct CddaOption {
+    // cdparanoia's default drive when not set
+    pub device: Option<String>,
+    // tracks are ripped here before they are played
+    pub rip_dir: PathBuf,
+    // `host:port` of a CDDB server to name the tracks with, none to not look them up
+    pub cddb_server: Option<String>,
+}
+
+impl std::default::Default for CddaOption {
+    fn default() -> Self {
+        Self::from(CddaOptionRaw::default())
+    }
+}
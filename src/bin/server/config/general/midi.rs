@@ -0,0 +1,26 @@
+use std::path::PathBuf;
+
+use serde::Deserialize;
+use shellexpand::tilde_with_context;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct MidiOptionRaw {
+    #[serde(default)]
+    pub soundfont: Option<String>,
+}
+
+impl From<MidiOptionRaw> for MidiOption {
+    fn from(crude: MidiOptionRaw) -> Self {
+        let soundfont = crude.soundfont.map(|s| {
+            let s = tilde_with_context(&s, dirs_next::home_dir);
+            PathBuf::from(s.as_ref())
+        });
+        Self { soundfont }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct MidiOption {
+    // SoundFont (.sf2) midi files are played with, they aren't playable without one
+    pub soundfont: Option<PathBuf>,
+}
@@ -1,7 +1,19 @@
 pub mod app;
+#[cfg(feature = "cdda")]
+pub mod cdda;
+#[cfg(feature = "midi")]
+pub mod midi;
 pub mod player;
 pub mod server;
+#[cfg(feature = "web")]
+pub mod web;
 
 pub use self::app::*;
+#[cfg(feature = "cdda")]
+pub use self::cdda::*;
+#[cfg(feature = "midi")]
+pub use self::midi::*;
 pub use self::player::*;
 pub use self::server::*;
+#[cfg(feature = "web")]
+pub use self::web::*;
@@ -1,5 +1,12 @@
+use std::time;
+
 use serde::Deserialize;
 
+use dizi::player::RepeatMode;
+use dizi::song::ReplayGainMode;
+
+use crate::audio::volume::VolumeCurve;
+
 const fn default_true() -> bool {
     true
 }
@@ -8,25 +15,85 @@ const fn default_volume() -> usize {
     50
 }
 
+const fn default_volume_max() -> usize {
+    100
+}
+
+// 20 minutes, longer than most songs
+const fn default_resume_min_length() -> u64 {
+    20 * 60
+}
+
+// long enough to keep starts and stops from clicking
+const fn default_fade_ms() -> u64 {
+    10
+}
+
+const fn default_repeat() -> RepeatModeRaw {
+    RepeatModeRaw::Mode(RepeatMode::All)
+}
+
+// `true` and `false` are read as they were before there were modes
+#[derive(Clone, Copy, Debug, Deserialize)]
+#[serde(untagged)]
+pub enum RepeatModeRaw {
+    Bool(bool),
+    Mode(RepeatMode),
+}
+
+impl From<RepeatModeRaw> for RepeatMode {
+    fn from(crude: RepeatModeRaw) -> Self {
+        match crude {
+            RepeatModeRaw::Bool(true) => RepeatMode::All,
+            RepeatModeRaw::Bool(false) => RepeatMode::Off,
+            RepeatModeRaw::Mode(mode) => mode,
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct PlayerOptionRaw {
     #[serde(default)]
     pub shuffle: bool,
-    #[serde(default = "default_true")]
-    pub repeat: bool,
+    #[serde(default = "default_repeat")]
+    pub repeat: RepeatModeRaw,
     #[serde(default = "default_true")]
     pub next: bool,
     #[serde(default = "default_volume")]
     pub volume: usize,
+    #[serde(default = "default_volume_max")]
+    pub volume_max: usize,
+    #[serde(default)]
+    pub volume_curve: VolumeCurve,
+    #[serde(default = "default_true")]
+    pub visualizer: bool,
+    #[serde(default = "default_resume_min_length")]
+    pub resume_min_length: u64,
+    #[serde(default)]
+    pub chapter_skip: bool,
+    #[serde(default)]
+    pub crossfade_secs: u64,
+    #[serde(default = "default_fade_ms")]
+    pub fade_ms: u64,
+    #[serde(default)]
+    pub replaygain: ReplayGainMode,
 }
 
 impl std::default::Default for PlayerOptionRaw {
     fn default() -> Self {
         Self {
             shuffle: false,
-            repeat: true,
+            repeat: default_repeat(),
             next: true,
             volume: default_volume(),
+            volume_max: default_volume_max(),
+            volume_curve: VolumeCurve::Linear,
+            visualizer: true,
+            resume_min_length: default_resume_min_length(),
+            chapter_skip: false,
+            crossfade_secs: 0,
+            fade_ms: default_fade_ms(),
+            replaygain: ReplayGainMode::Off,
         }
     }
 }
@@ -35,9 +102,17 @@ impl From<PlayerOptionRaw> for PlayerOption {
     fn from(crude: PlayerOptionRaw) -> Self {
         Self {
             shuffle: crude.shuffle,
-            repeat: crude.repeat,
+            repeat: crude.repeat.into(),
             next: crude.next,
             volume: crude.volume,
+            volume_max: crude.volume_max.min(100),
+            volume_curve: crude.volume_curve,
+            visualizer: crude.visualizer,
+            resume_min_length: time::Duration::from_secs(crude.resume_min_length),
+            chapter_skip: crude.chapter_skip,
+            crossfade: time::Duration::from_secs(crude.crossfade_secs),
+            fade: time::Duration::from_millis(crude.fade_ms),
+            replaygain: crude.replaygain,
         }
     }
 }
@@ -45,18 +120,45 @@ impl From<PlayerOptionRaw> for PlayerOption {
 #[derive(Clone, Debug)]
 pub struct PlayerOption {
     pub shuffle: bool,
-    pub repeat: bool,
+    pub repeat: RepeatMode,
     pub next: bool,
     pub volume: usize,
+    // the volume can't be set above this
+    pub volume_max: usize,
+    pub volume_curve: VolumeCurve,
+    pub visualizer: bool,
+    // songs at least this long resume where they were left off, zero never does
+    pub resume_min_length: time::Duration,
+    // next and previous move between the chapters of a song before moving to another song
+    pub chapter_skip: bool,
+    // how long the end of a song overlaps the start of the next, zero never does
+    pub crossfade: time::Duration,
+    // how long playback fades in when it starts or resumes and out when it stops or pauses
+    pub fade: time::Duration,
+    pub replaygain: ReplayGainMode,
+}
+
+impl PlayerOption {
+    pub fn resumes(&self, duration: time::Duration) -> bool {
+        !self.resume_min_length.is_zero() && duration >= self.resume_min_length
+    }
 }
 
 impl std::default::Default for PlayerOption {
     fn default() -> Self {
         Self {
             shuffle: false,
-            repeat: true,
+            repeat: RepeatMode::All,
             next: true,
             volume: default_volume(),
+            volume_max: default_volume_max(),
+            volume_curve: VolumeCurve::Linear,
+            visualizer: true,
+            resume_min_length: time::Duration::from_secs(default_resume_min_length()),
+            chapter_skip: false,
+            crossfade: time::Duration::ZERO,
+            fade: time::Duration::from_millis(default_fade_ms()),
+            replaygain: ReplayGainMode::Off,
         }
     }
 }
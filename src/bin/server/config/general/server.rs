@@ -4,7 +4,13 @@ use std::path::{Path, PathBuf};
 use serde::Deserialize;
 use shellexpand::tilde_with_context;
 
+#[cfg(feature = "cdda")]
+use super::{CddaOption, CddaOptionRaw};
+#[cfg(feature = "midi")]
+use super::{MidiOption, MidiOptionRaw};
 use super::{PlayerOption, PlayerOptionRaw};
+#[cfg(feature = "web")]
+use super::{WebOption, WebOptionRaw};
 
 fn default_socket_string() -> String {
     "~/dizi-server-socket".to_string()
@@ -14,6 +20,18 @@ fn default_playlist_string() -> String {
     "~/dizi-playlist.m3u".to_string()
 }
 
+fn default_ratings_string() -> String {
+    "~/dizi-ratings.json".to_string()
+}
+
+fn default_positions_string() -> String {
+    "~/dizi-positions.json".to_string()
+}
+
+fn default_equalizer_string() -> String {
+    "~/dizi-equalizer.json".to_string()
+}
+
 fn default_socket_path() -> PathBuf {
     let s = default_socket_string();
     PathBuf::from(tilde_with_context(&s, dirs_next::home_dir).as_ref())
@@ -24,6 +42,21 @@ fn default_playlist_path() -> PathBuf {
     PathBuf::from(tilde_with_context(&s, dirs_next::home_dir).as_ref())
 }
 
+fn default_ratings_path() -> PathBuf {
+    let s = default_ratings_string();
+    PathBuf::from(tilde_with_context(&s, dirs_next::home_dir).as_ref())
+}
+
+fn default_positions_path() -> PathBuf {
+    let s = default_positions_string();
+    PathBuf::from(tilde_with_context(&s, dirs_next::home_dir).as_ref())
+}
+
+fn default_equalizer_path() -> PathBuf {
+    let s = default_equalizer_string();
+    PathBuf::from(tilde_with_context(&s, dirs_next::home_dir).as_ref())
+}
+
 fn default_audio_system() -> cpal::HostId {
     #[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd"))]
     {
@@ -68,12 +101,29 @@ pub struct ServerConfigRaw {
     pub socket: String,
     #[serde(default = "default_playlist_string")]
     pub playlist: String,
+    #[serde(default = "default_ratings_string")]
+    pub ratings: String,
+    #[serde(default = "default_positions_string")]
+    pub positions: String,
+    #[serde(default = "default_equalizer_string")]
+    pub equalizer: String,
     #[serde(default = "default_audio_system_string")]
     pub audio_system: String,
     #[serde(default)]
     pub on_song_change: Option<String>,
     #[serde(default)]
+    pub root: Option<String>,
+    #[serde(default)]
     pub player: PlayerOptionRaw,
+    #[cfg(feature = "cdda")]
+    #[serde(default)]
+    pub cdda: CddaOptionRaw,
+    #[cfg(feature = "midi")]
+    #[serde(default)]
+    pub midi: MidiOptionRaw,
+    #[cfg(feature = "web")]
+    #[serde(default)]
+    pub web: WebOptionRaw,
 }
 
 impl std::default::Default for ServerConfigRaw {
@@ -81,9 +131,19 @@ impl std::default::Default for ServerConfigRaw {
         Self {
             socket: default_socket_string(),
             playlist: default_playlist_string(),
+            ratings: default_ratings_string(),
+            positions: default_positions_string(),
+            equalizer: default_equalizer_string(),
             audio_system: default_audio_system_string(),
             on_song_change: None,
+            root: None,
             player: PlayerOptionRaw::default(),
+            #[cfg(feature = "cdda")]
+            cdda: CddaOptionRaw::default(),
+            #[cfg(feature = "midi")]
+            midi: MidiOptionRaw::default(),
+            #[cfg(feature = "web")]
+            web: WebOptionRaw::default(),
         }
     }
 }
@@ -92,9 +152,22 @@ impl std::default::Default for ServerConfigRaw {
 pub struct ServerConfig {
     pub socket: PathBuf,
     pub playlist: PathBuf,
+    pub ratings: PathBuf,
+    // where long songs were left off
+    pub positions: PathBuf,
+    // gains of the equalizer's bands
+    pub equalizer: PathBuf,
     pub audio_system: cpal::HostId,
     pub on_song_change: Option<PathBuf>,
+    // the only directory clients may browse and add songs from
+    pub root: Option<PathBuf>,
     pub player: PlayerOption,
+    #[cfg(feature = "cdda")]
+    pub cdda: CddaOption,
+    #[cfg(feature = "midi")]
+    pub midi: MidiOption,
+    #[cfg(feature = "web")]
+    pub web: WebOption,
 }
 
 impl ServerConfig {
@@ -104,9 +177,33 @@ impl ServerConfig {
     pub fn playlist_ref(&self) -> &Path {
         self.playlist.as_path()
     }
+    pub fn ratings_ref(&self) -> &Path {
+        self.ratings.as_path()
+    }
+    pub fn positions_ref(&self) -> &Path {
+        self.positions.as_path()
+    }
+    pub fn equalizer_ref(&self) -> &Path {
+        self.equalizer.as_path()
+    }
+    pub fn root_ref(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
     pub fn player_ref(&self) -> &PlayerOption {
         &self.player
     }
+    #[cfg(feature = "cdda")]
+    pub fn cdda_ref(&self) -> &CddaOption {
+        &self.cdda
+    }
+    #[cfg(feature = "midi")]
+    pub fn midi_ref(&self) -> &MidiOption {
+        &self.midi
+    }
+    #[cfg(feature = "web")]
+    pub fn web_ref(&self) -> &WebOption {
+        &self.web
+    }
 }
 
 impl std::default::Default for ServerConfig {
@@ -114,9 +211,19 @@ impl std::default::Default for ServerConfig {
         Self {
             socket: default_socket_path(),
             playlist: default_playlist_path(),
+            ratings: default_ratings_path(),
+            positions: default_positions_path(),
+            equalizer: default_equalizer_path(),
             audio_system: default_audio_system(),
             on_song_change: None,
+            root: None,
             player: PlayerOption::default(),
+            #[cfg(feature = "cdda")]
+            cdda: CddaOption::default(),
+            #[cfg(feature = "midi")]
+            midi: MidiOption::default(),
+            #[cfg(feature = "web")]
+            web: WebOption::default(),
         }
     }
 }
@@ -128,16 +235,32 @@ impl From<ServerConfigRaw> for ServerConfig {
 
         let socket = tilde_with_context(&raw.socket, dirs_next::home_dir);
         let playlist = tilde_with_context(&raw.playlist, dirs_next::home_dir);
+        let ratings = tilde_with_context(&raw.ratings, dirs_next::home_dir);
+        let positions = tilde_with_context(&raw.positions, dirs_next::home_dir);
+        let equalizer = tilde_with_context(&raw.equalizer, dirs_next::home_dir);
         let on_song_change = raw
             .on_song_change
             .map(|path| PathBuf::from(tilde_with_context(&path, dirs_next::home_dir).as_ref()));
+        let root = raw
+            .root
+            .map(|path| PathBuf::from(tilde_with_context(&path, dirs_next::home_dir).as_ref()));
 
         Self {
             socket: PathBuf::from(socket.as_ref()),
             playlist: PathBuf::from(playlist.as_ref()),
+            ratings: PathBuf::from(ratings.as_ref()),
+            positions: PathBuf::from(positions.as_ref()),
+            equalizer: PathBuf::from(equalizer.as_ref()),
             audio_system,
             on_song_change,
+            root,
             player: PlayerOption::from(raw.player),
+            #[cfg(feature = "cdda")]
+            cdda: CddaOption::from(raw.cdda),
+            #[cfg(feature = "midi")]
+            midi: MidiOption::from(raw.midi),
+            #[cfg(feature = "web")]
+            web: WebOption::from(raw.web),
         }
     }
 }
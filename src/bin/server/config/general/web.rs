@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct WebOptionRaw {
+    #[serde(default)]
+    pub address: Option<String>,
+    #[serde(default)]
+    pub rest: bool,
+    #[serde(default)]
+    pub password: Option<String>,
+}
+
+impl From<WebOptionRaw> for WebOption {
+    fn from(crude: WebOptionRaw) -> Self {
+        Self {
+            address: crude.address,
+            rest: crude.rest,
+            password: crude.password.filter(|password| !password.is_empty()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct WebOption {
+    // host:port the web client is served on, not served without one
+    pub address: Option<String>,
+    // also answer requests such as `GET /player/state` on the same address
+    pub rest: bool,
+    // asked of the web client's WebSocket and of REST requests, if set
+    pub password: Option<String>,
+}
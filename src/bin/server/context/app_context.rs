@@ -1,5 +1,6 @@
 use crate::audio::symphonia::player::SymphoniaPlayer;
 use crate::config;
+use crate::context::{CrossfadeContext, LibraryContext, ScheduleContext};
 use crate::events::Events;
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -14,6 +15,9 @@ pub struct AppContext {
     pub events: Events,
     pub quit: QuitType,
     pub player: SymphoniaPlayer,
+    pub library: LibraryContext,
+    pub schedule: ScheduleContext,
+    pub crossfade: CrossfadeContext,
 }
 
 impl AppContext {
@@ -0,0 +1,57 @@
+/// Keeps a crossfaded song from moving the playlist on twice. The playlist moves on
+/// when the song reaches its crossfade, but the song can still end before
+/// the player got to the next one, which is reported as the song being done
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CrossfadeContext {
+    // the next song was asked for at the crossfade and hasn't loaded yet
+    pub advanced: bool,
+}
+
+impl CrossfadeContext {
+    /// The playing song reached its crossfade and the next one was played
+    pub fn advance(&mut self) {
+        self.advanced = true;
+    }
+
+    /// A song loaded, the one before it can no longer end on its own
+    pub fn loaded(&mut self) {
+        self.advanced = false;
+    }
+
+    /// The playing song ended, returns whether the playlist still has to move on
+    pub fn done(&mut self) -> bool {
+        !std::mem::take(&mut self.advanced)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ends_without_crossfade() {
+        let mut crossfade = CrossfadeContext::default();
+        assert!(crossfade.done());
+        crossfade.loaded();
+        assert!(crossfade.done());
+    }
+
+    #[test]
+    fn ends_before_next_loaded() {
+        let mut crossfade = CrossfadeContext::default();
+        crossfade.advance();
+        // the song ran out before the player started the next one
+        assert!(!crossfade.done());
+        crossfade.loaded();
+        // the next song then ends by itself
+        assert!(crossfade.done());
+    }
+
+    #[test]
+    fn next_loaded_over_crossfade() {
+        let mut crossfade = CrossfadeContext::default();
+        crossfade.advance();
+        crossfade.loaded();
+        assert!(crossfade.done());
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use dizi::error::DiziResult;
+
+/// Song ratings, from 1 to 5 stars, and where long songs were left off,
+/// kept across server restarts
+#[derive(Clone, Debug, Default)]
+pub struct LibraryContext {
+    ratings: HashMap<PathBuf, u8>,
+    // in seconds
+    positions: HashMap<PathBuf, u64>,
+    // peaks of songs decoded since the server started
+    waveforms: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl LibraryContext {
+    pub fn from_file(path: &Path) -> Self {
+        Self {
+            ratings: load_json(path),
+            ..Self::default()
+        }
+    }
+
+    /// Positions have a file of their own, so clearing them keeps the ratings
+    pub fn load_positions(&mut self, path: &Path) {
+        self.positions = load_json(path);
+    }
+
+    pub fn save_positions(&self, path: &Path) -> DiziResult {
+        save_json(path, &self.positions)
+    }
+
+    pub fn save(&self, path: &Path) -> DiziResult {
+        save_json(path, &self.ratings)
+    }
+
+    pub fn ratings_ref(&self) -> &HashMap<PathBuf, u8> {
+        &self.ratings
+    }
+
+    pub fn position(&self, path: &Path) -> Option<time::Duration> {
+        self.positions
+            .get(path)
+            .map(|secs| time::Duration::from_secs(*secs))
+    }
+
+    /// A position of 0 forgets the song's position
+    pub fn set_position(&mut self, path: PathBuf, position: time::Duration) {
+        if position.as_secs() == 0 {
+            self.positions.remove(&path);
+        } else {
+            self.positions.insert(path, position.as_secs());
+        }
+    }
+
+    pub fn waveform(&self, path: &Path) -> Option<&[u8]> {
+        self.waveforms.get(path).map(|peaks| peaks.as_slice())
+    }
+
+    pub fn set_waveform(&mut self, path: PathBuf, peaks: Vec<u8>) {
+        self.waveforms.insert(path, peaks);
+    }
+
+    /// A rating of 0 removes the song's rating
+    pub fn set_rating(&mut self, path: PathBuf, rating: u8) {
+        if rating == 0 {
+            self.ratings.remove(&path);
+        } else {
+            self.ratings.insert(path, rating);
+        }
+    }
+}
+
+/// A missing file is an empty map. One that can't be read is moved aside to
+/// `<path>.bak` before starting empty, so the next save doesn't lose it
+fn load_json<T: DeserializeOwned + Default>(path: &Path) -> T {
+    let res = fs::read_to_string(path)
+        .and_then(|contents| serde_json::from_str(&contents).map_err(io::Error::from));
+    match res {
+        Ok(value) => value,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => T::default(),
+        Err(err) => {
+            let backup = with_suffix(path, ".bak");
+            tracing::error!(
+                "Failed to read {:?}, moving it to {:?}: {}",
+                path,
+                backup,
+                err
+            );
+            if let Err(err) = fs::rename(path, &backup) {
+                tracing::error!("Failed to move {:?}: {}", path, err);
+            }
+            T::default()
+        }
+    }
+}
+
+/// Writes to a temporary file next to `path` first, so a crash midway
+/// leaves the previous file in place
+fn save_json<T: Serialize>(path: &Path, value: &T) -> DiziResult {
+    let contents = serde_json::to_string(value)?;
+    let tmp = with_suffix(path, ".tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = OsString::from(path.as_os_str());
+    name.push(suffix);
+    PathBuf::from(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dizi-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn missing_file_is_empty() {
+        let dir = temp_dir("library-missing");
+        let library = LibraryContext::from_file(&dir.join("ratings.json"));
+        assert!(library.ratings_ref().is_empty());
+        assert!(!dir.join("ratings.json.bak").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn save_and_load() {
+        let dir = temp_dir("library-save");
+        let path = dir.join("ratings.json");
+        let mut library = LibraryContext::default();
+        library.set_rating(PathBuf::from("/a.flac"), 4);
+        library.save(&path).unwrap();
+        assert!(!dir.join("ratings.json.tmp").exists());
+
+        let library = LibraryContext::from_file(&path);
+        assert_eq!(library.ratings_ref().get(Path::new("/a.flac")), Some(&4));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn invalid_file_is_backed_up() {
+        let dir = temp_dir("library-invalid");
+        let path = dir.join("ratings.json");
+        fs::write(&path, "{\"/a.flac\": 4,").unwrap();
+
+        let mut library = LibraryContext::from_file(&path);
+        assert!(library.ratings_ref().is_empty());
+        library.set_rating(PathBuf::from("/b.flac"), 2);
+        library.save(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(dir.join("ratings.json.bak")).unwrap(),
+            "{\"/a.flac\": 4,"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
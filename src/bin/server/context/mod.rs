@@ -1,5 +1,11 @@
 mod app_context;
+mod crossfade_context;
+mod library_context;
 mod playlist_context;
+mod schedule_context;
 
 pub use app_context::*;
+pub use crossfade_context::*;
+pub use library_context::*;
 pub use playlist_context::*;
+pub use schedule_context::*;
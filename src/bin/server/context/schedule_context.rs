@@ -0,0 +1,20 @@
+use dizi::player::PlaybackSchedule;
+
+/// Playback scheduled to start later, and the volume ramp it starts with
+#[derive(Clone, Debug, Default)]
+pub struct ScheduleContext {
+    // bumped whenever the schedule changes, so the timers of an older one are ignored
+    pub generation: u64,
+    pub schedule: Option<PlaybackSchedule>,
+    // volume the ramp ends at, while the volume is being raised
+    pub ramp_target: Option<usize>,
+}
+
+impl ScheduleContext {
+    /// Forgets the scheduled playback, returns the generation of the timers to start next
+    pub fn reset(&mut self) -> u64 {
+        self.generation += 1;
+        self.schedule = None;
+        self.generation
+    }
+}
@@ -1,19 +1,67 @@
 use std::collections::HashMap;
 use std::os::unix::net::UnixStream;
-use std::sync::mpsc;
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
 use std::thread;
 use std::time;
 
+use dizi::error::DiziResult;
+use dizi::player::OutputConfig;
 use dizi::request::client::ClientRequest;
 use dizi::response::server::ServerBroadcastEvent;
+use dizi::song::DiziAudioFile;
 
 #[derive(Debug)]
 pub enum ServerEvent {
     // new client
     NewClient(UnixStream),
+    // new browser, connected to the web client's WebSocket
+    #[cfg(feature = "web")]
+    NewWebClient(std::net::TcpStream),
+    // a request made through the REST api, only answered to `response_tx`
+    #[cfg(feature = "web")]
+    RestRequest {
+        request: ClientRequest,
+        response_tx: ServerBroadcastEventSender,
+    },
 
     PlayerProgressUpdate(time::Duration),
+    PlayerSpectrumUpdate(Vec<u8>),
     PlayerDone,
+    // the song is within the crossfade of its end
+    PlayerEnding,
+    // the song of the play request of this generation is playing, or failed to
+    PlayerLoaded {
+        generation: u64,
+        loaded: DiziResult<LoadedSong>,
+    },
+    // reading or ripping the audio CD failed
+    #[cfg(feature = "cdda")]
+    CddaError(dizi::error::DiziError),
+
+    // the time of the schedule of this generation has come
+    ScheduleStart(u64),
+    // raise the volume by a step, after the scheduled playback started
+    ScheduleRamp(usize),
+
+    // songs found by a recursive append
+    PlaylistScanned {
+        path: PathBuf,
+        audio_files: Vec<DiziAudioFile>,
+    },
+    // a song decoded to answer a waveform query
+    WaveformRead {
+        path: PathBuf,
+        buckets: usize,
+        waveform: DiziResult<Vec<u8>>,
+    },
+}
+
+/// What is known of a song once it is decoded
+#[derive(Debug)]
+pub struct LoadedSong {
+    pub duration: Option<time::Duration>,
+    pub output: OutputConfig,
 }
 
 #[derive(Debug)]
@@ -34,8 +82,9 @@ pub type ClientRequestSender = mpsc::Sender<(String, ClientRequest)>;
 pub type ServerEventSender = mpsc::Sender<ServerEvent>;
 // pub type ServerEventReceiver = mpsc::Receiver<ServerEvent>;
 
-pub type ServerBroadcastEventSender = mpsc::Sender<ServerBroadcastEvent>;
-pub type ServerBroadcastEventReceiver = mpsc::Receiver<ServerBroadcastEvent>;
+/// Broadcast events are shared by all clients instead of copied for each
+pub type ServerBroadcastEventSender = mpsc::Sender<Arc<ServerBroadcastEvent>>;
+pub type ServerBroadcastEventReceiver = mpsc::Receiver<Arc<ServerBroadcastEvent>>;
 
 /// A small event handler that wrap termion input and tick events. Each event
 /// type is handled in its own thread and returned to a common `Receiver`
@@ -110,6 +159,7 @@ impl Events {
     pub fn broadcast_event(&mut self, event: ServerBroadcastEvent) {
         match &event {
             ServerBroadcastEvent::PlayerState { .. } => {}
+            ServerBroadcastEvent::PlayerSpectrumUpdate { .. } => {}
             event => {
                 tracing::debug!(
                     "Server broadcast: {:#?} to {} clients",
@@ -118,8 +168,9 @@ impl Events {
                 );
             }
         }
-        for (_, server_tx) in self.server_broadcast_listeners.iter() {
-            let _ = server_tx.send(event.clone());
-        }
+        let event = Arc::new(event);
+        // clients that are gone are forgotten
+        self.server_broadcast_listeners
+            .retain(|_, server_tx| server_tx.send(Arc::clone(&event)).is_ok());
     }
 }
@@ -3,12 +3,16 @@ mod client;
 mod config;
 mod context;
 mod events;
+#[cfg(feature = "mpris")]
+mod mpris;
 mod playlist;
 mod server;
 mod server_commands;
 mod server_util;
 mod traits;
 mod util;
+#[cfg(feature = "web")]
+mod web;
 
 use std::path::PathBuf;
 
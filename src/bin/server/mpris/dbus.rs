@@ -0,0 +1,890 @@
+use std::io::{Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+pub const METHOD_CALL: u8 = 1;
+pub const METHOD_RETURN: u8 = 2;
+pub const ERROR: u8 = 3;
+pub const SIGNAL: u8 = 4;
+
+// the caller won't wait for a reply
+pub const NO_REPLY_EXPECTED: u8 = 0x1;
+
+// the bus refuses messages larger than this
+const MAX_MESSAGE: usize = 1 << 27;
+// 32 levels of arrays and 32 of structs are allowed
+const MAX_DEPTH: usize = 64;
+
+/// A value of the D-Bus type system, of the types MPRIS uses
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Byte(u8),
+    Bool(bool),
+    I16(i16),
+    U16(u16),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    Double(f64),
+    Str(String),
+    Path(String),
+    Signature(String),
+    // signature of the elements, so empty arrays have one
+    Array(String, Vec<Value>),
+    Struct(Vec<Value>),
+    DictEntry(Box<Value>, Box<Value>),
+    Variant(Box<Value>),
+}
+
+impl Value {
+    /// An `a{sv}` dictionary, as properties and metadata are sent
+    pub fn dict(entries: Vec<(&str, Value)>) -> Self {
+        let entries = entries
+            .into_iter()
+            .map(|(key, value)| {
+                Value::DictEntry(
+                    Box::new(Value::Str(key.to_string())),
+                    Box::new(Value::Variant(Box::new(value))),
+                )
+            })
+            .collect();
+        Value::Array("{sv}".to_string(), entries)
+    }
+
+    pub fn str_array(strings: Vec<String>) -> Self {
+        Value::Array(
+            "s".to_string(),
+            strings.into_iter().map(Value::Str).collect(),
+        )
+    }
+
+    pub fn signature(&self) -> String {
+        match self {
+            Value::Byte(_) => "y".to_string(),
+            Value::Bool(_) => "b".to_string(),
+            Value::I16(_) => "n".to_string(),
+            Value::U16(_) => "q".to_string(),
+            Value::I32(_) => "i".to_string(),
+            Value::U32(_) => "u".to_string(),
+            Value::I64(_) => "x".to_string(),
+            Value::U64(_) => "t".to_string(),
+            Value::Double(_) => "d".to_string(),
+            Value::Str(_) => "s".to_string(),
+            Value::Path(_) => "o".to_string(),
+            Value::Signature(_) => "g".to_string(),
+            Value::Array(element, _) => format!("a{}", element),
+            Value::Struct(fields) => {
+                let fields: String = fields.iter().map(|field| field.signature()).collect();
+                format!("({})", fields)
+            }
+            Value::DictEntry(key, value) => {
+                format!("{{{}{}}}", key.signature(), value.signature())
+            }
+            Value::Variant(_) => "v".to_string(),
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Value::Str(s) | Value::Path(s) | Value::Signature(s) => Some(s),
+            Value::Variant(value) => value.as_str(),
+            _ => None,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self {
+            Value::I64(n) => Some(*n),
+            Value::Variant(value) => value.as_i64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        match self {
+            Value::Double(n) => Some(*n),
+            Value::Variant(value) => value.as_f64(),
+            _ => None,
+        }
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            Value::Bool(b) => Some(*b),
+            Value::Variant(value) => value.as_bool(),
+            _ => None,
+        }
+    }
+}
+
+fn alignment(signature: &str) -> usize {
+    match signature.as_bytes().first() {
+        Some(b'n' | b'q') => 2,
+        Some(b'b' | b'i' | b'u' | b'h' | b's' | b'o' | b'a') => 4,
+        Some(b'x' | b't' | b'd' | b'(' | b'{') => 8,
+        _ => 1,
+    }
+}
+
+/// Splits the first complete type off a signature
+fn next_type(signature: &str) -> DiziResult<(&str, &str)> {
+    let bytes = signature.as_bytes();
+    let end = match bytes.first() {
+        None => return Err(malformed("Signature ended early")),
+        Some(b'a') => 1 + next_type(&signature[1..])?.0.len(),
+        Some(open @ (b'(' | b'{')) => {
+            let close = if *open == b'(' { b')' } else { b'}' };
+            let mut depth = 0;
+            let mut end = None;
+            for (i, byte) in bytes.iter().enumerate() {
+                if byte == open {
+                    depth += 1;
+                } else if *byte == close {
+                    depth -= 1;
+                    if depth == 0 {
+                        end = Some(i + 1);
+                        break;
+                    }
+                }
+            }
+            match end {
+                None => return Err(malformed("Unbalanced signature")),
+                // structs and dict entries can't be empty
+                Some(2) => return Err(malformed("Empty struct in signature")),
+                Some(end) => end,
+            }
+        }
+        Some(byte) if byte.is_ascii() => 1,
+        Some(_) => return Err(malformed("Unknown type in signature")),
+    };
+    Ok(signature.split_at(end))
+}
+
+fn malformed(msg: &str) -> DiziError {
+    DiziError::new(DiziErrorKind::ParseError, format!("D-Bus: {}", msg))
+}
+
+/// Marshals values, aligned relative to the start of the message
+#[derive(Debug, Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn pad(&mut self, alignment: usize) {
+        while !self.buf.len().is_multiple_of(alignment) {
+            self.buf.push(0);
+        }
+    }
+
+    fn u32(&mut self, n: u32) {
+        self.pad(4);
+        self.buf.extend(n.to_le_bytes());
+    }
+
+    fn string(&mut self, s: &str) {
+        self.u32(s.len() as u32);
+        self.buf.extend(s.as_bytes());
+        self.buf.push(0);
+    }
+
+    fn write(&mut self, value: &Value) {
+        match value {
+            Value::Byte(n) => self.buf.push(*n),
+            Value::Bool(b) => self.u32(*b as u32),
+            Value::I16(n) => {
+                self.pad(2);
+                self.buf.extend(n.to_le_bytes());
+            }
+            Value::U16(n) => {
+                self.pad(2);
+                self.buf.extend(n.to_le_bytes());
+            }
+            Value::I32(n) => {
+                self.pad(4);
+                self.buf.extend(n.to_le_bytes());
+            }
+            Value::U32(n) => self.u32(*n),
+            Value::I64(n) => {
+                self.pad(8);
+                self.buf.extend(n.to_le_bytes());
+            }
+            Value::U64(n) => {
+                self.pad(8);
+                self.buf.extend(n.to_le_bytes());
+            }
+            Value::Double(n) => {
+                self.pad(8);
+                self.buf.extend(n.to_le_bytes());
+            }
+            Value::Str(s) | Value::Path(s) => self.string(s),
+            Value::Signature(s) => {
+                self.buf.push(s.len() as u8);
+                self.buf.extend(s.as_bytes());
+                self.buf.push(0);
+            }
+            Value::Array(element, values) => {
+                self.u32(0);
+                let len_at = self.buf.len() - 4;
+                // padding to the first element isn't counted in the length
+                self.pad(alignment(element));
+                let start = self.buf.len();
+                for value in values {
+                    self.write(value);
+                }
+                let len = (self.buf.len() - start) as u32;
+                self.buf[len_at..len_at + 4].copy_from_slice(&len.to_le_bytes());
+            }
+            Value::Struct(fields) => {
+                self.pad(8);
+                for field in fields {
+                    self.write(field);
+                }
+            }
+            Value::DictEntry(key, value) => {
+                self.pad(8);
+                self.write(key);
+                self.write(value);
+            }
+            Value::Variant(value) => {
+                self.write(&Value::Signature(value.signature()));
+                self.write(value);
+            }
+        }
+    }
+}
+
+/// Unmarshals values of a whole message
+struct Reader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+    depth: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> DiziResult<&'a [u8]> {
+        let bytes = self
+            .buf
+            .get(self.pos..self.pos + len)
+            .ok_or_else(|| malformed("Message ended early"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn align(&mut self, alignment: usize) -> DiziResult {
+        let padding = (alignment - self.pos % alignment) % alignment;
+        self.take(padding)?;
+        Ok(())
+    }
+
+    fn fixed<const N: usize>(&mut self) -> DiziResult<[u8; N]> {
+        self.align(N)?;
+        let mut bytes: [u8; N] = self.take(N)?.try_into().unwrap_or([0; N]);
+        // read as little endian either way
+        if self.big_endian {
+            bytes.reverse();
+        }
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> DiziResult<u32> {
+        Ok(u32::from_le_bytes(self.fixed()?))
+    }
+
+    fn string(&mut self, len: usize) -> DiziResult<String> {
+        let bytes = self.take(len + 1)?;
+        Ok(String::from_utf8_lossy(&bytes[..len]).to_string())
+    }
+
+    fn read(&mut self, signature: &str) -> DiziResult<Value> {
+        // nested variants would otherwise run the stack out
+        if self.depth == MAX_DEPTH {
+            return Err(malformed("Values nested too deep"));
+        }
+        self.depth += 1;
+        let value = self.read_value(signature);
+        self.depth -= 1;
+        value
+    }
+
+    fn read_value(&mut self, signature: &str) -> DiziResult<Value> {
+        let value = match signature.as_bytes().first() {
+            Some(b'y') => Value::Byte(self.take(1)?[0]),
+            Some(b'b') => Value::Bool(self.u32()? != 0),
+            Some(b'n') => Value::I16(i16::from_le_bytes(self.fixed()?)),
+            Some(b'q') => Value::U16(u16::from_le_bytes(self.fixed()?)),
+            Some(b'i') => Value::I32(i32::from_le_bytes(self.fixed()?)),
+            Some(b'u' | b'h') => Value::U32(self.u32()?),
+            Some(b'x') => Value::I64(i64::from_le_bytes(self.fixed()?)),
+            Some(b't') => Value::U64(u64::from_le_bytes(self.fixed()?)),
+            Some(b'd') => Value::Double(f64::from_le_bytes(self.fixed()?)),
+            Some(b's') => {
+                let len = self.u32()? as usize;
+                Value::Str(self.string(len)?)
+            }
+            Some(b'o') => {
+                let len = self.u32()? as usize;
+                Value::Path(self.string(len)?)
+            }
+            Some(b'g') => {
+                let len = self.take(1)?[0] as usize;
+                Value::Signature(self.string(len)?)
+            }
+            Some(b'a') => {
+                let element = &signature[1..];
+                let len = self.u32()? as usize;
+                self.align(alignment(element))?;
+                let end = self.pos + len;
+                if end > self.buf.len() {
+                    return Err(malformed("Array longer than its message"));
+                }
+                let mut values = Vec::new();
+                while self.pos < end {
+                    let start = self.pos;
+                    values.push(self.read(element)?);
+                    // an element of no bytes would never reach the end
+                    if self.pos == start {
+                        return Err(malformed("Empty array element"));
+                    }
+                }
+                Value::Array(element.to_string(), values)
+            }
+            Some(b'(') => {
+                self.align(8)?;
+                let mut fields = Vec::new();
+                let mut rest = signature[1..]
+                    .strip_suffix(')')
+                    .ok_or_else(|| malformed("Unbalanced signature"))?;
+                while !rest.is_empty() {
+                    let (field, next) = next_type(rest)?;
+                    fields.push(self.read(field)?);
+                    rest = next;
+                }
+                Value::Struct(fields)
+            }
+            Some(b'{') => {
+                self.align(8)?;
+                let entry = signature[1..]
+                    .strip_suffix('}')
+                    .ok_or_else(|| malformed("Unbalanced signature"))?;
+                let (key, rest) = next_type(entry)?;
+                let key = self.read(key)?;
+                let value = self.read(rest)?;
+                Value::DictEntry(Box::new(key), Box::new(value))
+            }
+            Some(b'v') => {
+                let len = self.take(1)?[0] as usize;
+                let inner = self.string(len)?;
+                let (inner, _) = next_type(&inner)?;
+                Value::Variant(Box::new(self.read(inner)?))
+            }
+            _ => return Err(malformed("Unknown type in signature")),
+        };
+        Ok(value)
+    }
+}
+
+/// A D-Bus message with the header fields MPRIS needs
+#[derive(Clone, Debug, Default)]
+pub struct Message {
+    pub kind: u8,
+    pub flags: u8,
+    pub serial: u32,
+    pub path: Option<String>,
+    pub interface: Option<String>,
+    pub member: Option<String>,
+    pub error_name: Option<String>,
+    pub reply_serial: Option<u32>,
+    pub destination: Option<String>,
+    pub sender: Option<String>,
+    pub body: Vec<Value>,
+}
+
+impl Message {
+    pub fn method_call(destination: &str, path: &str, interface: &str, member: &str) -> Self {
+        Self {
+            kind: METHOD_CALL,
+            destination: Some(destination.to_string()),
+            path: Some(path.to_string()),
+            interface: Some(interface.to_string()),
+            member: Some(member.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn signal(path: &str, interface: &str, member: &str) -> Self {
+        Self {
+            kind: SIGNAL,
+            path: Some(path.to_string()),
+            interface: Some(interface.to_string()),
+            member: Some(member.to_string()),
+            ..Self::default()
+        }
+    }
+
+    pub fn reply(&self, body: Vec<Value>) -> Self {
+        Self {
+            kind: METHOD_RETURN,
+            reply_serial: Some(self.serial),
+            destination: self.sender.clone(),
+            body,
+            ..Self::default()
+        }
+    }
+
+    pub fn error(&self, name: &str, msg: &str) -> Self {
+        Self {
+            kind: ERROR,
+            error_name: Some(name.to_string()),
+            reply_serial: Some(self.serial),
+            destination: self.sender.clone(),
+            body: vec![Value::Str(msg.to_string())],
+            ..Self::default()
+        }
+    }
+
+    pub fn with_body(mut self, body: Vec<Value>) -> Self {
+        self.body = body;
+        self
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        // the body is aligned from its own start, which is 8 byte aligned in the message
+        let mut body = Writer::default();
+        for value in self.body.iter() {
+            body.write(value);
+        }
+        let signature: String = self.body.iter().map(|value| value.signature()).collect();
+
+        let mut fields: Vec<Value> = Vec::new();
+        let mut field = |code: u8, value: Value| {
+            fields.push(Value::Struct(vec![
+                Value::Byte(code),
+                Value::Variant(Box::new(value)),
+            ]))
+        };
+        if let Some(path) = self.path.as_ref() {
+            field(1, Value::Path(path.clone()));
+        }
+        if let Some(interface) = self.interface.as_ref() {
+            field(2, Value::Str(interface.clone()));
+        }
+        if let Some(member) = self.member.as_ref() {
+            field(3, Value::Str(member.clone()));
+        }
+        if let Some(error_name) = self.error_name.as_ref() {
+            field(4, Value::Str(error_name.clone()));
+        }
+        if let Some(reply_serial) = self.reply_serial {
+            field(5, Value::U32(reply_serial));
+        }
+        if let Some(destination) = self.destination.as_ref() {
+            field(6, Value::Str(destination.clone()));
+        }
+        if !signature.is_empty() {
+            field(8, Value::Signature(signature));
+        }
+
+        let mut message = Writer::default();
+        message.buf.extend([b'l', self.kind, self.flags, 1]);
+        message.u32(body.buf.len() as u32);
+        message.u32(self.serial);
+        message.write(&Value::Array("(yv)".to_string(), fields));
+        message.pad(8);
+        message.buf.extend(body.buf);
+        message.buf
+    }
+
+    fn decode(buf: &[u8]) -> DiziResult<Self> {
+        let big_endian = match buf.first() {
+            Some(b'l') => false,
+            Some(b'B') => true,
+            _ => return Err(malformed("Unknown byte order")),
+        };
+        let mut reader = Reader {
+            buf,
+            pos: 4,
+            big_endian,
+            depth: 0,
+        };
+        let body_len = reader.u32()? as usize;
+        let mut message = Self {
+            kind: buf[1],
+            flags: buf[2],
+            serial: reader.u32()?,
+            ..Self::default()
+        };
+
+        let mut signature = String::new();
+        if let Value::Array(_, fields) = reader.read("a(yv)")? {
+            for field in fields {
+                let (code, value) = match field {
+                    Value::Struct(mut pair) if pair.len() == 2 => {
+                        let value = pair.remove(1);
+                        (pair.remove(0), value)
+                    }
+                    _ => continue,
+                };
+                let text = value.as_str().map(|s| s.to_string());
+                match code {
+                    Value::Byte(1) => message.path = text,
+                    Value::Byte(2) => message.interface = text,
+                    Value::Byte(3) => message.member = text,
+                    Value::Byte(4) => message.error_name = text,
+                    Value::Byte(5) => {
+                        if let Value::Variant(value) = value {
+                            if let Value::U32(serial) = *value {
+                                message.reply_serial = Some(serial);
+                            }
+                        }
+                    }
+                    Value::Byte(6) => message.destination = text,
+                    Value::Byte(7) => message.sender = text,
+                    Value::Byte(8) => signature = text.unwrap_or_default(),
+                    _ => {}
+                }
+            }
+        }
+        reader.align(8)?;
+
+        let mut body = Reader {
+            buf: reader.take(body_len)?,
+            pos: 0,
+            big_endian,
+            depth: 0,
+        };
+        let mut rest = signature.as_str();
+        while !rest.is_empty() {
+            let (value, next) = next_type(rest)?;
+            message.body.push(body.read(value)?);
+            rest = next;
+        }
+        Ok(message)
+    }
+}
+
+/// Reads the next whole message off the bus
+pub fn read_message(reader: &mut impl Read) -> DiziResult<Message> {
+    let mut fixed = [0; 16];
+    reader.read_exact(&mut fixed)?;
+    let u32_at = |i: usize| {
+        let bytes: [u8; 4] = fixed[i..i + 4].try_into().unwrap_or_default();
+        match fixed[0] {
+            b'B' => u32::from_be_bytes(bytes),
+            _ => u32::from_le_bytes(bytes),
+        }
+    };
+    let body_len = u32_at(4) as usize;
+    let fields_len = u32_at(12) as usize;
+    // the header fields are padded to 8 bytes before the body
+    let header_len = (16 + fields_len).div_ceil(8) * 8;
+    let len = header_len + body_len;
+    if len > MAX_MESSAGE {
+        return Err(malformed("Message too large"));
+    }
+    let mut buf = fixed.to_vec();
+    buf.resize(len, 0);
+    reader.read_exact(&mut buf[16..])?;
+    Message::decode(&buf)
+}
+
+/// A connection to the session bus, which only writes.
+/// Messages are read with `read_message` off a clone of `stream`
+#[derive(Debug)]
+pub struct Connection {
+    pub stream: UnixStream,
+    serial: u32,
+}
+
+impl Connection {
+    /// Connects to the bus at `DBUS_SESSION_BUS_ADDRESS`, or the user's bus
+    /// under `/run/user` without one, and says hello to it
+    pub fn session() -> DiziResult<Self> {
+        let uid = std::fs::metadata("/proc/self")?.uid();
+        let address = std::env::var("DBUS_SESSION_BUS_ADDRESS")
+            .unwrap_or_else(|_| format!("unix:path=/run/user/{}/bus", uid));
+        let mut stream = connect(&address)?;
+
+        // authenticate as the user this process runs as
+        let uid_hex: String = uid
+            .to_string()
+            .bytes()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        stream.write_all(b"\0")?;
+        stream.write_all(format!("AUTH EXTERNAL {}\r\n", uid_hex).as_bytes())?;
+        let line = read_line(&mut stream)?;
+        if !line.starts_with("OK") {
+            return Err(DiziError::new(
+                DiziErrorKind::Server,
+                format!("D-Bus refused to authenticate: {}", line),
+            ));
+        }
+        stream.write_all(b"BEGIN\r\n")?;
+
+        let mut connection = Self { stream, serial: 0 };
+        connection.send(Message::method_call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "Hello",
+        ))?;
+        Ok(connection)
+    }
+
+    /// Takes `name` on the bus, unless another process already has it
+    pub fn request_name(&mut self, name: &str) -> DiziResult {
+        // DBUS_NAME_FLAG_DO_NOT_QUEUE
+        let message = Message::method_call(
+            "org.freedesktop.DBus",
+            "/org/freedesktop/DBus",
+            "org.freedesktop.DBus",
+            "RequestName",
+        )
+        .with_body(vec![Value::Str(name.to_string()), Value::U32(4)]);
+        self.send(message)
+    }
+
+    pub fn send(&mut self, mut message: Message) -> DiziResult {
+        self.serial += 1;
+        message.serial = self.serial;
+        self.stream.write_all(&message.encode())?;
+        Ok(())
+    }
+}
+
+/// Connects to the first address of `address` it can, `unix:path=` or `unix:abstract=`
+fn connect(address: &str) -> DiziResult<UnixStream> {
+    for address in address.split(';') {
+        let params = match address.strip_prefix("unix:") {
+            Some(params) => params,
+            None => continue,
+        };
+        for param in params.split(',') {
+            match param.split_once('=') {
+                Some(("path", path)) => return Ok(UnixStream::connect(PathBuf::from(path))?),
+                #[cfg(target_os = "linux")]
+                Some(("abstract", name)) => {
+                    use std::os::linux::net::SocketAddrExt;
+                    let addr = std::os::unix::net::SocketAddr::from_abstract_name(name)?;
+                    return Ok(UnixStream::connect_addr(&addr)?);
+                }
+                _ => {}
+            }
+        }
+    }
+    Err(DiziError::new(
+        DiziErrorKind::Server,
+        format!("No D-Bus address to connect to in '{}'", address),
+    ))
+}
+
+fn read_line(stream: &mut UnixStream) -> DiziResult<String> {
+    // a byte at a time, so nothing after the line is read away
+    let mut line = Vec::new();
+    let mut byte = [0];
+    while !line.ends_with(b"\r\n") {
+        if stream.read(&mut byte)? == 0 {
+            break;
+        }
+        line.push(byte[0]);
+    }
+    Ok(String::from_utf8_lossy(&line).trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // a message with only a signature header field, as `order` ('l' or 'B')
+    fn raw_message(order: u8, signature: &str, body: &[u8]) -> Vec<u8> {
+        let u32_bytes = |n: u32| match order {
+            b'B' => n.to_be_bytes(),
+            _ => n.to_le_bytes(),
+        };
+        let mut buf = vec![order, SIGNAL, 0, 1];
+        buf.extend(u32_bytes(body.len() as u32));
+        buf.extend(u32_bytes(1));
+        buf.extend(u32_bytes(6 + signature.len() as u32));
+        buf.extend([8, 1, b'g', 0, signature.len() as u8]);
+        buf.extend(signature.as_bytes());
+        buf.push(0);
+        while !buf.len().is_multiple_of(8) {
+            buf.push(0);
+        }
+        buf.extend(body);
+        buf
+    }
+
+    fn round_trip(message: &Message) -> Message {
+        let buf = message.encode();
+        read_message(&mut buf.as_slice()).unwrap()
+    }
+
+    #[test]
+    fn method_call_round_trip() {
+        let mut message = Message::method_call(
+            "org.mpris.MediaPlayer2.dizi",
+            "/org/mpris/MediaPlayer2",
+            "org.mpris.MediaPlayer2.Player",
+            "SetPosition",
+        )
+        .with_body(vec![
+            Value::Byte(7),
+            Value::Bool(true),
+            Value::I16(-2),
+            Value::U16(3),
+            Value::I32(-4),
+            Value::U32(5),
+            Value::I64(-6_000_000_000),
+            Value::U64(7),
+            Value::Double(0.5),
+            Value::Str("dizi".to_string()),
+            Value::Path("/org/dizi/track/1".to_string()),
+            Value::Signature("a{sv}".to_string()),
+        ]);
+        message.serial = 9;
+        message.flags = NO_REPLY_EXPECTED;
+
+        let decoded = round_trip(&message);
+        assert_eq!(decoded.kind, METHOD_CALL);
+        assert_eq!(decoded.flags, NO_REPLY_EXPECTED);
+        assert_eq!(decoded.serial, 9);
+        assert_eq!(decoded.destination, message.destination);
+        assert_eq!(decoded.path, message.path);
+        assert_eq!(decoded.interface, message.interface);
+        assert_eq!(decoded.member, message.member);
+        assert_eq!(decoded.body, message.body);
+    }
+
+    #[test]
+    fn dict_round_trip() {
+        let metadata = Value::dict(vec![
+            ("mpris:length", Value::I64(180_000_000)),
+            ("xesam:artist", Value::str_array(vec!["a".to_string()])),
+            ("xesam:genre", Value::str_array(Vec::new())),
+        ]);
+        let properties = Value::dict(vec![
+            ("Metadata", metadata),
+            ("Volume", Value::Double(1.0)),
+            ("CanPlay", Value::Bool(true)),
+        ]);
+        let message = Message::signal(
+            "/org/mpris/MediaPlayer2",
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+        )
+        .with_body(vec![
+            Value::Str("org.mpris.MediaPlayer2.Player".to_string()),
+            properties,
+            Value::str_array(Vec::new()),
+        ]);
+
+        let decoded = round_trip(&message);
+        assert_eq!(decoded.kind, SIGNAL);
+        assert_eq!(decoded.body, message.body);
+        assert_eq!(decoded.body[1].signature(), "a{sv}");
+    }
+
+    #[test]
+    fn reply_and_error_round_trip() {
+        let mut call = Message::method_call("a.b", "/", "a.b", "Get");
+        call.serial = 3;
+        call.sender = Some(":1.2".to_string());
+
+        let reply = round_trip(&call.reply(vec![Value::Variant(Box::new(Value::U32(1)))]));
+        assert_eq!(reply.kind, METHOD_RETURN);
+        assert_eq!(reply.reply_serial, Some(3));
+        assert_eq!(reply.destination.as_deref(), Some(":1.2"));
+        assert_eq!(reply.body, vec![Value::Variant(Box::new(Value::U32(1)))]);
+
+        let error = round_trip(&call.error("org.freedesktop.DBus.Error.Failed", "no"));
+        assert_eq!(error.kind, ERROR);
+        assert_eq!(
+            error.error_name.as_deref(),
+            Some("org.freedesktop.DBus.Error.Failed")
+        );
+        assert_eq!(error.body, vec![Value::Str("no".to_string())]);
+    }
+
+    #[test]
+    fn decodes_both_byte_orders() {
+        let mut little = 258u32.to_le_bytes().to_vec();
+        little.extend(2u32.to_le_bytes());
+        little.extend(b"hi\0");
+        let mut big = 258u32.to_be_bytes().to_vec();
+        big.extend(2u32.to_be_bytes());
+        big.extend(b"hi\0");
+
+        for buf in [
+            raw_message(b'l', "us", &little),
+            raw_message(b'B', "us", &big),
+        ] {
+            let message = read_message(&mut buf.as_slice()).unwrap();
+            assert_eq!(message.kind, SIGNAL);
+            assert_eq!(message.serial, 1);
+            assert_eq!(
+                message.body,
+                vec![Value::U32(258), Value::Str("hi".to_string())]
+            );
+        }
+    }
+
+    #[test]
+    fn malformed_signatures() {
+        for signature in ["(yv", "a", "z", "({)}", "{}", "(y}"] {
+            let buf = raw_message(b'l', signature, &[0; 16]);
+            assert!(Message::decode(&buf).is_err(), "{}", signature);
+        }
+        // a variant naming a type outside the ASCII signature characters
+        let buf = raw_message(b'l', "v", &[2, 0xc3, 0xa9, 0]);
+        assert!(Message::decode(&buf).is_err());
+    }
+
+    #[test]
+    fn deeply_nested_variants() {
+        let body: Vec<u8> = [1, b'v', 0].repeat(100_000);
+        let buf = raw_message(b'l', "v", &body);
+        assert!(Message::decode(&buf).is_err());
+    }
+
+    #[test]
+    fn truncated_message() {
+        let message = Message::signal("/", "a.b", "C").with_body(vec![Value::U64(1)]);
+        let buf = message.encode();
+        assert!(read_message(&mut &buf[..buf.len() - 1]).is_err());
+        assert!(Message::decode(&buf[..buf.len() - 1]).is_err());
+        assert!(Message::decode(&[]).is_err());
+    }
+
+    #[test]
+    fn array_longer_than_message() {
+        let mut body = 64u32.to_le_bytes().to_vec();
+        body.extend(1u32.to_le_bytes());
+        let buf = raw_message(b'l', "au", &body);
+        assert!(Message::decode(&buf).is_err());
+    }
+
+    #[test]
+    fn array_of_empty_structs() {
+        let mut body = 8u32.to_le_bytes().to_vec();
+        body.extend([0; 12]);
+        for signature in ["a()", "a{}", "(())"] {
+            let buf = raw_message(b'l', signature, &body);
+            assert!(Message::decode(&buf).is_err(), "{}", signature);
+        }
+        // elements that take no bytes can't loop forever past the signature check
+        let mut reader = Reader {
+            buf: &body,
+            pos: 0,
+            big_endian: false,
+            depth: 0,
+        };
+        assert!(reader.read("a()").is_err());
+    }
+}
@@ -0,0 +1,592 @@
+mod dbus;
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::PathBuf;
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time;
+
+use dizi::error::DiziResult;
+use dizi::player::{PlayerInfo, PlayerState, PlayerStatus, RepeatMode};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+
+use crate::events::{ClientRequestSender, ServerBroadcastEventReceiver};
+
+use self::dbus::{Connection, Message, Value};
+
+// the name and uuid requests are made under
+pub const UUID: &str = "mpris";
+
+const BUS_NAME: &str = "org.mpris.MediaPlayer2.dizi";
+const OBJECT_PATH: &str = "/org/mpris/MediaPlayer2";
+const ROOT_INTERFACE: &str = "org.mpris.MediaPlayer2";
+const PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+const PROPERTIES_INTERFACE: &str = "org.freedesktop.DBus.Properties";
+const NO_TRACK: &str = "/org/mpris/MediaPlayer2/TrackList/NoTrack";
+
+// progress updates further apart than this are taken to be seeks
+const SEEK_THRESHOLD: time::Duration = time::Duration::from_secs(3);
+
+const INTROSPECTION: &str = r#"<!DOCTYPE node PUBLIC "-//freedesktop//DTD D-BUS Object Introspection 1.0//EN"
+ "http://www.freedesktop.org/standards/dbus/1.0/introspect.dtd">
+<node>
+  <interface name="org.freedesktop.DBus.Introspectable">
+    <method name="Introspect"><arg name="xml" type="s" direction="out"/></method>
+  </interface>
+  <interface name="org.freedesktop.DBus.Peer">
+    <method name="Ping"/>
+  </interface>
+  <interface name="org.freedesktop.DBus.Properties">
+    <method name="Get">
+      <arg name="interface" type="s" direction="in"/>
+      <arg name="property" type="s" direction="in"/>
+      <arg name="value" type="v" direction="out"/>
+    </method>
+    <method name="GetAll">
+      <arg name="interface" type="s" direction="in"/>
+      <arg name="properties" type="a{sv}" direction="out"/>
+    </method>
+    <method name="Set">
+      <arg name="interface" type="s" direction="in"/>
+      <arg name="property" type="s" direction="in"/>
+      <arg name="value" type="v" direction="in"/>
+    </method>
+    <signal name="PropertiesChanged">
+      <arg name="interface" type="s"/>
+      <arg name="changed" type="a{sv}"/>
+      <arg name="invalidated" type="as"/>
+    </signal>
+  </interface>
+  <interface name="org.mpris.MediaPlayer2">
+    <method name="Raise"/>
+    <method name="Quit"/>
+    <property name="CanQuit" type="b" access="read"/>
+    <property name="CanRaise" type="b" access="read"/>
+    <property name="HasTrackList" type="b" access="read"/>
+    <property name="Identity" type="s" access="read"/>
+    <property name="SupportedUriSchemes" type="as" access="read"/>
+    <property name="SupportedMimeTypes" type="as" access="read"/>
+  </interface>
+  <interface name="org.mpris.MediaPlayer2.Player">
+    <method name="Next"/>
+    <method name="Previous"/>
+    <method name="Pause"/>
+    <method name="PlayPause"/>
+    <method name="Stop"/>
+    <method name="Play"/>
+    <method name="Seek"><arg name="offset" type="x" direction="in"/></method>
+    <method name="SetPosition">
+      <arg name="track" type="o" direction="in"/>
+      <arg name="position" type="x" direction="in"/>
+    </method>
+    <method name="OpenUri"><arg name="uri" type="s" direction="in"/></method>
+    <signal name="Seeked"><arg name="position" type="x"/></signal>
+    <property name="PlaybackStatus" type="s" access="read"/>
+    <property name="LoopStatus" type="s" access="readwrite"/>
+    <property name="Rate" type="d" access="readwrite"/>
+    <property name="Shuffle" type="b" access="readwrite"/>
+    <property name="Metadata" type="a{sv}" access="read"/>
+    <property name="Volume" type="d" access="readwrite"/>
+    <property name="Position" type="x" access="read"/>
+    <property name="MinimumRate" type="d" access="read"/>
+    <property name="MaximumRate" type="d" access="read"/>
+    <property name="CanGoNext" type="b" access="read"/>
+    <property name="CanGoPrevious" type="b" access="read"/>
+    <property name="CanPlay" type="b" access="read"/>
+    <property name="CanPause" type="b" access="read"/>
+    <property name="CanSeek" type="b" access="read"/>
+    <property name="CanControl" type="b" access="read"/>
+  </interface>
+</node>"#;
+
+#[derive(Debug)]
+enum MprisMessage {
+    Bus(Message),
+    Server(Arc<ServerBroadcastEvent>),
+}
+
+/// Translates MPRIS method calls into client requests,
+/// and the player's state into MPRIS properties
+struct Mpris {
+    connection: Connection,
+    client_request_tx: ClientRequestSender,
+    // the player as last broadcast
+    info: PlayerInfo,
+}
+
+/// Owns `org.mpris.MediaPlayer2.dizi` on the session bus until the server quits,
+/// so desktop media keys and widgets control the player
+pub fn serve(
+    client_request_tx: ClientRequestSender,
+    server_event_rx: ServerBroadcastEventReceiver,
+) -> DiziResult {
+    let mut connection = Connection::session()?;
+    connection.request_name(BUS_NAME)?;
+
+    let (event_tx, event_rx) = mpsc::channel();
+
+    // listen for method calls from the bus
+    let mut stream = connection.stream.try_clone()?;
+    let event_tx_clone = event_tx.clone();
+    let _ = thread::spawn(move || {
+        while let Ok(message) = dbus::read_message(&mut stream) {
+            if event_tx_clone.send(MprisMessage::Bus(message)).is_err() {
+                return;
+            }
+        }
+        tracing::debug!("D-Bus connection closed");
+    });
+
+    // listen for events broadcasted by the server
+    let _ = thread::spawn(move || {
+        while let Ok(server_event) = server_event_rx.recv() {
+            if event_tx.send(MprisMessage::Server(server_event)).is_err() {
+                return;
+            }
+        }
+    });
+
+    let mut mpris = Mpris {
+        connection,
+        client_request_tx,
+        info: PlayerState::new().info,
+    };
+    mpris.request(ClientRequest::player_state());
+
+    while let Ok(message) = event_rx.recv() {
+        let res = match message {
+            MprisMessage::Bus(message) => mpris.process_bus_message(message),
+            MprisMessage::Server(event) => {
+                if let ServerBroadcastEvent::ServerQuit = event.as_ref() {
+                    break;
+                }
+                mpris.process_server_event(event.as_ref())
+            }
+        };
+        if let Err(err) = res {
+            tracing::debug!("MPRIS error: {}", err);
+        }
+    }
+    Ok(())
+}
+
+fn micros(duration: time::Duration) -> i64 {
+    duration.as_micros() as i64
+}
+
+fn from_micros(micros: i64) -> time::Duration {
+    time::Duration::from_micros(micros.max(0) as u64)
+}
+
+impl Mpris {
+    fn request(&self, request: ClientRequest) {
+        // the server only goes away when it quits
+        let _ = self.client_request_tx.send((UUID.to_string(), request));
+    }
+
+    fn track_id(&self) -> String {
+        match self.info.song.as_ref() {
+            Some(song) => {
+                let mut hasher = DefaultHasher::new();
+                song.file_path().hash(&mut hasher);
+                format!("/org/dizi/track/{:016x}", hasher.finish())
+            }
+            None => NO_TRACK.to_string(),
+        }
+    }
+
+    fn playback_status(&self) -> &'static str {
+        match (self.info.status, self.info.song.is_some()) {
+            (PlayerStatus::Playing | PlayerStatus::Loading, true) => "Playing",
+            (PlayerStatus::Paused, true) => "Paused",
+            _ => "Stopped",
+        }
+    }
+
+    fn loop_status(&self) -> &'static str {
+        match (self.info.repeat, self.info.next) {
+            (RepeatMode::One, _) => "Track",
+            (RepeatMode::All, true) => "Playlist",
+            _ => "None",
+        }
+    }
+
+    fn metadata(&self) -> Value {
+        let song = match self.info.song.as_ref() {
+            Some(song) => song,
+            None => return Value::dict(vec![("mpris:trackid", Value::Path(self.track_id()))]),
+        };
+        let tags = &song.music_metadata().standard_tags;
+        let mut entries = vec![
+            ("mpris:trackid", Value::Path(self.track_id())),
+            (
+                "xesam:title",
+                Value::Str(
+                    tags.get("TrackTitle")
+                        .cloned()
+                        .unwrap_or_else(|| song.file_name().to_string()),
+                ),
+            ),
+            (
+                "xesam:url",
+                Value::Str(format!("file://{}", song.file_path().to_string_lossy())),
+            ),
+        ];
+        if let Some(duration) = self.info.total_duration() {
+            entries.push(("mpris:length", Value::I64(micros(duration))));
+        }
+        if let Some(artist) = tags.get("Artist") {
+            entries.push(("xesam:artist", Value::str_array(vec![artist.clone()])));
+        }
+        if let Some(album) = tags.get("Album") {
+            entries.push(("xesam:album", Value::Str(album.clone())));
+        }
+        Value::dict(entries)
+    }
+
+    fn root_properties(&self) -> Vec<(&'static str, Value)> {
+        vec![
+            ("CanQuit", Value::Bool(true)),
+            ("CanRaise", Value::Bool(false)),
+            ("HasTrackList", Value::Bool(false)),
+            ("Identity", Value::Str("dizi".to_string())),
+            (
+                "SupportedUriSchemes",
+                Value::str_array(vec!["file".to_string()]),
+            ),
+            ("SupportedMimeTypes", Value::str_array(Vec::new())),
+        ]
+    }
+
+    fn player_properties(&self) -> Vec<(&'static str, Value)> {
+        let has_song = self.info.song.is_some();
+        vec![
+            (
+                "PlaybackStatus",
+                Value::Str(self.playback_status().to_string()),
+            ),
+            ("LoopStatus", Value::Str(self.loop_status().to_string())),
+            ("Rate", Value::Double(1.0)),
+            ("Shuffle", Value::Bool(self.info.shuffle)),
+            ("Metadata", self.metadata()),
+            ("Volume", Value::Double(self.info.volume as f64 / 100.0)),
+            ("Position", Value::I64(micros(self.info.elapsed))),
+            ("MinimumRate", Value::Double(1.0)),
+            ("MaximumRate", Value::Double(1.0)),
+            ("CanGoNext", Value::Bool(true)),
+            ("CanGoPrevious", Value::Bool(true)),
+            ("CanPlay", Value::Bool(has_song)),
+            ("CanPause", Value::Bool(has_song)),
+            ("CanSeek", Value::Bool(has_song)),
+            ("CanControl", Value::Bool(true)),
+        ]
+    }
+
+    fn properties(&self, interface: &str) -> Vec<(&'static str, Value)> {
+        match interface {
+            ROOT_INTERFACE => self.root_properties(),
+            PLAYER_INTERFACE => self.player_properties(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Tells the bus the player properties `names` changed
+    fn properties_changed(&mut self, names: &[&str]) -> DiziResult {
+        let changed: Vec<(&str, Value)> = self
+            .player_properties()
+            .into_iter()
+            .filter(|(name, _)| names.contains(name))
+            .collect();
+        let signal = Message::signal(OBJECT_PATH, PROPERTIES_INTERFACE, "PropertiesChanged")
+            .with_body(vec![
+                Value::Str(PLAYER_INTERFACE.to_string()),
+                Value::dict(changed),
+                Value::str_array(Vec::new()),
+            ]);
+        self.connection.send(signal)
+    }
+
+    fn process_server_event(&mut self, event: &ServerBroadcastEvent) -> DiziResult {
+        match event {
+            ServerBroadcastEvent::PlayerState { state } => {
+                self.info = state.info.clone();
+                self.properties_changed(&[
+                    "PlaybackStatus",
+                    "LoopStatus",
+                    "Shuffle",
+                    "Metadata",
+                    "Volume",
+                    "CanPlay",
+                    "CanPause",
+                    "CanSeek",
+                ])?;
+            }
+            ServerBroadcastEvent::PlayerFilePlay { file } => {
+                self.info.song = Some(file.clone());
+                self.info.duration = None;
+                self.info.elapsed = time::Duration::ZERO;
+                self.info.status = PlayerStatus::Playing;
+                self.properties_changed(&[
+                    "PlaybackStatus",
+                    "Metadata",
+                    "CanPlay",
+                    "CanPause",
+                    "CanSeek",
+                ])?;
+            }
+            ServerBroadcastEvent::TrackTransition { current, .. } => {
+                self.info.elapsed = time::Duration::ZERO;
+                self.info.duration = None;
+                match current {
+                    Some(song) => self.info.song = Some(song.as_ref().clone()),
+                    None => self.info.status = PlayerStatus::Stopped,
+                }
+                self.properties_changed(&["PlaybackStatus", "Metadata"])?;
+            }
+            ServerBroadcastEvent::PlayerPause => {
+                self.info.status = PlayerStatus::Paused;
+                self.properties_changed(&["PlaybackStatus"])?;
+            }
+            ServerBroadcastEvent::PlayerResume => {
+                self.info.status = PlayerStatus::Playing;
+                self.properties_changed(&["PlaybackStatus"])?;
+            }
+            ServerBroadcastEvent::PlayerStop => {
+                self.info.status = PlayerStatus::Stopped;
+                self.properties_changed(&["PlaybackStatus"])?;
+            }
+            ServerBroadcastEvent::PlayerRepeat { mode } => {
+                self.info.repeat = *mode;
+                self.properties_changed(&["LoopStatus"])?;
+            }
+            ServerBroadcastEvent::PlayerNext { on } => {
+                self.info.next = *on;
+                self.properties_changed(&["LoopStatus"])?;
+            }
+            ServerBroadcastEvent::PlayerShuffle { on } => {
+                self.info.shuffle = *on;
+                self.properties_changed(&["Shuffle"])?;
+            }
+            ServerBroadcastEvent::PlayerVolumeUpdate { volume } => {
+                self.info.volume = *volume;
+                self.properties_changed(&["Volume"])?;
+            }
+            ServerBroadcastEvent::PlayerProgressUpdate { elapsed, duration } => {
+                let previous = self.info.elapsed;
+                self.info.elapsed = *elapsed;
+                // Position isn't signalled as it changes, only when it jumps
+                if *elapsed < previous || *elapsed > previous + SEEK_THRESHOLD {
+                    let seeked = Message::signal(OBJECT_PATH, PLAYER_INTERFACE, "Seeked")
+                        .with_body(vec![Value::I64(micros(*elapsed))]);
+                    self.connection.send(seeked)?;
+                }
+                if duration.is_some() && *duration != self.info.duration {
+                    self.info.duration = *duration;
+                    self.properties_changed(&["Metadata"])?;
+                }
+            }
+            _ => {}
+        }
+        Ok(())
+    }
+
+    fn process_bus_message(&mut self, message: Message) -> DiziResult {
+        // replies to our own calls and signals from the bus
+        if message.kind != dbus::METHOD_CALL {
+            return Ok(());
+        }
+        let interface = message.interface.clone().unwrap_or_default();
+        let member = message.member.clone().unwrap_or_default();
+        let arg = |i: usize| message.body.get(i);
+
+        let response = match (interface.as_str(), member.as_str()) {
+            (ROOT_INTERFACE, "Raise") => Ok(Vec::new()),
+            (ROOT_INTERFACE, "Quit") => {
+                self.request(ClientRequest::server_quit());
+                Ok(Vec::new())
+            }
+            (PLAYER_INTERFACE, "Next") => {
+                self.request(ClientRequest::play_next());
+                Ok(Vec::new())
+            }
+            (PLAYER_INTERFACE, "Previous") => {
+                self.request(ClientRequest::play_previous());
+                Ok(Vec::new())
+            }
+            // the player has no stop of its own, pausing keeps the song to resume
+            (PLAYER_INTERFACE, "Pause" | "Stop") => {
+                self.request(ClientRequest::pause());
+                Ok(Vec::new())
+            }
+            (PLAYER_INTERFACE, "PlayPause") => {
+                self.request(ClientRequest::toggle_play());
+                Ok(Vec::new())
+            }
+            (PLAYER_INTERFACE, "Play") => {
+                self.request(ClientRequest::resume());
+                Ok(Vec::new())
+            }
+            (PLAYER_INTERFACE, "Seek") => match arg(0).and_then(Value::as_i64) {
+                Some(offset) => {
+                    self.seek(micros(self.info.elapsed).saturating_add(offset));
+                    Ok(Vec::new())
+                }
+                None => Err(invalid_args()),
+            },
+            (PLAYER_INTERFACE, "SetPosition") => {
+                match (
+                    arg(0).and_then(Value::as_str),
+                    arg(1).and_then(Value::as_i64),
+                ) {
+                    (Some(track), Some(position)) => {
+                        // positions for a song that already ended are ignored
+                        let length = self.info.total_duration().map(micros);
+                        if track == self.track_id()
+                            && position >= 0
+                            && length.is_none_or(|length| position <= length)
+                        {
+                            self.seek(position);
+                        }
+                        Ok(Vec::new())
+                    }
+                    _ => Err(invalid_args()),
+                }
+            }
+            (PLAYER_INTERFACE, "OpenUri") => match arg(0).and_then(Value::as_str) {
+                Some(uri) => match uri.strip_prefix("file://") {
+                    Some(path) => {
+                        self.request(ClientRequest::play_file(PathBuf::from(path)));
+                        Ok(Vec::new())
+                    }
+                    None => Err((
+                        "org.freedesktop.DBus.Error.NotSupported",
+                        "Only file:// uris can be opened",
+                    )),
+                },
+                None => Err(invalid_args()),
+            },
+            (PROPERTIES_INTERFACE, "Get") => {
+                match (
+                    arg(0).and_then(Value::as_str),
+                    arg(1).and_then(Value::as_str),
+                ) {
+                    (Some(interface), Some(name)) => self
+                        .properties(interface)
+                        .into_iter()
+                        .find(|(property, _)| *property == name)
+                        .map(|(_, value)| vec![Value::Variant(Box::new(value))])
+                        .ok_or(unknown_property()),
+                    _ => Err(invalid_args()),
+                }
+            }
+            (PROPERTIES_INTERFACE, "GetAll") => match arg(0).and_then(Value::as_str) {
+                Some(interface) => Ok(vec![Value::dict(self.properties(interface))]),
+                None => Err(invalid_args()),
+            },
+            (PROPERTIES_INTERFACE, "Set") => {
+                match (
+                    arg(0).and_then(Value::as_str),
+                    arg(1).and_then(Value::as_str),
+                    arg(2),
+                ) {
+                    (Some(PLAYER_INTERFACE), Some(name), Some(value)) => {
+                        self.set_property(name, value).map(|_| Vec::new())
+                    }
+                    (Some(_), Some(_), Some(_)) => Err(unknown_property()),
+                    _ => Err(invalid_args()),
+                }
+            }
+            ("org.freedesktop.DBus.Introspectable", "Introspect") => {
+                Ok(vec![Value::Str(INTROSPECTION.to_string())])
+            }
+            ("org.freedesktop.DBus.Peer", "Ping") => Ok(Vec::new()),
+            ("org.freedesktop.DBus.Peer", "GetMachineId") => {
+                let id = std::fs::read_to_string("/etc/machine-id").unwrap_or_default();
+                Ok(vec![Value::Str(id.trim().to_string())])
+            }
+            _ => Err(("org.freedesktop.DBus.Error.UnknownMethod", "No such method")),
+        };
+
+        if message.flags & dbus::NO_REPLY_EXPECTED != 0 {
+            return Ok(());
+        }
+        let reply = match response {
+            Ok(body) => message.reply(body),
+            Err((name, msg)) => message.error(name, msg),
+        };
+        self.connection.send(reply)
+    }
+
+    /// Seeks to `position` microseconds into the song, past its end skips it
+    fn seek(&self, position: i64) {
+        let past_end = self
+            .info
+            .total_duration()
+            .is_some_and(|length| position > micros(length));
+        let request = match past_end {
+            true => ClientRequest::play_next(),
+            false => ClientRequest::seek(from_micros(position)),
+        };
+        self.request(request);
+    }
+
+    fn set_property(&self, name: &str, value: &Value) -> Result<(), (&'static str, &'static str)> {
+        match name {
+            "LoopStatus" => {
+                let (repeat, next) = match value.as_str() {
+                    Some("None") => (RepeatMode::Off, self.info.next),
+                    Some("Track") => (RepeatMode::One, self.info.next),
+                    Some("Playlist") => (RepeatMode::All, true),
+                    _ => return Err(invalid_args()),
+                };
+                if repeat != self.info.repeat {
+                    self.request(ClientRequest::set_repeat(repeat));
+                }
+                if next != self.info.next {
+                    self.request(ClientRequest::toggle_next());
+                }
+            }
+            "Shuffle" => match value.as_bool() {
+                Some(shuffle) if shuffle != self.info.shuffle => {
+                    self.request(ClientRequest::toggle_shuffle())
+                }
+                Some(_) => {}
+                None => return Err(invalid_args()),
+            },
+            "Volume" => {
+                let volume = value.as_f64().ok_or_else(invalid_args)?;
+                let volume = (volume.clamp(0.0, 1.0) * 100.0).round() as usize;
+                if volume > self.info.volume {
+                    self.request(ClientRequest::volume_up(volume - self.info.volume));
+                } else if volume < self.info.volume {
+                    self.request(ClientRequest::volume_down(self.info.volume - volume));
+                }
+            }
+            // only normal speed is supported
+            "Rate" => {}
+            _ => {
+                return Err((
+                    "org.freedesktop.DBus.Error.PropertyReadOnly",
+                    "Property can't be set",
+                ))
+            }
+        }
+        Ok(())
+    }
+}
+
+fn invalid_args() -> (&'static str, &'static str) {
+    (
+        "org.freedesktop.DBus.Error.InvalidArgs",
+        "Invalid arguments",
+    )
+}
+
+fn unknown_property() -> (&'static str, &'static str) {
+    (
+        "org.freedesktop.DBus.Error.UnknownProperty",
+        "No such property",
+    )
+}
@@ -1,6 +1,7 @@
 use rand::prelude::SliceRandom;
 use rand::thread_rng;
 
+use dizi::playlist;
 use dizi::song::DiziSongEntry;
 
 use super::DiziPlaylist;
@@ -19,8 +20,16 @@ impl DiziPlaylistTrait for DiziPlaylist {
         // add song to end of playlist order
         self.order.push(index);
     }
+    fn insert(&mut self, index: usize, song: DiziSongEntry) {
+        self.contents.insert(index, song);
+        let order_index = playlist::insert_into_order(&mut self.order, index, 1);
+        match self.order_index {
+            Some(i) if i >= order_index => self.order_index = Some(i + 1),
+            _ => {}
+        }
+    }
     fn remove(&mut self, index: usize) {
-        self.contents.remove(index);
+        self.remove_entry(index);
     }
     fn clear(&mut self) {
         self.contents.clear();
@@ -29,14 +38,13 @@ impl DiziPlaylistTrait for DiziPlaylist {
     }
     fn swap(&mut self, index1: usize, index2: usize) {
         self.contents.swap(index1, index2);
-        // if one of the songs is the one curently being played,
-        // swap the playlist index as well
-        if let Some(index) = self.order_index {
-            if index == index1 {
-                self.order_index = Some(index2);
-            }
-            if index == index2 {
-                self.order_index = Some(index1);
+        // the songs keep their place in the play order,
+        // so neither shuffling nor the playing song is affected
+        for i in self.order.iter_mut() {
+            if *i == index1 {
+                *i = index2;
+            } else if *i == index2 {
+                *i = index1;
             }
         }
     }
@@ -53,7 +61,7 @@ impl DiziPlaylistTrait for DiziPlaylist {
 
     fn current_entry(&self) -> Option<DiziPlaylistEntry> {
         let order_index = self.order_index?;
-        let playlist_index = self.order[order_index];
+        let playlist_index = *self.order.get(order_index)?;
 
         Some(DiziPlaylistEntry {
             entry_index: playlist_index,
@@ -63,8 +71,15 @@ impl DiziPlaylistTrait for DiziPlaylist {
     }
 
     fn next_song_peak(&self) -> Option<DiziPlaylistEntry> {
-        let order_index = self.order_index?;
-        let order_index = (order_index + 1) % self.len();
+        if self.is_empty() {
+            return None;
+        }
+        // with the first song in the order removed while playing, the new first is next
+        let order_index = match self.order_index {
+            Some(order_index) => (order_index + 1) % self.len(),
+            None if self.order.is_empty() => return None,
+            None => 0,
+        };
 
         let entry_index = self.order[order_index];
 
@@ -76,6 +91,9 @@ impl DiziPlaylistTrait for DiziPlaylist {
     }
     fn previous_song_peak(&self) -> Option<DiziPlaylistEntry> {
         let order_index = self.order_index?;
+        if self.is_empty() {
+            return None;
+        }
         let order_index = (order_index + self.len() - 1) % self.len();
 
         let entry_index = self.order[order_index];
@@ -112,8 +130,7 @@ impl DiziPlaylistTrait for DiziPlaylist {
     fn unshuffle(&mut self) {
         // make sure unshuffle doesn't cause us to forget which song we were on
         if let Some(playlist_index) = self.order_index {
-            let song_index = self.order[playlist_index];
-            self.order_index = Some(song_index);
+            self.order_index = self.order.get(playlist_index).copied();
         }
         self.order = (0..self.len()).collect();
     }
@@ -1,12 +1,14 @@
 mod impl_playlist;
 
-use std::fs;
 use std::io;
 use std::path::Path;
 
+use dizi::archive;
 use dizi::error::DiziResult;
-use dizi::playlist::FilePlaylist;
+use dizi::playlist::{self, FilePlaylist};
+use dizi::playlist_format::PlaylistFormat;
 use dizi::song::{DiziFile, DiziSongEntry};
+use dizi::utils;
 
 #[derive(Clone, Debug)]
 pub struct DiziPlaylist {
@@ -28,13 +30,13 @@ impl DiziPlaylist {
     pub fn from_dir(path: &Path) -> io::Result<Self> {
         // only process regular files
         // if we can't read it, then don't play it
-        let mut contents: Vec<_> = fs::read_dir(path)?
-            .filter_map(|entry| entry.ok())
-            .map(|entry| entry.path())
-            .filter(|p| p.is_file())
-            .map(|path| DiziSongEntry::Unloaded(DiziFile::new(&path)))
+        let mut contents: Vec<_> = archive::list_dir(path)?
+            .into_iter()
+            .filter(|(_, is_dir)| !is_dir)
+            .map(|(path, _)| DiziSongEntry::Unloaded(DiziFile::new(&path)))
             .collect();
-        contents.sort_by(|a, b| a.file_name().cmp(b.file_name()));
+        // numbered tracks play in the order they are numbered
+        contents.sort_by(|a, b| utils::natural_cmp(a.file_name(), b.file_name()));
 
         let len = contents.len();
         Ok(Self {
@@ -45,38 +47,41 @@ impl DiziPlaylist {
     }
 
     pub fn from_file(cwd: &Path, path: &Path) -> io::Result<DiziPlaylist> {
-        let mut reader = m3u::Reader::open(path)?;
-        let read_playlist: Vec<_> = reader.entries().map(|entry| entry.unwrap()).collect();
         let mut entries = Vec::new();
-        for entry in read_playlist {
-            if let m3u::Entry::Path(p) = entry {
-                let file_path = if p.is_absolute() {
-                    p
-                } else {
-                    let mut new_path = cwd.to_path_buf();
-                    new_path.push(p);
-                    new_path
-                };
-                let entry = DiziSongEntry::Unloaded(DiziFile::new(&file_path));
-                entries.push(entry);
-            }
+        for playlist_entry in PlaylistFormat::read_file(path)? {
+            let p = playlist_entry.path;
+            let file_path = if p.is_absolute() {
+                p
+            } else {
+                let mut new_path = cwd.to_path_buf();
+                new_path.push(p);
+                new_path
+            };
+            let mut file = DiziFile::new(&file_path);
+            file.title = playlist_entry.title;
+            file.duration = playlist_entry.duration;
+            entries.push(DiziSongEntry::Unloaded(file));
         }
         let playlist = DiziPlaylist::new(entries);
         Ok(playlist)
     }
 
+    /// Index into `contents` of the playing song
+    pub fn playing_index(&self) -> Option<usize> {
+        self.order_index.and_then(|i| self.order.get(i)).map(|i| *i)
+    }
+
     pub fn to_file_playlist(&self) -> FilePlaylist {
-        let playing_index = self.order_index.and_then(|i| self.order.get(i)).map(|i| *i);
         FilePlaylist {
             list: self.contents.clone(),
             cursor_index: None,
-            playing_index,
+            playing_index: self.playing_index(),
+            order: self.order.clone(),
         }
     }
 
     pub fn load_current_entry_metadata(&mut self) -> DiziResult<()> {
-        if let Some(order_index) = self.order_index {
-            let entry_index = self.order[order_index];
+        if let Some(entry_index) = self.playing_index() {
             let entry = self.contents[entry_index].clone();
             let audio_file = entry.load_metadata()?;
             self.contents[entry_index] = DiziSongEntry::Loaded(audio_file);
@@ -89,16 +94,63 @@ impl DiziPlaylist {
         self.order.push(self.contents.len() - 1);
     }
 
-    pub fn remove_entry(&mut self, index: usize) {
-        self.contents.remove(index);
-        let new_len = self.contents.len();
-        let new_order: Vec<usize> = self
-            .order
+    /// Inserts `entries` right after the playing song, so they are played next.
+    /// Returns the index of the first inserted entry
+    pub fn insert_entries_next(&mut self, entries: Vec<DiziSongEntry>) -> usize {
+        let (index, order_index) = match self.order_index {
+            Some(order_index) => (self.order[order_index] + 1, order_index + 1),
+            None => (self.contents.len(), self.order.len()),
+        };
+        let count = entries.len();
+        self.contents.splice(index..index, entries);
+        for i in self.order.iter_mut().filter(|i| **i >= index) {
+            *i += count;
+        }
+        self.order
+            .splice(order_index..order_index, index..index + count);
+        index
+    }
+
+    /// Moves the entry at `index` to `to`, keeping the play order of every song
+    pub fn move_entry(&mut self, index: usize, to: usize) {
+        let entry = self.contents.remove(index);
+        self.contents.insert(to, entry);
+        for i in self.order.iter_mut() {
+            *i = playlist::moved_index(*i, index, to);
+        }
+    }
+
+    /// Removes the entries `keep` is false for.
+    /// Returns their indices from last to first, the order they were removed in
+    pub fn retain_entries(&mut self, mut keep: impl FnMut(&DiziSongEntry) -> bool) -> Vec<usize> {
+        let removed: Vec<usize> = self
+            .contents
             .iter()
-            .filter(|i| **i < new_len)
-            .map(|i| *i)
+            .enumerate()
+            .filter(|(_, entry)| !keep(entry))
+            .map(|(i, _)| i)
+            .rev()
             .collect();
-        self.order = new_order;
+        for i in removed.iter() {
+            self.remove_entry(*i);
+        }
+        removed
+    }
+
+    /// Removes the entry at `index`. Removing the playing song leaves the one
+    /// played before it as the playing one, so the song after it still plays next
+    pub fn remove_entry(&mut self, index: usize) {
+        self.contents.remove(index);
+        if let Some(pos) = self.order.iter().position(|i| *i == index) {
+            self.order.remove(pos);
+            self.order_index = match self.order_index {
+                Some(order_index) if order_index >= pos => order_index.checked_sub(1),
+                order_index => order_index,
+            };
+        }
+        for i in self.order.iter_mut().filter(|i| **i > index) {
+            *i -= 1;
+        }
     }
 }
 
@@ -111,3 +163,115 @@ impl std::default::Default for DiziPlaylist {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::DiziPlaylistTrait;
+
+    fn playlist(len: usize) -> DiziPlaylist {
+        let entries = (0..len)
+            .map(|i| DiziSongEntry::Unloaded(DiziFile::new(Path::new(&format!("/{}.flac", i)))))
+            .collect();
+        DiziPlaylist::new(entries)
+    }
+
+    fn playing_name(playlist: &DiziPlaylist) -> Option<String> {
+        playlist
+            .current_entry()
+            .map(|entry| entry.entry.file_name().to_string())
+    }
+
+    fn next_name(playlist: &DiziPlaylist) -> Option<String> {
+        playlist
+            .next_song_peak()
+            .map(|entry| entry.entry.file_name().to_string())
+    }
+
+    #[test]
+    fn insert_matches_client_order() {
+        let mut server = playlist(5);
+        server.order = vec![3, 1, 4, 0, 2];
+        let mut client = FilePlaylist::new();
+        for song in server.contents.iter() {
+            client.append_song(song.clone());
+        }
+        client.order = server.order.clone();
+
+        let new_songs = |names: &[&str]| -> Vec<DiziSongEntry> {
+            names
+                .iter()
+                .map(|name| DiziSongEntry::Unloaded(DiziFile::new(Path::new(name))))
+                .collect()
+        };
+        // as `playlist_insert` puts them in, and the client is told to
+        for (index, names) in [
+            (1, vec!["/a.flac", "/b.flac"]),
+            (0, vec!["/c.flac"]),
+            (8, vec!["/d.flac"]),
+        ] {
+            for (i, song) in new_songs(&names).into_iter().enumerate() {
+                server.insert(index + i, song);
+            }
+            client.insert_songs(index, new_songs(&names));
+            assert_eq!(server.order, client.order);
+        }
+        assert_eq!(server.order, vec![6, 2, 3, 4, 7, 0, 1, 5, 8]);
+    }
+
+    #[test]
+    fn remove_playing() {
+        let mut playlist = playlist(3);
+        playlist.order_index = Some(1);
+        playlist.remove_entry(1);
+        assert_eq!(playing_name(&playlist).as_deref(), Some("0.flac"));
+        assert_eq!(next_name(&playlist).as_deref(), Some("2.flac"));
+    }
+
+    #[test]
+    fn remove_playing_first() {
+        let mut playlist = playlist(3);
+        playlist.order_index = Some(0);
+        playlist.remove_entry(0);
+        assert_eq!(playlist.playing_index(), None);
+        assert_eq!(next_name(&playlist).as_deref(), Some("1.flac"));
+    }
+
+    #[test]
+    fn remove_playing_last_in_order() {
+        let mut playlist = playlist(3);
+        playlist.order = vec![2, 0, 1];
+        playlist.order_index = Some(2);
+        playlist.remove_entry(1);
+        assert_eq!(playlist.order, vec![1, 0]);
+        assert_eq!(playlist.order_index, Some(1));
+        assert_eq!(playing_name(&playlist).as_deref(), Some("0.flac"));
+        // used to index past the end of the order
+        playlist.unshuffle();
+        assert_eq!(playing_name(&playlist).as_deref(), Some("0.flac"));
+        playlist.shuffle();
+        assert_eq!(playing_name(&playlist).as_deref(), Some("0.flac"));
+        assert!(playlist.load_current_entry_metadata().is_err());
+    }
+
+    #[test]
+    fn remove_only() {
+        let mut playlist = playlist(1);
+        playlist.order_index = Some(0);
+        playlist.remove_entry(0);
+        assert!(playlist.is_empty());
+        assert_eq!(playlist.order_index, None);
+        assert!(playlist.current_entry().is_none());
+        assert!(playlist.next_song_peak().is_none());
+        assert!(playlist.previous_song_peak().is_none());
+    }
+
+    #[test]
+    fn retain_playing() {
+        let mut playlist = playlist(4);
+        playlist.order_index = Some(3);
+        let removed = playlist.retain_entries(|entry| entry.file_name() == "0.flac");
+        assert_eq!(removed, vec![3, 2, 1]);
+        assert_eq!(playing_name(&playlist).as_deref(), Some("0.flac"));
+    }
+}
@@ -4,12 +4,14 @@ use std::path::Path;
 use std::thread;
 
 use dizi::error::DiziResult;
+use dizi::playlist_format::PlaylistFormat;
 use dizi::response::server::ServerBroadcastEvent;
 
 use crate::audio::symphonia::player::SymphoniaPlayer;
 use crate::config::AppConfig;
-use crate::context::{AppContext, QuitType};
+use crate::context::{AppContext, CrossfadeContext, LibraryContext, QuitType, ScheduleContext};
 use crate::events::{AppEvent, Events, ServerEvent, ServerEventSender};
+use crate::server_commands::library;
 use crate::server_util;
 
 pub fn setup_socket(config: &AppConfig) -> DiziResult<UnixListener> {
@@ -29,11 +31,17 @@ pub fn serve(config: AppConfig) -> DiziResult {
         SymphoniaPlayer::new(&config, server_event_tx)?
     };
 
+    let mut library = LibraryContext::from_file(config.server_ref().ratings_ref());
+    library.load_positions(config.server_ref().positions_ref());
+
     let mut context = AppContext {
         events,
         config,
         quit: QuitType::DoNot,
         player,
+        library,
+        schedule: ScheduleContext::default(),
+        crossfade: CrossfadeContext::default(),
     };
 
     let listener = setup_socket(context.config_ref())?;
@@ -42,6 +50,28 @@ pub fn serve(config: AppConfig) -> DiziResult {
         let server_event_tx = context.events.server_event_sender().clone();
         thread::spawn(|| listen_for_clients(listener, server_event_tx));
     }
+    #[cfg(feature = "web")]
+    if let Some(address) = context.config_ref().server_ref().web_ref().address.as_ref() {
+        let listener = std::net::TcpListener::bind(address)?;
+        tracing::debug!("Serving the web client on http://{}", address);
+        let server_event_tx = context.events.server_event_sender().clone();
+        let options = context.config_ref().server_ref().web_ref().clone();
+        thread::spawn(move || crate::web::listen_for_browsers(listener, server_event_tx, options));
+    }
+    #[cfg(feature = "mpris")]
+    {
+        let client_request_tx = context.events.client_request_sender().clone();
+        let (server_tx, server_rx) = std::sync::mpsc::channel();
+        context
+            .events
+            .add_broadcast_listener(crate::mpris::UUID.to_string(), server_tx);
+        thread::spawn(move || {
+            // without a session bus the server runs as before
+            if let Err(err) = crate::mpris::serve(client_request_tx, server_rx) {
+                tracing::debug!("MPRIS unavailable: {}", err);
+            }
+        });
+    }
 
     while context.quit == QuitType::DoNot {
         let event = match context.events.next() {
@@ -72,17 +102,16 @@ pub fn serve(config: AppConfig) -> DiziResult {
         }
     }
 
+    if let Err(err) = library::library_save_positions(&context) {
+        tracing::error!("Failed to save positions: {err}");
+    }
+
     let playlist_path = context.config_ref().server_ref().playlist_ref();
     let playlist = &context.player.playlist_context.file_playlist;
 
     tracing::debug!("Saving playlist to '{}'", playlist_path.to_string_lossy());
 
-    let mut file = std::fs::File::create(playlist_path)?;
-    let mut writer = m3u::Writer::new(&mut file);
-    for song in playlist.contents.iter() {
-        let entry = m3u::Entry::Path(song.file_path().to_path_buf());
-        writer.write_entry(&entry)?;
-    }
+    PlaylistFormat::write_file(playlist_path, &playlist.contents)?;
     tracing::debug!("Playlist saved!");
 
     // broadcast to all clients that the server has exited
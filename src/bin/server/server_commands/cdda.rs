@@ -0,0 +1,81 @@
+#[cfg(feature = "cdda")]
+use std::fs;
+#[cfg(feature = "cdda")]
+use std::thread;
+
+use dizi::error::DiziResult;
+#[cfg(not(feature = "cdda"))]
+use dizi::error::{DiziError, DiziErrorKind};
+#[cfg(feature = "cdda")]
+use dizi::song::{DiziAudioFile, DiziFile};
+
+#[cfg(feature = "cdda")]
+use crate::config::CddaOption;
+use crate::context::AppContext;
+#[cfg(feature = "cdda")]
+use crate::events::{ServerEvent, ServerEventSender};
+#[cfg(feature = "cdda")]
+use crate::util::cdda;
+
+/// Reads the disc in the drive on another thread and rips its tracks one by one,
+/// each is appended to the playlist as soon as it is ripped
+#[cfg(feature = "cdda")]
+pub fn cdda_open(context: &mut AppContext) -> DiziResult {
+    let options = context.config_ref().server_ref().cdda_ref().clone();
+    let event_tx = context.events.server_event_sender().clone();
+    thread::spawn(move || {
+        if let Err(err) = rip_disc(&options, &event_tx) {
+            let _ = event_tx.send(ServerEvent::CddaError(err));
+        }
+    });
+    Ok(())
+}
+
+#[cfg(not(feature = "cdda"))]
+pub fn cdda_open(_context: &mut AppContext) -> DiziResult {
+    Err(DiziError::new(
+        DiziErrorKind::InvalidParameters,
+        "dizi-server was built without the cdda feature".to_string(),
+    ))
+}
+
+#[cfg(feature = "cdda")]
+fn rip_disc(options: &CddaOption, event_tx: &ServerEventSender) -> DiziResult {
+    let device = options.device.as_deref();
+    let toc = cdda::read_toc(device)?;
+
+    // the tracks are still played without names if the lookup fails
+    let info = match options.cddb_server.as_deref() {
+        Some(server) => cdda::cddb_lookup(server, &toc).unwrap_or_else(|err| {
+            tracing::debug!("CDDB lookup failed: {}", err);
+            None
+        }),
+        None => None,
+    }
+    .unwrap_or_default();
+
+    let dir = options.rip_dir.join(toc.disc_id());
+    fs::create_dir_all(&dir)?;
+    for track in toc.tracks.iter() {
+        let path = dir.join(format!("{:02}.wav", track.number));
+        let path = cdda::rip_track(device, track, &path)?;
+
+        let mut audio_file = DiziAudioFile::try_from(DiziFile::new(&path))?;
+        let tags = &mut audio_file.music_metadata.standard_tags;
+        tags.insert("TrackNumber".to_string(), track.number.to_string());
+        if let Some(title) = info.title(track) {
+            tags.insert("TrackTitle".to_string(), title.to_string());
+        }
+        if let Some(artist) = info.artist.as_ref() {
+            tags.insert("Artist".to_string(), artist.clone());
+        }
+        if let Some(album) = info.album.as_ref() {
+            tags.insert("Album".to_string(), album.clone());
+        }
+        event_tx.send(ServerEvent::PlaylistScanned {
+            path: dir.clone(),
+            audio_files: vec![audio_file],
+        })?;
+    }
+    Ok(())
+}
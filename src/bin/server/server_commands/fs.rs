@@ -0,0 +1,114 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use dizi::archive;
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::response::server::FsEntry;
+use dizi::utils;
+
+use crate::context::AppContext;
+
+const AUDIO_EXTENSIONS: [&str; 9] = [
+    "aac", "flac", "mp3", "mp4", "m4a", "ogg", "opus", "wav", "webm",
+];
+
+/// Errors unless `path` is inside the configured root,
+/// every path is allowed when there is no root
+pub fn check_within_root(context: &AppContext, path: &Path) -> DiziResult<PathBuf> {
+    within_root(context.config_ref().server_ref().root_ref(), path)
+}
+
+fn within_root(root: Option<&Path>, path: &Path) -> DiziResult<PathBuf> {
+    // members of archives aren't on the file system, their archive is
+    let path = match archive::split_archive_path(path) {
+        Some((archive, member)) if !member.is_empty() => archive.canonicalize()?.join(member),
+        _ => path.canonicalize()?,
+    };
+    if let Some(root) = root {
+        let root = root.canonicalize()?;
+        if !path.starts_with(&root) {
+            return Err(DiziError::new(
+                DiziErrorKind::IoError(io::ErrorKind::PermissionDenied),
+                format!("'{}' is outside of '{}'", path.display(), root.display()),
+            ));
+        }
+    }
+    Ok(path)
+}
+
+/// Directories and songs of a directory on the server's machine,
+/// the root, or the home directory without a root, when not given a path
+pub fn fs_list(context: &AppContext, path: Option<PathBuf>) -> DiziResult<(PathBuf, Vec<FsEntry>)> {
+    let path = match path {
+        Some(path) => path,
+        None => match context.config_ref().server_ref().root_ref() {
+            Some(root) => root.to_path_buf(),
+            None => dirs_next::home_dir().unwrap_or_else(|| PathBuf::from("/")),
+        },
+    };
+    let path = check_within_root(context, &path)?;
+
+    // archives are listed as directories, so they can be browsed into
+    let mut entries: Vec<FsEntry> = archive::list_dir(&path)?
+        .into_iter()
+        .filter_map(|(entry_path, is_dir)| {
+            let name = entry_path.file_name()?.to_string_lossy().to_string();
+            if name.starts_with('.') {
+                return None;
+            }
+            if !is_dir && !is_audio_extension(&entry_path) {
+                return None;
+            }
+            Some(FsEntry {
+                name,
+                path: entry_path,
+                is_dir,
+            })
+        })
+        .collect();
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| utils::natural_cmp(&a.name, &b.name))
+    });
+    Ok((path, entries))
+}
+
+fn is_audio_extension(path: &Path) -> bool {
+    if dizi::song::is_optional_format(path) {
+        return true;
+    }
+    path.extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("dizi-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn outside_root_is_refused() {
+        let dir = temp_dir("fs-root");
+        let root = dir.join("music");
+        fs::create_dir_all(&root).unwrap();
+        fs::write(root.join("a.flac"), b"").unwrap();
+        // lyrics are read next to the song, so this is what `/player/lyrics` would open
+        fs::write(dir.join("secret.lrc"), b"").unwrap();
+
+        assert!(within_root(Some(&root), &root.join("a.flac")).is_ok());
+        assert!(within_root(Some(&root), &dir.join("secret.lrc")).is_err());
+        assert!(within_root(Some(&root), &root.join("../secret.lrc")).is_err());
+        assert!(within_root(None, &dir.join("secret.lrc")).is_ok());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
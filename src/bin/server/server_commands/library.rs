@@ -0,0 +1,116 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::player::PlayerStatus;
+use dizi::response::server::ServerBroadcastEvent;
+use dizi::song::{DiziAudioFile, DiziFile};
+
+use crate::audio::waveform;
+use crate::context::AppContext;
+use crate::events::ServerEvent;
+use crate::traits::AudioPlayer;
+
+pub const MAX_RATING: u8 = 5;
+
+pub fn library_rate(context: &mut AppContext, path: PathBuf, rating: u8) -> DiziResult {
+    if rating > MAX_RATING {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("rating must be between 0 and {}", MAX_RATING),
+        ));
+    }
+    context.library.set_rating(path, rating);
+    let ratings_path = context.config_ref().server_ref().ratings_ref();
+    context.library.save(ratings_path)
+}
+
+/// Path of the playing song, if it is long enough to resume where it was left off
+fn resumable_song(context: &AppContext) -> Option<PathBuf> {
+    let player_option = context.config_ref().server_ref().player_ref();
+    let duration = context.player.state.total_duration()?;
+    if !player_option.resumes(duration) {
+        return None;
+    }
+    context
+        .player
+        .current_song_ref()
+        .map(|song| song.file_path().to_path_buf())
+}
+
+/// Remembers how far into the playing song playback is
+pub fn library_track_position(context: &mut AppContext, elapsed: time::Duration) {
+    // a song still loading reports the start, not where it will resume from
+    if context.player.state.status != PlayerStatus::Playing {
+        return;
+    }
+    if let Some(path) = resumable_song(context) {
+        context.library.set_position(path, elapsed);
+    }
+}
+
+/// Continues the song that just loaded from where it was left off
+pub fn library_resume(context: &mut AppContext) -> DiziResult {
+    let position = resumable_song(context).and_then(|path| context.library.position(&path));
+    match position {
+        Some(position) => context.player.seek(position),
+        None => Ok(()),
+    }
+}
+
+/// Plays the song from the start and forgets where it was left off
+pub fn library_restart(context: &mut AppContext) -> DiziResult {
+    if let Some(song) = context.player.current_song_ref() {
+        let path = song.file_path().to_path_buf();
+        context.library.set_position(path, time::Duration::ZERO);
+    }
+    context.player.seek(time::Duration::ZERO)?;
+    library_save_positions(context)
+}
+
+/// The song played to its end, so it starts over next time
+pub fn library_finish_song(context: &mut AppContext) {
+    if let Some(song) = context.player.current_song_ref() {
+        let path = song.file_path().to_path_buf();
+        context.library.set_position(path, time::Duration::ZERO);
+    }
+}
+
+pub fn library_save_positions(context: &AppContext) -> DiziResult {
+    let positions_path = context.config_ref().server_ref().positions_ref();
+    context.library.save_positions(positions_path)
+}
+
+/// Reads the lyrics of a song, preferring a `.lrc` file of the same name
+/// over the lyrics embedded in its tags
+pub fn library_lyrics(path: &Path) -> Option<String> {
+    if let Ok(lyrics) = fs::read_to_string(path.with_extension("lrc")) {
+        return Some(lyrics);
+    }
+    DiziAudioFile::try_from(DiziFile::new(path))
+        .ok()
+        .and_then(|song| song.music_metadata().standard_tags.get("Lyrics").cloned())
+}
+
+/// Answers with the song's waveform right away if it was read before,
+/// otherwise once it has been decoded on another thread
+pub fn library_waveform(context: &mut AppContext, path: PathBuf, buckets: usize) {
+    if let Some(peaks) = context.library.waveform(&path) {
+        let peaks = waveform::downsample(peaks, buckets);
+        context
+            .events
+            .broadcast_event(ServerBroadcastEvent::Waveform { path, peaks });
+        return;
+    }
+    let event_tx = context.events.server_event_sender().clone();
+    thread::spawn(move || {
+        let waveform = waveform::read_waveform(&path);
+        let _ = event_tx.send(ServerEvent::WaveformRead {
+            path,
+            buckets,
+            waveform,
+        });
+    });
+}
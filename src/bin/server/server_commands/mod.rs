@@ -1,5 +1,10 @@
+pub mod cdda;
+pub mod fs;
+pub mod library;
 pub mod player;
 pub mod playlist;
+pub mod queue;
+pub mod schedule;
 pub mod server;
 
 pub use self::player::*;
@@ -1,6 +1,7 @@
 use std::path::Path;
 
-use dizi::error::DiziResult;
+use dizi::chapter::{self, Chapter};
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
 use dizi::player::PlayerStatus;
 
 use crate::context::AppContext;
@@ -33,6 +34,8 @@ pub fn player_get_volume(context: &mut AppContext) -> usize {
 
 pub fn player_set_volume(context: &mut AppContext, volume: usize) -> DiziResult {
     context.player.set_volume(volume)?;
+    // the volume set by hand is kept, over the ramp of a scheduled playback
+    context.schedule.ramp_target = None;
     Ok(())
 }
 
@@ -45,6 +48,8 @@ pub fn player_volume_increase(context: &mut AppContext, amount: usize) -> DiziRe
         volume + amount
     };
     player_set_volume(context, volume)?;
+    // kept under the configured maximum
+    let volume = player_get_volume(context);
 
     tracing::debug!("volume is now: {volume}");
     Ok(volume)
@@ -55,6 +60,8 @@ pub fn player_volume_decrease(context: &mut AppContext, amount: usize) -> DiziRe
 
     let volume = if amount > volume { 0 } else { volume - amount };
     player_set_volume(context, volume)?;
+    // kept under the configured maximum
+    let volume = player_get_volume(context);
 
     tracing::debug!("volume is now: {volume}");
     Ok(volume)
@@ -77,3 +84,77 @@ pub fn player_play_previous(context: &mut AppContext) -> DiziResult {
     run_on_song_change(context);
     Ok(())
 }
+
+pub fn player_chapters(context: &AppContext) -> Vec<Chapter> {
+    context
+        .player
+        .current_song_ref()
+        .map(|song| song.chapters().to_vec())
+        .unwrap_or_default()
+}
+
+pub fn player_chapter(context: &mut AppContext, index: usize) -> DiziResult {
+    let start = match player_chapters(context).get(index) {
+        Some(chapter) => chapter.start,
+        None => {
+            return Err(DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                format!("No chapter {} in the playing song", index),
+            ))
+        }
+    };
+    context.player.seek(start)
+}
+
+/// Jumps to `percent` of the playing song, which must have a known length
+pub fn player_seek_percent(context: &mut AppContext, percent: u8) -> DiziResult {
+    if percent > 100 {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("{}% is past the end of the song", percent),
+        ));
+    }
+    let duration = match context.player.state.total_duration() {
+        Some(duration) => duration,
+        None => {
+            return Err(DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                "Length of the playing song is not known".to_string(),
+            ))
+        }
+    };
+    context.player.seek(duration * percent as u32 / 100)
+}
+
+/// Index of the playing chapter with `chapter_skip` on, for next and
+/// previous to move from
+fn skippable_chapter(context: &AppContext) -> Option<(usize, Vec<Chapter>)> {
+    if !context.config_ref().server_ref().player_ref().chapter_skip {
+        return None;
+    }
+    let chapters = player_chapters(context);
+    let index = chapter::chapter_at(&chapters, context.player.state.elapsed)?;
+    Some((index, chapters))
+}
+
+/// Jumps to the next chapter of the playing song, false on its last chapter
+pub fn player_next_chapter(context: &mut AppContext) -> DiziResult<bool> {
+    match skippable_chapter(context) {
+        Some((index, chapters)) if index + 1 < chapters.len() => {
+            context.player.seek(chapters[index + 1].start)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
+
+/// Jumps to the previous chapter of the playing song, false on its first chapter
+pub fn player_previous_chapter(context: &mut AppContext) -> DiziResult<bool> {
+    match skippable_chapter(context) {
+        Some((index, chapters)) if index > 0 => {
+            context.player.seek(chapters[index - 1].start)?;
+            Ok(true)
+        }
+        _ => Ok(false),
+    }
+}
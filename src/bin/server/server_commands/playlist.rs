@@ -1,12 +1,18 @@
 use std::cmp::Ordering;
-use std::fs;
+use std::collections::HashSet;
 use std::path::{Path, PathBuf};
+use std::thread;
 
+use dizi::archive;
 use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::playlist_format::PlaylistFormat;
 use dizi::song::{DiziAudioFile, DiziFile, DiziSongEntry};
+use dizi::utils;
 
 use crate::context::AppContext;
+use crate::events::ServerEvent;
 use crate::playlist::DiziPlaylist;
+use crate::server_commands::fs;
 use crate::server_util::run_on_song_change;
 use crate::traits::{AudioPlayer, DiziPlaylistTrait};
 use crate::util::mimetype::is_playable;
@@ -34,6 +40,24 @@ pub fn playlist_load(context: &mut AppContext, cwd: &Path, path: &Path) -> DiziR
     Ok(())
 }
 
+/// Writes the playlist to `path`, which may not exist yet but must be in a directory
+/// under the root. Returns where it was written
+pub fn playlist_save(context: &mut AppContext, path: &Path) -> DiziResult<PathBuf> {
+    let (dir, file_name) = match (path.parent(), path.file_name()) {
+        (Some(dir), Some(file_name)) if path.is_absolute() => (dir, file_name),
+        _ => {
+            return Err(DiziError::new(
+                DiziErrorKind::InvalidParameters,
+                format!("{:?} is not an absolute path to a file", path),
+            ))
+        }
+    };
+    let path = fs::check_within_root(context, dir)?.join(file_name);
+    let playlist = &context.player.playlist_context.file_playlist;
+    PlaylistFormat::write_file(&path, &playlist.contents)?;
+    Ok(path)
+}
+
 pub fn playlist_clear(context: &mut AppContext) -> DiziResult {
     context.player.playlist_context_mut().file_playlist.clear();
     Ok(())
@@ -41,7 +65,7 @@ pub fn playlist_clear(context: &mut AppContext) -> DiziResult {
 
 pub fn playlist_append(context: &mut AppContext, path: &Path) -> DiziResult<Vec<DiziAudioFile>> {
     let playlist = &mut context.player.playlist_context_mut().file_playlist;
-    if path.is_dir() {
+    if archive::is_directory(path) {
         let audio_files = recursively_find_songs(path);
         for audio_file in audio_files.iter() {
             let entry = DiziSongEntry::Loaded(audio_file.clone());
@@ -62,6 +86,104 @@ pub fn playlist_append(context: &mut AppContext, path: &Path) -> DiziResult<Vec<
     }
 }
 
+/// Looks for songs under `path` on another thread, they are appended with
+/// `playlist_append_songs` once the whole directory tree has been read
+pub fn playlist_append_recursive(context: &mut AppContext, path: PathBuf) -> DiziResult {
+    playlist_append_dir(context, path, true)
+}
+
+/// Reads the songs of the directory at `path` sorted by name, along with
+/// those of its subdirectories when `recursive` is set, on another thread.
+/// They are appended once the scan is handed back as `PlaylistScanned`
+pub fn playlist_append_dir(context: &mut AppContext, path: PathBuf, recursive: bool) -> DiziResult {
+    if !archive::is_directory(&path) {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("{:?} is not a directory", path),
+        ));
+    }
+    let event_tx = context.events.server_event_sender().clone();
+    thread::spawn(move || {
+        let mut audio_files = Vec::new();
+        find_songs(&mut audio_files, &path, recursive);
+        let _ = event_tx.send(ServerEvent::PlaylistScanned { path, audio_files });
+    });
+    Ok(())
+}
+
+/// Puts the song at `path`, or every song under it, at `index` of the playlist
+pub fn playlist_insert(
+    context: &mut AppContext,
+    path: &Path,
+    index: usize,
+) -> DiziResult<Vec<DiziAudioFile>> {
+    let playlist = &mut context.player.playlist_context_mut().file_playlist;
+    if index > playlist.len() {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("Index {} is past the end of the playlist", index),
+        ));
+    }
+    let audio_files = if archive::is_directory(path) {
+        recursively_find_songs(path)
+    } else if is_playable(path)? {
+        vec![DiziAudioFile::try_from(DiziFile::new(path))?]
+    } else {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "File not playable".to_string(),
+        ));
+    };
+    for (i, audio_file) in audio_files.iter().enumerate() {
+        playlist.insert(index + i, DiziSongEntry::Loaded(audio_file.clone()));
+    }
+    Ok(audio_files)
+}
+
+pub fn playlist_append_songs(context: &mut AppContext, audio_files: &[DiziAudioFile]) {
+    let playlist = &mut context.player.playlist_context_mut().file_playlist;
+    for audio_file in audio_files.iter() {
+        playlist.push_entry(DiziSongEntry::Loaded(audio_file.clone()));
+    }
+}
+
+/// Appends every song found in `paths`, or inserts them after the playing song
+/// when `next` is set. Returns the index of the first new song along with the songs
+pub fn playlist_append_batch(
+    context: &mut AppContext,
+    paths: &[PathBuf],
+    next: bool,
+) -> DiziResult<(usize, Vec<DiziAudioFile>)> {
+    let mut audio_files = Vec::new();
+    for path in paths {
+        if archive::is_directory(path) {
+            audio_files.extend(recursively_find_songs(path));
+        } else if let Ok(true) = is_playable(path) {
+            let file = DiziFile::new(path);
+            match DiziAudioFile::try_from(file) {
+                Ok(audio_file) => audio_files.push(audio_file),
+                Err(e) => tracing::debug!("Skipping {:?}: {}", path, e),
+            }
+        }
+    }
+
+    let entries: Vec<_> = audio_files
+        .iter()
+        .map(|audio_file| DiziSongEntry::Loaded(audio_file.clone()))
+        .collect();
+    let playlist = &mut context.player.playlist_context_mut().file_playlist;
+    let index = if next {
+        playlist.insert_entries_next(entries)
+    } else {
+        let index = playlist.len();
+        entries
+            .into_iter()
+            .for_each(|entry| playlist.push_entry(entry));
+        index
+    };
+    Ok((index, audio_files))
+}
+
 pub fn playlist_remove(context: &mut AppContext, index: usize) -> DiziResult {
     let playlist = &mut context.player.playlist_context_mut().file_playlist;
     if index >= playlist.len() {
@@ -74,6 +196,26 @@ pub fn playlist_remove(context: &mut AppContext, index: usize) -> DiziResult {
     Ok(())
 }
 
+/// Removes songs already listed earlier in the playlist, returns
+/// the indices removed from last to first
+pub fn playlist_dedup(context: &mut AppContext) -> Vec<usize> {
+    let playlist = &mut context.player.playlist_context_mut().file_playlist;
+    let mut seen = HashSet::new();
+    playlist.retain_entries(|entry| seen.insert(entry.file_path().to_path_buf()))
+}
+
+/// Removes songs whose files are gone, returns the indices removed from last to first
+pub fn playlist_prune_missing(context: &mut AppContext) -> Vec<usize> {
+    let playlist = &mut context.player.playlist_context_mut().file_playlist;
+    playlist.retain_entries(|entry| {
+        // members of archives are there as long as their archive is
+        match archive::split_archive_path(entry.file_path()) {
+            Some((archive, _)) => archive.exists(),
+            None => entry.file_path().exists(),
+        }
+    })
+}
+
 pub fn playlist_move_up(context: &mut AppContext, index: usize) -> DiziResult {
     if index == 0 {
         return Err(DiziError::new(
@@ -95,6 +237,18 @@ pub fn playlist_move_up(context: &mut AppContext, index: usize) -> DiziResult {
     Ok(())
 }
 
+pub fn playlist_move_to(context: &mut AppContext, index: usize, to: usize) -> DiziResult {
+    let playlist = &mut context.player.playlist_context_mut().file_playlist;
+    if index >= playlist.len() || to >= playlist.len() {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "index out of range".to_string(),
+        ));
+    }
+    playlist.move_entry(index, to);
+    Ok(())
+}
+
 pub fn playlist_move_down(context: &mut AppContext, index: usize) -> DiziResult {
     let playlist = &mut context.player.playlist_context_mut().file_playlist;
 
@@ -110,29 +264,29 @@ pub fn playlist_move_down(context: &mut AppContext, index: usize) -> DiziResult
     Ok(())
 }
 
-fn sort_function(p1: &Path, p2: &Path) -> Ordering {
-    let p1_is_dir = p1.is_dir();
-    let p2_is_dir = p2.is_dir();
+fn sort_function((p1, p1_is_dir): &(PathBuf, bool), (p2, p2_is_dir): &(PathBuf, bool)) -> Ordering {
     match (p1_is_dir, p2_is_dir) {
         (true, false) => Ordering::Less,
         (false, true) => Ordering::Greater,
-        _ => alphanumeric_sort::compare_path(p1, p2),
+        _ => utils::natural_cmp_path(p1, p2),
     }
 }
 
 fn recursively_find_songs(path: &Path) -> Vec<DiziAudioFile> {
     let mut songs: Vec<_> = Vec::new();
-    find_songs_rec(&mut songs, path);
+    find_songs(&mut songs, path, true);
     songs
 }
 
-fn find_songs_rec(songs: &mut Vec<DiziAudioFile>, path: &Path) {
-    if let Ok(readdir) = fs::read_dir(path) {
-        let mut paths: Vec<PathBuf> = readdir.flatten().map(|entry| entry.path()).collect();
-        paths.sort_by(|p1, p2| sort_function(p1, p2));
-        for entry_path in paths.iter() {
-            if entry_path.is_dir() {
-                find_songs_rec(songs, entry_path);
+fn find_songs(songs: &mut Vec<DiziAudioFile>, path: &Path, recursive: bool) {
+    // archives are searched like directories
+    if let Ok(mut entries) = archive::list_dir(path) {
+        entries.sort_by(sort_function);
+        for (entry_path, is_dir) in entries.iter() {
+            if *is_dir {
+                if recursive {
+                    find_songs(songs, entry_path, recursive);
+                }
                 continue;
             }
 
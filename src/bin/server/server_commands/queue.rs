@@ -0,0 +1,35 @@
+use std::path::Path;
+
+use dizi::archive;
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::song::{DiziAudioFile, DiziFile};
+
+use crate::context::AppContext;
+use crate::server_util::run_on_song_change;
+use crate::traits::AudioPlayer;
+use crate::util::mimetype::is_playable;
+
+pub fn queue_push(context: &mut AppContext, path: &Path) -> DiziResult {
+    if archive::is_directory(path) || !is_playable(path)? {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "File not playable".to_string(),
+        ));
+    }
+    let audio_file = DiziAudioFile::try_from(DiziFile::new(path))?;
+    context.player.queue_mut().push_back(audio_file);
+    Ok(())
+}
+
+pub fn queue_clear(context: &mut AppContext) {
+    context.player.queue_mut().clear();
+}
+
+/// Plays the first queued song, false when nothing is queued
+pub fn queue_play(context: &mut AppContext) -> DiziResult<bool> {
+    if !context.player.play_queued()? {
+        return Ok(false);
+    }
+    run_on_song_change(context);
+    Ok(true)
+}
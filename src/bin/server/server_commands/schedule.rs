@@ -0,0 +1,134 @@
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time;
+
+use chrono::{DateTime, Duration, Local, NaiveDateTime, NaiveTime, TimeZone};
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::player::PlaybackSchedule;
+
+use crate::context::AppContext;
+use crate::events::ServerEvent;
+use crate::server_commands::fs;
+use crate::server_commands::playlist::{playlist_clear, playlist_load, playlist_play};
+use crate::traits::AudioPlayer;
+
+// how long the volume takes to go from silent back to where it was
+const RAMP_DURATION: time::Duration = time::Duration::from_secs(60);
+const RAMP_STEPS: usize = 30;
+
+/// `HH:MM` is the next time the clock reads it, `YYYY-MM-DD HH:MM` a given day
+fn parse_time(s: &str, now: DateTime<Local>) -> DiziResult<DateTime<Local>> {
+    let invalid = || {
+        DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("Invalid time '{}', expected HH:MM or YYYY-MM-DD HH:MM", s),
+        )
+    };
+    let naive = match NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M") {
+        Ok(naive) => naive,
+        Err(_) => {
+            let time = NaiveTime::parse_from_str(s, "%H:%M").map_err(|_| invalid())?;
+            let today = now.date_naive().and_time(time);
+            if today > now.naive_local() {
+                today
+            } else {
+                today + Duration::days(1)
+            }
+        }
+    };
+    Local
+        .from_local_datetime(&naive)
+        .earliest()
+        .ok_or_else(invalid)
+}
+
+/// Starts playing the playlist file at `playlist` at `time`, replacing any earlier schedule
+pub fn player_schedule(
+    context: &mut AppContext,
+    time: &str,
+    playlist: PathBuf,
+) -> DiziResult<PlaybackSchedule> {
+    let now = Local::now();
+    let at = parse_time(time, now)?;
+    if at <= now {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("'{}' has already passed", time),
+        ));
+    }
+    let playlist = fs::check_within_root(context, &playlist)?;
+    if !playlist.is_file() {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!("Playlist {:?} is not a file", playlist),
+        ));
+    }
+
+    let generation = context.schedule.reset();
+    let schedule = PlaybackSchedule {
+        time: at.format("%Y-%m-%d %H:%M").to_string(),
+        playlist,
+    };
+    context.schedule.schedule = Some(schedule.clone());
+
+    let wait = (at - now).to_std().unwrap_or_default();
+    let event_tx = context.events.server_event_sender().clone();
+    thread::spawn(move || {
+        thread::sleep(wait);
+        let _ = event_tx.send(ServerEvent::ScheduleStart(generation));
+    });
+    Ok(schedule)
+}
+
+/// Cancels the scheduled playback, and finishes raising the volume if it had already started.
+/// Returns the volume when it was changed
+pub fn player_schedule_cancel(context: &mut AppContext) -> DiziResult<Option<usize>> {
+    context.schedule.reset();
+    match context.schedule.ramp_target.take() {
+        Some(volume) => {
+            context.player.set_volume(volume)?;
+            Ok(Some(volume))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Replaces the playlist with the scheduled one and plays it from silence,
+/// the volume is then raised back to where it was over `RAMP_DURATION`
+pub fn schedule_start(context: &mut AppContext, path: &Path) -> DiziResult {
+    let volume = context.player.get_volume();
+
+    playlist_clear(context)?;
+    let cwd = path.parent().unwrap_or_else(|| Path::new("/"));
+    playlist_load(context, cwd, path)?;
+    context.player.set_volume(0)?;
+    playlist_play(context, 0)?;
+    context.schedule.ramp_target = Some(volume);
+
+    let event_tx = context.events.server_event_sender().clone();
+    thread::spawn(move || {
+        for step in 1..=RAMP_STEPS {
+            thread::sleep(RAMP_DURATION / RAMP_STEPS as u32);
+            if event_tx.send(ServerEvent::ScheduleRamp(step)).is_err() {
+                return;
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Raises the volume by a step of the ramp, returns the new volume.
+/// Nothing is done once the ramp was cancelled or the volume was changed by hand
+pub fn schedule_ramp(context: &mut AppContext, step: usize) -> DiziResult<Option<usize>> {
+    let target = match context.schedule.ramp_target {
+        Some(target) => target,
+        None => return Ok(None),
+    };
+    let volume = target * step / RAMP_STEPS;
+    context.player.set_volume(volume)?;
+    if step >= RAMP_STEPS {
+        context.schedule.ramp_target = None;
+    }
+    Ok(Some(volume))
+}
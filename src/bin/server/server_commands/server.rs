@@ -1,4 +1,7 @@
 use dizi::error::DiziResult;
+use dizi::player;
+
+use crate::traits::DiziPlaylistTrait;
 
 use crate::{
     context::{AppContext, QuitType},
@@ -11,7 +14,12 @@ pub fn quit_server(context: &mut AppContext) -> DiziResult {
 }
 
 pub fn query(context: &mut AppContext, query: &str) -> DiziResult<String> {
-    let player_state = context.player.player_state();
-    let res = player_state.query(query)?;
+    // without copying the playlists, only their length is needed
+    let playlist = &context.player.playlist_context.file_playlist;
+    let vars = context
+        .player
+        .player_info()
+        .query_all(playlist.playing_index(), playlist.len());
+    let res = player::format_query(query, &vars)?;
     Ok(res)
 }
@@ -6,11 +6,13 @@ use std::time::Duration;
 use uuid::Uuid;
 
 use dizi::error::DiziResult;
-use dizi::player::PlayerStatus;
+use dizi::player::{PlayerStatus, RepeatMode};
 use dizi::playlist::PlaylistType;
 use dizi::request::client::ClientRequest;
 use dizi::response::server::ServerBroadcastEvent;
+use dizi::song::{DiziAudioFile, DiziSongEntry};
 
+use crate::audio::equalizer;
 use crate::client;
 use crate::context::AppContext;
 use crate::events::ServerEvent;
@@ -35,14 +37,198 @@ pub fn process_server_event(context: &mut AppContext, event: ServerEvent) -> Diz
                 .events
                 .add_broadcast_listener(uuid_string, server_tx);
         }
+        #[cfg(feature = "web")]
+        ServerEvent::NewWebClient(stream) => {
+            let client_tx2 = context.events.client_request_sender().clone();
+            let (server_tx, server_rx) = mpsc::channel();
+
+            let client_uuid = Uuid::new_v4();
+            let uuid_string = client_uuid.to_string();
+            let writes_allowed = context.config_ref().server_ref().root_ref().is_some();
+
+            thread::spawn(move || {
+                crate::web::handle_web_client(
+                    client_uuid,
+                    stream,
+                    client_tx2,
+                    server_rx,
+                    writes_allowed,
+                )
+            });
+            context
+                .events
+                .add_broadcast_listener(uuid_string, server_tx);
+        }
+        #[cfg(feature = "web")]
+        ServerEvent::RestRequest {
+            request,
+            response_tx,
+        } => {
+            // listens like any other client while the request is processed,
+            // so only what this request broadcast is taken as its answer
+            let uuid = Uuid::new_v4().to_string();
+            context
+                .events
+                .add_broadcast_listener(uuid.clone(), response_tx.clone());
+            let writes_allowed = context.config_ref().server_ref().root_ref().is_some();
+            let res = crate::web::check_writes(&request, writes_allowed)
+                .and_then(|_| process_client_request(context, &uuid, request));
+            let _ = context.events.server_broadcast_listeners.remove(&uuid);
+            if let Err(err) = res {
+                let event = ServerBroadcastEvent::ServerError {
+                    msg: err.to_string(),
+                };
+                let _ = response_tx.send(std::sync::Arc::new(event));
+            }
+        }
         ServerEvent::PlayerProgressUpdate(elapsed) => {
             context.player.set_elapsed(elapsed);
+            library::library_track_position(context, elapsed);
+            let duration = context.player.state.total_duration();
             context
                 .events
-                .broadcast_event(ServerBroadcastEvent::PlayerProgressUpdate { elapsed });
+                .broadcast_event(ServerBroadcastEvent::PlayerProgressUpdate { elapsed, duration });
+        }
+        ServerEvent::PlayerSpectrumUpdate(bands) => {
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerSpectrumUpdate { bands });
         }
         ServerEvent::PlayerDone => {
-            process_done_song(context)?;
+            // already moved on when the song reached its crossfade
+            if context.crossfade.done() {
+                library::library_finish_song(context);
+                process_done_song(context)?;
+            }
+        }
+        ServerEvent::PlayerEnding => {
+            // the last song of the playlist plays out to its end instead
+            if crossfades(context) {
+                library::library_finish_song(context);
+                process_done_song(context)?;
+                context.crossfade.advance();
+            }
+        }
+        ServerEvent::PlayerLoaded { generation, loaded } => {
+            // another song was played before this one loaded
+            if generation != context.player.load_generation {
+                return Ok(());
+            }
+            context.crossfade.loaded();
+            // stopped while loading
+            if context.player.state.status == PlayerStatus::Stopped {
+                return Ok(());
+            }
+            match loaded {
+                Ok(loaded) => {
+                    if loaded.duration.is_some() {
+                        context.player.state.duration = loaded.duration;
+                    }
+                    context.player.state.output = Some(loaded.output);
+                    // paused while loading stays paused
+                    if context.player.state.status == PlayerStatus::Loading {
+                        context.player.state.status = PlayerStatus::Playing;
+                        context
+                            .events
+                            .broadcast_event(ServerBroadcastEvent::PlayerResume);
+                    }
+                    library::library_resume(context)?;
+                    // also saves where the previous song was left off
+                    library::library_save_positions(context)?;
+                }
+                Err(err) => {
+                    context.player.state.status = PlayerStatus::Stopped;
+                    context.player.state.output = None;
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::ServerError {
+                            msg: err.to_string(),
+                        });
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::PlayerStop);
+                }
+            }
+        }
+        ServerEvent::PlaylistScanned { path, audio_files } => {
+            playlist::playlist_append_songs(context, &audio_files);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistAppend { audio_files });
+            broadcast_playlist_order(context);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistScanDone { path });
+        }
+        ServerEvent::WaveformRead {
+            path,
+            buckets,
+            waveform,
+        } => match waveform {
+            Ok(peaks) => {
+                context.library.set_waveform(path.clone(), peaks);
+                library::library_waveform(context, path, buckets);
+            }
+            Err(err) => {
+                context
+                    .events
+                    .broadcast_event(ServerBroadcastEvent::ServerError {
+                        msg: err.to_string(),
+                    });
+            }
+        },
+        ServerEvent::ScheduleStart(generation) => {
+            // a newer schedule replaced this one
+            if generation != context.schedule.generation {
+                return Ok(());
+            }
+            let schedule = match context.schedule.schedule.take() {
+                Some(schedule) => schedule,
+                None => return Ok(()),
+            };
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerSchedule { schedule: None });
+            match schedule::schedule_start(context, &schedule.playlist) {
+                Ok(()) => {
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::PlaylistClear);
+                    let state = context.player.player_state();
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::PlaylistOpen { state });
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::PlayerVolumeUpdate { volume: 0 });
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::PlaylistPlay { index: 0 });
+                    broadcast_playlist_order(context);
+                }
+                Err(err) => {
+                    context
+                        .events
+                        .broadcast_event(ServerBroadcastEvent::ServerError {
+                            msg: format!("Scheduled playback: {}", err),
+                        });
+                }
+            }
+        }
+        ServerEvent::ScheduleRamp(step) => {
+            if let Some(volume) = schedule::schedule_ramp(context, step)? {
+                context
+                    .events
+                    .broadcast_event(ServerBroadcastEvent::PlayerVolumeUpdate { volume });
+            }
+        }
+        #[cfg(feature = "cdda")]
+        ServerEvent::CddaError(err) => {
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::ServerError {
+                    msg: err.to_string(),
+                });
         }
     }
     Ok(())
@@ -67,6 +253,8 @@ pub fn process_client_request(
         ClientRequest::ClientLeave { uuid } => {
             let _ = context.events.server_broadcast_listeners.remove(&uuid);
         }
+        // clients on the unix socket are local and trusted
+        ClientRequest::ClientAuth { .. } => {}
         ClientRequest::PlayerState => {
             let state = context.player.player_state();
             context
@@ -74,7 +262,16 @@ pub fn process_client_request(
                 .broadcast_event(ServerBroadcastEvent::PlayerState { state });
         }
         ClientRequest::PlayerFilePlay { path: Some(p) } => {
+            fs::check_within_root(context, &p)?;
             player_play(context, p.as_path())?;
+            let playlist = context
+                .player
+                .playlist_context
+                .directory_playlist
+                .to_file_playlist();
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistDirectory { playlist });
             if let Some(song) = context.player.current_song_ref() {
                 let song = song.clone();
                 context
@@ -87,6 +284,7 @@ pub fn process_client_request(
             context
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlayerPause);
+            library::library_save_positions(context)?;
         }
         ClientRequest::PlayerResume => {
             player_resume(context)?;
@@ -106,6 +304,24 @@ pub fn process_client_request(
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlayerVolumeUpdate { volume });
         }
+        ClientRequest::PlayerSchedule { time, playlist } => {
+            let schedule = schedule::player_schedule(context, &time, playlist)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerSchedule {
+                    schedule: Some(schedule),
+                });
+        }
+        ClientRequest::PlayerScheduleCancel => {
+            if let Some(volume) = schedule::player_schedule_cancel(context)? {
+                context
+                    .events
+                    .broadcast_event(ServerBroadcastEvent::PlayerVolumeUpdate { volume });
+            }
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerSchedule { schedule: None });
+        }
         ClientRequest::PlayerTogglePlay => {
             let status = player_toggle_play(context)?;
             match status {
@@ -127,24 +343,69 @@ pub fn process_client_request(
             }
         }
         ClientRequest::PlayerPlayNext => {
-            player_play_next(context)?;
-            send_latest_song_info(context)?;
+            if !player_next_chapter(context)? && !play_queued(context)? {
+                player_play_next(context)?;
+                send_latest_song_info(context)?;
+            }
         }
         ClientRequest::PlayerPlayPrevious => {
-            player_play_previous(context)?;
-            send_latest_song_info(context)?;
+            if !player_previous_chapter(context)? {
+                player_play_previous(context)?;
+                send_latest_song_info(context)?;
+            }
         }
         ClientRequest::PlaylistAppend { path: Some(p) } => {
+            fs::check_within_root(context, &p)?;
             let songs = playlist::playlist_append(context, &p)?;
             context
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlaylistAppend { audio_files: songs });
+            broadcast_playlist_order(context);
+        }
+        ClientRequest::PlaylistAppendBatch { paths, next } => {
+            for p in paths.iter() {
+                fs::check_within_root(context, p)?;
+            }
+            let (index, songs) = playlist::playlist_append_batch(context, &paths, next)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistInsert {
+                    index,
+                    audio_files: songs,
+                });
+            broadcast_playlist_order(context);
+        }
+        ClientRequest::PlaylistAppendRecursive { path: Some(path) } => {
+            fs::check_within_root(context, &path)?;
+            playlist::playlist_append_recursive(context, path)?;
+        }
+        ClientRequest::PlaylistAppendDir {
+            path: Some(path),
+            recursive,
+        } => {
+            fs::check_within_root(context, &path)?;
+            playlist::playlist_append_dir(context, path, recursive)?;
+        }
+        ClientRequest::PlaylistInsert {
+            path: Some(path),
+            index: Some(index),
+        } => {
+            fs::check_within_root(context, &path)?;
+            let songs = playlist::playlist_insert(context, &path, index)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistInsert {
+                    index,
+                    audio_files: songs,
+                });
+            broadcast_playlist_order(context);
         }
         ClientRequest::PlaylistRemove { index: Some(index) } => {
             playlist::playlist_remove(context, index)?;
             context
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlaylistRemove { index });
+            broadcast_playlist_order(context);
         }
         ClientRequest::PlaylistClear => {
             playlist::playlist_clear(context)?;
@@ -152,6 +413,20 @@ pub fn process_client_request(
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlaylistClear);
         }
+        ClientRequest::PlaylistDedup => {
+            let indices = playlist::playlist_dedup(context);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistRemoveMany { indices });
+            broadcast_playlist_order(context);
+        }
+        ClientRequest::PlaylistPruneMissing => {
+            let indices = playlist::playlist_prune_missing(context);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistRemoveMany { indices });
+            broadcast_playlist_order(context);
+        }
         ClientRequest::PlaylistMoveUp { index: Some(index) } => {
             playlist::playlist_move_up(context, index)?;
             context
@@ -170,16 +445,31 @@ pub fn process_client_request(
                     index2: index + 1,
                 });
         }
+        ClientRequest::PlaylistMoveTo { index, to } => {
+            playlist::playlist_move_to(context, index, to)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistMove { index, to });
+            broadcast_playlist_order(context);
+        }
+        ClientRequest::PlaylistSave { path } => {
+            let path = playlist::playlist_save(context, &path)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlaylistSave { path });
+        }
         ClientRequest::PlaylistPlay { index: Some(index) } => {
             playlist::playlist_play(context, index)?;
             context
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlaylistPlay { index });
+            broadcast_playlist_order(context);
         }
         ClientRequest::PlaylistOpen {
             cwd: Some(cwd),
             path: Some(path),
         } => {
+            fs::check_within_root(context, &cwd.join(&path))?;
             playlist::playlist_load(context, &cwd, &path)?;
             let state = context.player.player_state();
             context
@@ -194,11 +484,24 @@ pub fn process_client_request(
                 .broadcast_event(ServerBroadcastEvent::PlayerNext { on: !enabled });
         }
         ClientRequest::PlayerToggleRepeat => {
-            let enabled = context.player.repeat_enabled();
-            context.player.set_repeat(!enabled);
+            let mode = context.player.repeat_mode().next();
+            context.player.set_repeat_mode(mode);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerRepeat { mode });
+        }
+        ClientRequest::PlayerSetRepeat { mode } => {
+            context.player.set_repeat_mode(mode);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerRepeat { mode });
+        }
+        ClientRequest::PlayerToggleSingle => {
+            let enabled = context.player.single_enabled();
+            context.player.set_single(!enabled);
             context
                 .events
-                .broadcast_event(ServerBroadcastEvent::PlayerRepeat { on: !enabled });
+                .broadcast_event(ServerBroadcastEvent::PlayerSingle { on: !enabled });
         }
         ClientRequest::PlayerToggleShuffle => {
             let enabled = context.player.shuffle_enabled();
@@ -206,6 +509,32 @@ pub fn process_client_request(
             context
                 .events
                 .broadcast_event(ServerBroadcastEvent::PlayerShuffle { on: !enabled });
+            broadcast_playlist_order(context);
+        }
+        ClientRequest::PlayerEqSetBand { band, gain } => {
+            context.player.set_eq_band(band, gain)?;
+            let gains = context.player.eq_gains();
+            equalizer::save_gains(context.config_ref().server_ref().equalizer_ref(), &gains)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerEqState {
+                    bands: equalizer::bands(&gains),
+                });
+        }
+        ClientRequest::PlayerEqGetState => {
+            let gains = context.player.eq_gains();
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerEqState {
+                    bands: equalizer::bands(&gains),
+                });
+        }
+        ClientRequest::PlayerToggleReplayGain => {
+            let mode = context.player.replaygain_mode().next();
+            context.player.set_replaygain_mode(mode)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerReplayGain { mode });
         }
         ClientRequest::PlayerFastForward { amount } => {
             let duration = Duration::from_secs(amount as u64);
@@ -215,6 +544,86 @@ pub fn process_client_request(
             let duration = Duration::from_secs(amount as u64);
             context.player.rewind(duration)?;
         }
+        ClientRequest::PlayerSeek { position } => {
+            let duration = Duration::from_secs(position as u64);
+            context.player.seek(duration)?;
+        }
+        ClientRequest::PlayerSeekPercent { percent } => {
+            player_seek_percent(context, percent)?;
+        }
+        ClientRequest::PlayerRestart => {
+            library::library_restart(context)?;
+        }
+        ClientRequest::PlayerChapters => {
+            let chapters = player_chapters(context);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerChapters { chapters });
+        }
+        ClientRequest::PlayerChapter { index } => {
+            player_chapter(context, index)?;
+        }
+        ClientRequest::CddaOpen => {
+            cdda::cdda_open(context)?;
+        }
+        ClientRequest::LibraryRate { path, rating } => {
+            let path = match path {
+                Some(path) => fs::check_within_root(context, &path)?,
+                None => match context.player.current_song_ref() {
+                    Some(song) => song.file_path().to_path_buf(),
+                    None => return Ok(()),
+                },
+            };
+            library::library_rate(context, path.clone(), rating)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::LibraryRating { path, rating });
+        }
+        ClientRequest::PlayerLyrics { path } => {
+            let path = match path {
+                Some(path) => fs::check_within_root(context, &path)?,
+                None => match context.player.current_song_ref() {
+                    Some(song) => song.file_path().to_path_buf(),
+                    None => return Ok(()),
+                },
+            };
+            let lyrics = library::library_lyrics(&path);
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::PlayerLyrics { path, lyrics });
+        }
+        ClientRequest::QueryWaveform { path, buckets } => {
+            let path = match path {
+                Some(path) => fs::check_within_root(context, &path)?,
+                None => match context.player.current_song_ref() {
+                    Some(song) => song.file_path().to_path_buf(),
+                    None => return Ok(()),
+                },
+            };
+            library::library_waveform(context, path, buckets);
+        }
+        ClientRequest::QueuePush { path: Some(p) } => {
+            fs::check_within_root(context, &p)?;
+            queue::queue_push(context, &p)?;
+            broadcast_queue(context);
+        }
+        ClientRequest::QueueList => broadcast_queue(context),
+        ClientRequest::QueueClear => {
+            queue::queue_clear(context);
+            broadcast_queue(context);
+        }
+        ClientRequest::LibraryRatings => {
+            let ratings = context.library.ratings_ref().clone();
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::LibraryRatings { ratings });
+        }
+        ClientRequest::FsList { path } => {
+            let (path, entries) = fs::fs_list(context, path)?;
+            context
+                .events
+                .broadcast_event(ServerBroadcastEvent::FsList { path, entries });
+        }
         ClientRequest::ServerQueryAll => {}
         s => {
             tracing::debug!("'{:?}' not implemented", s);
@@ -248,11 +657,22 @@ pub fn send_latest_song_info(context: &mut AppContext) -> DiziResult {
 pub fn process_done_song(context: &mut AppContext) -> DiziResult {
     tracing::debug!("Processing done song trigger");
 
+    let previous = context.player.current_song_ref().cloned().map(Box::new);
     let next_enabled = context.player.next_enabled();
-    let repeat_enabled = context.player.repeat_enabled();
+    let repeat_mode = context.player.repeat_mode();
 
-    if next_enabled {
-        if !repeat_enabled && end_of_playlist(context) {
+    if context.player.single_enabled() {
+        context.player.stop()?;
+        context
+            .events
+            .broadcast_event(ServerBroadcastEvent::PlayerStop);
+    } else if play_queued(context)? {
+        // queued songs come first, whether or not the playlist would move on
+    } else if repeat_mode == RepeatMode::One {
+        player_play_again(context)?;
+        send_latest_song_info(context)?;
+    } else if next_enabled {
+        if repeat_mode == RepeatMode::Off && end_of_playlist(context) {
             context.player.stop()?;
             context
                 .events
@@ -261,19 +681,87 @@ pub fn process_done_song(context: &mut AppContext) -> DiziResult {
             player_play_next(context)?;
             send_latest_song_info(context)?;
         }
-    } else if repeat_enabled {
-        player_play_again(context)?;
-        send_latest_song_info(context)?;
     } else {
+        // without next, playback ends with the song
     }
+    broadcast_track_transition(context, previous);
 
     Ok(())
 }
 
+/// Lets clients know what was played, what is playing and what comes next, all at once
+fn broadcast_track_transition(context: &mut AppContext, previous: Option<Box<DiziAudioFile>>) {
+    let current = match context.player.state.status {
+        PlayerStatus::Stopped => None,
+        _ => context.player.current_song_ref().cloned().map(Box::new),
+    };
+    let next = current
+        .as_ref()
+        .and_then(|_| match context.player.queue_ref().front() {
+            Some(song) => Some(DiziSongEntry::Loaded(song.clone())),
+            None => context
+                .player
+                .playlist_context
+                .next_song_peak()
+                .map(|entry| entry.entry),
+        })
+        .map(Box::new);
+    context
+        .events
+        .broadcast_event(ServerBroadcastEvent::TrackTransition {
+            previous,
+            current,
+            next,
+        });
+}
+
+/// Whether the song after the one ending starts before it ends
+fn crossfades(context: &AppContext) -> bool {
+    let next_enabled = context.player.next_enabled();
+    let repeat_mode = context.player.repeat_mode();
+    context.player.state.status == PlayerStatus::Playing
+        && !context.player.single_enabled()
+        && (!context.player.queue_ref().is_empty()
+            || match repeat_mode {
+                RepeatMode::One => true,
+                RepeatMode::All => next_enabled,
+                RepeatMode::Off => next_enabled && !end_of_playlist(context),
+            })
+}
+
 pub fn end_of_playlist(context: &AppContext) -> bool {
     context.player.playlist_context.is_end()
 }
 
+/// Plays the next queued song, letting clients know it left the queue
+fn play_queued(context: &mut AppContext) -> DiziResult<bool> {
+    if !queue::queue_play(context)? {
+        return Ok(false);
+    }
+    if let Some(file) = context.player.current_song_ref().cloned() {
+        context
+            .events
+            .broadcast_event(ServerBroadcastEvent::PlayerFilePlay { file });
+    }
+    broadcast_queue(context);
+    Ok(true)
+}
+
+fn broadcast_queue(context: &mut AppContext) {
+    let songs = context.player.queue_ref().iter().cloned().collect();
+    context
+        .events
+        .broadcast_event(ServerBroadcastEvent::QueueState { songs });
+}
+
+/// Lets clients know which songs are coming up after the playlist's order changed
+fn broadcast_playlist_order(context: &mut AppContext) {
+    let order = context.player.playlist_context.file_playlist.order.clone();
+    context
+        .events
+        .broadcast_event(ServerBroadcastEvent::PlaylistOrder { order });
+}
+
 pub fn run_on_song_change(context: &AppContext) {
     let server_config = context.config_ref().server_ref();
     if let Some(path) = server_config.on_song_change.as_ref() {
@@ -1,13 +1,17 @@
+use std::collections::VecDeque;
 use std::path::Path;
 use std::time;
 
 use dizi::error::DiziResult;
-use dizi::player::{PlayerState, PlayerStatus};
-use dizi::song::DiziAudioFile;
+use dizi::player::{PlayerInfo, PlayerState, PlayerStatus, RepeatMode};
+use dizi::song::{DiziAudioFile, ReplayGainMode};
 
+use crate::audio::equalizer::EQ_BANDS;
 use crate::context::PlaylistContext;
 
 pub trait AudioPlayer {
+    fn player_info(&self) -> PlayerInfo;
+    // copies both playlists, only for when a client needs them
     fn player_state(&self) -> PlayerState;
 
     fn play_directory(&mut self, path: &Path) -> DiziResult;
@@ -24,17 +28,31 @@ pub trait AudioPlayer {
 
     fn fast_forward(&mut self, duration: time::Duration) -> DiziResult;
     fn rewind(&mut self, duration: time::Duration) -> DiziResult;
+    fn seek(&mut self, position: time::Duration) -> DiziResult;
 
     fn get_volume(&self) -> usize;
     fn set_volume(&mut self, volume: usize) -> DiziResult;
 
     fn next_enabled(&self) -> bool;
-    fn repeat_enabled(&self) -> bool;
+    fn repeat_mode(&self) -> RepeatMode;
     fn shuffle_enabled(&self) -> bool;
+    fn single_enabled(&self) -> bool;
 
     fn set_next(&mut self, next: bool);
-    fn set_repeat(&mut self, repeat: bool);
+    fn set_repeat_mode(&mut self, mode: RepeatMode);
     fn set_shuffle(&mut self, shuffle: bool);
+    fn set_single(&mut self, single: bool);
+
+    fn replaygain_mode(&self) -> ReplayGainMode;
+    fn set_replaygain_mode(&mut self, mode: ReplayGainMode) -> DiziResult;
+
+    fn eq_gains(&self) -> [f32; EQ_BANDS];
+    fn set_eq_band(&mut self, band: usize, gain: f32) -> DiziResult;
+
+    fn queue_ref(&self) -> &VecDeque<DiziAudioFile>;
+    fn queue_mut(&mut self) -> &mut VecDeque<DiziAudioFile>;
+    // plays the first queued song, false when nothing is queued
+    fn play_queued(&mut self) -> DiziResult<bool>;
 
     fn set_elapsed(&mut self, elapsed: time::Duration);
 
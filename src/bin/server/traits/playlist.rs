@@ -13,6 +13,8 @@ pub trait DiziPlaylistTrait {
     fn is_empty(&self) -> bool;
     fn len(&self) -> usize;
     fn push(&mut self, song: DiziSongEntry);
+    // `index` is at most `len()`
+    fn insert(&mut self, index: usize, song: DiziSongEntry);
     fn remove(&mut self, index: usize);
     fn clear(&mut self);
     fn swap(&mut self, index1: usize, index2: usize);
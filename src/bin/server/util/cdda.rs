@@ -0,0 +1,267 @@
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpStream;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::time;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+
+// CD audio is addressed in sectors, 75 to a second
+const SECTORS_PER_SEC: u64 = 75;
+// the first track starts after a two second lead-in
+const LEAD_IN: u64 = 150;
+
+const CDDB_TIMEOUT: time::Duration = time::Duration::from_secs(10);
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdTrack {
+    // 1 based, as cdparanoia counts
+    pub number: usize,
+    pub begin: u64,
+    pub length: u64,
+}
+
+/// Audio tracks of the disc in the drive
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CdToc {
+    pub tracks: Vec<CdTrack>,
+}
+
+impl CdToc {
+    /// Parses the table of contents printed by `cdparanoia -Q`
+    pub fn parse(s: &str) -> Self {
+        let tracks = s
+            .lines()
+            .filter_map(|line| {
+                let mut words = line.split_whitespace();
+                let number = words.next()?.strip_suffix('.')?.parse().ok()?;
+                let length = words.next()?.parse().ok()?;
+                let _ = words.next()?;
+                let begin = words.next()?.parse().ok()?;
+                Some(CdTrack {
+                    number,
+                    begin,
+                    length,
+                })
+            })
+            .collect();
+        Self { tracks }
+    }
+
+    fn offsets(&self) -> impl Iterator<Item = u64> + '_ {
+        self.tracks.iter().map(|track| track.begin + LEAD_IN)
+    }
+
+    fn lead_out(&self) -> u64 {
+        self.tracks
+            .last()
+            .map(|track| track.begin + track.length + LEAD_IN)
+            .unwrap_or(LEAD_IN)
+    }
+
+    /// The freedb disc id CDDB servers know the disc by
+    pub fn disc_id(&self) -> String {
+        fn digit_sum(mut n: u64) -> u64 {
+            let mut sum = 0;
+            while n > 0 {
+                sum += n % 10;
+                n /= 10;
+            }
+            sum
+        }
+        let checksum: u64 = self
+            .offsets()
+            .map(|offset| digit_sum(offset / SECTORS_PER_SEC))
+            .sum();
+        let first = self.offsets().next().unwrap_or(LEAD_IN) / SECTORS_PER_SEC;
+        let length = self.lead_out() / SECTORS_PER_SEC - first;
+        let id = (checksum % 0xff) << 24 | length << 8 | self.tracks.len() as u64;
+        format!("{:08x}", id)
+    }
+}
+
+/// Names of the disc and its tracks, as a CDDB server knows them
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CdInfo {
+    pub artist: Option<String>,
+    pub album: Option<String>,
+    // indexed by track, starting from the first
+    pub titles: Vec<String>,
+}
+
+impl CdInfo {
+    /// Parses an xmcd entry such as the one `cddb read` replies with
+    pub fn parse(s: &str) -> Self {
+        let mut dtitle = String::new();
+        let mut titles: Vec<String> = Vec::new();
+        for line in s.lines() {
+            let (key, value) = match line.split_once('=') {
+                Some(pair) => pair,
+                None => continue,
+            };
+            // long values are split over several lines of the same key
+            if key == "DTITLE" {
+                dtitle.push_str(value);
+            } else if let Some(index) = key.strip_prefix("TTITLE") {
+                if let Ok(index) = index.parse::<usize>() {
+                    if titles.len() <= index {
+                        titles.resize(index + 1, String::new());
+                    }
+                    titles[index].push_str(value);
+                }
+            }
+        }
+        let (artist, album) = match dtitle.split_once(" / ") {
+            Some((artist, album)) => (Some(artist.to_string()), Some(album.to_string())),
+            None if dtitle.is_empty() => (None, None),
+            None => (None, Some(dtitle)),
+        };
+        Self {
+            artist,
+            album,
+            titles,
+        }
+    }
+
+    pub fn title(&self, track: &CdTrack) -> Option<&str> {
+        self.titles
+            .get(track.number - 1)
+            .map(|s| s.as_str())
+            .filter(|s| !s.is_empty())
+    }
+}
+
+fn cdparanoia(device: Option<&str>) -> Command {
+    let mut command = Command::new("cdparanoia");
+    if let Some(device) = device {
+        command.arg("-d").arg(device);
+    }
+    command
+}
+
+pub fn read_toc(device: Option<&str>) -> DiziResult<CdToc> {
+    let output = cdparanoia(device).arg("-Q").output()?;
+    // cdparanoia prints the table of contents to stderr
+    let toc = CdToc::parse(&String::from_utf8_lossy(&output.stderr));
+    if !output.status.success() || toc.tracks.is_empty() {
+        return Err(DiziError::new(
+            DiziErrorKind::NoDevice,
+            "No audio CD found in the drive".to_string(),
+        ));
+    }
+    Ok(toc)
+}
+
+/// Rips `track` into a wav file at `path`, unless an earlier rip is still there
+pub fn rip_track(device: Option<&str>, track: &CdTrack, path: &Path) -> DiziResult<PathBuf> {
+    if path.exists() {
+        return Ok(path.to_path_buf());
+    }
+    // ripped under another name first, so a cancelled rip isn't mistaken for a finished one
+    let partial = path.with_extension("part");
+    let status = cdparanoia(device)
+        .arg("-q")
+        .arg("-w")
+        .arg(track.number.to_string())
+        .arg(&partial)
+        .status()?;
+    if !status.success() {
+        return Err(DiziError::new(
+            DiziErrorKind::Server,
+            format!("Failed to rip track {}", track.number),
+        ));
+    }
+    std::fs::rename(&partial, path)?;
+    Ok(path.to_path_buf())
+}
+
+/// Reads one reply off a CDDBP connection, along with the lines following it
+/// for codes that have them, which end with a line of a single '.'
+fn cddb_reply(reader: &mut impl BufRead) -> DiziResult<(u32, String, Vec<String>)> {
+    let mut line = String::new();
+    reader.read_line(&mut line)?;
+    let line = line.trim_end();
+    let code: u32 = line
+        .get(..3)
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| {
+            DiziError::new(
+                DiziErrorKind::ParseError,
+                format!("Unexpected CDDB reply '{}'", line),
+            )
+        })?;
+    let mut lines = Vec::new();
+    // x1x codes are followed by more lines
+    if (code / 10) % 10 == 1 {
+        loop {
+            let mut next = String::new();
+            if reader.read_line(&mut next)? == 0 {
+                break;
+            }
+            let next = next.trim_end();
+            if next == "." {
+                break;
+            }
+            lines.push(next.to_string());
+        }
+    }
+    Ok((code, line.to_string(), lines))
+}
+
+fn cddb_command(
+    stream: &mut TcpStream,
+    reader: &mut impl BufRead,
+    command: &str,
+) -> DiziResult<(u32, String, Vec<String>)> {
+    stream.write_all(command.as_bytes())?;
+    stream.write_all(b"\n")?;
+    cddb_reply(reader)
+}
+
+/// Looks the disc up on a CDDB server such as `gnudb.gnudb.org:8880`,
+/// taking the first match if there are several
+pub fn cddb_lookup(server: &str, toc: &CdToc) -> DiziResult<Option<CdInfo>> {
+    let mut stream = TcpStream::connect(server)?;
+    stream.set_read_timeout(Some(CDDB_TIMEOUT))?;
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    cddb_reply(&mut reader)?;
+    let hello = format!(
+        "cddb hello dizi localhost dizi {}",
+        env!("CARGO_PKG_VERSION")
+    );
+    cddb_command(&mut stream, &mut reader, &hello)?;
+    // protocol level 6 answers in utf-8
+    cddb_command(&mut stream, &mut reader, "proto 6")?;
+
+    let offsets: Vec<String> = toc.offsets().map(|offset| offset.to_string()).collect();
+    let query = format!(
+        "cddb query {} {} {} {}",
+        toc.disc_id(),
+        toc.tracks.len(),
+        offsets.join(" "),
+        toc.lead_out() / SECTORS_PER_SEC
+    );
+    let (code, line, lines) = cddb_command(&mut stream, &mut reader, &query)?;
+    // `200 category discid title` for a single match, one match a line otherwise
+    let found = match code {
+        200 => line.get(4..).map(|s| s.to_string()),
+        210 | 211 => lines.into_iter().next(),
+        _ => None,
+    };
+    let (category, disc_id) = match found.as_deref().and_then(|s| {
+        let mut words = s.split_whitespace();
+        Some((words.next()?.to_string(), words.next()?.to_string()))
+    }) {
+        Some(found) => found,
+        None => return Ok(None),
+    };
+
+    let read = format!("cddb read {} {}", category, disc_id);
+    let (code, _, lines) = cddb_command(&mut stream, &mut reader, &read)?;
+    let _ = cddb_command(&mut stream, &mut reader, "quit");
+    if code != 210 {
+        return Ok(None);
+    }
+    Ok(Some(CdInfo::parse(&lines.join("\n"))))
+}
@@ -15,17 +15,25 @@ pub fn get_mimetype(p: &Path) -> io::Result<String> {
 }
 
 pub fn is_playable(p: &Path) -> io::Result<bool> {
-    let mimetype = get_mimetype(p)?;
-    let is_audio_mimetype = is_mimetype_audio(&mimetype) || is_mimetype_video(&mimetype);
-    if is_audio_mimetype {
+    if dizi::song::is_optional_format(p) {
         return Ok(true);
     }
+    // `file` can't look inside archives, their members go by extension
+    if dizi::archive::is_archive_member(p) {
+        return Ok(is_audio_extension(p));
+    }
+    let mimetype = get_mimetype(p)?;
+    let is_audio_mimetype = is_mimetype_audio(&mimetype) || is_mimetype_video(&mimetype);
+    Ok(is_audio_mimetype || is_audio_extension(p))
+}
+
+fn is_audio_extension(p: &Path) -> bool {
     match p.extension() {
-        None => Ok(false),
-        Some(s) => match s.to_string_lossy().as_ref() {
-            "aac" | "flac" | "mp3" | "mp4" | "m4a" | "ogg" | "opus" | "wav" | "webm" => Ok(true),
-            _ => Ok(false),
-        },
+        None => false,
+        Some(s) => matches!(
+            s.to_string_lossy().as_ref(),
+            "aac" | "flac" | "mp3" | "mp4" | "m4a" | "ogg" | "opus" | "wav" | "webm"
+        ),
     }
 }
 
@@ -1 +1,3 @@
+#[cfg(feature = "cdda")]
+pub mod cdda;
 pub mod mimetype;
@@ -0,0 +1,388 @@
+mod rest;
+mod websocket;
+
+use std::collections::HashMap;
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::{mpsc, Arc};
+use std::thread;
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+
+use crate::config::WebOption;
+use crate::events::{
+    ClientRequestSender, ServerBroadcastEventReceiver, ServerEvent, ServerEventSender,
+};
+
+use self::websocket::{Message, MessageReader};
+
+// the whole web client, so the server has nothing to install alongside it
+const INDEX_HTML: &str = include_str!("index.html");
+// the request line and headers, browsers send a few kilobytes of them
+const MAX_HEAD: u64 = 1 << 14;
+
+#[derive(Clone, Debug)]
+enum WebMessage {
+    Client(String),
+    Ping(Vec<u8>),
+    Server(Arc<ServerBroadcastEvent>),
+}
+
+/// Serves the web client and, with `rest`, the REST api,
+/// and hands the WebSockets the web client opens over to the server
+pub fn listen_for_browsers(
+    listener: TcpListener,
+    event_tx: ServerEventSender,
+    options: WebOption,
+) -> DiziResult {
+    let options = Arc::new(options);
+    for stream in listener.incoming().flatten() {
+        let event_tx = event_tx.clone();
+        let options = Arc::clone(&options);
+        thread::spawn(move || {
+            if let Err(err) = handle_http(stream, &event_tx, &options) {
+                tracing::debug!("Web request failed: {}", err);
+            }
+        });
+    }
+    Ok(())
+}
+
+fn handle_http(
+    mut stream: TcpStream,
+    event_tx: &ServerEventSender,
+    options: &WebOption,
+) -> DiziResult {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let (request_line, headers) = read_head(&mut reader)?;
+
+    let mut words = request_line.split_whitespace();
+    let method = words.next().unwrap_or("GET");
+    let target = words.next().unwrap_or("/");
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    let password = options.password.as_deref();
+    match (path, headers.get("sec-websocket-key")) {
+        // browsers let any page open a WebSocket to any address, the page has to be ours
+        ("/ws", Some(_)) if !same_origin(&headers) => write_response(
+            &mut stream,
+            "403 Forbidden",
+            "text/plain",
+            "Cross-origin WebSockets are not allowed",
+        )?,
+        // browsers can't set headers on WebSockets, the web client passes it on from its own url
+        ("/ws", Some(_)) if !authorized(password, query_value(query, "password").as_deref()) => {
+            write_response(
+                &mut stream,
+                "401 Unauthorized",
+                "text/plain",
+                "Wrong password",
+            )?
+        }
+        ("/ws", Some(key)) => {
+            stream.write_all(websocket::handshake_response(key).as_bytes())?;
+            event_tx.send(ServerEvent::NewWebClient(stream))?;
+        }
+        ("/" | "/index.html", _) => write_response(
+            &mut stream,
+            "200 OK",
+            "text/html; charset=utf-8",
+            INDEX_HTML,
+        )?,
+        _ if options.rest => match rest::refusal(&headers, password) {
+            Some((status, msg)) => write_response(&mut stream, status, "text/plain", msg)?,
+            None => {
+                let mut body = vec![0; rest::body_len(&headers)];
+                reader.read_exact(&mut body)?;
+                let body = String::from_utf8_lossy(&body);
+                let content_type = headers.get("content-type").map(String::as_str);
+                rest::handle_rest(&mut stream, event_tx, method, target, content_type, &body)?
+            }
+        },
+        _ => write_response(&mut stream, "404 Not Found", "text/plain", "Not found")?,
+    }
+    Ok(())
+}
+
+/// Reads the request line and the headers, whose names are lowercased
+fn read_head(reader: &mut impl BufRead) -> io::Result<(String, HashMap<String, String>)> {
+    // lines are read up to a newline, which might never come
+    let mut head = reader.take(MAX_HEAD);
+    let mut read_line = |line: &mut String| {
+        let len = head.read_line(line)?;
+        if head.limit() == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "HTTP request head too large",
+            ));
+        }
+        Ok(len)
+    };
+    let mut request_line = String::new();
+    read_line(&mut request_line)?;
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        if read_line(&mut line)? == 0 {
+            break;
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((key, value)) = line.split_once(':') {
+            headers.insert(key.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+    Ok((request_line, headers))
+}
+
+/// Requests that write files wherever they are asked to,
+/// only let through when clients are kept to the server's `root`
+fn writes_files(request: &ClientRequest) -> bool {
+    matches!(request, ClientRequest::PlaylistSave { .. })
+}
+
+/// Refuses requests from browsers that would write files anywhere on the server's machine
+pub fn check_writes(request: &ClientRequest, writes_allowed: bool) -> DiziResult {
+    if writes_files(request) && !writes_allowed {
+        return Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            "Writing files from the web needs `root` to be set".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Whether `given` is the password, compared in full so how long
+/// the comparison takes doesn't tell how much of it was right
+fn authorized(password: Option<&str>, given: Option<&str>) -> bool {
+    let (password, given) = match (password, given) {
+        (None, _) => return true,
+        (Some(password), Some(given)) => (password.as_bytes(), given.as_bytes()),
+        (Some(_), None) => return false,
+    };
+    let differences = password
+        .iter()
+        .zip(given)
+        .fold(0, |differences, (a, b)| differences | (a ^ b));
+    password.len() == given.len() && differences == 0
+}
+
+/// The password of a REST request, sent as `Authorization: Bearer <password>`
+fn bearer_token(headers: &HashMap<String, String>) -> Option<&str> {
+    headers
+        .get("authorization")
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::trim)
+}
+
+fn query_value(query: &str, key: &str) -> Option<String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(k, _)| *k == key)
+        .map(|(_, value)| rest::percent_decode(value))
+}
+
+/// Whether a request was made by the web client itself or by something other than a browser,
+/// which leaves out `Origin`. Browsers send it with every WebSocket and cross-origin request
+fn same_origin(headers: &HashMap<String, String>) -> bool {
+    let origin = match headers.get("origin") {
+        Some(origin) => origin,
+        None => return true,
+    };
+    let host = match headers.get("host") {
+        Some(host) => host,
+        None => return false,
+    };
+    origin
+        .strip_prefix("http://")
+        .or_else(|| origin.strip_prefix("https://"))
+        .is_some_and(|origin| origin.eq_ignore_ascii_case(host))
+}
+
+fn write_response(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &str,
+) -> DiziResult {
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes())?;
+    Ok(())
+}
+
+/// Reads a request sent by a browser and hands it to the server
+fn forward_web_request(
+    client_request_tx: &ClientRequestSender,
+    uuid: &str,
+    text: &str,
+    writes_allowed: bool,
+) -> DiziResult {
+    let request: ClientRequest = serde_json::from_str(text)?;
+    check_writes(&request, writes_allowed)?;
+    client_request_tx.send((uuid.to_string(), request))?;
+    Ok(())
+}
+
+/// Same as `client::handle_client`, with requests and broadcasts sent as WebSocket messages.
+/// Requests that write files are refused unless `writes_allowed`
+pub fn handle_web_client(
+    uuid: uuid::Uuid,
+    mut stream: TcpStream,
+    client_request_tx: ClientRequestSender,
+    server_event_rx: ServerBroadcastEventReceiver,
+    writes_allowed: bool,
+) -> DiziResult {
+    let (event_tx, event_rx) = mpsc::channel();
+
+    // listen for events broadcasted by the server
+    let event_tx_clone = event_tx.clone();
+    let _ = thread::spawn(move || {
+        while let Ok(server_event) = server_event_rx.recv() {
+            if event_tx_clone
+                .send(WebMessage::Server(server_event))
+                .is_err()
+            {
+                return;
+            }
+        }
+    });
+
+    let uuid_string = uuid.to_string();
+
+    // listen for requests sent by the browser
+    let mut stream_clone = stream.try_clone()?;
+    let _ = thread::spawn(move || {
+        let mut reader = MessageReader::new();
+        loop {
+            let message = match reader.read_message(&mut stream_clone) {
+                Ok(Message::Text(text)) => WebMessage::Client(text),
+                Ok(Message::Ping(payload)) => WebMessage::Ping(payload),
+                Ok(Message::Other) => continue,
+                Ok(Message::Close) | Err(_) => break,
+            };
+            if event_tx.send(message).is_err() {
+                return;
+            }
+        }
+
+        let response = ClientRequest::ClientLeave {
+            uuid: uuid.to_string(),
+        };
+        if let Ok(json) = serde_json::to_string(&response) {
+            let _ = event_tx.send(WebMessage::Client(json));
+        }
+    });
+
+    while let Ok(event) = event_rx.recv() {
+        match event {
+            WebMessage::Server(event) => {
+                let json = serde_json::to_string(event.as_ref())?;
+                websocket::write_text(&mut stream, &json)?;
+                if let ServerBroadcastEvent::ServerQuit = event.as_ref() {
+                    websocket::write_close(&mut stream)?;
+                }
+            }
+            WebMessage::Ping(payload) => websocket::write_pong(&mut stream, &payload)?,
+            WebMessage::Client(text) => {
+                if text.is_empty() {
+                    continue;
+                }
+                let res =
+                    forward_web_request(&client_request_tx, &uuid_string, &text, writes_allowed);
+                // a request the server can't read shouldn't end the connection
+                if let Err(err) = res {
+                    let event = ServerBroadcastEvent::ServerError {
+                        msg: err.to_string(),
+                    };
+                    websocket::write_text(&mut stream, &serde_json::to_string(&event)?)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn head() {
+        let request =
+            "GET /ws?password=a HTTP/1.1\r\nHost: dizi:8088\r\nSec-WebSocket-Key: k\r\n\r\n{}";
+        let mut reader = request.as_bytes();
+        let (request_line, headers) = read_head(&mut reader).unwrap();
+        assert_eq!(request_line, "GET /ws?password=a HTTP/1.1\r\n");
+        assert_eq!(
+            headers.get("sec-websocket-key").map(String::as_str),
+            Some("k")
+        );
+        // the body is left to read
+        assert_eq!(reader, b"{}");
+
+        let endless = format!("GET / HTTP/1.1\r\nX: {}", "a".repeat(MAX_HEAD as usize));
+        let err = read_head(&mut endless.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        let endless = format!("GET /{}", "a".repeat(MAX_HEAD as usize));
+        let err = read_head(&mut endless.as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn password() {
+        assert!(authorized(None, None));
+        assert!(authorized(Some("secret"), Some("secret")));
+        assert!(!authorized(Some("secret"), Some("secre")));
+        assert!(!authorized(Some("secret"), Some("secrets")));
+        assert!(!authorized(Some("secret"), None));
+        assert_eq!(
+            query_value("a=1&password=se%20cret", "password").as_deref(),
+            Some("se cret")
+        );
+    }
+
+    #[test]
+    fn writes_need_root() {
+        let save = ClientRequest::PlaylistSave {
+            path: "/home/user/.bashrc".into(),
+        };
+        assert!(check_writes(&save, false).is_err());
+        assert!(check_writes(&save, true).is_ok());
+        assert!(check_writes(&ClientRequest::PlaylistClear, false).is_ok());
+    }
+
+    #[test]
+    fn same_origin_only() {
+        assert!(same_origin(&headers(&[("host", "dizi:8088")])));
+        assert!(same_origin(&headers(&[
+            ("host", "dizi:8088"),
+            ("origin", "http://dizi:8088")
+        ])));
+        assert!(!same_origin(&headers(&[
+            ("host", "dizi:8088"),
+            ("origin", "http://evil.example")
+        ])));
+        assert!(!same_origin(&headers(&[
+            ("host", "dizi:8088"),
+            ("origin", "null")
+        ])));
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::net::TcpStream;
+use std::sync::mpsc;
+use std::time;
+
+use serde_json::{Map, Value};
+
+use dizi::error::{DiziError, DiziErrorKind, DiziResult};
+use dizi::request::client::ClientRequest;
+use dizi::response::server::ServerBroadcastEvent;
+
+use crate::events::{ServerEvent, ServerEventSender};
+
+// how long the server gets to answer a request, most requests are answered right away
+const RESPONSE_TIMEOUT: time::Duration = time::Duration::from_secs(2);
+// bodies of REST requests are small json objects
+const MAX_BODY: usize = 1 << 16;
+
+/// Requests that only read the server's state, the only ones answered over `GET`
+/// so links and images on other sites can't change anything
+fn is_read_only(request: &ClientRequest) -> bool {
+    matches!(
+        request,
+        ClientRequest::ServerQuery { .. }
+            | ClientRequest::ServerQueryAll
+            | ClientRequest::PlayerState
+            | ClientRequest::PlayerGetVolume
+            | ClientRequest::PlayerChapters
+            | ClientRequest::PlayerLyrics { .. }
+            | ClientRequest::PlayerEqGetState
+            | ClientRequest::PlaylistState
+            | ClientRequest::QueueList
+            | ClientRequest::LibraryRatings
+            | ClientRequest::FsList { .. }
+            | ClientRequest::QueryWaveform { .. }
+    )
+}
+
+/// The status and message a request is turned away with before its body is read, if any
+pub fn refusal(
+    headers: &HashMap<String, String>,
+    password: Option<&str>,
+) -> Option<(&'static str, &'static str)> {
+    // pages on other sites may send requests, but browsers tell where they come from
+    if !super::same_origin(headers) {
+        return Some(("403 Forbidden", "Cross-origin requests are not allowed"));
+    }
+    if !super::authorized(password, super::bearer_token(headers)) {
+        return Some(("401 Unauthorized", "Wrong password"));
+    }
+    let len_ok = headers
+        .get("content-length")
+        .is_none_or(|len| len.parse::<usize>().is_ok_and(|len| len <= MAX_BODY));
+    if !len_ok {
+        return Some(("413 Payload Too Large", "Request body too large"));
+    }
+    None
+}
+
+/// Length of the body to read, once `refusal` let the request through
+pub fn body_len(headers: &HashMap<String, String>) -> usize {
+    headers
+        .get("content-length")
+        .and_then(|len| len.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(MAX_BODY)
+}
+
+/// The request `GET /player/state` or `POST /playlist/append` stands for.
+/// Fields are taken from the json body and the query string, e.g. `/fs/list?path=/srv/music`.
+/// Anything that changes the server's state has to be posted as json,
+/// which a browser only sends for other sites after asking first
+fn to_client_request(
+    method: &str,
+    target: &str,
+    content_type: Option<&str>,
+    body: &str,
+) -> DiziResult<ClientRequest> {
+    let invalid = |msg: String| DiziError::new(DiziErrorKind::InvalidParameters, msg);
+    match method {
+        "GET" => {}
+        "POST" => {
+            let is_json = content_type
+                .and_then(|content_type| content_type.split(';').next())
+                .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("application/json"));
+            if !is_json {
+                return Err(invalid(
+                    "POST requests must be sent as application/json".to_string(),
+                ));
+            }
+        }
+        _ => return Err(invalid(format!("Unsupported method {}", method))),
+    }
+
+    let (path, query) = target.split_once('?').unwrap_or((target, ""));
+    // requests about the connection itself make no sense over http
+    if path.starts_with("/client/") {
+        return Err(invalid(format!("Unknown api {}", path)));
+    }
+
+    let mut fields = match body.trim() {
+        "" => Map::new(),
+        body => match serde_json::from_str(body)? {
+            Value::Object(fields) => fields,
+            _ => return Err(invalid("Request body must be a json object".to_string())),
+        },
+    };
+    for pair in query.split('&').filter(|s| !s.is_empty()) {
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        let value = percent_decode(value);
+        // numbers and booleans are passed as such, anything else as a string
+        let value = serde_json::from_str(&value).unwrap_or(Value::String(value));
+        fields.insert(percent_decode(key), value);
+    }
+    fields.insert("api".to_string(), Value::String(path.to_string()));
+
+    let request: ClientRequest = serde_json::from_value(Value::Object(fields))
+        .map_err(|err| invalid(format!("Invalid request {}: {}", path, err)))?;
+    if method == "GET" && !is_read_only(&request) {
+        return Err(invalid(format!("{} must be sent with POST", path)));
+    }
+    Ok(request)
+}
+
+pub fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = bytes
+            .get(i + 1..i + 3)
+            .filter(|_| bytes[i] == b'%')
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (bytes[i], escaped) {
+            (_, Some(b)) => {
+                decoded.push(b);
+                i += 2;
+            }
+            (b'+', None) => decoded.push(b' '),
+            (b, None) => decoded.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+/// Sends the request to the server and answers with the first event it broadcasts
+/// while processing it, as json. `204 No Content` when the request is answered with nothing
+pub fn handle_rest(
+    stream: &mut TcpStream,
+    event_tx: &ServerEventSender,
+    method: &str,
+    target: &str,
+    content_type: Option<&str>,
+    body: &str,
+) -> DiziResult {
+    let request = match to_client_request(method, target, content_type, body) {
+        Ok(request) => request,
+        Err(err) => {
+            let event = ServerBroadcastEvent::ServerError {
+                msg: err.to_string(),
+            };
+            return write_json(stream, "400 Bad Request", &event);
+        }
+    };
+
+    let (response_tx, response_rx) = mpsc::channel();
+    event_tx.send(ServerEvent::RestRequest {
+        request,
+        response_tx,
+    })?;
+
+    let start = time::Instant::now();
+    while let Some(timeout) = RESPONSE_TIMEOUT.checked_sub(start.elapsed()) {
+        // the server lets go of `response_tx` once it processed the request
+        let event = match response_rx.recv_timeout(timeout) {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+        match event.as_ref() {
+            // sent while playing, not in response to anything
+            event if event.is_periodic() => {}
+            ServerBroadcastEvent::ServerError { .. } => {
+                return write_json(stream, "400 Bad Request", event.as_ref());
+            }
+            event => return write_json(stream, "200 OK", event),
+        }
+    }
+    stream.write_all(b"HTTP/1.1 204 No Content\r\nConnection: close\r\n\r\n")?;
+    Ok(())
+}
+
+fn write_json(stream: &mut TcpStream, status: &str, event: &ServerBroadcastEvent) -> DiziResult {
+    let body = serde_json::to_string(event)?;
+    super::write_response(stream, status, "application/json", &body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn get_only_reads() {
+        let request = to_client_request("GET", "/fs/list?path=/srv/music", None, "").unwrap();
+        assert!(matches!(request, ClientRequest::FsList { path: Some(_) }));
+        assert!(to_client_request("GET", "/server/quit", None, "").is_err());
+        assert!(to_client_request("GET", "/playlist/save?path=/tmp/a.m3u", None, "").is_err());
+    }
+
+    #[test]
+    fn post_must_be_json() {
+        let json = Some("application/json; charset=utf-8");
+        assert!(to_client_request("POST", "/playlist/clear", json, "").is_ok());
+        assert!(to_client_request("POST", "/playlist/clear", None, "").is_err());
+        let form = Some("application/x-www-form-urlencoded");
+        assert!(to_client_request("POST", "/playlist/clear", form, "").is_err());
+    }
+
+    #[test]
+    fn get_refuses_every_change() {
+        for target in [
+            "/player/pause",
+            "/player/volume/increase?amount=1",
+            "/playlist/append?path=/srv/music/a.flac",
+            "/library/rate?path=/a.flac&rating=5",
+            "/server/quit",
+        ] {
+            assert!(
+                to_client_request("GET", target, None, "").is_err(),
+                "{}",
+                target
+            );
+        }
+        // the body doesn't make a GET any less of a GET
+        let json = Some("application/json");
+        assert!(to_client_request("GET", "/server/quit", json, "{}").is_err());
+        assert!(to_client_request("PUT", "/player/state", json, "").is_err());
+        assert!(to_client_request("POST", "/client/leave", json, "").is_err());
+    }
+
+    #[test]
+    fn post_refuses_other_content_types() {
+        for content_type in ["text/plain", "multipart/form-data", "application/jsonx"] {
+            let content_type = Some(content_type);
+            assert!(to_client_request("POST", "/player/pause", content_type, "{}").is_err());
+        }
+        let json = Some("Application/JSON");
+        assert!(to_client_request("POST", "/player/pause", json, "[]").is_err());
+        assert!(to_client_request("POST", "/player/pause", json, "{}").is_ok());
+    }
+
+    #[test]
+    fn bearer_token_is_checked() {
+        let no_token = headers(&[("host", "dizi:8088")]);
+        let wrong = headers(&[("host", "dizi:8088"), ("authorization", "Bearer nope")]);
+        let right = headers(&[("host", "dizi:8088"), ("authorization", "Bearer secret")]);
+        let basic = headers(&[("host", "dizi:8088"), ("authorization", "Basic secret")]);
+        assert!(refusal(&no_token, None).is_none());
+        assert_eq!(
+            refusal(&no_token, Some("secret")).unwrap().0,
+            "401 Unauthorized"
+        );
+        assert_eq!(
+            refusal(&wrong, Some("secret")).unwrap().0,
+            "401 Unauthorized"
+        );
+        assert_eq!(
+            refusal(&basic, Some("secret")).unwrap().0,
+            "401 Unauthorized"
+        );
+        assert!(refusal(&right, Some("secret")).is_none());
+
+        let cross_origin = headers(&[
+            ("host", "dizi:8088"),
+            ("origin", "http://evil.example"),
+            ("authorization", "Bearer secret"),
+        ]);
+        assert_eq!(
+            refusal(&cross_origin, Some("secret")).unwrap().0,
+            "403 Forbidden"
+        );
+    }
+
+    #[test]
+    fn body_is_limited() {
+        let len = |len: &str| headers(&[("content-length", len)]);
+        assert!(refusal(&len("2"), None).is_none());
+        assert_eq!(body_len(&len("2")), 2);
+        assert!(refusal(&len(&MAX_BODY.to_string()), None).is_none());
+        for too_long in [(MAX_BODY + 1).to_string().as_str(), "-1", "lots"] {
+            assert_eq!(
+                refusal(&len(too_long), None).unwrap().0,
+                "413 Payload Too Large"
+            );
+        }
+        assert_eq!(body_len(&headers(&[])), 0);
+    }
+}
@@ -0,0 +1,383 @@
+use std::io::{self, Read, Write};
+
+// appended to the client's key to prove the server understood the handshake
+const HANDSHAKE_GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+// requests are small, anything bigger is not from the web client
+const MAX_PAYLOAD: u64 = 1 << 20;
+
+const OPCODE_CONTINUATION: u8 = 0x0;
+const OPCODE_TEXT: u8 = 0x1;
+const OPCODE_BINARY: u8 = 0x2;
+const OPCODE_CLOSE: u8 = 0x8;
+const OPCODE_PING: u8 = 0x9;
+const OPCODE_PONG: u8 = 0xA;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Message {
+    Text(String),
+    Ping(Vec<u8>),
+    Close,
+    // binary messages and pongs, which the web client doesn't send
+    Other,
+}
+
+/// The `Sec-WebSocket-Accept` answer to a client's `Sec-WebSocket-Key`
+pub fn accept_key(key: &str) -> String {
+    let digest = sha1(format!("{}{}", key.trim(), HANDSHAKE_GUID).as_bytes());
+    base64::encode(digest)
+}
+
+/// The response upgrading the HTTP connection of `key` to a WebSocket
+pub fn handshake_response(key: &str) -> String {
+    format!(
+        "HTTP/1.1 101 Switching Protocols\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Accept: {}\r\n\r\n",
+        accept_key(key)
+    )
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg.to_string())
+}
+
+/// Reads whole messages, putting fragmented messages back together.
+/// A message being put together is kept over the control frames sent between its fragments
+#[derive(Debug, Default)]
+pub struct MessageReader {
+    opcode: Option<u8>,
+    payload: Vec<u8>,
+}
+
+impl MessageReader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn read_message(&mut self, reader: &mut impl Read) -> io::Result<Message> {
+        loop {
+            if let Some(message) = self.read_frame(reader)? {
+                return Ok(message);
+            }
+        }
+    }
+
+    /// Reads one frame, returns the message it finished if any
+    fn read_frame(&mut self, reader: &mut impl Read) -> io::Result<Option<Message>> {
+        let mut header = [0; 2];
+        reader.read_exact(&mut header)?;
+        let fin = header[0] & 0x80 != 0;
+        let frame_opcode = header[0] & 0x0F;
+        let masked = header[1] & 0x80 != 0;
+        // no extensions are agreed on in the handshake
+        if header[0] & 0x70 != 0 {
+            return Err(invalid("WebSocket frame with reserved bits set"));
+        }
+        // browsers always mask, so this isn't a browser
+        if !masked {
+            return Err(invalid("Unmasked WebSocket frame"));
+        }
+
+        let len = match header[1] & 0x7F {
+            126 => {
+                let mut len = [0; 2];
+                reader.read_exact(&mut len)?;
+                u16::from_be_bytes(len) as u64
+            }
+            127 => {
+                let mut len = [0; 8];
+                reader.read_exact(&mut len)?;
+                u64::from_be_bytes(len)
+            }
+            len => len as u64,
+        };
+        let control = frame_opcode & 0x8 != 0;
+        if control && (!fin || len > 125) {
+            return Err(invalid("Fragmented or long WebSocket control frame"));
+        }
+        if (self.payload.len() as u64).saturating_add(len) > MAX_PAYLOAD {
+            return Err(invalid("WebSocket message too large"));
+        }
+        let mut mask = [0; 4];
+        reader.read_exact(&mut mask)?;
+        let mut data = vec![0; len as usize];
+        reader.read_exact(&mut data)?;
+        data.iter_mut()
+            .enumerate()
+            .for_each(|(i, b)| *b ^= mask[i % 4]);
+
+        match frame_opcode {
+            // control frames may come in between the fragments of a message
+            OPCODE_CLOSE => return Ok(Some(Message::Close)),
+            OPCODE_PING => return Ok(Some(Message::Ping(data))),
+            OPCODE_PONG => return Ok(None),
+            OPCODE_CONTINUATION if self.opcode.is_some() => self.payload.extend(data),
+            OPCODE_CONTINUATION => {
+                return Err(invalid("WebSocket continuation without a message"));
+            }
+            OPCODE_TEXT | OPCODE_BINARY if self.opcode.is_some() => {
+                return Err(invalid("WebSocket message inside a fragmented message"));
+            }
+            OPCODE_TEXT | OPCODE_BINARY => {
+                self.opcode = Some(frame_opcode);
+                self.payload = data;
+            }
+            _ => return Err(invalid("Unknown WebSocket opcode")),
+        }
+        if !fin {
+            return Ok(None);
+        }
+        let payload = std::mem::take(&mut self.payload);
+        match self.opcode.take() {
+            Some(OPCODE_TEXT) => String::from_utf8(payload)
+                .map(|text| Some(Message::Text(text)))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+            _ => Ok(Some(Message::Other)),
+        }
+    }
+}
+
+fn write_frame(writer: &mut impl Write, opcode: u8, payload: &[u8]) -> io::Result<()> {
+    let mut header = vec![0x80 | opcode];
+    // frames sent by servers are never masked
+    match payload.len() {
+        len if len < 126 => header.push(len as u8),
+        len if len <= u16::MAX as usize => {
+            header.push(126);
+            header.extend((len as u16).to_be_bytes());
+        }
+        len => {
+            header.push(127);
+            header.extend((len as u64).to_be_bytes());
+        }
+    }
+    writer.write_all(&header)?;
+    writer.write_all(payload)?;
+    writer.flush()
+}
+
+pub fn write_text(writer: &mut impl Write, text: &str) -> io::Result<()> {
+    write_frame(writer, OPCODE_TEXT, text.as_bytes())
+}
+
+pub fn write_pong(writer: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    write_frame(writer, OPCODE_PONG, payload)
+}
+
+pub fn write_close(writer: &mut impl Write) -> io::Result<()> {
+    write_frame(writer, OPCODE_CLOSE, &[])
+}
+
+/// SHA-1, only ever used for the handshake
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [0x67452301, 0xEFCDAB89, 0x98BADCFE, 0x10325476, 0xC3D2E1F0];
+
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend((data.len() as u64 * 8).to_be_bytes());
+
+    for chunk in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in chunk.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, word) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A827999),
+                20..=39 => (b ^ c ^ d, 0x6ED9EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1BBCDC),
+                _ => (b ^ c ^ d, 0xCA62C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(*word);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (h, v) in h.iter_mut().zip([a, b, c, d, e]) {
+            *h = h.wrapping_add(v);
+        }
+    }
+
+    let mut digest = [0; 20];
+    for (i, word) in h.iter().enumerate() {
+        digest[i * 4..i * 4 + 4].copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(frames: &[u8]) -> io::Result<Message> {
+        MessageReader::new().read_message(&mut &frames[..])
+    }
+
+    // a frame as browsers send them, masked
+    fn frame(first: u8, payload: &[u8]) -> Vec<u8> {
+        let mask = [0x12, 0x34, 0x56, 0x78];
+        let mut frame = vec![first];
+        match payload.len() {
+            len if len < 126 => frame.push(0x80 | len as u8),
+            len if len <= u16::MAX as usize => {
+                frame.push(0x80 | 126);
+                frame.extend((len as u16).to_be_bytes());
+            }
+            len => {
+                frame.push(0x80 | 127);
+                frame.extend((len as u64).to_be_bytes());
+            }
+        }
+        frame.extend(mask);
+        frame.extend(payload.iter().enumerate().map(|(i, b)| b ^ mask[i % 4]));
+        frame
+    }
+
+    fn concat(frames: &[Vec<u8>]) -> Vec<u8> {
+        frames.concat()
+    }
+
+    #[test]
+    fn accept_key_rfc6455() {
+        // RFC 6455 section 1.3
+        assert_eq!(
+            accept_key("dGhlIHNhbXBsZSBub25jZQ=="),
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn read_masked() {
+        // RFC 6455 section 5.7
+        let frames = [
+            0x81, 0x85, 0x37, 0xfa, 0x21, 0x3d, 0x7f, 0x9f, 0x4d, 0x51, 0x58,
+        ];
+        assert_eq!(read(&frames).unwrap(), Message::Text("Hello".to_string()));
+    }
+
+    #[test]
+    fn handshake() {
+        let response = handshake_response("dGhlIHNhbXBsZSBub25jZQ==");
+        assert!(response.starts_with("HTTP/1.1 101 Switching Protocols\r\n"));
+        assert!(response.contains("\r\nSec-WebSocket-Accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n"));
+        assert!(response.ends_with("\r\n\r\n"));
+    }
+
+    #[test]
+    fn read_fragments() {
+        let frames = concat(&[frame(0x01, b"Hel"), frame(0x80, b"lo")]);
+        assert_eq!(read(&frames).unwrap(), Message::Text("Hello".to_string()));
+    }
+
+    #[test]
+    fn read_lengths() {
+        for len in [125, 126, 65535, 65536] {
+            let text = "x".repeat(len);
+            let frames = frame(0x81, text.as_bytes());
+            assert_eq!(read(&frames).unwrap(), Message::Text(text));
+        }
+    }
+
+    #[test]
+    fn read_close_and_binary() {
+        assert_eq!(read(&frame(0x88, &[0x03, 0xe8])).unwrap(), Message::Close);
+        assert_eq!(read(&frame(0x82, &[1, 2, 3])).unwrap(), Message::Other);
+        // pongs are skipped
+        let frames = concat(&[frame(0x8A, b""), frame(0x81, b"a")]);
+        assert_eq!(read(&frames).unwrap(), Message::Text("a".to_string()));
+    }
+
+    #[test]
+    fn read_invalid() {
+        let invalid = [
+            // unmasked
+            vec![0x81, 0x01, b'a'],
+            // reserved bits
+            frame(0xC1, b"a"),
+            // fragmented control frame
+            frame(0x09, b"a"),
+            // control frame longer than 125 bytes
+            frame(0x89, &[0; 126]),
+            // unknown opcode
+            frame(0x83, b"a"),
+            // continuation without a message
+            frame(0x80, b"a"),
+            // a new message before the last one was finished
+            concat(&[frame(0x01, b"a"), frame(0x81, b"b")]),
+            // not UTF-8
+            frame(0x81, &[0xff, 0xfe]),
+        ];
+        for frames in invalid.iter() {
+            let err = read(frames).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{:?}", frames);
+        }
+    }
+
+    #[test]
+    fn read_truncated() {
+        let frames = frame(0x81, b"Hello");
+        for len in [1, 2, 5, frames.len() - 1] {
+            let err = read(&frames[..len]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+        }
+    }
+
+    #[test]
+    fn read_ping_between_fragments() {
+        let frames = concat(&[frame(0x01, b"Hel"), frame(0x89, b"!"), frame(0x80, b"lo")]);
+        let mut reader = MessageReader::new();
+        let mut frames = &frames[..];
+        assert_eq!(
+            reader.read_message(&mut frames).unwrap(),
+            Message::Ping(vec![b'!'])
+        );
+        assert_eq!(
+            reader.read_message(&mut frames).unwrap(),
+            Message::Text("Hello".to_string())
+        );
+    }
+
+    #[test]
+    fn read_too_large() {
+        let mut frames = vec![0x81, 0xFF];
+        frames.extend(u64::MAX.to_be_bytes());
+        let err = read(&frames).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        // fragments adding up to more than the limit
+        let frames = concat(&[
+            frame(0x01, &vec![0; MAX_PAYLOAD as usize]),
+            frame(0x80, &[0]),
+        ]);
+        let err = read(&frames).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn write_unmasked() {
+        let mut frames = Vec::new();
+        write_text(&mut frames, &"x".repeat(300)).unwrap();
+        assert_eq!(frames[..4], [0x81, 126, 0x01, 0x2C]);
+        assert_eq!(frames.len(), 4 + 300);
+
+        let mut frames = Vec::new();
+        write_close(&mut frames).unwrap();
+        assert_eq!(frames, [0x88, 0x00]);
+    }
+}
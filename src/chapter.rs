@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{self, Read, Seek, SeekFrom};
+use std::path::Path;
+use std::time;
+
+use serde::{Deserialize, Serialize};
+
+/// A point of a single-file audiobook or mix to jump to
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Chapter {
+    pub title: String,
+    pub start: time::Duration,
+}
+
+/// Index of the chapter playing at `elapsed`
+pub fn chapter_at(chapters: &[Chapter], elapsed: time::Duration) -> Option<usize> {
+    chapters
+        .partition_point(|chapter| chapter.start <= elapsed)
+        .checked_sub(1)
+}
+
+/// Chapters of the song at `path`, from its Vorbis comments (`tags`),
+/// its ID3v2 `CHAP` frames or its mp4 Nero `chpl` box
+pub fn read_chapters(path: &Path, tags: &HashMap<String, String>) -> Vec<Chapter> {
+    let chapters = vorbis_chapters(tags);
+    if !chapters.is_empty() || crate::archive::is_archive_member(path) {
+        return chapters;
+    }
+    match read_file_chapters(path) {
+        Ok(chapters) => chapters,
+        Err(err) => {
+            tracing::debug!("Failed to read chapters of {:?}: {}", path, err);
+            Vec::new()
+        }
+    }
+}
+
+fn read_file_chapters(path: &Path) -> io::Result<Vec<Chapter>> {
+    let mut file = File::open(path)?;
+    let mut header = [0; 10];
+    if file.read(&mut header)? < header.len() {
+        return Ok(Vec::new());
+    }
+    if &header[..3] == b"ID3" {
+        let size = synchsafe(&header[6..10]) as usize;
+        let mut tag = vec![0; size];
+        file.read_exact(&mut tag)?;
+        return Ok(id3_chapters(header[3], header[5], &tag));
+    }
+    if &header[4..8] == b"ftyp" {
+        file.seek(SeekFrom::Start(0))?;
+        return Ok(match find_mp4_box(&mut file, b"moov")? {
+            Some(moov) => mp4_chapters(&moov),
+            None => Vec::new(),
+        });
+    }
+    Ok(Vec::new())
+}
+
+/// `CHAPTER001=00:00:00.000` and `CHAPTER001NAME=Title` pairs
+fn vorbis_chapters(tags: &HashMap<String, String>) -> Vec<Chapter> {
+    let mut starts: HashMap<&str, time::Duration> = HashMap::new();
+    let mut titles: HashMap<&str, &str> = HashMap::new();
+    for (key, value) in tags.iter() {
+        let number = match key.get(..7) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("CHAPTER") => &key[7..],
+            _ => continue,
+        };
+        match number.len().checked_sub(4).map(|i| number.split_at(i)) {
+            Some((number, name)) if name.eq_ignore_ascii_case("NAME") => {
+                titles.insert(number, value.as_str());
+            }
+            _ => {
+                if let Some(start) = parse_timestamp(value) {
+                    starts.insert(number, start);
+                }
+            }
+        }
+    }
+    let mut chapters: Vec<Chapter> = starts
+        .into_iter()
+        .map(|(number, start)| Chapter {
+            title: titles
+                .get(number)
+                .map(|s| s.to_string())
+                .unwrap_or_else(|| number.trim_start_matches('0').to_string()),
+            start,
+        })
+        .collect();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// `HH:MM:SS.mmm`
+fn parse_timestamp(s: &str) -> Option<time::Duration> {
+    let mut parts = s.trim().splitn(3, ':');
+    let hours: u64 = parts.next()?.parse().ok()?;
+    let minutes: u64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    if !(0.0..60.0).contains(&seconds) {
+        return None;
+    }
+    Some(
+        time::Duration::from_secs(hours * 3600 + minutes * 60)
+            + time::Duration::from_secs_f64(seconds),
+    )
+}
+
+fn synchsafe(bytes: &[u8]) -> u32 {
+    bytes
+        .iter()
+        .fold(0, |size, byte| (size << 7) | (*byte & 0x7f) as u32)
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0, |n, byte| (n << 8) | *byte as u32)
+}
+
+/// Frames of an ID3v2 tag body as `(id, body)`, frame sizes being synchsafe from v2.4 on
+fn id3_frames(version: u8, mut data: &[u8]) -> Vec<(&[u8], &[u8])> {
+    let mut frames = Vec::new();
+    while data.len() >= 10 && data[0] != 0 {
+        let size = match version {
+            4 => synchsafe(&data[4..8]),
+            _ => be_u32(&data[4..8]),
+        } as usize;
+        let body = match data.get(10..10 + size) {
+            Some(body) => body,
+            None => break,
+        };
+        frames.push((&data[..4], body));
+        data = &data[10 + size..];
+    }
+    frames
+}
+
+fn id3_chapters(version: u8, flags: u8, tag: &[u8]) -> Vec<Chapter> {
+    // only v2.3 and later have chapters
+    if version < 3 {
+        return Vec::new();
+    }
+    let mut tag = tag;
+    // skip the extended header
+    if flags & 0x40 != 0 && tag.len() >= 4 {
+        let size = match version {
+            4 => synchsafe(&tag[..4]) as usize,
+            _ => be_u32(&tag[..4]) as usize + 4,
+        };
+        tag = tag.get(size..).unwrap_or_default();
+    }
+
+    let mut chapters: Vec<Chapter> = id3_frames(version, tag)
+        .into_iter()
+        .filter(|(id, _)| *id == b"CHAP")
+        .filter_map(|(_, body)| {
+            let id_end = body.iter().position(|b| *b == 0)?;
+            let times = body.get(id_end + 1..id_end + 17)?;
+            let start = time::Duration::from_millis(be_u32(&times[..4]) as u64);
+            let title = id3_frames(version, &body[id_end + 17..])
+                .into_iter()
+                .find(|(id, _)| *id == b"TIT2")
+                .map(|(_, text)| id3_text(text))
+                .unwrap_or_else(|| String::from_utf8_lossy(&body[..id_end]).to_string());
+            Some(Chapter { title, start })
+        })
+        .collect();
+    chapters.sort_by_key(|chapter| chapter.start);
+    chapters
+}
+
+/// Text of a text frame, after its encoding byte
+fn id3_text(text: &[u8]) -> String {
+    let (encoding, text) = match text.split_first() {
+        Some(split) => split,
+        None => return String::new(),
+    };
+    let utf16 = |text: &[u8], big_endian: bool| {
+        let units: Vec<u16> = text
+            .chunks_exact(2)
+            .map(|pair| match big_endian {
+                true => u16::from_be_bytes([pair[0], pair[1]]),
+                false => u16::from_le_bytes([pair[0], pair[1]]),
+            })
+            .collect();
+        String::from_utf16_lossy(&units)
+    };
+    let s = match encoding {
+        0 => text.iter().map(|b| *b as char).collect(),
+        1 => match text {
+            [0xfe, 0xff, rest @ ..] => utf16(rest, true),
+            [0xff, 0xfe, rest @ ..] => utf16(rest, false),
+            _ => utf16(text, false),
+        },
+        2 => utf16(text, true),
+        _ => String::from_utf8_lossy(text).to_string(),
+    };
+    s.trim_end_matches('\0').to_string()
+}
+
+/// Reads the body of the top level box `name`, skipping over the others
+fn find_mp4_box<R: Read + Seek>(reader: &mut R, name: &[u8; 4]) -> io::Result<Option<Vec<u8>>> {
+    loop {
+        let mut header = [0; 8];
+        if reader.read(&mut header)? < header.len() {
+            return Ok(None);
+        }
+        let mut size = be_u32(&header[..4]) as u64;
+        let mut header_len = 8;
+        if size == 1 {
+            let mut large = [0; 8];
+            reader.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_len = 16;
+        }
+        if size < header_len {
+            return Ok(None);
+        }
+        if &header[4..8] == name {
+            let mut body = vec![0; (size - header_len) as usize];
+            reader.read_exact(&mut body)?;
+            return Ok(Some(body));
+        }
+        reader.seek(SeekFrom::Current((size - header_len) as i64))?;
+    }
+}
+
+/// The child box `name` of an mp4 box body
+fn mp4_child<'a>(mut data: &'a [u8], name: &[u8; 4]) -> Option<&'a [u8]> {
+    while data.len() >= 8 {
+        let size = be_u32(&data[..4]) as usize;
+        if size < 8 || size > data.len() {
+            return None;
+        }
+        if &data[4..8] == name {
+            return Some(&data[8..size]);
+        }
+        data = &data[size..];
+    }
+    None
+}
+
+/// Nero chapters of `moov/udta/chpl`, with starts in 100ns units
+fn mp4_chapters(moov: &[u8]) -> Vec<Chapter> {
+    let chpl = match mp4_child(moov, b"udta").and_then(|udta| mp4_child(udta, b"chpl")) {
+        Some(chpl) => chpl,
+        None => return Vec::new(),
+    };
+    let skip = match chpl.first() {
+        Some(0) => 4,
+        Some(_) => 8,
+        None => return Vec::new(),
+    };
+    let count = match chpl.get(skip) {
+        Some(count) => *count as usize,
+        None => return Vec::new(),
+    };
+    let mut data = &chpl[skip + 1..];
+    let mut chapters = Vec::with_capacity(count);
+    for _ in 0..count {
+        let (start, len) = match (data.get(..8), data.get(8)) {
+            (Some(start), Some(len)) => (start, *len as usize),
+            _ => break,
+        };
+        let title = match data.get(9..9 + len) {
+            Some(title) => String::from_utf8_lossy(title).to_string(),
+            None => break,
+        };
+        let start = u64::from_be_bytes(start.try_into().unwrap_or_default());
+        chapters.push(Chapter {
+            title,
+            start: time::Duration::from_nanos(start * 100),
+        });
+        data = &data[9 + len..];
+    }
+    chapters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id3_frame(id: &[u8], body: &[u8]) -> Vec<u8> {
+        let mut frame = id.to_vec();
+        frame.extend((body.len() as u32).to_be_bytes());
+        frame.extend([0, 0]);
+        frame.extend(body);
+        frame
+    }
+
+    #[test]
+    fn read_vorbis_chapters() {
+        let tags = HashMap::from([
+            ("CHAPTER002".to_string(), "00:10:00.500".to_string()),
+            ("CHAPTER002NAME".to_string(), "Two".to_string()),
+            ("chapter001".to_string(), "00:00:00.000".to_string()),
+        ]);
+        let chapters = vorbis_chapters(&tags);
+        assert_eq!(chapters.len(), 2);
+        assert_eq!(chapters[0].title, "1");
+        assert_eq!(chapters[1].title, "Two");
+        assert_eq!(chapters[1].start, time::Duration::from_millis(600_500));
+        assert_eq!(
+            chapter_at(&chapters, time::Duration::from_secs(60)),
+            Some(0)
+        );
+    }
+
+    #[test]
+    fn read_id3_chapters() {
+        let mut chap = b"ch1\0".to_vec();
+        chap.extend(90_000u32.to_be_bytes());
+        chap.extend(180_000u32.to_be_bytes());
+        chap.extend([0xff; 8]);
+        chap.extend(id3_frame(b"TIT2", b"\x03Second"));
+        let mut tag = id3_frame(b"TIT2", b"\x03Book");
+        tag.extend(id3_frame(b"CHAP", &chap));
+
+        let chapters = id3_chapters(3, 0, &tag);
+        assert_eq!(
+            chapters,
+            vec![Chapter {
+                title: "Second".to_string(),
+                start: time::Duration::from_secs(90),
+            }]
+        );
+    }
+
+    #[test]
+    fn read_nero_chapters() {
+        let mut chpl = vec![1, 0, 0, 0, 0, 0, 0, 0, 1];
+        chpl.extend(300_000_000u64.to_be_bytes());
+        chpl.push(5);
+        chpl.extend(b"Intro");
+        let mut udta = ((chpl.len() + 8) as u32).to_be_bytes().to_vec();
+        udta.extend(b"chpl");
+        udta.extend(chpl);
+        let mut moov = ((udta.len() + 8) as u32).to_be_bytes().to_vec();
+        moov.extend(b"udta");
+        moov.extend(udta);
+
+        let chapters = mp4_chapters(&moov);
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].title, "Intro");
+        assert_eq!(chapters[0].start, time::Duration::from_secs(30));
+    }
+}
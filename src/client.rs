@@ -0,0 +1,170 @@
+//! Connection to a running dizi-server, for bots, scrobblers and other
+//! frontends that would rather not speak the socket protocol themselves
+//!
+//! Requests aren't answered one by one: what they change is broadcast to
+//! every client, like it is to the terminal client, so it is watched for
+//! on `events`.
+//!
+//! ```no_run
+//! use dizi::client::Connection;
+//! use dizi::request::client::ClientRequest;
+//! use dizi::response::server::ServerBroadcastEvent;
+//!
+//! # async fn run() -> dizi::error::DiziResult {
+//! let connection = Connection::connect("/tmp/dizi-server-socket").await?;
+//!
+//! let mut events = connection.events();
+//! connection.send(&ClientRequest::player_state()).await?;
+//! while let Some(event) = events.next().await {
+//!     if let Some(state) = event.player_state() {
+//!         println!("{:?}", state);
+//!     }
+//!     if let ServerBroadcastEvent::PlayerFilePlay { file } = event {
+//!         println!("now playing {}", file.file_name());
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::path::Path;
+
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::net::{TcpStream, UnixStream};
+use tokio::sync::{broadcast, Mutex};
+
+use crate::error::DiziResult;
+use crate::request::client::ClientRequest;
+use crate::response::server::ServerBroadcastEvent;
+use crate::utils;
+
+// events kept for subscribers that fall behind
+const EVENT_CAPACITY: usize = 64;
+
+/// A connection to the server, shared by reference between tasks
+///
+/// Lines from the server are read on a task of their own, so the
+/// connection has to be made on a tokio runtime.
+pub struct Connection {
+    writer: Mutex<Box<dyn AsyncWrite + Send + Unpin>>,
+    // held so new subscribers can be made, the reader task owns the sender
+    events: broadcast::Receiver<ServerBroadcastEvent>,
+}
+
+impl Connection {
+    /// Connects to the server's socket, `server.socket` in its config
+    pub async fn connect(path: impl AsRef<Path>) -> DiziResult<Self> {
+        let (reader, writer) = UnixStream::connect(path).await?.into_split();
+        Ok(Self::new(reader, writer))
+    }
+
+    /// Connects to a server listening on TCP, `authenticate` has to be
+    /// called before anything else if it has a password
+    pub async fn connect_tcp(address: &str) -> DiziResult<Self> {
+        let (reader, writer) = TcpStream::connect(address).await?.into_split();
+        Ok(Self::new(reader, writer))
+    }
+
+    fn new(
+        reader: impl AsyncRead + Send + Unpin + 'static,
+        writer: impl AsyncWrite + Send + Unpin + 'static,
+    ) -> Self {
+        let (event_tx, event_rx) = broadcast::channel(EVENT_CAPACITY);
+        tokio::spawn(async move {
+            let mut lines = BufReader::new(reader).lines();
+            while let Ok(Some(line)) = lines.next_line().await {
+                match ServerBroadcastEvent::from_json(&line) {
+                    Ok(event) => {
+                        // nobody listening is fine
+                        let _ = event_tx.send(event);
+                    }
+                    Err(err) => tracing::debug!("Unrecognized server event: {:?}", err),
+                }
+            }
+            // dropping the sender ends every event stream
+        });
+        Self {
+            writer: Mutex::new(Box::new(writer)),
+            events: event_rx,
+        }
+    }
+
+    pub async fn authenticate(&self, password: &str) -> DiziResult {
+        self.send(&ClientRequest::auth(password)).await
+    }
+
+    /// Sends a request, what it changes is broadcast on `events`.
+    /// Take an event stream before sending so nothing is missed
+    pub async fn send(&self, request: &ClientRequest) -> DiziResult {
+        let json = request.to_json()?;
+        let mut writer = self.writer.lock().await;
+        writer.write_all(json.as_bytes()).await?;
+        writer.write_all(utils::NEWLINE).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    /// Every event broadcast by the server from now on
+    pub fn events(&self) -> EventStream {
+        EventStream {
+            events: self.events.resubscribe(),
+        }
+    }
+}
+
+/// Events broadcast by the server, as they arrive
+pub struct EventStream {
+    events: broadcast::Receiver<ServerBroadcastEvent>,
+}
+
+impl EventStream {
+    /// The next event, or `None` once the server has closed the connection
+    ///
+    /// Events are skipped if they aren't taken as fast as they arrive.
+    pub async fn next(&mut self) -> Option<ServerBroadcastEvent> {
+        loop {
+            match self.events.recv().await {
+                Ok(event) => return Some(event),
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    tracing::debug!("Skipped {} server events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use tokio::io::AsyncBufReadExt;
+
+    #[tokio::test]
+    async fn sends_requests_and_reads_events() {
+        let (client, server) = UnixStream::pair().unwrap();
+        let (reader, writer) = client.into_split();
+        let connection = Connection::new(reader, writer);
+        let mut events = connection.events();
+
+        let (server_reader, mut server_writer) = server.into_split();
+        connection.send(&ClientRequest::pause()).await.unwrap();
+        let mut line = String::new();
+        BufReader::new(server_reader)
+            .read_line(&mut line)
+            .await
+            .unwrap();
+        assert!(line.contains("/player/pause"));
+
+        server_writer.write_all(b"not an event\n").await.unwrap();
+        server_writer
+            .write_all(b"{\"ServerError\":{\"msg\":\"nothing playing\"}}\n")
+            .await
+            .unwrap();
+        drop(server_writer);
+
+        let err = events.next().await.unwrap().into_result().unwrap_err();
+        assert_eq!(err.to_string(), "nothing playing");
+        assert!(events.next().await.is_none());
+    }
+}
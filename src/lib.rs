@@ -1,8 +1,17 @@
+pub mod archive;
+pub mod chapter;
+#[cfg(feature = "client")]
+pub mod client;
 pub mod error;
+#[cfg(feature = "midi")]
+pub mod midi;
 pub mod player;
 pub mod playlist;
+pub mod playlist_format;
 pub mod request;
 pub mod response;
 pub mod song;
+#[cfg(feature = "openmpt")]
+pub mod tracker;
 pub mod traits;
 pub mod utils;
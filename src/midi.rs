@@ -0,0 +1,265 @@
+//! Standard MIDI files, read here for their name and length.
+//! The server renders them through a SoundFont synthesizer
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::time;
+
+use crate::error::{DiziError, DiziErrorKind, DiziResult};
+use crate::song::{AudioMetadata, DiziAudioFile, DiziFile, MusicMetadata};
+
+pub const MIDI_EXTENSIONS: [&str; 3] = ["kar", "mid", "midi"];
+
+// midi files are always rendered in stereo
+const CHANNELS: usize = 2;
+// the synthesizer renders at any sample rate, this one is only reported until a device is picked
+const SAMPLE_RATE: u32 = 44100;
+// 120 bpm, until a tempo event says otherwise
+const DEFAULT_TEMPO: u64 = 500_000;
+
+/// What is read off a midi file without playing it
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct MidiInfo {
+    pub title: Option<String>,
+    pub duration: time::Duration,
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(bytes)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4)
+            .map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    // variable length quantity, 7 bits a byte
+    fn vlq(&mut self) -> Option<u64> {
+        let mut value = 0;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | (byte & 0x7f) as u64;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+impl MidiInfo {
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        let mut reader = Reader { data, pos: 0 };
+        if reader.bytes(4)? != b"MThd" {
+            return None;
+        }
+        let header_len = reader.u32()? as usize;
+        let header = reader.bytes(header_len)?;
+        let mut header = Reader {
+            data: header,
+            pos: 0,
+        };
+        let _format = header.u16()?;
+        let track_count = header.u16()?;
+        let division = header.u16()?;
+
+        let mut title = None;
+        // (tick, microseconds per quarter note) of every tempo change
+        let mut tempos: Vec<(u64, u64)> = Vec::new();
+        let mut end_tick = 0;
+
+        for track in 0..track_count {
+            let (chunk_type, chunk_len) = match (reader.bytes(4), reader.u32()) {
+                (Some(chunk_type), Some(chunk_len)) => (chunk_type, chunk_len as usize),
+                _ => break,
+            };
+            let chunk = match reader.bytes(chunk_len) {
+                Some(chunk) => chunk,
+                None => break,
+            };
+            if chunk_type != b"MTrk" {
+                continue;
+            }
+            let mut events = Reader {
+                data: chunk,
+                pos: 0,
+            };
+            let mut tick = 0;
+            let mut running_status = 0;
+            while let Some(delta) = events.vlq() {
+                tick += delta;
+                let mut status = match events.u8() {
+                    Some(status) => status,
+                    None => break,
+                };
+                match status {
+                    0xff => {
+                        let (kind, len) = match (events.u8(), events.vlq()) {
+                            (Some(kind), Some(len)) => (kind, len as usize),
+                            _ => break,
+                        };
+                        let value = match events.bytes(len) {
+                            Some(value) => value,
+                            None => break,
+                        };
+                        match kind {
+                            // the first track's name is the song's
+                            0x03 if track == 0 && title.is_none() => {
+                                let name = String::from_utf8_lossy(value).trim().to_string();
+                                title = Some(name).filter(|s| !s.is_empty());
+                            }
+                            0x51 if value.len() == 3 => {
+                                let tempo = u32::from_be_bytes([0, value[0], value[1], value[2]]);
+                                tempos.push((tick, tempo as u64));
+                            }
+                            0x2f => break,
+                            _ => {}
+                        }
+                    }
+                    0xf0 | 0xf7 => {
+                        let len = events.vlq().unwrap_or(0) as usize;
+                        if events.bytes(len).is_none() {
+                            break;
+                        }
+                    }
+                    _ => {
+                        // data bytes without a status repeat the previous one
+                        if status < 0x80 {
+                            events.pos -= 1;
+                            status = running_status;
+                        } else {
+                            running_status = status;
+                        }
+                        let len = match status & 0xf0 {
+                            0xc0 | 0xd0 => 1,
+                            0x80..=0xe0 => 2,
+                            _ => break,
+                        };
+                        if events.bytes(len).is_none() {
+                            break;
+                        }
+                    }
+                }
+            }
+            end_tick = end_tick.max(tick);
+        }
+
+        let duration = if division & 0x8000 != 0 {
+            // SMPTE frames a second and ticks a frame
+            let fps = 256 - (division >> 8) as u64;
+            let ticks_per_sec = fps * (division & 0xff) as u64;
+            time::Duration::from_secs_f64(end_tick as f64 / ticks_per_sec.max(1) as f64)
+        } else {
+            let ticks_per_quarter = division.max(1) as u64;
+            tempos.sort_by_key(|(tick, _)| *tick);
+            let mut micros = 0;
+            let mut last_tick = 0;
+            let mut tempo = DEFAULT_TEMPO;
+            for (tick, new_tempo) in tempos.into_iter().filter(|(tick, _)| *tick < end_tick) {
+                micros += (tick - last_tick) * tempo / ticks_per_quarter;
+                last_tick = tick;
+                tempo = new_tempo;
+            }
+            micros += (end_tick - last_tick) * tempo / ticks_per_quarter;
+            time::Duration::from_micros(micros)
+        };
+        Some(Self { title, duration })
+    }
+}
+
+pub fn is_midi_file(path: &Path) -> bool {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => MIDI_EXTENSIONS
+            .iter()
+            .any(|midi_ext| midi_ext.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+pub fn load_audio_file(file: DiziFile) -> DiziResult<DiziAudioFile> {
+    tracing::debug!("Loading midi metadata for {:?}", file.file_path);
+    let data = crate::archive::read_file(&file.file_path)?;
+    let info = MidiInfo::parse(&data).ok_or_else(|| {
+        DiziError::new(
+            DiziErrorKind::UnrecognizedFormat,
+            format!("Not a midi file: {:?}", file.file_path),
+        )
+    })?;
+
+    let audio_metadata = AudioMetadata {
+        track_id: 0,
+        bit_depth: None,
+        channels: Some(CHANNELS),
+        sample_rate: Some(SAMPLE_RATE),
+        total_duration: Some(info.duration),
+        codec: Some("MIDI".to_string()),
+        bitrate: None,
+    };
+    let mut standard_tags = HashMap::new();
+    if let Some(title) = info.title {
+        standard_tags.insert("TrackTitle".to_string(), title);
+    }
+    Ok(DiziAudioFile {
+        file,
+        audio_metadata,
+        music_metadata: MusicMetadata {
+            standard_tags,
+            tags: HashMap::new(),
+        },
+        chapters: Box::default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn track(events: &[u8]) -> Vec<u8> {
+        let mut chunk = b"MTrk".to_vec();
+        chunk.extend((events.len() as u32).to_be_bytes());
+        chunk.extend(events);
+        chunk
+    }
+
+    #[test]
+    fn parse_title_and_tempo_changes() {
+        let mut data = b"MThd\x00\x00\x00\x06\x00\x01\x00\x02\x00\x60".to_vec();
+        // 96 ticks a quarter note, a quarter note at 120 bpm, then two at 60 bpm
+        data.extend(track(&[
+            0x00, 0xff, 0x03, 0x04, b's', b'o', b'n', b'g', // name
+            0x00, 0xff, 0x51, 0x03, 0x07, 0xa1, 0x20, // 500000
+            0x60, 0xff, 0x51, 0x03, 0x0f, 0x42, 0x40, // 1000000
+            0x00, 0xff, 0x2f, 0x00,
+        ]));
+        data.extend(track(&[
+            0x00, 0x90, 0x3c, 0x40, // note on
+            0x81, 0x40, 0x3c, 0x00, // 192 ticks later, running status
+            0x81, 0x40, 0x80, 0x3c, 0x00, // off another 192 ticks later
+            0x00, 0xff, 0x2f, 0x00,
+        ]));
+        let info = MidiInfo::parse(&data).unwrap();
+        assert_eq!(info.title.as_deref(), Some("song"));
+        assert_eq!(info.duration, time::Duration::from_millis(3500));
+    }
+
+    #[test]
+    fn parse_not_midi() {
+        assert_eq!(MidiInfo::parse(b"RIFF\x00\x00\x00\x00"), None);
+    }
+}
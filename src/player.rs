@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::string::ToString;
 use std::time;
 
@@ -8,13 +9,15 @@ use strfmt::strfmt;
 
 use crate::error::{DiziError, DiziErrorKind, DiziResult};
 use crate::playlist::{FilePlaylist, PlaylistType};
-use crate::song::DiziAudioFile;
+use crate::song::{DiziAudioFile, ReplayGainMode};
 
 #[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum PlayerStatus {
     Playing,
     Paused,
     Stopped,
+    // the song is being opened and decoded
+    Loading,
 }
 
 impl ToString for PlayerStatus {
@@ -23,14 +26,87 @@ impl ToString for PlayerStatus {
             Self::Playing => "playing".to_string(),
             Self::Paused => "paused".to_string(),
             Self::Stopped => "stopped".to_string(),
+            Self::Loading => "loading".to_string(),
         }
     }
 }
 
+/// What plays once a song ends, before `next` is looked at
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatMode {
+    // the playlist stops at its end
+    #[default]
+    Off,
+    // the song plays again
+    One,
+    // the playlist starts over from its first song
+    All,
+}
+
+impl RepeatMode {
+    /// The mode after this one, for toggling through them
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::All,
+            Self::All => Self::One,
+            Self::One => Self::Off,
+        }
+    }
+}
+
+impl std::fmt::Display for RepeatMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Off => write!(f, "off"),
+            Self::One => write!(f, "one"),
+            Self::All => write!(f, "all"),
+        }
+    }
+}
+
+/// A playlist file to start playing at a later time
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlaybackSchedule {
+    // local time, as `YYYY-MM-DD HH:MM`
+    pub time: String,
+    pub playlist: PathBuf,
+}
+
+/// Center frequencies of the equalizer's bands, an octave apart
+pub const EQ_FREQUENCIES: [f32; 10] = [
+    31.0, 62.0, 125.0, 250.0, 500.0, 1000.0, 2000.0, 4000.0, 8000.0, 16000.0,
+];
+/// Gains of the equalizer's bands go from minus this to this many dB
+pub const EQ_MAX_GAIN: f32 = 12.0;
+
+/// A band of the equalizer, its gain in dB
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EqBand {
+    pub frequency: f32,
+    pub gain: f32,
+}
+
+/// The format songs are sent to the audio device in
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OutputConfig {
+    pub channels: u16,
+    pub sample_rate: u32,
+    pub sample_format: String,
+    // the song's own sample rate, when it is resampled to `sample_rate`
+    #[serde(default)]
+    pub resampled_from: Option<u32>,
+}
+
+/// The player's status without its playlists,
+/// cheap to copy around and to send with every update
 #[derive(Clone, Debug, Serialize, Deserialize)]
-pub struct PlayerState {
+pub struct PlayerInfo {
     pub song: Option<DiziAudioFile>,
     pub elapsed: time::Duration,
+    // length of the song as probed when it was loaded
+    #[serde(default)]
+    pub duration: Option<time::Duration>,
 
     pub status: PlayerStatus,
     pub playlist_status: PlaylistType,
@@ -38,72 +114,91 @@ pub struct PlayerState {
     pub volume: usize,
 
     pub next: bool,
-    pub repeat: bool,
+    pub repeat: RepeatMode,
     pub shuffle: bool,
-
-    pub playlist: FilePlaylist,
+    // playback stops once the playing song ends, whatever `next` and `repeat` say
+    #[serde(default)]
+    pub single: bool,
+    #[serde(default)]
+    pub replaygain: ReplayGainMode,
 
     pub audio_host: String,
+    // how the playing song is sent to the audio device
+    #[serde(default)]
+    pub output: Option<OutputConfig>,
 }
 
-impl PlayerState {
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    pub fn query(&self, query: &str) -> DiziResult<String> {
-        let vars = self.query_all();
-
-        match strfmt(&query, &vars) {
-            Ok(s) => Ok(s),
-            Err(e) => Err(DiziError::new(
-                DiziErrorKind::InvalidParameters,
-                format!(
-                    "Failed to process query '{}', Reason: '{}'",
-                    query,
-                    e.to_string()
-                ),
-            )),
-        }
+impl PlayerInfo {
+    /// Length of the playing song, from its metadata until it is loaded
+    pub fn total_duration(&self) -> Option<time::Duration> {
+        self.duration.or_else(|| {
+            self.song
+                .as_ref()
+                .and_then(|song| song.audio_metadata.total_duration)
+        })
     }
 
-    pub fn query_all(&self) -> HashMap<String, String> {
+    /// `playlist_index` and `playlist_len` describe the playlist file,
+    /// which is not part of `PlayerInfo`
+    pub fn query_all(
+        &self,
+        playlist_index: Option<usize>,
+        playlist_len: usize,
+    ) -> HashMap<String, String> {
         let mut vars = HashMap::new();
         Self::load_player_query_vars(&mut vars, self);
+        if let Some(index) = playlist_index {
+            vars.insert("playlist.index".to_string(), format!("{}", index));
+        }
+        vars.insert("playlist.length".to_string(), format!("{}", playlist_len));
         if let Some(song) = self.song.as_ref() {
             Self::load_song_query_vars(&mut vars, song);
         }
         vars
     }
 
-    fn load_player_query_vars(vars: &mut HashMap<String, String>, player_state: &PlayerState) {
-        vars.insert("player.status".to_string(), player_state.status.to_string());
+    fn load_player_query_vars(vars: &mut HashMap<String, String>, player_info: &PlayerInfo) {
+        vars.insert("player.status".to_string(), player_info.status.to_string());
         vars.insert(
             "player.volume".to_string(),
-            format!("{}", player_state.volume),
+            format!("{}", player_info.volume),
         );
-        vars.insert("player.next".to_string(), format!("{}", player_state.next));
+        vars.insert("player.next".to_string(), format!("{}", player_info.next));
+        vars.insert("player.repeat".to_string(), player_info.repeat.to_string());
         vars.insert(
-            "player.repeat".to_string(),
-            format!("{}", player_state.repeat),
+            "player.shuffle".to_string(),
+            format!("{}", player_info.shuffle),
         );
         vars.insert(
-            "player.shuffle".to_string(),
-            format!("{}", player_state.shuffle),
+            "player.single".to_string(),
+            format!("{}", player_info.single),
         );
         vars.insert(
-            "playlist.status".to_string(),
-            player_state.playlist_status.to_string(),
+            "player.replaygain".to_string(),
+            player_info.replaygain.to_string(),
         );
-
-        if let Some(index) = player_state.playlist.get_playing_index() {
-            vars.insert("playlist.index".to_string(), format!("{}", index));
-        }
         vars.insert(
-            "playlist.length".to_string(),
-            format!("{}", player_state.playlist.len()),
+            "playlist.status".to_string(),
+            player_info.playlist_status.to_string(),
         );
-        vars.insert("audio.host".to_string(), player_state.audio_host.clone());
+        if let Some(duration) = player_info.total_duration() {
+            vars.insert(
+                "player.duration".to_string(),
+                duration.as_secs().to_string(),
+            );
+        }
+        vars.insert("audio.host".to_string(), player_info.audio_host.clone());
+        if let Some(output) = player_info.output.as_ref() {
+            vars.insert("audio.channels".to_string(), output.channels.to_string());
+            vars.insert(
+                "audio.sample_rate".to_string(),
+                output.sample_rate.to_string(),
+            );
+            vars.insert(
+                "audio.sample_format".to_string(),
+                output.sample_format.clone(),
+            );
+        }
     }
 
     fn load_song_query_vars(vars: &mut HashMap<String, String>, song: &DiziAudioFile) {
@@ -127,19 +222,64 @@ impl PlayerState {
     }
 }
 
-impl std::default::Default for PlayerState {
+impl std::default::Default for PlayerInfo {
     fn default() -> Self {
         Self {
             song: None,
             status: PlayerStatus::Stopped,
             playlist_status: PlaylistType::PlaylistFile,
             elapsed: time::Duration::from_secs(0),
+            duration: None,
             volume: 50,
             next: true,
-            repeat: false,
+            repeat: RepeatMode::Off,
             shuffle: false,
-            playlist: FilePlaylist::new(),
+            single: false,
+            replaygain: ReplayGainMode::Off,
             audio_host: "UNKNOWN".to_string(),
+            output: None,
         }
     }
 }
+
+/// The whole player, only sent when asked for or when the playlists are replaced
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct PlayerState {
+    #[serde(flatten)]
+    pub info: PlayerInfo,
+
+    pub playlist: FilePlaylist,
+    // songs of the directory the playing song was started from
+    #[serde(default)]
+    pub directory_playlist: FilePlaylist,
+}
+
+impl PlayerState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn query(&self, query: &str) -> DiziResult<String> {
+        format_query(query, &self.query_all())
+    }
+
+    pub fn query_all(&self) -> HashMap<String, String> {
+        self.info
+            .query_all(self.playlist.get_playing_index(), self.playlist.len())
+    }
+}
+
+/// Fills the `{var}` placeholders of `query` in with `vars`
+pub fn format_query(query: &str, vars: &HashMap<String, String>) -> DiziResult<String> {
+    match strfmt(query, vars) {
+        Ok(s) => Ok(s),
+        Err(e) => Err(DiziError::new(
+            DiziErrorKind::InvalidParameters,
+            format!(
+                "Failed to process query '{}', Reason: '{}'",
+                query,
+                e.to_string()
+            ),
+        )),
+    }
+}
@@ -24,6 +24,24 @@ pub struct FilePlaylist {
     pub list: Vec<DiziSongEntry>,
     pub cursor_index: Option<usize>,
     pub playing_index: Option<usize>,
+    /// Order the server plays the songs in, as indices into `list`
+    #[serde(default)]
+    pub order: Vec<usize>,
+}
+
+/// Makes room in a play order for `count` songs inserted in the list at `index`,
+/// returning where they were put in the order. They play right before the song
+/// they were put in front of, or last when they were put at the end
+pub fn insert_into_order(order: &mut Vec<usize>, index: usize, count: usize) -> usize {
+    let order_index = order
+        .iter()
+        .position(|i| *i == index)
+        .unwrap_or(order.len());
+    for i in order.iter_mut().filter(|i| **i >= index) {
+        *i += count;
+    }
+    order.splice(order_index..order_index, index..index + count);
+    order_index
 }
 
 impl FilePlaylist {
@@ -44,16 +62,46 @@ impl FilePlaylist {
 
     pub fn clear(&mut self) {
         self.list_mut().clear();
+        self.order.clear();
         self.cursor_index = None;
         self.playing_index = None;
     }
 
     pub fn append_song(&mut self, s: DiziSongEntry) {
         self.list_mut().push(s);
+        self.order.push(self.list.len() - 1);
+    }
+
+    /// Inserts `songs` at `index`, they are played in the order the server
+    /// plays them, see `insert_into_order`
+    pub fn insert_songs(&mut self, index: usize, songs: Vec<DiziSongEntry>) {
+        let count = songs.len();
+        self.list_mut().splice(index..index, songs);
+        insert_into_order(&mut self.order, index, count);
+
+        match self.get_playing_index() {
+            Some(i) if i >= index => {
+                self.set_playing_index(Some(i + count));
+            }
+            _ => {}
+        }
+        match self.get_cursor_index() {
+            Some(i) if i >= index => {
+                self.set_cursor_index(Some(i + count));
+            }
+            None if !self.list_ref().is_empty() => {
+                self.set_cursor_index(Some(0));
+            }
+            _ => {}
+        }
     }
 
     pub fn remove_song(&mut self, index: usize) -> DiziSongEntry {
         let song = self.list_mut().remove(index);
+        self.order.retain(|i| *i != index);
+        for i in self.order.iter_mut().filter(|i| **i > index) {
+            *i -= 1;
+        }
 
         if let Some(playing_index) = self.playing_index {
             if playing_index == index {
@@ -79,6 +127,37 @@ impl FilePlaylist {
         song
     }
 
+    /// Swaps two songs, the cursor follows the song moved to `index2`.
+    /// Both keep their place in the play order
+    pub fn swap_songs(&mut self, index1: usize, index2: usize) {
+        self.list_mut().swap(index1, index2);
+        self.cursor_index = Some(index2);
+        let swapped = |i: usize| {
+            if i == index1 {
+                index2
+            } else if i == index2 {
+                index1
+            } else {
+                i
+            }
+        };
+        self.playing_index = self.playing_index.map(swapped);
+        for i in self.order.iter_mut() {
+            *i = swapped(*i);
+        }
+    }
+
+    /// Moves the song at `index` to `to`, the cursor follows the song
+    pub fn move_song(&mut self, index: usize, to: usize) {
+        let song = self.list_mut().remove(index);
+        self.list_mut().insert(to, song);
+        self.playing_index = self.playing_index.map(|i| moved_index(i, index, to));
+        self.cursor_index = Some(to);
+        for i in self.order.iter_mut() {
+            *i = moved_index(*i, index, to);
+        }
+    }
+
     pub fn get_cursor_index(&self) -> Option<usize> {
         self.cursor_index
     }
@@ -93,6 +172,28 @@ impl FilePlaylist {
         self.playing_index = index;
     }
 
+    /// Indices of the next `count` songs to be played after the playing one.
+    /// Falls back to the playlist's order if the play order is out of date
+    pub fn up_next(&self, count: usize) -> Vec<usize> {
+        let len = self.len();
+        let order: Vec<usize> = if self.order.len() == len {
+            self.order.clone()
+        } else {
+            (0..len).collect()
+        };
+        let start = self
+            .get_playing_index()
+            .and_then(|playing| order.iter().position(|i| *i == playing))
+            .map(|pos| pos + 1)
+            .unwrap_or(0);
+        order
+            .into_iter()
+            .skip(start)
+            .filter(|i| *i < len)
+            .take(count)
+            .collect()
+    }
+
     pub fn len(&self) -> usize {
         self.list.len()
     }
@@ -115,10 +216,24 @@ impl std::default::Default for FilePlaylist {
             list: Vec::new(),
             cursor_index: None,
             playing_index: None,
+            order: Vec::new(),
         }
     }
 }
 
+/// Where the entry at `i` ends up once the entry at `from` is moved to `to`
+pub fn moved_index(i: usize, from: usize, to: usize) -> usize {
+    if i == from {
+        to
+    } else if from < i && i <= to {
+        i - 1
+    } else if to <= i && i < from {
+        i + 1
+    } else {
+        i
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct DirectoryPlaylist {
     _list: Vec<PathBuf>,
@@ -157,3 +272,68 @@ impl std::default::Default for DirectoryPlaylist {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::song::DiziFile;
+
+    fn playlist(len: usize) -> FilePlaylist {
+        let mut playlist = FilePlaylist::new();
+        for i in 0..len {
+            let path = format!("/music/{}.flac", i);
+            playlist.append_song(DiziSongEntry::Unloaded(DiziFile::new(Path::new(&path))));
+        }
+        playlist
+    }
+
+    #[test]
+    fn up_next_follows_play_order() {
+        let mut playlist = playlist(5);
+        playlist.order = vec![3, 1, 4, 0, 2];
+        playlist.set_playing_index(Some(1));
+        assert_eq!(playlist.up_next(2), vec![4, 0]);
+        assert_eq!(playlist.up_next(10), vec![4, 0, 2]);
+    }
+
+    #[test]
+    fn move_song_keeps_playing_song() {
+        let mut playlist = playlist(5);
+        playlist.order = vec![4, 3, 2, 1, 0];
+        playlist.set_playing_index(Some(2));
+        playlist.move_song(0, 3);
+        let names: Vec<&str> = playlist.list_ref().iter().map(|s| s.file_name()).collect();
+        assert_eq!(
+            names,
+            vec!["1.flac", "2.flac", "3.flac", "0.flac", "4.flac"]
+        );
+        assert_eq!(playlist.get_playing_index(), Some(1));
+        assert_eq!(playlist.get_cursor_index(), Some(3));
+        assert_eq!(playlist.order, vec![4, 2, 1, 0, 3]);
+    }
+
+    #[test]
+    fn edits_keep_shuffled_order() {
+        let mut playlist = playlist(4);
+        playlist.order = vec![2, 0, 3, 1];
+        playlist.swap_songs(0, 1);
+        assert_eq!(playlist.order, vec![2, 1, 3, 0]);
+        playlist.remove_song(2);
+        assert_eq!(playlist.order, vec![1, 2, 0]);
+        let song = DiziSongEntry::Unloaded(DiziFile::new(Path::new("/music/new.flac")));
+        playlist.insert_songs(1, vec![song]);
+        // played right before the song it was put in front of
+        assert_eq!(playlist.order, vec![1, 2, 3, 0]);
+        assert_eq!(playlist.list_ref()[1].file_name(), "new.flac");
+    }
+
+    #[test]
+    fn up_next_without_play_order() {
+        let mut playlist = playlist(4);
+        assert_eq!(playlist.up_next(2), vec![0, 1]);
+        playlist.set_playing_index(Some(2));
+        assert_eq!(playlist.up_next(2), vec![3]);
+    }
+}
@@ -0,0 +1,458 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::song::DiziSongEntry;
+
+/// A song of a playlist file, with what the file says about it
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PlaylistEntry {
+    pub path: PathBuf,
+    pub title: Option<String>,
+    pub duration: Option<Duration>,
+}
+
+impl PlaylistEntry {
+    fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            ..Self::default()
+        }
+    }
+}
+
+/// The formats playlist files are read and saved in, told apart by their extension
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PlaylistFormat {
+    #[default]
+    M3u,
+    Pls,
+    Xspf,
+}
+
+impl PlaylistFormat {
+    /// The format of the playlist at `path`, None if it isn't named like a playlist
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_string_lossy().to_lowercase();
+        match ext.as_str() {
+            "m3u" | "m3u8" => Some(Self::M3u),
+            "pls" => Some(Self::Pls),
+            "xspf" => Some(Self::Xspf),
+            _ => None,
+        }
+    }
+
+    /// Songs of the playlist at `path`, in its format or m3u if it isn't
+    /// named like a playlist. Relative paths are kept as written
+    pub fn read_file(path: &Path) -> io::Result<Vec<PlaylistEntry>> {
+        let format = Self::from_path(path).unwrap_or_default();
+        let mut s = String::new();
+        File::open(path)?.read_to_string(&mut s)?;
+        Ok(format.parse(&s))
+    }
+
+    pub fn parse(&self, s: &str) -> Vec<PlaylistEntry> {
+        match self {
+            Self::M3u => parse_m3u(s),
+            Self::Pls => parse_pls(s),
+            Self::Xspf => parse_xspf(s),
+        }
+    }
+
+    /// Saves `songs` to `path`, in its format or m3u if it isn't named like a playlist
+    pub fn write_file(path: &Path, songs: &[DiziSongEntry]) -> io::Result<()> {
+        let format = Self::from_path(path).unwrap_or_default();
+        let mut writer = BufWriter::new(File::create(path)?);
+        format.write(&mut writer, songs)?;
+        writer.flush()
+    }
+
+    /// m3u is written as extended m3u, with the length and title of each song
+    pub fn write(&self, writer: &mut impl Write, songs: &[DiziSongEntry]) -> io::Result<()> {
+        match self {
+            Self::M3u => {
+                let mut writer = m3u::Writer::new_ext(writer)?;
+                for song in songs {
+                    // -1 for a length that isn't known
+                    let length = song.duration().map(|d| d.as_secs() as f64).unwrap_or(-1.0);
+                    let title = song_title(song).unwrap_or_default();
+                    let entry = m3u::path_entry(song.file_path()).extend(length, title);
+                    writer.write_entry(&entry)?;
+                }
+                Ok(())
+            }
+            Self::Pls => write_pls(writer, songs),
+            Self::Xspf => write_xspf(writer, songs),
+        }
+    }
+}
+
+fn song_title(song: &DiziSongEntry) -> Option<&str> {
+    match song {
+        DiziSongEntry::Loaded(song) => song
+            .music_metadata()
+            .standard_tags
+            .get("TrackTitle")
+            .map(|s| s.as_str()),
+        DiziSongEntry::Unloaded(file) => file.title.as_deref(),
+    }
+}
+
+// streams can't be played
+fn is_stream(location: &str) -> bool {
+    location.contains("://") && !location.starts_with("file://")
+}
+
+/// Length in seconds as playlists write it, negative when it isn't known
+fn parse_seconds(s: &str) -> Option<Duration> {
+    s.trim()
+        .parse::<f64>()
+        .ok()
+        .filter(|secs| secs.is_finite() && *secs >= 0.0)
+        .map(Duration::from_secs_f64)
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    let s = s.trim();
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+/// Every line that isn't a comment, with the length and title of the
+/// `#EXTINF:` line before it in extended m3u
+fn parse_m3u(s: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut extinf = None;
+    for line in s.lines() {
+        let line = line.trim();
+        if let Some(info) = line.strip_prefix("#EXTINF:") {
+            // `#EXTINF:<seconds> <attributes>,<title>`
+            let (info, title) = info.split_once(',').unwrap_or((info, ""));
+            let duration = info.split_whitespace().next().and_then(parse_seconds);
+            extinf = Some((non_empty(title), duration));
+            continue;
+        }
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (title, duration) = extinf.take().unwrap_or_default();
+        if is_stream(line) {
+            continue;
+        }
+        entries.push(PlaylistEntry {
+            path: uri_to_path(line),
+            title,
+            duration,
+        });
+    }
+    entries
+}
+
+/// `FileN=`, `TitleN=` and `LengthN=` keys of the `[playlist]` section,
+/// in the order they are numbered
+fn parse_pls(s: &str) -> Vec<PlaylistEntry> {
+    let mut files: BTreeMap<usize, PlaylistEntry> = BTreeMap::new();
+    for line in s.lines() {
+        let (key, value) = match line.trim().split_once('=') {
+            Some(pair) => pair,
+            None => continue,
+        };
+        let key = key.trim();
+        let split = key.find(|c: char| c.is_ascii_digit()).unwrap_or(key.len());
+        let number = match key[split..].parse() {
+            Ok(number) => number,
+            Err(_) => continue,
+        };
+        let entry = files.entry(number).or_default();
+        match &key[..split] {
+            "File" => entry.path = PathBuf::from(value.trim()),
+            "Title" => entry.title = non_empty(value),
+            "Length" => entry.duration = parse_seconds(value),
+            _ => {}
+        }
+    }
+    files
+        .into_values()
+        .filter_map(|mut entry| {
+            let location = entry.path.to_string_lossy().to_string();
+            if location.is_empty() || is_stream(&location) {
+                return None;
+            }
+            entry.path = uri_to_path(&location);
+            Some(entry)
+        })
+        .collect()
+}
+
+fn write_pls(writer: &mut impl Write, songs: &[DiziSongEntry]) -> io::Result<()> {
+    writeln!(writer, "[playlist]")?;
+    for (i, song) in songs.iter().enumerate() {
+        let number = i + 1;
+        writeln!(writer, "File{}={}", number, song.file_path().display())?;
+        if let Some(title) = song_title(song) {
+            writeln!(writer, "Title{}={}", number, title)?;
+        }
+        // -1 for a length that isn't known
+        let length = song.duration().map(|d| d.as_secs() as i64).unwrap_or(-1);
+        writeln!(writer, "Length{}={}", number, length)?;
+    }
+    writeln!(writer, "NumberOfEntries={}", songs.len())?;
+    writeln!(writer, "Version=2")
+}
+
+/// Text between `<tag>` and `</tag>`, the first time they appear in `s`
+fn xml_text<'a>(s: &'a str, tag: &str) -> Option<&'a str> {
+    let open = format!("<{}>", tag);
+    let close = format!("</{}>", tag);
+    let start = s.find(&open)? + open.len();
+    let end = s[start..].find(&close)?;
+    Some(s[start..start + end].trim())
+}
+
+/// `<location>`, `<title>` and `<duration>` of every `<track>`, local files only
+fn parse_xspf(s: &str) -> Vec<PlaylistEntry> {
+    let mut entries = Vec::new();
+    let mut rest = s;
+    while let Some(start) = rest.find("<track>") {
+        rest = &rest[start + "<track>".len()..];
+        let end = match rest.find("</track>") {
+            Some(end) => end,
+            None => break,
+        };
+        let track = &rest[..end];
+        rest = &rest[end..];
+        let location = match xml_text(track, "location") {
+            Some(location) => xml_unescape(location),
+            None => continue,
+        };
+        if is_stream(&location) {
+            continue;
+        }
+        let mut entry = PlaylistEntry::new(uri_to_path(&location));
+        entry.title = xml_text(track, "title").and_then(|s| non_empty(&xml_unescape(s)));
+        // milliseconds
+        entry.duration = xml_text(track, "duration")
+            .and_then(|s| s.parse().ok())
+            .map(Duration::from_millis);
+        entries.push(entry);
+    }
+    entries
+}
+
+fn write_xspf(writer: &mut impl Write, songs: &[DiziSongEntry]) -> io::Result<()> {
+    writeln!(writer, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+    writeln!(
+        writer,
+        r#"<playlist version="1" xmlns="http://xspf.org/ns/0/">"#
+    )?;
+    writeln!(writer, "  <trackList>")?;
+    for song in songs {
+        writeln!(writer, "    <track>")?;
+        let location = path_to_uri(song.file_path());
+        writeln!(
+            writer,
+            "      <location>{}</location>",
+            xml_escape(&location)
+        )?;
+        if let Some(title) = song_title(song) {
+            writeln!(writer, "      <title>{}</title>", xml_escape(title))?;
+        }
+        if let Some(duration) = song.duration() {
+            writeln!(
+                writer,
+                "      <duration>{}</duration>",
+                duration.as_millis()
+            )?;
+        }
+        writeln!(writer, "    </track>")?;
+    }
+    writeln!(writer, "  </trackList>")?;
+    writeln!(writer, "</playlist>")
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn xml_unescape(s: &str) -> String {
+    let mut unescaped = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(start) = rest.find('&') {
+        unescaped.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let end = match rest.find(';') {
+            Some(end) => end,
+            None => break,
+        };
+        let entity = &rest[1..end];
+        let c = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            _ => match entity
+                .strip_prefix("#x")
+                .or_else(|| entity.strip_prefix("#X"))
+            {
+                Some(hex) => u32::from_str_radix(hex, 16).ok().and_then(char::from_u32),
+                None => entity
+                    .strip_prefix('#')
+                    .and_then(|n| n.parse().ok())
+                    .and_then(char::from_u32),
+            },
+        };
+        match c {
+            Some(c) => {
+                unescaped.push(c);
+                rest = &rest[end + 1..];
+            }
+            // not an entity, kept as written
+            None => {
+                unescaped.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    unescaped.push_str(rest);
+    unescaped
+}
+
+/// Path of a `file://` uri, anything else is taken to be a path as it is
+fn uri_to_path(location: &str) -> PathBuf {
+    match location.strip_prefix("file://") {
+        // `file://localhost/path` names the same file as `file:///path`
+        Some(path) => {
+            let path = path.strip_prefix("localhost").unwrap_or(path);
+            PathBuf::from(percent_decode(path))
+        }
+        None => PathBuf::from(location),
+    }
+}
+
+fn path_to_uri(path: &Path) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.to_string_lossy().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            byte => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+fn percent_decode(s: &str) -> String {
+    let bytes = s.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = match (bytes[i], bytes.get(i + 1..i + 3)) {
+            (b'%', Some(hex)) => std::str::from_utf8(hex)
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok()),
+            _ => None,
+        };
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&decoded).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::song::DiziFile;
+
+    #[test]
+    fn read_pls() {
+        let pls = "[playlist]\nFile2=/music/b.flac\nTitle2=B\n\
+                   File1=file:///music/a%20b.ogg\nFile3=http://radio/stream\n\
+                   NumberOfEntries=3\nVersion=2\n";
+        assert_eq!(
+            parse_pls(pls),
+            vec![
+                PlaylistEntry::new(PathBuf::from("/music/a b.ogg")),
+                PlaylistEntry {
+                    path: PathBuf::from("/music/b.flac"),
+                    title: Some("B".to_string()),
+                    duration: None,
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn m3u_round_trip() {
+        let mut titled = DiziFile::new(Path::new("/music/b c.mp3"));
+        titled.title = Some("B, C".to_string());
+        titled.duration = Some(Duration::from_secs(215));
+        let songs = vec![
+            DiziSongEntry::Unloaded(DiziFile::new(Path::new("/music/a.flac"))),
+            DiziSongEntry::Unloaded(titled),
+        ];
+        let mut m3u = Vec::new();
+        PlaylistFormat::M3u.write(&mut m3u, &songs).unwrap();
+        let m3u = String::from_utf8(m3u).unwrap();
+        assert_eq!(
+            m3u,
+            "#EXTM3U\n#EXTINF:-1,\n/music/a.flac\n#EXTINF:215,B, C\n/music/b c.mp3\n"
+        );
+        assert_eq!(
+            parse_m3u(&m3u),
+            vec![
+                PlaylistEntry::new(PathBuf::from("/music/a.flac")),
+                PlaylistEntry {
+                    path: PathBuf::from("/music/b c.mp3"),
+                    title: Some("B, C".to_string()),
+                    duration: Some(Duration::from_secs(215)),
+                }
+            ]
+        );
+    }
+
+    #[test]
+    fn read_plain_m3u() {
+        let m3u =
+            "# mix\n/music/a.flac\n\n#EXTINF:12 tvg-id=\"x\",Radio\nhttp://radio/stream\nb.ogg\n";
+        assert_eq!(
+            parse_m3u(m3u),
+            vec![
+                PlaylistEntry::new(PathBuf::from("/music/a.flac")),
+                PlaylistEntry::new(PathBuf::from("b.ogg"))
+            ]
+        );
+    }
+
+    #[test]
+    fn xspf_round_trip() {
+        let songs = vec![
+            DiziSongEntry::Unloaded(DiziFile::new(Path::new("/music/Tom & Jerry.flac"))),
+            DiziSongEntry::Unloaded(DiziFile::new(Path::new("/music/été.mp3"))),
+        ];
+        let mut xspf = Vec::new();
+        PlaylistFormat::Xspf.write(&mut xspf, &songs).unwrap();
+        let xspf = String::from_utf8(xspf).unwrap();
+        assert!(xspf.contains("<location>file:///music/Tom%20%26%20Jerry.flac</location>"));
+        assert_eq!(
+            parse_xspf(&xspf),
+            vec![
+                PlaylistEntry::new(PathBuf::from("/music/Tom & Jerry.flac")),
+                PlaylistEntry::new(PathBuf::from("/music/été.mp3"))
+            ]
+        );
+    }
+}
@@ -1,7 +1,11 @@
 use std::path::PathBuf;
+use std::time;
 
 use serde::{Deserialize, Serialize};
 
+use crate::error::DiziResult;
+use crate::player::RepeatMode;
+
 #[derive(Clone, Debug, Deserialize, Serialize)]
 #[serde(tag = "api")]
 pub enum ClientRequest {
@@ -16,6 +20,9 @@ pub enum ClientRequest {
     // client left
     #[serde(rename = "/client/leave")]
     ClientLeave { uuid: String },
+    // must be sent first by clients connecting from another machine
+    #[serde(rename = "/client/auth")]
+    ClientAuth { password: String },
 
     // player requests
     #[serde(rename = "/player/state")]
@@ -39,20 +46,64 @@ pub enum ClientRequest {
     PlayerRewind { amount: usize },
     #[serde(rename = "/player/fast_forward")]
     PlayerFastForward { amount: usize },
+    // jump to `position` seconds into the playing song
+    #[serde(rename = "/player/seek")]
+    PlayerSeek { position: usize },
+    // jump to `percent`, from 0 to 100, of the playing song's length
+    #[serde(rename = "/player/seek_percent")]
+    PlayerSeekPercent { percent: u8 },
+    // play the song from the start, forgetting where it was left off
+    #[serde(rename = "/player/restart")]
+    PlayerRestart,
+    // chapters of the playing song, answered with `PlayerChapters`
+    #[serde(rename = "/player/chapters")]
+    PlayerChapters,
+    // jump to the start of the chapter at `index` of the playing song
+    #[serde(rename = "/player/chapter")]
+    PlayerChapter { index: usize },
+
+    // play the playlist file at `playlist` at `time`, `HH:MM` for the next time
+    // the clock reads it or `YYYY-MM-DD HH:MM`, starting silent and getting louder
+    #[serde(rename = "/player/schedule")]
+    PlayerSchedule { time: String, playlist: PathBuf },
+    #[serde(rename = "/player/schedule/cancel")]
+    PlayerScheduleCancel,
+
+    // rip the tracks of the audio CD in the drive into the playlist,
+    // each followed by `PlaylistScanDone`
+    #[serde(rename = "/cdda/open")]
+    CddaOpen,
 
     #[serde(rename = "/player/toggle/play")]
     PlayerTogglePlay,
     #[serde(rename = "/player/toggle/next")]
     PlayerToggleNext,
+    // goes from off to all to one
     #[serde(rename = "/player/toggle/repeat")]
     PlayerToggleRepeat,
+    #[serde(rename = "/player/set/repeat")]
+    PlayerSetRepeat { mode: RepeatMode },
+    // stop once the playing song ends
+    #[serde(rename = "/player/toggle/single")]
+    PlayerToggleSingle,
     #[serde(rename = "/player/toggle/shuffle")]
     PlayerToggleShuffle,
+    // moves on from off to track to album gains
+    #[serde(rename = "/player/toggle/replaygain")]
+    PlayerToggleReplayGain,
 
     #[serde(rename = "/player/volume/increase")]
     PlayerVolumeUp { amount: usize },
     #[serde(rename = "/player/volume/decrease")]
     PlayerVolumeDown { amount: usize },
+    // lyrics of the given song, or of the playing song without a path
+    #[serde(rename = "/player/lyrics")]
+    PlayerLyrics { path: Option<PathBuf> },
+    // gain in dB of one of the equalizer's bands, counting from the lowest
+    #[serde(rename = "/player/eq/set_band")]
+    PlayerEqSetBand { band: usize, gain: f32 },
+    #[serde(rename = "/player/eq/state")]
+    PlayerEqGetState,
 
     // playlist requests
     #[serde(rename = "/playlist/state")]
@@ -67,20 +118,94 @@ pub enum ClientRequest {
 
     #[serde(rename = "/playlist/append")]
     PlaylistAppend { path: Option<PathBuf> },
+    // append several songs at once, or queue them after the playing song
+    #[serde(rename = "/playlist/append_batch")]
+    PlaylistAppendBatch {
+        #[serde(default)]
+        paths: Vec<PathBuf>,
+        #[serde(default)]
+        next: bool,
+    },
+    // read every song under a directory without holding up the server,
+    // followed by `PlaylistScanDone`
+    #[serde(rename = "/playlist/append_recursive")]
+    PlaylistAppendRecursive { path: Option<PathBuf> },
+    // the songs of a directory, and of its subdirectories with `recursive`,
+    // read without holding up the server and appended with a single
+    // `PlaylistAppend`, followed by `PlaylistScanDone`
+    #[serde(rename = "/playlist/append_dir")]
+    PlaylistAppendDir {
+        path: Option<PathBuf>,
+        #[serde(default)]
+        recursive: bool,
+    },
+    // put a song, or every song under a directory, at `index`,
+    // answered with `PlaylistInsert`
+    #[serde(rename = "/playlist/insert")]
+    PlaylistInsert {
+        path: Option<PathBuf>,
+        index: Option<usize>,
+    },
     #[serde(rename = "/playlist/remove")]
     PlaylistRemove { index: Option<usize> },
     #[serde(rename = "/playlist/clear")]
     PlaylistClear,
+    // remove every song listed earlier in the playlist, answered with `PlaylistRemoveMany`
+    #[serde(rename = "/playlist/dedup")]
+    PlaylistDedup,
+    // remove the songs whose files no longer exist, answered with `PlaylistRemoveMany`
+    #[serde(rename = "/playlist/prune_missing")]
+    PlaylistPruneMissing,
     #[serde(rename = "/playlist/move_up")]
     PlaylistMoveUp { index: Option<usize> },
     #[serde(rename = "/playlist/move_down")]
     PlaylistMoveDown { index: Option<usize> },
+    // move the song at `index` so it ends up at `to`
+    #[serde(rename = "/playlist/move_to")]
+    PlaylistMoveTo { index: usize, to: usize },
+    // write the playlist to `path`, as m3u unless it is named like a pls or xspf file,
+    // followed by `PlaylistSave`
+    #[serde(rename = "/playlist/save")]
+    PlaylistSave { path: PathBuf },
+
+    // queue requests
+    // play a song once the playing one ends, before the playlist moves on,
+    // answered with `QueueState` like the others
+    #[serde(rename = "/queue/push")]
+    QueuePush { path: Option<PathBuf> },
+    #[serde(rename = "/queue/list")]
+    QueueList,
+    #[serde(rename = "/queue/clear")]
+    QueueClear,
+
+    // library requests
+    // rate a song from 1 to 5 stars, 0 removes its rating,
+    // the playing song is rated without a path
+    #[serde(rename = "/library/rate")]
+    LibraryRate { path: Option<PathBuf>, rating: u8 },
+    #[serde(rename = "/library/ratings")]
+    LibraryRatings,
+
+    // filesystem requests
+    // list a directory on the server's machine, for clients on another machine
+    #[serde(rename = "/fs/list")]
+    FsList { path: Option<PathBuf> },
+
+    // query requests
+    // loudest levels of `buckets` even stretches of a song,
+    // of the playing song without a path
+    #[serde(rename = "/query/waveform")]
+    QueryWaveform {
+        path: Option<PathBuf>,
+        buckets: usize,
+    },
 }
 
 impl ClientRequest {
     pub fn api_path(&self) -> &'static str {
         match &*self {
             Self::ClientLeave { .. } => "/client/leave",
+            Self::ClientAuth { .. } => "/client/auth",
             Self::ServerQuit => "/server/quit",
             Self::ServerQuery { .. } => "/server/query",
             Self::ServerQueryAll => "/server/query_all",
@@ -94,23 +219,367 @@ impl ClientRequest {
             Self::PlayerGetVolume => "/player/volume/get",
             Self::PlayerRewind { .. } => "/player/rewind",
             Self::PlayerFastForward { .. } => "/player/fast_forward",
+            Self::PlayerSeek { .. } => "/player/seek",
+            Self::PlayerSeekPercent { .. } => "/player/seek_percent",
+            Self::PlayerRestart => "/player/restart",
+            Self::PlayerChapters => "/player/chapters",
+            Self::PlayerChapter { .. } => "/player/chapter",
+            Self::PlayerSchedule { .. } => "/player/schedule",
+            Self::PlayerScheduleCancel => "/player/schedule/cancel",
+            Self::CddaOpen => "/cdda/open",
             Self::PlayerTogglePlay => "/player/toggle/play",
             Self::PlayerToggleNext => "/player/toggle/next",
             Self::PlayerToggleRepeat => "/player/toggle/repeat",
+            Self::PlayerSetRepeat { .. } => "/player/set/repeat",
+            Self::PlayerToggleSingle => "/player/toggle/single",
             Self::PlayerToggleShuffle => "/player/toggle/shuffle",
+            Self::PlayerToggleReplayGain => "/player/toggle/replaygain",
             Self::PlayerVolumeUp { .. } => "/player/volume/increase",
             Self::PlayerVolumeDown { .. } => "/player/volume/decrease",
+            Self::PlayerLyrics { .. } => "/player/lyrics",
+            Self::PlayerEqSetBand { .. } => "/player/eq/set_band",
+            Self::PlayerEqGetState => "/player/eq/state",
 
             Self::PlaylistState => "/playlist/state",
             Self::PlaylistOpen { .. } => "/playlist/open",
             Self::PlaylistPlay { .. } => "/playlist/play",
 
             Self::PlaylistAppend { .. } => "/playlist/append",
+            Self::PlaylistAppendBatch { .. } => "/playlist/append_batch",
+            Self::PlaylistAppendRecursive { .. } => "/playlist/append_recursive",
+            Self::PlaylistAppendDir { .. } => "/playlist/append_dir",
+            Self::PlaylistInsert { .. } => "/playlist/insert",
             Self::PlaylistRemove { .. } => "/playlist/remove",
             Self::PlaylistClear => "/playlist/clear",
+            Self::PlaylistDedup => "/playlist/dedup",
+            Self::PlaylistPruneMissing => "/playlist/prune_missing",
 
             Self::PlaylistMoveUp { .. } => "/playlist/move_up",
             Self::PlaylistMoveDown { .. } => "/playlist/move_down",
+            Self::PlaylistMoveTo { .. } => "/playlist/move_to",
+            Self::PlaylistSave { .. } => "/playlist/save",
+
+            Self::QueuePush { .. } => "/queue/push",
+            Self::QueueList => "/queue/list",
+            Self::QueueClear => "/queue/clear",
+
+            Self::LibraryRate { .. } => "/library/rate",
+            Self::LibraryRatings => "/library/ratings",
+
+            Self::FsList { .. } => "/fs/list",
+
+            Self::QueryWaveform { .. } => "/query/waveform",
+        }
+    }
+}
+
+/// Constructors for the requests, so they need not be assembled by hand
+impl ClientRequest {
+    pub fn from_json(s: &str) -> DiziResult<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+    /// The request as the server reads it, a single line of json
+    pub fn to_json(&self) -> DiziResult<String> {
+        Ok(serde_json::to_string(self)?)
+    }
+
+    pub fn auth(password: impl Into<String>) -> Self {
+        Self::ClientAuth {
+            password: password.into(),
+        }
+    }
+    pub fn server_quit() -> Self {
+        Self::ServerQuit
+    }
+    /// `query` is a template such as `{player.artist} - {player.title}`
+    pub fn server_query(query: impl Into<String>) -> Self {
+        Self::ServerQuery {
+            query: query.into(),
+        }
+    }
+    pub fn server_query_all() -> Self {
+        Self::ServerQueryAll
+    }
+
+    pub fn player_state() -> Self {
+        Self::PlayerState
+    }
+    /// Plays the song at `path` along with the rest of its directory
+    pub fn play_file(path: impl Into<PathBuf>) -> Self {
+        Self::PlayerFilePlay {
+            path: Some(path.into()),
+        }
+    }
+    pub fn play_next() -> Self {
+        Self::PlayerPlayNext
+    }
+    pub fn play_previous() -> Self {
+        Self::PlayerPlayPrevious
+    }
+    pub fn pause() -> Self {
+        Self::PlayerPause
+    }
+    pub fn resume() -> Self {
+        Self::PlayerResume
+    }
+    pub fn toggle_play() -> Self {
+        Self::PlayerTogglePlay
+    }
+    pub fn toggle_next() -> Self {
+        Self::PlayerToggleNext
+    }
+    pub fn toggle_repeat() -> Self {
+        Self::PlayerToggleRepeat
+    }
+    pub fn set_repeat(mode: RepeatMode) -> Self {
+        Self::PlayerSetRepeat { mode }
+    }
+    pub fn toggle_single() -> Self {
+        Self::PlayerToggleSingle
+    }
+    pub fn toggle_shuffle() -> Self {
+        Self::PlayerToggleShuffle
+    }
+    pub fn toggle_replaygain() -> Self {
+        Self::PlayerToggleReplayGain
+    }
+    pub fn restart() -> Self {
+        Self::PlayerRestart
+    }
+    pub fn chapters() -> Self {
+        Self::PlayerChapters
+    }
+    pub fn chapter(index: usize) -> Self {
+        Self::PlayerChapter { index }
+    }
+
+    /// Requests are precise to the second
+    pub fn rewind(amount: time::Duration) -> Self {
+        Self::PlayerRewind {
+            amount: amount.as_secs() as usize,
+        }
+    }
+    pub fn fast_forward(amount: time::Duration) -> Self {
+        Self::PlayerFastForward {
+            amount: amount.as_secs() as usize,
+        }
+    }
+    pub fn seek_percent(percent: u8) -> Self {
+        Self::PlayerSeekPercent { percent }
+    }
+    pub fn seek(position: time::Duration) -> Self {
+        Self::PlayerSeek {
+            position: position.as_secs() as usize,
+        }
+    }
+
+    pub fn get_volume() -> Self {
+        Self::PlayerGetVolume
+    }
+    /// `amount` is in percent
+    pub fn volume_up(amount: usize) -> Self {
+        Self::PlayerVolumeUp { amount }
+    }
+    pub fn volume_down(amount: usize) -> Self {
+        Self::PlayerVolumeDown { amount }
+    }
+
+    /// Lyrics of the song at `path`, or of the playing song without one
+    pub fn lyrics(path: Option<PathBuf>) -> Self {
+        Self::PlayerLyrics { path }
+    }
+    pub fn eq_set_band(band: usize, gain: f32) -> Self {
+        Self::PlayerEqSetBand { band, gain }
+    }
+    pub fn eq_state() -> Self {
+        Self::PlayerEqGetState
+    }
+    /// Waveform of the song at `path`, or of the playing song without one
+    pub fn waveform(path: Option<PathBuf>, buckets: usize) -> Self {
+        Self::QueryWaveform { path, buckets }
+    }
+    pub fn schedule(time: impl Into<String>, playlist: impl Into<PathBuf>) -> Self {
+        Self::PlayerSchedule {
+            time: time.into(),
+            playlist: playlist.into(),
+        }
+    }
+    pub fn cancel_schedule() -> Self {
+        Self::PlayerScheduleCancel
+    }
+    pub fn cdda_open() -> Self {
+        Self::CddaOpen
+    }
+
+    pub fn playlist_state() -> Self {
+        Self::PlaylistState
+    }
+    /// Loads the playlist file at `path`, the playlist must be cleared first
+    pub fn playlist_open(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        Self::PlaylistOpen {
+            cwd: path.parent().map(|p| p.to_path_buf()),
+            path: Some(path),
+        }
+    }
+    pub fn playlist_play(index: usize) -> Self {
+        Self::PlaylistPlay { index: Some(index) }
+    }
+    pub fn playlist_append(path: impl Into<PathBuf>) -> Self {
+        Self::PlaylistAppend {
+            path: Some(path.into()),
+        }
+    }
+    /// Appends the songs, or queues them after the playing song with `next`
+    pub fn playlist_append_batch(paths: Vec<PathBuf>, next: bool) -> Self {
+        Self::PlaylistAppendBatch { paths, next }
+    }
+    pub fn playlist_append_recursive(path: impl Into<PathBuf>) -> Self {
+        Self::PlaylistAppendRecursive {
+            path: Some(path.into()),
+        }
+    }
+    pub fn playlist_append_dir(path: impl Into<PathBuf>, recursive: bool) -> Self {
+        Self::PlaylistAppendDir {
+            path: Some(path.into()),
+            recursive,
+        }
+    }
+    pub fn playlist_insert(path: impl Into<PathBuf>, index: usize) -> Self {
+        Self::PlaylistInsert {
+            path: Some(path.into()),
+            index: Some(index),
+        }
+    }
+    pub fn playlist_remove(index: usize) -> Self {
+        Self::PlaylistRemove { index: Some(index) }
+    }
+    pub fn playlist_clear() -> Self {
+        Self::PlaylistClear
+    }
+    pub fn playlist_dedup() -> Self {
+        Self::PlaylistDedup
+    }
+    pub fn playlist_prune_missing() -> Self {
+        Self::PlaylistPruneMissing
+    }
+    pub fn playlist_move_up(index: usize) -> Self {
+        Self::PlaylistMoveUp { index: Some(index) }
+    }
+    pub fn playlist_move_down(index: usize) -> Self {
+        Self::PlaylistMoveDown { index: Some(index) }
+    }
+    pub fn playlist_move_to(index: usize, to: usize) -> Self {
+        Self::PlaylistMoveTo { index, to }
+    }
+    pub fn playlist_save(path: impl Into<PathBuf>) -> Self {
+        Self::PlaylistSave { path: path.into() }
+    }
+
+    /// `rating` is from 1 to 5 stars, 0 removes the rating
+    pub fn rate(path: impl Into<PathBuf>, rating: u8) -> Self {
+        Self::LibraryRate {
+            path: Some(path.into()),
+            rating,
+        }
+    }
+    pub fn queue_push(path: impl Into<PathBuf>) -> Self {
+        Self::QueuePush {
+            path: Some(path.into()),
+        }
+    }
+    pub fn queue_list() -> Self {
+        Self::QueueList
+    }
+    pub fn queue_clear() -> Self {
+        Self::QueueClear
+    }
+
+    pub fn rate_playing(rating: u8) -> Self {
+        Self::LibraryRate { path: None, rating }
+    }
+    pub fn ratings() -> Self {
+        Self::LibraryRatings
+    }
+
+    /// Lists a directory of the server's machine, its root without a path
+    pub fn fs_list(path: Option<PathBuf>) -> Self {
+        Self::FsList { path }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seek_to_json() {
+        let request = ClientRequest::seek(time::Duration::from_millis(90_500));
+        assert_eq!(
+            request.to_json().unwrap(),
+            r#"{"api":"/player/seek","position":90}"#
+        );
+    }
+
+    #[test]
+    fn eq_set_band_from_json() {
+        let request =
+            ClientRequest::from_json(r#"{"api":"/player/eq/set_band","band":2,"gain":-4.5}"#);
+        match request.unwrap() {
+            ClientRequest::PlayerEqSetBand { band, gain } => {
+                assert_eq!((band, gain), (2, -4.5))
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+    }
+
+    #[test]
+    fn queue_push_to_json() {
+        let request = ClientRequest::queue_push("/a.flac");
+        assert_eq!(
+            request.to_json().unwrap(),
+            r#"{"api":"/queue/push","path":"/a.flac"}"#
+        );
+    }
+
+    #[test]
+    fn set_repeat_to_json() {
+        let request = ClientRequest::set_repeat(RepeatMode::One);
+        assert_eq!(
+            request.to_json().unwrap(),
+            r#"{"api":"/player/set/repeat","mode":"one"}"#
+        );
+    }
+
+    #[test]
+    fn playlist_insert_to_json() {
+        let request = ClientRequest::playlist_insert("/a.flac", 2);
+        assert_eq!(
+            request.to_json().unwrap(),
+            r#"{"api":"/playlist/insert","path":"/a.flac","index":2}"#
+        );
+    }
+
+    #[test]
+    fn playlist_append_dir_from_json() {
+        let request = ClientRequest::from_json(r#"{"api":"/playlist/append_dir","path":"/a"}"#);
+        match request.unwrap() {
+            ClientRequest::PlaylistAppendDir { path, recursive } => {
+                assert_eq!(path, Some(PathBuf::from("/a")));
+                assert!(!recursive);
+            }
+            request => panic!("unexpected request {:?}", request),
+        }
+    }
+
+    #[test]
+    fn playlist_append_from_json() {
+        let request = ClientRequest::from_json(r#"{"api":"/playlist/append","path":"/a.flac"}"#);
+        match request.unwrap() {
+            ClientRequest::PlaylistAppend { path } => {
+                assert_eq!(path, Some(PathBuf::from("/a.flac")))
+            }
+            request => panic!("unexpected request {:?}", request),
         }
     }
 }
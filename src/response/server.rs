@@ -1,10 +1,23 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::time;
 
 use serde::{Deserialize, Serialize};
 
-use crate::player::PlayerState;
-use crate::song::DiziAudioFile;
+use crate::error::{DiziError, DiziErrorKind, DiziResult};
+
+use crate::chapter::Chapter;
+use crate::player::{EqBand, PlaybackSchedule, PlayerState, RepeatMode};
+use crate::playlist::FilePlaylist;
+use crate::song::{DiziAudioFile, DiziSongEntry, ReplayGainMode};
+
+/// A directory or song on the server's machine
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct FsEntry {
+    pub name: String,
+    pub path: PathBuf,
+    pub is_dir: bool,
+}
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub enum ServerBroadcastEvent {
@@ -34,7 +47,7 @@ pub enum ServerBroadcastEvent {
     PlayerStop,
 
     PlayerRepeat {
-        on: bool,
+        mode: RepeatMode,
     },
     PlayerShuffle {
         on: bool,
@@ -42,12 +55,57 @@ pub enum ServerBroadcastEvent {
     PlayerNext {
         on: bool,
     },
+    PlayerSingle {
+        on: bool,
+    },
+    PlayerReplayGain {
+        mode: ReplayGainMode,
+    },
+    // after every change to the equalizer and when asked for
+    PlayerEqState {
+        bands: Vec<EqBand>,
+    },
 
     PlayerVolumeUpdate {
         volume: usize,
     },
     PlayerProgressUpdate {
         elapsed: time::Duration,
+        // length of the playing song, for progress bars and remaining time
+        #[serde(default)]
+        duration: Option<time::Duration>,
+    },
+    // playback scheduled with `/player/schedule`, None once cancelled or started
+    PlayerSchedule {
+        schedule: Option<PlaybackSchedule>,
+    },
+    // frequency band levels of the audio being played, from low to high
+    PlayerSpectrumUpdate {
+        bands: Vec<u8>,
+    },
+    // a song played to its end and `current` was started after it,
+    // None when there was nothing left to play
+    TrackTransition {
+        previous: Option<Box<DiziAudioFile>>,
+        current: Option<Box<DiziAudioFile>>,
+        // the song that will play after `current`
+        next: Option<Box<DiziSongEntry>>,
+    },
+    // lyrics read from a `.lrc` file next to the song or from its tags,
+    // possibly with LRC timestamps
+    PlayerLyrics {
+        path: PathBuf,
+        lyrics: Option<String>,
+    },
+    // chapters of the playing song, empty for songs without any
+    PlayerChapters {
+        chapters: Vec<Chapter>,
+    },
+    // loudest level of each stretch of the song from 0 to 255,
+    // relative to the loudest part of the song
+    Waveform {
+        path: PathBuf,
+        peaks: Vec<u8>,
     },
 
     // playlist
@@ -60,6 +118,10 @@ pub enum ServerBroadcastEvent {
     PlaylistAppend {
         audio_files: Vec<DiziAudioFile>,
     },
+    PlaylistInsert {
+        index: usize,
+        audio_files: Vec<DiziAudioFile>,
+    },
     PlaylistRemove {
         index: usize,
     },
@@ -67,5 +129,82 @@ pub enum ServerBroadcastEvent {
         index1: usize,
         index2: usize,
     },
+    PlaylistMove {
+        index: usize,
+        to: usize,
+    },
     PlaylistClear,
+    // the songs at `indices` were removed, in this order, from last to first
+    PlaylistRemoveMany {
+        indices: Vec<usize>,
+    },
+    // the songs found under `path` have been appended
+    PlaylistScanDone {
+        path: PathBuf,
+    },
+    // play order of the file playlist, sent whenever it changes
+    PlaylistOrder {
+        order: Vec<usize>,
+    },
+    // songs of the directory a song was just played from
+    PlaylistDirectory {
+        playlist: FilePlaylist,
+    },
+    // the playlist was written to `path`
+    PlaylistSave {
+        path: PathBuf,
+    },
+
+    // queue
+    // songs to play before the playlist moves on, in the order they will,
+    // after every change to the queue and when asked for
+    QueueState {
+        songs: Vec<DiziAudioFile>,
+    },
+
+    // library
+    LibraryRating {
+        path: PathBuf,
+        rating: u8,
+    },
+    LibraryRatings {
+        ratings: HashMap<PathBuf, u8>,
+    },
+
+    // filesystem
+    // directories and songs of `path`, directories first
+    FsList {
+        path: PathBuf,
+        entries: Vec<FsEntry>,
+    },
+}
+
+impl ServerBroadcastEvent {
+    pub fn from_json(s: &str) -> DiziResult<Self> {
+        Ok(serde_json::from_str(s)?)
+    }
+
+    /// Broadcast over and over while playing, rather than in response to a request
+    pub fn is_periodic(&self) -> bool {
+        matches!(
+            self,
+            Self::PlayerProgressUpdate { .. } | Self::PlayerSpectrumUpdate { .. }
+        )
+    }
+
+    /// `ServerError` as an error, any other event as is
+    pub fn into_result(self) -> DiziResult<Self> {
+        match self {
+            Self::ServerError { msg } => Err(DiziError::new(DiziErrorKind::Server, msg)),
+            event => Ok(event),
+        }
+    }
+
+    /// The state of the player, for the events that carry all of it
+    pub fn player_state(&self) -> Option<&PlayerState> {
+        match self {
+            Self::PlayerState { state } | Self::PlaylistOpen { state } => Some(state),
+            _ => None,
+        }
+    }
 }
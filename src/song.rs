@@ -1,14 +1,18 @@
 use std::collections::HashMap;
+use std::io;
 use std::path::{Path, PathBuf};
 use std::time;
 
+use symphonia::core::codecs::CodecParameters;
 use symphonia::core::formats::{FormatOptions, Track};
-use symphonia::core::io::MediaSourceStream;
+use symphonia::core::io::{MediaSource, MediaSourceStream};
 use symphonia::core::meta::{MetadataOptions, MetadataRevision};
 use symphonia::core::probe::{Hint, ProbeResult};
 
 use serde::{Deserialize, Serialize};
 
+use crate::archive;
+use crate::chapter::{self, Chapter};
 use crate::error::{DiziError, DiziResult};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -37,6 +41,15 @@ impl DiziSongEntry {
             Self::Loaded(s) => &s.file.file_name,
         }
     }
+
+    /// Length of the song, unknown until its metadata is loaded
+    /// unless the playlist it was read from gave it
+    pub fn duration(&self) -> Option<time::Duration> {
+        match self {
+            Self::Unloaded(s) => s.duration,
+            Self::Loaded(s) => s.audio_metadata.total_duration,
+        }
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -44,6 +57,12 @@ pub struct DiziFile {
     pub file_name: String,
     pub file_path: PathBuf,
     pub file_ext: Option<String>,
+    // title and length the playlist file it was read from gave it,
+    // for showing the song before its metadata is loaded
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub duration: Option<time::Duration>,
 }
 
 impl DiziFile {
@@ -62,9 +81,20 @@ impl DiziFile {
             file_name,
             file_path: path.to_path_buf(),
             file_ext,
+            title: None,
+            duration: None,
         }
     }
 
+    /// Members of archives are decompressed into memory, symphonia seeks while probing
+    fn media_source(&self) -> DiziResult<Box<dyn MediaSource>> {
+        if archive::is_archive_member(&self.file_path) {
+            let data = archive::read(&self.file_path)?;
+            return Ok(Box::new(io::Cursor::new(data)));
+        }
+        Ok(Box::new(std::fs::File::open(&self.file_path)?))
+    }
+
     pub fn get_probe_result(&self) -> DiziResult<ProbeResult> {
         let mut hint = Hint::new();
         if let Some(ext) = self.file_ext.as_ref() {
@@ -75,9 +105,8 @@ impl DiziFile {
         let meta_opts: MetadataOptions = Default::default();
         let fmt_opts: FormatOptions = Default::default();
 
-        let src = std::fs::File::open(&self.file_path)?;
         // Create the media source stream.
-        let mss = MediaSourceStream::new(Box::new(src), Default::default());
+        let mss = MediaSourceStream::new(self.media_source()?, Default::default());
         // get probe
         let probed = symphonia::default::get_probe().format(&hint, mss, &fmt_opts, &meta_opts)?;
         Ok(probed)
@@ -89,11 +118,23 @@ pub struct DiziAudioFile {
     pub file: DiziFile,
     pub audio_metadata: AudioMetadata,
     pub music_metadata: MusicMetadata,
+    // never changes once read, boxed to keep playlist entries small
+    #[serde(default)]
+    pub chapters: Box<[Chapter]>,
 }
 
 impl TryFrom<DiziFile> for DiziAudioFile {
     type Error = DiziError;
     fn try_from(value: DiziFile) -> Result<Self, Self::Error> {
+        #[cfg(feature = "openmpt")]
+        if crate::tracker::is_tracker_file(&value.file_path) {
+            return crate::tracker::load_audio_file(value);
+        }
+        #[cfg(feature = "midi")]
+        if crate::midi::is_midi_file(&value.file_path) {
+            return crate::midi::load_audio_file(value);
+        }
+
         tracing::debug!("Loading metadata for {:?}", value.file_path);
         let mut hint = Hint::new();
         if let Some(ext) = value.file_ext.as_ref() {
@@ -104,29 +145,39 @@ impl TryFrom<DiziFile> for DiziAudioFile {
         let meta_opts: MetadataOptions = Default::default();
         let fmt_opts: FormatOptions = Default::default();
 
-        let src = std::fs::File::open(&value.file_path)?;
         // Create the media source stream.
-        let mss = MediaSourceStream::new(Box::new(src), Default::default());
+        let mss = MediaSourceStream::new(value.media_source()?, Default::default());
         // get probe
         let probed = symphonia::default::get_probe().format(&hint, mss, &fmt_opts, &meta_opts)?;
 
         // Get the instantiated format reader.
         let mut format = probed.format;
 
-        let audio_metadata = format
+        let mut audio_metadata = format
             .default_track()
             .map(|track| AudioMetadata::from(track))
             .unwrap_or_else(|| AudioMetadata::default());
+        if let (Ok(file_metadata), Some(duration)) = (
+            std::fs::metadata(&value.file_path),
+            audio_metadata.total_duration,
+        ) {
+            if duration.as_secs() > 0 {
+                let kbps = file_metadata.len() * 8 / duration.as_secs() / 1000;
+                audio_metadata.bitrate = Some(kbps as u32);
+            }
+        }
 
         let music_metadata = format
             .metadata()
             .skip_to_latest()
             .map(|metadata| MusicMetadata::from(metadata))
             .unwrap_or_else(|| MusicMetadata::default());
+        let chapters = chapter::read_chapters(&value.file_path, &music_metadata.tags).into();
         Ok(Self {
             file: value,
             audio_metadata,
             music_metadata,
+            chapters,
         })
     }
 }
@@ -147,32 +198,33 @@ impl DiziAudioFile {
     pub fn music_metadata(&self) -> &MusicMetadata {
         &self.music_metadata
     }
+
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    pub fn replay_gain(&self) -> ReplayGain {
+        ReplayGain::from_metadata(&self.music_metadata)
+    }
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct AudioMetadata {
     #[serde(rename = "track_id")]
     pub track_id: u32,
     #[serde(rename = "bit_depth")]
-    pub bit_depth: u32,
+    pub bit_depth: Option<u32>,
     #[serde(rename = "channels")]
     pub channels: Option<usize>,
     #[serde(rename = "sample_rate")]
     pub sample_rate: Option<u32>,
     #[serde(rename = "total_duration")]
     pub total_duration: Option<time::Duration>,
-}
-
-impl std::default::Default for AudioMetadata {
-    fn default() -> Self {
-        Self {
-            track_id: 0,
-            bit_depth: 16,
-            channels: None,
-            sample_rate: None,
-            total_duration: None,
-        }
-    }
+    #[serde(rename = "codec", default)]
+    pub codec: Option<String>,
+    /// Average bitrate in kbps, estimated from the file size
+    #[serde(rename = "bitrate", default)]
+    pub bitrate: Option<u32>,
 }
 
 impl std::convert::From<&Track> for AudioMetadata {
@@ -183,16 +235,12 @@ impl std::convert::From<&Track> for AudioMetadata {
         let channels = value.codec_params.channels.map(|c| c.count());
         let sample_rate = value.codec_params.sample_rate;
 
-        let bit_depth = value.codec_params.bits_per_sample.unwrap_or(16);
+        let bit_depth = value.codec_params.bits_per_sample;
+        let codec = symphonia::default::get_codecs()
+            .get_codec(value.codec_params.codec)
+            .map(|descriptor| descriptor.short_name.to_uppercase());
 
-        let total_duration = match (value.codec_params.time_base, value.codec_params.n_frames) {
-            (Some(time_base), Some(n_frames)) => {
-                let unit_time = time_base.calc_time(n_frames);
-                let duration = time::Duration::from_secs(unit_time.seconds);
-                Some(duration)
-            }
-            _ => None,
-        };
+        let total_duration = track_duration(&value.codec_params);
 
         Self {
             track_id,
@@ -200,10 +248,43 @@ impl std::convert::From<&Track> for AudioMetadata {
             channels,
             sample_rate,
             total_duration,
+            codec,
+            bitrate: None,
         }
     }
 }
 
+/// Extensions of the formats symphonia doesn't read, played through optional features
+pub fn optional_format_extensions() -> Vec<&'static str> {
+    #[allow(unused_mut)]
+    let mut extensions = Vec::new();
+    #[cfg(feature = "openmpt")]
+    extensions.extend(crate::tracker::TRACKER_EXTENSIONS);
+    #[cfg(feature = "midi")]
+    extensions.extend(crate::midi::MIDI_EXTENSIONS);
+    extensions
+}
+
+pub fn is_optional_format(path: &Path) -> bool {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => optional_format_extensions()
+            .iter()
+            .any(|optional_ext| optional_ext.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+/// Length of a track, if its container knows how many frames it has
+pub fn track_duration(codec_params: &CodecParameters) -> Option<time::Duration> {
+    match (codec_params.time_base, codec_params.n_frames) {
+        (Some(time_base), Some(n_frames)) => {
+            let unit_time = time_base.calc_time(n_frames);
+            Some(time::Duration::from_secs(unit_time.seconds))
+        }
+        _ => None,
+    }
+}
+
 #[derive(Clone, Debug, Default, Serialize, Deserialize)]
 pub struct MusicMetadata {
     pub standard_tags: HashMap<String, String>,
@@ -232,3 +313,121 @@ impl std::convert::From<&MetadataRevision> for MusicMetadata {
         }
     }
 }
+
+/// Which of a song's ReplayGain adjustments the player applies
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplayGainMode {
+    #[default]
+    Off,
+    Track,
+    Album,
+}
+
+impl ReplayGainMode {
+    /// The mode after this one, for toggling through them
+    pub fn next(self) -> Self {
+        match self {
+            Self::Off => Self::Track,
+            Self::Track => Self::Album,
+            Self::Album => Self::Off,
+        }
+    }
+}
+
+impl std::fmt::Display for ReplayGainMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Off => write!(f, "off"),
+            Self::Track => write!(f, "track"),
+            Self::Album => write!(f, "album"),
+        }
+    }
+}
+
+/// Loudness adjustments of a song, gains in dB and peaks in full scale amplitude
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ReplayGain {
+    pub track_gain: Option<f32>,
+    pub track_peak: Option<f32>,
+    pub album_gain: Option<f32>,
+    pub album_peak: Option<f32>,
+}
+
+impl ReplayGain {
+    /// From `REPLAYGAIN_*` tags, or the `R128_*_GAIN` tags of Opus files
+    pub fn from_metadata(metadata: &MusicMetadata) -> Self {
+        let standard = |key: &str| {
+            metadata
+                .standard_tags
+                .get(key)
+                .and_then(|value| parse_gain(value))
+        };
+        // Q7.8 fixed point, relative to -23 LUFS instead of ReplayGain's -18
+        let r128 = |key: &str| {
+            metadata
+                .tags
+                .iter()
+                .find(|(tag, _)| tag.eq_ignore_ascii_case(key))
+                .and_then(|(_, value)| value.trim().parse::<i16>().ok())
+                .map(|gain| gain as f32 / 256.0 + 5.0)
+        };
+        Self {
+            track_gain: standard("ReplayGainTrackGain").or_else(|| r128("R128_TRACK_GAIN")),
+            track_peak: standard("ReplayGainTrackPeak"),
+            album_gain: standard("ReplayGainAlbumGain").or_else(|| r128("R128_ALBUM_GAIN")),
+            album_peak: standard("ReplayGainAlbumPeak"),
+        }
+    }
+
+    /// What samples are multiplied by, lowered so the song's peak doesn't clip.
+    /// Songs without album gains get their track gain in album mode
+    pub fn factor(&self, mode: ReplayGainMode) -> f32 {
+        let (gain, peak) = match mode {
+            ReplayGainMode::Off => return 1.0,
+            ReplayGainMode::Track => (self.track_gain, self.track_peak),
+            ReplayGainMode::Album => match self.album_gain {
+                Some(gain) => (Some(gain), self.album_peak.or(self.track_peak)),
+                None => (self.track_gain, self.track_peak),
+            },
+        };
+        let factor = gain.map_or(1.0, |gain| 10f32.powf(gain / 20.0));
+        match peak {
+            Some(peak) if peak > 0.0 => factor.min(1.0 / peak),
+            _ => factor,
+        }
+    }
+}
+
+/// `-6.48 dB`, `0.988525`
+fn parse_gain(s: &str) -> Option<f32> {
+    let s = s.trim();
+    let s = match s.len().checked_sub(2).map(|i| s.split_at(i)) {
+        Some((number, unit)) if unit.eq_ignore_ascii_case("db") => number.trim(),
+        _ => s,
+    };
+    s.parse().ok().filter(|gain: &f32| gain.is_finite())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn read_replay_gain() {
+        let metadata = MusicMetadata {
+            standard_tags: HashMap::from([
+                ("ReplayGainTrackGain".to_string(), "-6.02 dB".to_string()),
+                ("ReplayGainTrackPeak".to_string(), "0.25".to_string()),
+            ]),
+            tags: HashMap::from([("r128_album_gain".to_string(), "2560".to_string())]),
+        };
+        let gain = ReplayGain::from_metadata(&metadata);
+        assert_eq!(gain.track_gain, Some(-6.02));
+        assert_eq!(gain.album_gain, Some(15.0));
+        assert_eq!(gain.factor(ReplayGainMode::Off), 1.0);
+        assert!((gain.factor(ReplayGainMode::Track) - 0.5).abs() < 0.001);
+        // the track's peak still limits the album gain
+        assert_eq!(gain.factor(ReplayGainMode::Album), 4.0);
+    }
+}
@@ -0,0 +1,194 @@
+//! Tracker modules (MOD, XM, IT, S3M, ...), which symphonia doesn't read,
+//! decoded with libopenmpt
+
+use std::collections::HashMap;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_double, c_float, c_int, c_void};
+use std::path::Path;
+use std::ptr;
+use std::time;
+
+use crate::error::{DiziError, DiziErrorKind, DiziResult};
+use crate::song::{AudioMetadata, DiziAudioFile, DiziFile, MusicMetadata};
+
+pub const TRACKER_EXTENSIONS: [&str; 8] = ["it", "mod", "mptm", "mo3", "s3m", "stm", "umx", "xm"];
+
+// modules are always rendered in stereo
+const CHANNELS: usize = 2;
+// libopenmpt renders at any sample rate, this one is only reported until a device is picked
+const SAMPLE_RATE: u32 = 48000;
+// frames rendered per call into libopenmpt
+const RENDER_FRAMES: usize = 4096;
+
+#[repr(C)]
+struct OpenmptModule {
+    _private: [u8; 0],
+}
+
+type OpenmptLogFunc = Option<unsafe extern "C" fn(message: *const c_char, user: *mut c_void)>;
+type OpenmptErrorFunc = Option<unsafe extern "C" fn(error: c_int, user: *mut c_void) -> c_int>;
+
+#[link(name = "openmpt")]
+extern "C" {
+    fn openmpt_log_func_silent(message: *const c_char, user: *mut c_void);
+    fn openmpt_free_string(s: *const c_char);
+    fn openmpt_module_create_from_memory2(
+        filedata: *const c_void,
+        filesize: usize,
+        logfunc: OpenmptLogFunc,
+        loguser: *mut c_void,
+        errfunc: OpenmptErrorFunc,
+        erruser: *mut c_void,
+        error: *mut c_int,
+        error_message: *mut *const c_char,
+        ctls: *const c_void,
+    ) -> *mut OpenmptModule;
+    fn openmpt_module_destroy(module: *mut OpenmptModule);
+    fn openmpt_module_get_duration_seconds(module: *mut OpenmptModule) -> c_double;
+    fn openmpt_module_get_metadata(module: *mut OpenmptModule, key: *const c_char)
+        -> *const c_char;
+    fn openmpt_module_read_interleaved_float_stereo(
+        module: *mut OpenmptModule,
+        samplerate: i32,
+        count: usize,
+        interleaved_stereo: *mut c_float,
+    ) -> usize;
+}
+
+/// Copies and frees a string handed out by libopenmpt
+unsafe fn take_string(s: *const c_char) -> Option<String> {
+    if s.is_null() {
+        return None;
+    }
+    let string = CStr::from_ptr(s).to_string_lossy().into_owned();
+    openmpt_free_string(s);
+    Some(string)
+}
+
+pub struct TrackerModule {
+    module: *mut OpenmptModule,
+}
+
+impl TrackerModule {
+    pub fn open(path: &Path) -> DiziResult<Self> {
+        // libopenmpt keeps its own copy of the file
+        let data = crate::archive::read_file(path)?;
+        let mut error: c_int = 0;
+        let mut error_message: *const c_char = ptr::null();
+        let module = unsafe {
+            openmpt_module_create_from_memory2(
+                data.as_ptr() as *const c_void,
+                data.len(),
+                Some(openmpt_log_func_silent),
+                ptr::null_mut(),
+                None,
+                ptr::null_mut(),
+                &mut error,
+                &mut error_message,
+                ptr::null(),
+            )
+        };
+        let message = unsafe { take_string(error_message) };
+        if module.is_null() {
+            return Err(DiziError::new(
+                DiziErrorKind::UnrecognizedFormat,
+                format!(
+                    "Failed to open module {:?}: {}",
+                    path,
+                    message.unwrap_or_else(|| format!("error {}", error))
+                ),
+            ));
+        }
+        Ok(Self { module })
+    }
+
+    pub fn duration(&self) -> time::Duration {
+        let seconds = unsafe { openmpt_module_get_duration_seconds(self.module) };
+        time::Duration::from_secs_f64(seconds.max(0.0))
+    }
+
+    /// Metadata such as `title`, `artist` or `type_long`, none when the module leaves it empty
+    pub fn metadata(&self, key: &str) -> Option<String> {
+        let key = CString::new(key).ok()?;
+        let value = unsafe { take_string(openmpt_module_get_metadata(self.module, key.as_ptr())) };
+        value.filter(|s| !s.trim().is_empty())
+    }
+
+    /// Renders the whole module into interleaved stereo samples
+    pub fn render(&mut self, sample_rate: u32) -> Vec<f32> {
+        let mut samples = Vec::new();
+        let mut buffer = vec![0.0; RENDER_FRAMES * CHANNELS];
+        loop {
+            let frames = unsafe {
+                openmpt_module_read_interleaved_float_stereo(
+                    self.module,
+                    sample_rate as i32,
+                    RENDER_FRAMES,
+                    buffer.as_mut_ptr(),
+                )
+            };
+            if frames == 0 {
+                break;
+            }
+            samples.extend_from_slice(&buffer[..frames * CHANNELS]);
+        }
+        samples
+    }
+}
+
+impl Drop for TrackerModule {
+    fn drop(&mut self) {
+        unsafe { openmpt_module_destroy(self.module) };
+    }
+}
+
+pub fn is_tracker_file(path: &Path) -> bool {
+    match path.extension().and_then(|s| s.to_str()) {
+        Some(ext) => TRACKER_EXTENSIONS
+            .iter()
+            .any(|tracker_ext| tracker_ext.eq_ignore_ascii_case(ext)),
+        None => false,
+    }
+}
+
+pub fn load_audio_file(file: DiziFile) -> DiziResult<DiziAudioFile> {
+    tracing::debug!("Loading module metadata for {:?}", file.file_path);
+    let module = TrackerModule::open(&file.file_path)?;
+
+    let audio_metadata = AudioMetadata {
+        track_id: 0,
+        bit_depth: None,
+        channels: Some(CHANNELS),
+        sample_rate: Some(SAMPLE_RATE),
+        total_duration: Some(module.duration()),
+        codec: module.metadata("type").map(|s| s.to_uppercase()),
+        bitrate: None,
+    };
+
+    let mut standard_tags = HashMap::new();
+    if let Some(title) = module.metadata("title") {
+        standard_tags.insert("TrackTitle".to_string(), title);
+    }
+    if let Some(artist) = module.metadata("artist") {
+        standard_tags.insert("Artist".to_string(), artist);
+    }
+    let mut tags = HashMap::new();
+    if let Some(tracker) = module.metadata("tracker") {
+        tags.insert("tracker".to_string(), tracker);
+    }
+    Ok(DiziAudioFile {
+        file,
+        audio_metadata,
+        music_metadata: MusicMetadata {
+            standard_tags,
+            tags,
+        },
+        chapters: Box::default(),
+    })
+}
+
+/// Interleaved stereo samples of the whole module at `sample_rate`
+pub fn decode(path: &Path, sample_rate: u32) -> DiziResult<Vec<f32>> {
+    let mut module = TrackerModule::open(path)?;
+    Ok(module.render(sample_rate))
+}
@@ -1,3 +1,5 @@
+pub mod sort;
 pub mod stream;
 
+pub use self::sort::*;
 pub use self::stream::*;
@@ -0,0 +1,48 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+/// Orders names the way people number them, `track 2` before `track 10`,
+/// ignoring case unless the names differ only by case
+pub fn natural_cmp(s1: &str, s2: &str) -> Ordering {
+    alphanumeric_sort::compare_str(s1.to_lowercase(), s2.to_lowercase())
+        .then_with(|| alphanumeric_sort::compare_str(s1, s2))
+}
+
+/// Natural order of the file names of two paths
+pub fn natural_cmp_path(p1: &Path, p2: &Path) -> Ordering {
+    let name = |p: &Path| {
+        p.file_name()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    natural_cmp(&name(p1), &name(p2))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn natural_cmp_orders_numbers() {
+        let mut names = vec![
+            "10 - ten.flac",
+            "2 - Two.flac",
+            "1 - one.flac",
+            "B.flac",
+            "a.flac",
+        ];
+        names.sort_by(|a, b| natural_cmp(a, b));
+        assert_eq!(
+            names,
+            vec![
+                "1 - one.flac",
+                "2 - Two.flac",
+                "10 - ten.flac",
+                "a.flac",
+                "B.flac"
+            ]
+        );
+        assert_eq!(natural_cmp("v1.9", "v1.10"), Ordering::Less);
+        assert_ne!(natural_cmp("Song", "song"), Ordering::Equal);
+    }
+}